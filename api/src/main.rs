@@ -4,21 +4,49 @@ use api::modules::{
     auth::{handler::AuthHandlerImpl, repository::user::UserRepositoryImpl, AuthState},
     finance_manager::{
         handler::{
-            debt::{invoice::InvoiceHandlerImpl, DebtHandlerImpl},
+            budget::{BudgetHandlerImpl, DynBudgetHandler},
+            debt::{
+                categorization::{CategorizationHandlerImpl, DynCategorizationHandler},
+                category::{CategoryHandlerImpl, DynCategoryHandler},
+                invoice::InvoiceHandlerImpl,
+                recurrence::RecurrenceGenerationHandlerImpl,
+                DebtHandlerImpl,
+            },
+            export::ExportHandlerImpl,
             financial_instrument::FinancialInstrumentHandlerImpl,
             income::IncomeHandlerImpl,
+            investment::InvestmentHandlerImpl,
+            ledger::{DynLedgerHandler, LedgerHandlerImpl},
             payment::PaymentHandlerImpl,
             pubsub::PubSubHandlerImpl,
+            reminder::ReminderHandlerImpl,
+            report::{DynReportHandler, ReportHandlerImpl},
+            statement::StatementHandlerImpl,
+            telegram::TelegramHandlerImpl,
+            transfer::TransferHandlerImpl,
         },
         repository::{
+            budget::BudgetRepositoryImpl,
             debt::{
+                categorization::CategorizationRuleRepositoryImpl, category::CategoryRepositoryImpl,
                 installment::InstallmentRepositoryImpl, invoice::InvoiceRepositoryImpl,
-                DebtRepositoryImpl,
+                renegotiation::RenegotiationRepositoryImpl, DebtRepositoryImpl,
+            },
+            export::ExportRepositoryImpl,
+            financial_instrument::{
+                adjustment::AdjustmentRepositoryImpl, goal::SavingsGoalRepositoryImpl,
+                investment::YieldRepositoryImpl, FinancialInstrumentRepositoryImpl,
             },
-            financial_instrument::FinancialInstrumentRepositoryImpl,
             income::IncomeRepositoryImpl,
             payment::PaymentRepositoryImpl,
-            recurrence::RecurrenceRepositoryImpl,
+            recurrence::{
+                generation_run::RecurrenceGenerationRunRepositoryImpl,
+                income::IncomeRecurrenceRepositoryImpl, RecurrenceRepositoryImpl,
+            },
+            reminder::ReminderRepositoryImpl,
+            statement::StatementRepositoryImpl,
+            telegram::TelegramChatRepositoryImpl,
+            transfer::TransferRepositoryImpl,
         },
         FinanceManagerState,
     },
@@ -34,32 +62,112 @@ use api::modules::{
         MatchmakingState,
     },
     routes::{self, AppState},
+    shared::scheduler::{run_at_from_env, spawn_daily},
 };
 use axum::Router;
+use chrono::NaiveTime;
 use database::DbPool;
 use sqlx::{Pool, Postgres};
+use telegram_api::{telegram_api::DynTelegramApiGateway, TelegramApiClient};
 
 #[tokio::main]
 async fn main() {
+    tracing_subscriber::fmt::init();
+
     let db_conection = DbPool::new().await;
     let pool = db_conection.get_connection();
 
     let pubsub = build_pubsub(pool);
+    let telegram_gateway: Arc<DynTelegramApiGateway> = Arc::new(TelegramApiClient::new());
 
     // Build handlers
-    let payment_handler = build_payment_handler(pool, &pubsub);
-    let debt_handler = build_debt_handler(pool);
-    let invoice_handler = build_invoice_handler(pool);
+    let ledger_handler: Arc<DynLedgerHandler> = Arc::new(build_ledger_handler(pool));
+    let category_handler: Arc<DynCategoryHandler> = Arc::new(build_category_handler(pool));
+    let report_handler: Arc<DynReportHandler> =
+        Arc::new(build_report_handler(pool, &category_handler));
+    let budget_handler: Arc<DynBudgetHandler> = Arc::new(build_budget_handler(
+        pool,
+        &report_handler,
+        &telegram_gateway,
+        &category_handler,
+    ));
+    let categorization_handler: Arc<DynCategorizationHandler> =
+        Arc::new(build_categorization_handler(pool, &category_handler));
+    let payment_handler = build_payment_handler(pool, &pubsub, &ledger_handler);
+    let debt_handler = build_debt_handler(
+        pool,
+        &ledger_handler,
+        &budget_handler,
+        &category_handler,
+        &categorization_handler,
+    );
+    let invoice_handler = Arc::new(build_invoice_handler(pool, &ledger_handler));
     let financial_instrument_handler = build_financial_instrument_handler(pool);
     let income_handler = build_income_handler(pool);
+    let transfer_handler = build_transfer_handler(pool, &ledger_handler);
+    let investment_handler = Arc::new(build_investment_handler(pool, &ledger_handler));
+    let telegram_handler = build_telegram_handler(
+        pool,
+        &debt_handler,
+        &payment_handler,
+        &income_handler,
+        &telegram_gateway,
+    );
+    let statement_handler =
+        build_statement_handler(pool, &debt_handler, &payment_handler, &income_handler);
+    let reminder_handler = Arc::new(build_reminder_handler(pool, &telegram_gateway));
+    let recurrence_generation_handler = Arc::new(build_recurrence_generation_handler(
+        pool,
+        &budget_handler,
+        &categorization_handler,
+    ));
+
+    // Schedule background jobs
+    spawn_daily(
+        reminder_handler.clone(),
+        run_at_from_env("REMINDER_RUN_AT", NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+    );
+    spawn_daily(
+        recurrence_generation_handler.clone(),
+        run_at_from_env(
+            "RECURRENCE_GENERATION_RUN_AT",
+            NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        ),
+    );
+    spawn_daily(
+        invoice_handler.clone(),
+        run_at_from_env(
+            "INVOICE_CLOSING_RUN_AT",
+            NaiveTime::from_hms_opt(3, 0, 0).unwrap(),
+        ),
+    );
+    spawn_daily(
+        investment_handler.clone(),
+        run_at_from_env(
+            "INVESTMENT_YIELD_RUN_AT",
+            NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+        ),
+    );
 
     // Build states
     let finance_manager_state = FinanceManagerState {
         payment_handler: Arc::new(payment_handler.clone()),
         debt_handler: Arc::new(debt_handler.clone()),
-        invoice_handler: Arc::new(invoice_handler),
+        category_handler,
+        categorization_handler,
+        invoice_handler,
         financial_instrument_handler: Arc::new(financial_instrument_handler.clone()),
+        ledger_handler,
+        transfer_handler: Arc::new(transfer_handler),
+        investment_handler,
         income_handler: Arc::new(income_handler.clone()),
+        telegram_handler: Arc::new(telegram_handler),
+        reminder_handler,
+        report_handler,
+        budget_handler,
+        recurrence_generation_handler,
+        statement_handler: Arc::new(statement_handler),
+        export_handler: Arc::new(build_export_handler(pool)),
     };
 
     let auth_handler = build_auth_handler(pool);
@@ -93,26 +201,82 @@ fn build_pubsub(pool: &Pool<Postgres>) -> PubSubHandlerImpl {
     }
 }
 
-fn build_payment_handler(pool: &Pool<Postgres>, pubsub: &PubSubHandlerImpl) -> PaymentHandlerImpl {
+fn build_ledger_handler(pool: &Pool<Postgres>) -> LedgerHandlerImpl {
+    LedgerHandlerImpl {
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        income_repository: Arc::new(IncomeRepositoryImpl::new(pool)),
+        payment_repository: Arc::new(PaymentRepositoryImpl::new(pool)),
+        adjustment_repository: Arc::new(AdjustmentRepositoryImpl::new(pool)),
+        transfer_repository: Arc::new(TransferRepositoryImpl::new(pool)),
+        yield_repository: Arc::new(YieldRepositoryImpl::new(pool)),
+    }
+}
+
+fn build_payment_handler(
+    pool: &Pool<Postgres>,
+    pubsub: &PubSubHandlerImpl,
+    ledger: &Arc<DynLedgerHandler>,
+) -> PaymentHandlerImpl {
     PaymentHandlerImpl {
         payment_repository: Arc::new(PaymentRepositoryImpl::new(pool)),
         debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
+        invoice_repository: Arc::new(InvoiceRepositoryImpl::new(pool)),
         financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
         pubsub: Arc::new(pubsub.clone()),
+        ledger: ledger.clone(),
     }
 }
 
-fn build_debt_handler(pool: &Pool<Postgres>) -> DebtHandlerImpl {
+fn build_debt_handler(
+    pool: &Pool<Postgres>,
+    ledger: &Arc<DynLedgerHandler>,
+    budget: &Arc<DynBudgetHandler>,
+    category: &Arc<DynCategoryHandler>,
+    categorization: &Arc<DynCategorizationHandler>,
+) -> DebtHandlerImpl {
     DebtHandlerImpl {
         debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
         installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
         recurrence_repository: Arc::new(RecurrenceRepositoryImpl::new(pool)),
+        renegotiation_repository: Arc::new(RenegotiationRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        invoice_repository: Arc::new(InvoiceRepositoryImpl::new(pool)),
+        ledger: ledger.clone(),
+        budget: budget.clone(),
+        category: category.clone(),
+        categorization: categorization.clone(),
     }
 }
 
-fn build_invoice_handler(pool: &Pool<Postgres>) -> InvoiceHandlerImpl {
+fn build_category_handler(pool: &Pool<Postgres>) -> CategoryHandlerImpl {
+    CategoryHandlerImpl {
+        category_repository: Arc::new(CategoryRepositoryImpl::new(pool)),
+    }
+}
+
+fn build_categorization_handler(
+    pool: &Pool<Postgres>,
+    category: &Arc<DynCategoryHandler>,
+) -> CategorizationHandlerImpl {
+    CategorizationHandlerImpl {
+        rule_repository: Arc::new(CategorizationRuleRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        invoice_repository: Arc::new(InvoiceRepositoryImpl::new(pool)),
+        category: category.clone(),
+    }
+}
+
+fn build_invoice_handler(
+    pool: &Pool<Postgres>,
+    ledger: &Arc<DynLedgerHandler>,
+) -> InvoiceHandlerImpl {
     InvoiceHandlerImpl {
         invoice_repository: Arc::new(InvoiceRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        ledger: ledger.clone(),
     }
 }
 
@@ -122,9 +286,142 @@ fn build_financial_instrument_handler(pool: &Pool<Postgres>) -> FinancialInstrum
     }
 }
 
+fn build_transfer_handler(
+    pool: &Pool<Postgres>,
+    ledger: &Arc<DynLedgerHandler>,
+) -> TransferHandlerImpl {
+    TransferHandlerImpl {
+        transfer_repository: Arc::new(TransferRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        ledger: ledger.clone(),
+    }
+}
+
+fn build_investment_handler(
+    pool: &Pool<Postgres>,
+    ledger: &Arc<DynLedgerHandler>,
+) -> InvestmentHandlerImpl {
+    InvestmentHandlerImpl {
+        goal_repository: Arc::new(SavingsGoalRepositoryImpl::new(pool)),
+        yield_repository: Arc::new(YieldRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        ledger: ledger.clone(),
+    }
+}
+
 fn build_income_handler(pool: &Pool<Postgres>) -> IncomeHandlerImpl {
     IncomeHandlerImpl {
         income_repository: Arc::new(IncomeRepositoryImpl::new(pool)),
+        income_recurrence_repository: Arc::new(IncomeRecurrenceRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+    }
+}
+
+fn build_telegram_handler(
+    pool: &Pool<Postgres>,
+    debt_handler: &DebtHandlerImpl,
+    payment_handler: &PaymentHandlerImpl,
+    income_handler: &IncomeHandlerImpl,
+    telegram_gateway: &Arc<DynTelegramApiGateway>,
+) -> TelegramHandlerImpl {
+    TelegramHandlerImpl {
+        telegram_chat_repository: Arc::new(TelegramChatRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        debt_handler: Arc::new(debt_handler.clone()),
+        payment_handler: Arc::new(payment_handler.clone()),
+        income_handler: Arc::new(income_handler.clone()),
+        telegram_gateway: telegram_gateway.clone(),
+        webhook_secret: std::env::var("TELEGRAM_WEBHOOK_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty()),
+    }
+}
+
+fn build_statement_handler(
+    pool: &Pool<Postgres>,
+    debt_handler: &DebtHandlerImpl,
+    payment_handler: &PaymentHandlerImpl,
+    income_handler: &IncomeHandlerImpl,
+) -> StatementHandlerImpl {
+    StatementHandlerImpl {
+        statement_repository: Arc::new(StatementRepositoryImpl::new(pool)),
+        financial_instrument_repository: Arc::new(FinancialInstrumentRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
+        debt_handler: Arc::new(debt_handler.clone()),
+        income_handler: Arc::new(income_handler.clone()),
+        payment_handler: Arc::new(payment_handler.clone()),
+    }
+}
+
+fn build_export_handler(pool: &Pool<Postgres>) -> ExportHandlerImpl {
+    ExportHandlerImpl {
+        export_repository: Arc::new(ExportRepositoryImpl::new(pool)),
+    }
+}
+
+fn build_reminder_handler(
+    pool: &Pool<Postgres>,
+    telegram_gateway: &Arc<DynTelegramApiGateway>,
+) -> ReminderHandlerImpl {
+    ReminderHandlerImpl {
+        reminder_repository: Arc::new(ReminderRepositoryImpl::new(pool)),
+        telegram_chat_repository: Arc::new(TelegramChatRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
+        telegram_gateway: telegram_gateway.clone(),
+    }
+}
+
+fn build_report_handler(
+    pool: &Pool<Postgres>,
+    category: &Arc<DynCategoryHandler>,
+) -> ReportHandlerImpl {
+    ReportHandlerImpl {
+        income_repository: Arc::new(IncomeRepositoryImpl::new(pool)),
+        debt_repository: Arc::new(DebtRepositoryImpl::new(pool)),
+        installment_repository: Arc::new(InstallmentRepositoryImpl::new(pool)),
+        payment_repository: Arc::new(PaymentRepositoryImpl::new(pool)),
+        recurrence_repository: Arc::new(RecurrenceRepositoryImpl::new(pool)),
+        income_recurrence_repository: Arc::new(IncomeRecurrenceRepositoryImpl::new(pool)),
+        category: category.clone(),
+    }
+}
+
+fn build_budget_handler(
+    pool: &Pool<Postgres>,
+    report: &Arc<DynReportHandler>,
+    telegram_gateway: &Arc<DynTelegramApiGateway>,
+    category: &Arc<DynCategoryHandler>,
+) -> BudgetHandlerImpl {
+    BudgetHandlerImpl {
+        budget_repository: Arc::new(BudgetRepositoryImpl::new(pool)),
+        telegram_chat_repository: Arc::new(TelegramChatRepositoryImpl::new(pool)),
+        report: report.clone(),
+        telegram_gateway: telegram_gateway.clone(),
+        category: category.clone(),
+    }
+}
+
+fn build_recurrence_generation_handler(
+    pool: &Pool<Postgres>,
+    budget: &Arc<DynBudgetHandler>,
+    categorization: &Arc<DynCategorizationHandler>,
+) -> RecurrenceGenerationHandlerImpl {
+    let generation_day = std::env::var("RECURRENCE_GENERATION_DAY")
+        .ok()
+        .and_then(|day| day.parse::<u32>().ok())
+        .filter(|day| (1..=28).contains(day))
+        .unwrap_or(1);
+
+    RecurrenceGenerationHandlerImpl {
+        recurrence_repository: Arc::new(RecurrenceRepositoryImpl::new(pool)),
+        income_recurrence_repository: Arc::new(IncomeRecurrenceRepositoryImpl::new(pool)),
+        generation_run_repository: Arc::new(RecurrenceGenerationRunRepositoryImpl::new(pool)),
+        generation_day,
+        budget: budget.clone(),
+        categorization: categorization.clone(),
     }
 }
 
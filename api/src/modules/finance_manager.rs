@@ -4,10 +4,22 @@ use axum::Router;
 
 use crate::modules::{
     finance_manager::handler::{
-        debt::{invoice::DynInvoiceHandler, DynDebtHandler},
+        budget::DynBudgetHandler,
+        debt::{
+            categorization::DynCategorizationHandler, category::DynCategoryHandler,
+            invoice::DynInvoiceHandler, recurrence::DynRecurrenceGenerationHandler, DynDebtHandler,
+        },
+        export::DynExportHandler,
         financial_instrument::DynFinancialInstrumentHandler,
         income::DynIncomeHandler,
+        investment::DynInvestmentHandler,
+        ledger::DynLedgerHandler,
         payment::DynPaymentHandler,
+        reminder::DynReminderHandler,
+        report::DynReportHandler,
+        statement::DynStatementHandler,
+        telegram::DynTelegramHandler,
+        transfer::DynTransferHandler,
     },
     routes::AppState,
 };
@@ -21,8 +33,20 @@ pub struct FinanceManagerState {
     pub income_handler: Arc<DynIncomeHandler>,
     pub payment_handler: Arc<DynPaymentHandler>,
     pub debt_handler: Arc<DynDebtHandler>,
+    pub category_handler: Arc<DynCategoryHandler>,
+    pub categorization_handler: Arc<DynCategorizationHandler>,
     pub invoice_handler: Arc<DynInvoiceHandler>,
     pub financial_instrument_handler: Arc<DynFinancialInstrumentHandler>,
+    pub ledger_handler: Arc<DynLedgerHandler>,
+    pub transfer_handler: Arc<DynTransferHandler>,
+    pub investment_handler: Arc<DynInvestmentHandler>,
+    pub telegram_handler: Arc<DynTelegramHandler>,
+    pub reminder_handler: Arc<DynReminderHandler>,
+    pub report_handler: Arc<DynReportHandler>,
+    pub budget_handler: Arc<DynBudgetHandler>,
+    pub recurrence_generation_handler: Arc<DynRecurrenceGenerationHandler>,
+    pub statement_handler: Arc<DynStatementHandler>,
+    pub export_handler: Arc<DynExportHandler>,
 }
 
 pub fn configure_service_routes() -> Router<AppState> {
@@ -32,6 +56,14 @@ pub fn configure_service_routes() -> Router<AppState> {
             .merge(routes::payment::configure_routes())
             .merge(routes::debt::configure_routes())
             .merge(routes::financial_instrument::configure_routes())
-            .merge(routes::income::configure_routes()),
+            .merge(routes::income::configure_routes())
+            .merge(routes::telegram::configure_routes())
+            .merge(routes::reminder::configure_routes())
+            .merge(routes::transfer::configure_routes())
+            .merge(routes::goal::configure_routes())
+            .merge(routes::report::configure_routes())
+            .merge(routes::budget::configure_routes())
+            .merge(routes::statement::configure_routes())
+            .merge(routes::export::configure_routes()),
     )
 }
@@ -0,0 +1,421 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::{date::first_day_of_month, getters, DeletedBy};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{debt::DebtCategory, report::spending::SpendingTotals, telegram::format_currency},
+    handler::budget::use_cases::{CreateBudgetRequest, UpdateBudgetRequest},
+};
+
+/// Monthly spending limit of a client for a debt category or a tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    id: Uuid,
+    client_id: Uuid,
+    /// Exactly one of `category` and `tag` is set.
+    category: Option<DebtCategory>,
+    tag: Option<String>,
+    monthly_limit: Decimal,
+    /// Whether the part of the limit left unused in a month is added to the
+    /// limit of the next one.
+    rollover: bool,
+    /// Whether alerts are also sent to the client's Telegram chats.
+    notify_telegram: bool,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_by: Option<DeletedBy>,
+}
+
+/// How much of a budget is committed in a month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetUsage {
+    pub month: NaiveDate,
+    /// Unused limit carried over from the previous month.
+    pub rollover_amount: Decimal,
+    /// Monthly limit plus the rollover.
+    pub available_limit: Decimal,
+    /// Debts and installments of the category, subcategories included, or of
+    /// the tag due in the month.
+    pub committed_amount: Decimal,
+    /// Negative once the budget was exceeded.
+    pub remaining_amount: Decimal,
+    pub usage_percentage: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetSummary {
+    #[serde(flatten)]
+    pub budget: Budget,
+    pub usage: BudgetUsage,
+}
+
+impl Budget {
+    pub fn from_request(client_id: Uuid, request: CreateBudgetRequest) -> HttpResult<Self> {
+        let tag = request
+            .tag
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty());
+
+        if request.category.is_some() == tag.is_some() {
+            return Err(Box::new(HttpError::bad_request(
+                "A budget must target either a category or a tag",
+            )));
+        }
+        validate_limit(request.monthly_limit)?;
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            client_id,
+            category: request.category,
+            tag,
+            monthly_limit: request.monthly_limit,
+            rollover: request.rollover.unwrap_or(false),
+            notify_telegram: request.notify_telegram.unwrap_or(false),
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_by: None,
+        })
+    }
+
+    pub fn update(&mut self, request: UpdateBudgetRequest) -> HttpResult<()> {
+        if let Some(monthly_limit) = request.monthly_limit {
+            validate_limit(monthly_limit)?;
+            self.monthly_limit = monthly_limit;
+        }
+        if let Some(rollover) = request.rollover {
+            self.rollover = rollover;
+        }
+        if let Some(notify_telegram) = request.notify_telegram {
+            self.notify_telegram = notify_telegram;
+        }
+
+        self.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to manage this budget",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Category name or `#tag`, as shown to the client.
+    pub fn label(&self) -> String {
+        match (&self.category, &self.tag) {
+            (Some(category), _) => String::from(category.clone()),
+            (None, Some(tag)) => format!("#{tag}"),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn committed_amount(&self, totals: &SpendingTotals) -> Decimal {
+        let amounts = match (&self.category, &self.tag) {
+            (Some(category), _) => totals.category(category),
+            (None, Some(tag)) => totals.tag(tag),
+            (None, None) => Default::default(),
+        };
+
+        amounts.committed
+    }
+
+    /// Usage of the budget in `month`. `previous` holds the totals of the
+    /// month before, used for the rollover. Only what was left of the plain
+    /// limit last month carries over, never an older rollover: leftovers
+    /// don't pile up, so months without spending can't grow the limit
+    /// without bound, and planning a month only needs the one before it.
+    pub fn usage(
+        &self,
+        month: NaiveDate,
+        current: &SpendingTotals,
+        previous: &SpendingTotals,
+    ) -> BudgetUsage {
+        let rollover_amount = if self.rollover {
+            (self.monthly_limit - self.committed_amount(previous)).max(Decimal::ZERO)
+        } else {
+            Decimal::ZERO
+        };
+        let available_limit = self.monthly_limit + rollover_amount;
+        let committed_amount = self.committed_amount(current);
+
+        BudgetUsage {
+            month: first_day_of_month(month),
+            rollover_amount,
+            available_limit,
+            committed_amount,
+            remaining_amount: available_limit - committed_amount,
+            usage_percentage: (committed_amount / available_limit * Decimal::ONE_HUNDRED)
+                .round_dp(2),
+        }
+    }
+}
+
+getters! {
+    Budget {
+        id: Uuid,
+        client_id: Uuid,
+        category: Option<DebtCategory>,
+        tag: Option<String>,
+        monthly_limit: Decimal,
+        rollover: bool,
+        notify_telegram: bool,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+        deleted_by: Option<DeletedBy>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for Budget {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            category: row
+                .get::<Option<String>, _>("category")
+                .map(DebtCategory::from),
+            tag: row.get("tag"),
+            monthly_limit: row.get("monthly_limit"),
+            rollover: row.get("rollover"),
+            notify_telegram: row.get("notify_telegram"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            deleted_by: row
+                .get::<Option<Json<DeletedBy>>, _>("deleted_by")
+                .map(|json| json.0),
+        }
+    }
+}
+
+fn validate_limit(monthly_limit: Decimal) -> HttpResult<()> {
+    if monthly_limit <= Decimal::ZERO {
+        return Err(Box::new(HttpError::bad_request(
+            "Budget monthly limit must be positive",
+        )));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BudgetThreshold {
+    /// 80% of the available limit.
+    NearLimit,
+    /// 100% of the available limit.
+    OverLimit,
+}
+
+impl BudgetThreshold {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetThreshold::NearLimit => "NEAR_LIMIT",
+            BudgetThreshold::OverLimit => "OVER_LIMIT",
+        }
+    }
+
+    pub fn percentage(&self) -> Decimal {
+        match self {
+            BudgetThreshold::NearLimit => Decimal::from(80),
+            BudgetThreshold::OverLimit => Decimal::ONE_HUNDRED,
+        }
+    }
+
+    /// Thresholds reached by a usage, lowest first.
+    pub fn reached(usage: &BudgetUsage) -> Vec<Self> {
+        [BudgetThreshold::NearLimit, BudgetThreshold::OverLimit]
+            .into_iter()
+            .filter(|threshold| usage.usage_percentage >= threshold.percentage())
+            .collect()
+    }
+}
+
+impl From<String> for BudgetThreshold {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "OVER_LIMIT" => BudgetThreshold::OverLimit,
+            _ => BudgetThreshold::NearLimit,
+        }
+    }
+}
+
+/// Raised the first time a budget reaches a threshold in a month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlert {
+    id: Uuid,
+    client_id: Uuid,
+    budget_id: Uuid,
+    month: NaiveDate,
+    threshold: BudgetThreshold,
+    committed_amount: Decimal,
+    available_limit: Decimal,
+    usage_percentage: Decimal,
+    created_at: DateTime<Utc>,
+}
+
+impl BudgetAlert {
+    pub fn new(budget: &Budget, usage: &BudgetUsage, threshold: BudgetThreshold) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            client_id: budget.client_id,
+            budget_id: budget.id,
+            month: usage.month,
+            threshold,
+            committed_amount: usage.committed_amount,
+            available_limit: usage.available_limit,
+            usage_percentage: usage.usage_percentage,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Chat message announcing the alert.
+    pub fn message(&self, budget: &Budget) -> String {
+        let headline = match self.threshold {
+            BudgetThreshold::NearLimit => format!(
+                "⚠️ Orçamento de {} chegou a {}% do limite",
+                budget.label(),
+                self.usage_percentage.normalize()
+            ),
+            BudgetThreshold::OverLimit => {
+                format!("🚨 Orçamento de {} estourado", budget.label())
+            }
+        };
+
+        format!(
+            "{headline} em {}: {} de {}.",
+            self.month.format("%m/%Y"),
+            format_currency(self.committed_amount),
+            format_currency(self.available_limit)
+        )
+    }
+}
+
+getters! {
+    BudgetAlert {
+        id: Uuid,
+        client_id: Uuid,
+        budget_id: Uuid,
+        month: NaiveDate,
+        threshold: BudgetThreshold,
+        committed_amount: Decimal,
+        available_limit: Decimal,
+        usage_percentage: Decimal,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for BudgetAlert {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            budget_id: row.get("budget_id"),
+            month: row.get("month"),
+            threshold: BudgetThreshold::from(row.get::<String, _>("threshold")),
+            committed_amount: row.get("committed_amount"),
+            available_limit: row.get("available_limit"),
+            usage_percentage: row.get("usage_percentage"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::domain::{debt::Debt, report::ReportPeriod};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn budget(category: Option<DebtCategory>, tag: Option<&str>, rollover: bool) -> Budget {
+        Budget::from_request(
+            Uuid::new_v4(),
+            CreateBudgetRequest {
+                category,
+                tag: tag.map(str::to_string),
+                monthly_limit: Decimal::from(1000),
+                rollover: Some(rollover),
+                notify_telegram: None,
+            },
+        )
+        .unwrap()
+    }
+
+    fn totals(month: u32, amount: i64) -> SpendingTotals {
+        let debt = Debt::new(
+            Uuid::new_v4(),
+            "Mercado".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            date(month, 10),
+            Some(DebtCategory::new("FOOD")),
+            None,
+            Some(vec!["mercado".to_string()]),
+            None,
+        );
+
+        SpendingTotals::collect(&ReportPeriod::month(date(month, 1)), &[debt], &[], &[])
+    }
+
+    #[test]
+    fn test_budget_targets_category_or_tag() {
+        let tag_budget = budget(None, Some("mercado"), false);
+        assert_eq!(tag_budget.label(), "#mercado");
+
+        for (category, tag) in [
+            (Some(DebtCategory::new("FOOD")), Some("mercado")),
+            (None, Some(" ")),
+        ] {
+            let request = CreateBudgetRequest {
+                category,
+                tag: tag.map(str::to_string),
+                monthly_limit: Decimal::from(1000),
+                rollover: None,
+                notify_telegram: None,
+            };
+            assert!(Budget::from_request(Uuid::new_v4(), request).is_err());
+        }
+    }
+
+    #[test]
+    fn test_usage_with_rollover_and_thresholds() {
+        let food = budget(Some(DebtCategory::new("FOOD")), None, true);
+        let usage = food.usage(date(10, 17), &totals(10, 1000), &totals(9, 750));
+
+        assert_eq!(usage.month, date(10, 1));
+        assert_eq!(usage.rollover_amount, Decimal::from(250));
+        assert_eq!(usage.available_limit, Decimal::from(1250));
+        assert_eq!(usage.usage_percentage, Decimal::from(80));
+        assert_eq!(
+            BudgetThreshold::reached(&usage),
+            vec![BudgetThreshold::NearLimit]
+        );
+
+        let tag_budget = budget(None, Some("mercado"), false);
+        let usage = tag_budget.usage(date(10, 17), &totals(10, 1000), &totals(9, 750));
+
+        assert_eq!(usage.rollover_amount, Decimal::ZERO);
+        assert_eq!(usage.remaining_amount, Decimal::ZERO);
+        assert_eq!(
+            BudgetThreshold::reached(&usage),
+            vec![BudgetThreshold::NearLimit, BudgetThreshold::OverLimit]
+        );
+    }
+}
@@ -7,12 +7,28 @@ use uuid::Uuid;
 
 use util::DeletedBy;
 
-use crate::modules::finance_manager::domain::{debt::installment::Installment, payment::Payment};
-
+use crate::modules::finance_manager::{
+    domain::{
+        debt::{
+            amortization::{split_amount, FinancingTerms, InstallmentSchedule},
+            installment::Installment,
+            late_fee::{LateFeeBreakdown, LateFeePayment, LateFeePolicy},
+            renegotiation::{DebtRenegotiation, RenegotiationTerms},
+        },
+        payment::Payment,
+    },
+    handler::debt::use_cases::RenegotiateDebtRequest,
+};
+
+pub mod amortization;
+pub mod batch;
+pub mod categorization;
 pub mod category;
 pub mod installment;
 pub mod invoice;
+pub mod late_fee;
 pub mod recurrence;
+pub mod renegotiation;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -31,7 +47,29 @@ pub struct Debt {
     due_date: NaiveDate,
     #[serde(default)]
     status: DebtStatus,
+    /// Installments of the current plan.
     installment_count: Option<i32>,
+    /// Installment numbers taken by the plans a renegotiation replaced. The
+    /// current plan is numbered after them.
+    #[serde(default)]
+    installment_offset: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    late_fee_policy: Option<LateFeePolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    late_fee: Option<LateFeeBreakdown>,
+    /// Interest and amortization system of an installment plan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    financing: Option<FinancingTerms>,
+    /// Balance left unpaid on an invoice and moved to the next one.
+    #[serde(default)]
+    carried_over_amount: Decimal,
+    /// Debt billing the carried over balance on the next invoice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    carried_over_to: Option<Uuid>,
+    /// Invoice whose unpaid balance the debt bills again. Its amount is
+    /// already counted by the debts of that invoice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    carried_over_from: Option<Uuid>,
     created_at: DateTime<Utc>,
     updated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -71,6 +109,13 @@ impl Debt {
             due_date,
             status: DebtStatus::default(),
             installment_count,
+            installment_offset: 0,
+            late_fee_policy: None,
+            late_fee: None,
+            financing: None,
+            carried_over_amount: Decimal::ZERO,
+            carried_over_to: None,
+            carried_over_from: None,
             created_at: Utc::now(),
             updated_at: None,
             deleted_by: None,
@@ -78,9 +123,34 @@ impl Debt {
     }
 
     pub fn process_payment(&mut self, payment: &Payment) -> HttpResult<()> {
+        self.accrue_late_fees(*payment.payment_date(), None);
         self.validate_payment_amount(payment)?;
+        if let Some(late_fee) = &mut self.late_fee {
+            late_fee.payments.push(LateFeePayment::from(payment));
+        }
 
         self.paid_amount += payment.amount();
+        self.discount_amount += payment.discount_amount();
+
+        self.recalculate_remaining_amount();
+        self.recalculate_status();
+        self.updated_at = Some(Utc::now());
+
+        Ok(())
+    }
+
+    /// Moves part of the balance to the debt opened on the next invoice. The
+    /// amount closes the debt like a payment, but no money left an account.
+    pub fn carry_over(&mut self, amount: Decimal, carry_over_debt_id: Uuid) -> HttpResult<()> {
+        if amount > self.remaining_amount {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Carried over amount ({:.2}) exceeds remaining amount ({:.2})",
+                amount, self.remaining_amount
+            ))));
+        }
+
+        self.carried_over_amount += amount;
+        self.carried_over_to = Some(carry_over_debt_id);
 
         self.recalculate_remaining_amount();
         self.recalculate_status();
@@ -89,6 +159,23 @@ impl Debt {
         Ok(())
     }
 
+    /// Turns the debt into the one billing the balance left unpaid on
+    /// `invoice_id`.
+    pub fn bill_carry_over(&mut self, invoice_id: Uuid) {
+        self.carried_over_from = Some(invoice_id);
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn is_carry_over(&self) -> bool {
+        self.carried_over_from.is_some()
+    }
+
+    /// Amount the debt is expected to take out of an account: its total
+    /// without what was carried over to another debt.
+    pub fn expected_amount(&self) -> Decimal {
+        self.total_amount - self.carried_over_amount
+    }
+
     pub fn reconcile_with_actual_payment(&mut self, payment: &Payment) -> HttpResult<()> {
         self.total_amount = *payment.amount();
         self.paid_amount = *payment.amount();
@@ -110,6 +197,15 @@ impl Debt {
         }
 
         self.paid_amount -= payment.amount();
+        self.discount_amount -= payment.discount_amount();
+        if let Some(late_fee) = self.late_fee.take() {
+            self.late_fee = late_fee.without_payment(payment, self.due_date);
+            self.total_amount = self
+                .late_fee
+                .as_ref()
+                .map(LateFeeBreakdown::total)
+                .unwrap_or(late_fee.principal);
+        }
         self.recalculate_remaining_amount();
         self.recalculate_status();
         self.updated_at = Some(Utc::now());
@@ -118,41 +214,204 @@ impl Debt {
     }
 
     /// Generates installments based on the due day from the debt's due date.
+    /// With financing terms the interest of the plan is added to the total.
     /// Updates the debt's due_date to the last installment date.
     /// Should only be called when has_installments() is true.
     pub fn generate_installments(&mut self, due_day: u32) -> HttpResult<Vec<Installment>> {
         let installment_count = self.installment_count.unwrap_or(0);
-        let (base_amount, remainder) = self.calculate_installment_amount(installment_count);
+        let schedule = InstallmentSchedule::build(
+            self.total_amount,
+            installment_count,
+            self.due_date,
+            due_day,
+            1,
+            self.financing.as_ref(),
+        )?;
+        let installments = self.build_installments(&schedule);
 
-        let mut installments = Vec::new();
+        // Update debt's due_date to the last installment's due date
+        if let Some(last_installment) = installments.last() {
+            self.due_date = *last_installment.due_date();
+        }
 
-        for i in 1..=installment_count {
-            let amount = if i == 1 {
-                base_amount + remainder
-            } else {
-                base_amount
-            };
+        self.total_amount = schedule.total_amount;
+        self.recalculate_remaining_amount();
+
+        Ok(installments)
+    }
 
-            let target_date = self
-                .due_date
-                .checked_add_months(chrono::Months::new((i - 1) as u32))
-                .ok_or_else(|| {
-                    Box::new(HttpError::bad_request(format!(
-                        "Could not calculate due date for installment {}",
-                        i
-                    )))
-                })?;
-
-            let due_date = date_with_day_or_last(target_date.year(), target_date.month(), due_day);
-            installments.push(Installment::new(*self.id(), i, due_date, amount));
+    /// Re-splits the remaining amount into a new installment plan. Open
+    /// installments of the current plan are superseded, not removed, and the
+    /// new ones take the ids after them so the old plan stays as history.
+    /// The count and numbering of the debt then follow the new plan. The
+    /// optional interest rate is a flat percentage over the balance.
+    pub fn renegotiate(
+        &mut self,
+        installments: &mut [Installment],
+        request: &RenegotiateDebtRequest,
+        today: NaiveDate,
+    ) -> HttpResult<(DebtRenegotiation, Vec<Installment>)> {
+        request.validate()?;
+
+        if self.is_settled() {
+            return Err(Box::new(HttpError::bad_request(
+                "Settled debts cannot be renegotiated",
+            )));
         }
 
-        // Update debt's due_date to the last installment's due date
-        if let Some(last_installment) = installments.last() {
+        let balance = self.remaining_amount;
+        let interest_rate = request.interest_rate.unwrap_or_default();
+        let interest_amount = (balance * interest_rate / Decimal::ONE_HUNDRED).round_dp(2);
+
+        let due_day = request.due_day.unwrap_or_else(|| self.due_date.day());
+        let first_due_date = request.first_due_date.unwrap_or_else(|| {
+            let next_month = today + chrono::Months::new(1);
+            date_with_day_or_last(next_month.year(), next_month.month(), due_day)
+        });
+        let first_installment_id = installments
+            .iter()
+            .map(|installment| *installment.installment_id())
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let schedule = InstallmentSchedule::build(
+            balance + interest_amount,
+            request.installment_count,
+            first_due_date,
+            due_day,
+            first_installment_id,
+            None,
+        )?;
+        let new_installments = self.build_installments(&schedule);
+
+        let renegotiation_id = Uuid::new_v4();
+        let superseded_installment_ids = installments
+            .iter_mut()
+            .filter(|installment| installment.is_open())
+            .map(|installment| {
+                installment.supersede(renegotiation_id);
+                *installment.installment_id()
+            })
+            .collect();
+
+        let renegotiation = DebtRenegotiation::new(
+            renegotiation_id,
+            self,
+            RenegotiationTerms {
+                previous_remaining_amount: balance,
+                interest_rate,
+                interest_amount,
+                installment_count: request.installment_count,
+                due_day,
+                first_due_date,
+                first_installment_id,
+                superseded_installment_ids,
+            },
+        );
+
+        self.total_amount += interest_amount;
+        self.installment_count = Some(request.installment_count);
+        self.installment_offset = first_installment_id - 1;
+        if let Some(last_installment) = new_installments.last() {
             self.due_date = *last_installment.due_date();
         }
+        self.recalculate_remaining_amount();
+        self.recalculate_status();
+        self.updated_at = Some(Utc::now());
 
-        Ok(installments)
+        Ok((renegotiation, new_installments))
+    }
+
+    /// Number of the installment within the debt's current plan, as in
+    /// "parcela x/y".
+    pub fn installment_number(&self, installment_id: i32) -> i32 {
+        installment_id - self.installment_offset
+    }
+
+    fn build_installments(&self, schedule: &InstallmentSchedule) -> Vec<Installment> {
+        schedule
+            .entries
+            .iter()
+            .map(|entry| Installment::from_schedule(self.id, entry))
+            .collect()
+    }
+
+    /// Recomputes the total and remaining amounts with the late fees due up
+    /// to `until`. Uses the debt's own policy, then the one of a previous
+    /// accrual, then `fallback` (usually the financial instrument's policy).
+    /// Interest is simple, over the principal not paid yet.
+    /// Settled debts and debts paid in installments are left untouched.
+    pub fn accrue_late_fees(&mut self, until: NaiveDate, fallback: Option<&LateFeePolicy>) {
+        if self.status == DebtStatus::Settled || self.has_installments() {
+            return;
+        }
+
+        let Some(policy) = self
+            .late_fee_policy
+            .as_ref()
+            .or(self.late_fee.as_ref().map(|late_fee| &late_fee.policy))
+            .or(fallback)
+        else {
+            return;
+        };
+
+        // What was paid before the fees started running counts from the due
+        // date on.
+        let payments = match &self.late_fee {
+            Some(late_fee) => late_fee.payments.clone(),
+            None => {
+                let paid = self.paid_amount + self.discount_amount;
+                (paid > Decimal::ZERO)
+                    .then_some(LateFeePayment {
+                        payment_id: None,
+                        payment_date: self.due_date,
+                        amount: paid,
+                    })
+                    .into_iter()
+                    .collect()
+            }
+        };
+        // Never before a payment already charged
+        let until = payments
+            .iter()
+            .map(|payment| payment.payment_date)
+            .fold(until, NaiveDate::max);
+
+        let breakdown = policy.calculate(self.principal_amount(), self.due_date, payments, until);
+        if breakdown.days_late == 0 && self.late_fee.is_none() {
+            return;
+        }
+
+        self.total_amount = breakdown.total();
+        self.late_fee = Some(breakdown);
+        self.recalculate_remaining_amount();
+        self.recalculate_status();
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// Total amount without any accrued late fee.
+    pub fn principal_amount(&self) -> Decimal {
+        self.late_fee
+            .as_ref()
+            .map(|late_fee| late_fee.principal)
+            .unwrap_or(self.total_amount)
+    }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        matches!(self.status, DebtStatus::Open | DebtStatus::Overdue) && self.due_date < today
+    }
+
+    /// Open debts past their due date are reported as overdue. The status is
+    /// derived, so it is persisted as open.
+    pub(crate) fn refresh_overdue_status(&mut self, today: NaiveDate) {
+        if matches!(self.status, DebtStatus::Open | DebtStatus::Overdue) {
+            self.status = if self.is_overdue(today) {
+                DebtStatus::Overdue
+            } else {
+                DebtStatus::Open
+            };
+        }
     }
 
     pub fn has_installments(&self) -> bool {
@@ -175,11 +434,23 @@ impl Debt {
 
     /// Checks if the payment amount is valid to be processed
     pub fn validate_payment_amount(&self, payment: &Payment) -> HttpResult<()> {
-        if self.paid_amount >= self.total_amount {
+        if self.is_settled() {
             return Err(Box::new(HttpError::bad_request("Debt already paid")));
         }
 
-        if *payment.amount() > self.remaining_amount {
+        if *payment.discount_amount() < Decimal::ZERO {
+            return Err(Box::new(HttpError::bad_request(
+                "Early payment discount cannot be negative",
+            )));
+        }
+
+        if *payment.discount_amount() > Decimal::ZERO && !self.has_installments() {
+            return Err(Box::new(HttpError::bad_request(
+                "Early payment discount only applies to installments",
+            )));
+        }
+
+        if *payment.amount() + *payment.discount_amount() > self.remaining_amount {
             return Err(Box::new(HttpError::bad_request(format!(
                 "Payment amount ({:.2}) exceeds remaining amount ({:.2})",
                 payment.amount(),
@@ -191,7 +462,8 @@ impl Debt {
     }
 
     fn recalculate_remaining_amount(&mut self) {
-        self.remaining_amount = self.total_amount - self.paid_amount - self.discount_amount;
+        self.remaining_amount =
+            self.total_amount - self.paid_amount - self.discount_amount - self.carried_over_amount;
     }
 
     fn recalculate_status(&mut self) {
@@ -201,11 +473,12 @@ impl Debt {
             self.status = DebtStatus::Installment;
         } else {
             self.status = DebtStatus::Open;
+            self.refresh_overdue_status(Utc::now().date_naive());
         }
     }
 
     fn is_settled(&self) -> bool {
-        self.paid_amount >= self.total_amount
+        self.paid_amount + self.discount_amount + self.carried_over_amount >= self.total_amount
     }
 
     fn is_installment(&self) -> bool {
@@ -214,58 +487,59 @@ impl Debt {
 
     /// Calculates the amount of the installment and the remainder
     fn calculate_installment_amount(&self, installment_number: i32) -> (Decimal, Decimal) {
-        let installment_number = Decimal::from(installment_number);
+        split_amount(self.total_amount, installment_number)
+    }
+}
 
-        let base_amount = self.total_amount() / installment_number;
-        let remainder = self.total_amount() - (base_amount * installment_number);
-        (base_amount, remainder)
+/// Debt just registered, with the warnings raised while charging it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredDebt {
+    #[serde(flatten)]
+    pub debt: Debt,
+    /// Set when a card purchase exceeded the card's credit limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_limit_warning: Option<String>,
+}
+
+/// Name of one of the client's categories, see [`category::Category`].
+/// Debts, recurrences and budgets store the name, always uppercase.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct DebtCategory(String);
+
+impl DebtCategory {
+    /// Category of debts registered without one. Every client has it.
+    pub const UNKNOWN: &'static str = "UNKNOWN";
+
+    pub fn new(name: &str) -> Self {
+        Self(name.trim().to_uppercase())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.0 == Self::UNKNOWN
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum DebtCategory {
-    #[default]
-    Unknown,
-    Home,
-    Transport,
-    Health,
-    Food,
-    Lifestyle,
-    Education,
-    Goals,
-    Personal,
+impl Default for DebtCategory {
+    fn default() -> Self {
+        Self::new(Self::UNKNOWN)
+    }
 }
 
 impl From<String> for DebtCategory {
     fn from(s: String) -> Self {
-        match s.as_str() {
-            "HOME" => DebtCategory::Home,
-            "TRANSPORT" => DebtCategory::Transport,
-            "HEALTH" => DebtCategory::Health,
-            "FOOD" => DebtCategory::Food,
-            "LIFESTYLE" => DebtCategory::Lifestyle,
-            "EDUCATION" => DebtCategory::Education,
-            "GOALS" => DebtCategory::Goals,
-            "PERSONAL" => DebtCategory::Personal,
-            _ => DebtCategory::Unknown,
-        }
+        Self::new(&s)
     }
 }
 
 impl From<DebtCategory> for String {
     fn from(category: DebtCategory) -> Self {
-        match category {
-            DebtCategory::Home => "HOME".to_string(),
-            DebtCategory::Transport => "TRANSPORT".to_string(),
-            DebtCategory::Health => "HEALTH".to_string(),
-            DebtCategory::Food => "FOOD".to_string(),
-            DebtCategory::Lifestyle => "LIFESTYLE".to_string(),
-            DebtCategory::Education => "EDUCATION".to_string(),
-            DebtCategory::Goals => "GOALS".to_string(),
-            DebtCategory::Personal => "PERSONAL".to_string(),
-            DebtCategory::Unknown => "UNKNOWN".to_string(),
-        }
+        category.0
     }
 }
 
@@ -298,9 +572,13 @@ impl ExpenseType {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DebtStatus {
-    /// Debt is open, not yet paid. A.k.a. "Em aberto"
+    /// Debt is open, not yet paid. A.k.a. "Em aberto". As a filter it also
+    /// matches overdue debts.
     #[default]
     Open,
+    /// Open and past its due date. Derived from `Open`, never persisted.
+    /// A.k.a. "Vencida"
+    Overdue,
     /// Has installments being paid. A.k.a. "Parcelada"
     Installment,
     /// Fully paid. A.k.a. "Quitada"
@@ -311,6 +589,7 @@ impl From<String> for DebtStatus {
     fn from(s: String) -> Self {
         match s.as_str() {
             "OPEN" => DebtStatus::Open,
+            "OVERDUE" => DebtStatus::Overdue,
             "INSTALLMENT" => DebtStatus::Installment,
             "SETTLED" => DebtStatus::Settled,
             _ => DebtStatus::default(),
@@ -324,11 +603,13 @@ impl From<&str> for DebtStatus {
         match s_upper.as_str() {
             // Valores em inglês (banco de dados)
             "OPEN" => DebtStatus::Open,
+            "OVERDUE" => DebtStatus::Overdue,
             "INSTALLMENT" => DebtStatus::Installment,
             "SETTLED" => DebtStatus::Settled,
             // Valores em português (interface do usuário)
             "PENDENTE" => DebtStatus::Open,
-            "VENCIDA" => DebtStatus::Installment,
+            "VENCIDA" => DebtStatus::Overdue,
+            "PARCELADA" => DebtStatus::Installment,
             "PAGO" => DebtStatus::Settled,
             _ => DebtStatus::default(),
         }
@@ -339,6 +620,7 @@ impl From<DebtStatus> for String {
     fn from(status: DebtStatus) -> Self {
         match status {
             DebtStatus::Open => "OPEN".to_string(),
+            DebtStatus::Overdue => "OVERDUE".to_string(),
             DebtStatus::Installment => "INSTALLMENT".to_string(),
             DebtStatus::Settled => "SETTLED".to_string(),
         }
@@ -349,6 +631,7 @@ impl std::fmt::Display for DebtStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             DebtStatus::Open => "OPEN",
+            DebtStatus::Overdue => "OVERDUE",
             DebtStatus::Installment => "INSTALLMENT",
             DebtStatus::Settled => "SETTLED",
         };
@@ -372,6 +655,13 @@ getters!(
         due_date: NaiveDate,
         status: DebtStatus,
         installment_count: Option<i32>,
+        installment_offset: i32,
+        late_fee_policy: Option<LateFeePolicy>,
+        late_fee: Option<LateFeeBreakdown>,
+        financing: Option<FinancingTerms>,
+        carried_over_amount: Decimal,
+        carried_over_to: Option<Uuid>,
+        carried_over_from: Option<Uuid>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -401,6 +691,17 @@ impl Debt {
 
     pub fn set_due_date(&mut self, due_date: NaiveDate) {
         self.due_date = due_date;
+        self.refresh_overdue_status(Utc::now().date_naive());
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_late_fee_policy(&mut self, late_fee_policy: Option<LateFeePolicy>) {
+        self.late_fee_policy = late_fee_policy;
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn set_financing(&mut self, financing: Option<FinancingTerms>) {
+        self.financing = financing;
         self.updated_at = Some(Utc::now());
     }
 }
@@ -421,6 +722,13 @@ from_row_constructor! {
         due_date: NaiveDate,
         status: DebtStatus,
         installment_count: Option<i32>,
+        installment_offset: i32,
+        late_fee_policy: Option<LateFeePolicy>,
+        late_fee: Option<LateFeeBreakdown>,
+        financing: Option<FinancingTerms>,
+        carried_over_amount: Decimal,
+        carried_over_to: Option<Uuid>,
+        carried_over_from: Option<Uuid>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -522,3 +830,97 @@ impl DebtFilters {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::handler::payment::use_cases::PaymentBasicData;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn debt(amount: i64, installment_count: Option<i32>) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Mercado".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            date(10, 10),
+            None,
+            None,
+            None,
+            installment_count,
+        )
+    }
+
+    #[test]
+    fn test_negative_discount_rejected_without_installments() {
+        let debt = debt(300, None);
+        let payment = Payment::new(
+            &debt,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date: date(10, 10),
+                amount: Some(Decimal::from(100)),
+                installment_ids: None,
+                early_payment_discount: Some(Decimal::from(-50)),
+            },
+        );
+
+        assert!(debt.validate_payment_amount(&payment).is_err());
+    }
+
+    #[test]
+    fn test_split_amount_rounds_to_cents() {
+        // Splits used to keep the full precision of the division, which the
+        // cents of the stored installments then dropped. The rounding
+        // difference stays on the first installment, as before.
+        assert_eq!(
+            split_amount(Decimal::from(100), 3),
+            (Decimal::new(3333, 2), Decimal::new(1, 2))
+        );
+        assert_eq!(
+            split_amount(Decimal::from(200), 3),
+            (Decimal::new(6667, 2), Decimal::new(-1, 2))
+        );
+
+        let (base_amount, remainder) = split_amount(Decimal::new(10001, 2), 4);
+        assert_eq!(
+            base_amount * Decimal::from(4) + remainder,
+            Decimal::new(10001, 2)
+        );
+    }
+
+    #[test]
+    fn test_renegotiation_counts_and_numbers_the_new_plan() {
+        let mut debt = debt(400, Some(4));
+        let mut installments = debt.generate_installments(10).unwrap();
+
+        let (_, new_installments) = debt
+            .renegotiate(
+                &mut installments,
+                &RenegotiateDebtRequest {
+                    installment_count: 3,
+                    due_day: Some(20),
+                    first_due_date: None,
+                    interest_rate: None,
+                },
+                date(10, 16),
+            )
+            .unwrap();
+
+        assert_eq!(*debt.installment_count(), Some(3));
+        assert_eq!(
+            new_installments
+                .iter()
+                .map(|i| (
+                    *i.installment_id(),
+                    debt.installment_number(*i.installment_id())
+                ))
+                .collect::<Vec<_>>(),
+            vec![(5, 1), (6, 2), (7, 3)]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use chrono::{Datelike, Months, NaiveDate};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::date::date_with_day_or_last;
+
+/// Longest installment plan accepted, 35 years of monthly installments.
+pub const MAX_INSTALLMENT_COUNT: i32 = 420;
+
+/// Highest monthly interest percentage accepted for a financing.
+pub const MAX_MONTHLY_INTEREST_RATE: Decimal = Decimal::ONE_HUNDRED;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AmortizationSystem {
+    /// Fixed installment amount ("Tabela Price").
+    #[default]
+    Price,
+    /// Constant amortization, decreasing installments.
+    Sac,
+}
+
+/// Interest charged over an installment plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancingTerms {
+    /// Monthly interest percentage (e.g. `2` = 2% a.m.).
+    pub interest_rate: Decimal,
+    #[serde(default)]
+    pub amortization: AmortizationSystem,
+}
+
+impl FinancingTerms {
+    pub fn validate(&self) -> HttpResult<()> {
+        if self.interest_rate < Decimal::ZERO {
+            return Err(Box::new(HttpError::bad_request(
+                "Interest rate cannot be negative",
+            )));
+        }
+
+        if self.interest_rate > MAX_MONTHLY_INTEREST_RATE {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Interest rate cannot exceed {MAX_MONTHLY_INTEREST_RATE}% a month"
+            ))));
+        }
+
+        Ok(())
+    }
+
+    fn monthly_rate(&self) -> Decimal {
+        self.interest_rate / Decimal::ONE_HUNDRED
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    pub installment_id: i32,
+    pub due_date: NaiveDate,
+    pub amount: Decimal,
+    pub principal: Decimal,
+    pub interest: Decimal,
+    /// Principal still owed after this installment.
+    pub balance: Decimal,
+}
+
+/// Installments of a plan with their principal and interest components.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallmentSchedule {
+    pub principal: Decimal,
+    pub total_interest: Decimal,
+    pub total_amount: Decimal,
+    pub entries: Vec<ScheduleEntry>,
+}
+
+impl InstallmentSchedule {
+    /// Splits `principal` into `count` monthly installments numbered from
+    /// `first_installment_id`, the first one due in the month of
+    /// `first_due_date`. Without financing terms the principal is split evenly,
+    /// with the rounding difference on the first installment. With terms, the
+    /// last installment absorbs the rounding difference of the balance.
+    pub fn build(
+        principal: Decimal,
+        count: i32,
+        first_due_date: NaiveDate,
+        due_day: u32,
+        first_installment_id: i32,
+        terms: Option<&FinancingTerms>,
+    ) -> HttpResult<Self> {
+        validate_installment_count(count)?;
+
+        let components = match terms {
+            None => even_split(principal, count),
+            Some(terms) => {
+                terms.validate()?;
+                amortize(principal, count, terms)?
+            }
+        };
+
+        let mut entries = Vec::new();
+        let mut balance = principal;
+
+        for (i, (principal, interest)) in (1..=count).zip(components) {
+            let target_date = first_due_date
+                .checked_add_months(Months::new((i - 1) as u32))
+                .ok_or_else(|| {
+                    Box::new(HttpError::bad_request(format!(
+                        "Could not calculate due date for installment {}",
+                        i
+                    )))
+                })?;
+
+            balance -= principal;
+            entries.push(ScheduleEntry {
+                installment_id: first_installment_id + i - 1,
+                due_date: date_with_day_or_last(target_date.year(), target_date.month(), due_day),
+                amount: principal + interest,
+                principal,
+                interest,
+                balance,
+            });
+        }
+
+        let total_interest = entries.iter().map(|entry| entry.interest).sum();
+
+        Ok(Self {
+            principal,
+            total_interest,
+            total_amount: principal + total_interest,
+            entries,
+        })
+    }
+}
+
+pub fn validate_installment_count(count: i32) -> HttpResult<()> {
+    if count <= 0 {
+        return Err(Box::new(HttpError::bad_request(
+            "Installment count must be greater than zero",
+        )));
+    }
+
+    if count > MAX_INSTALLMENT_COUNT {
+        return Err(Box::new(HttpError::bad_request(format!(
+            "Installment count cannot exceed {MAX_INSTALLMENT_COUNT}"
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Splits `amount` in `installment_number` parts, returning the base amount
+/// and the rounding remainder.
+pub(crate) fn split_amount(amount: Decimal, installment_number: i32) -> (Decimal, Decimal) {
+    let installment_number = Decimal::from(installment_number);
+
+    // Installments are stored with cents, so the rounding difference
+    // goes to the remainder instead of being lost.
+    let base_amount = (amount / installment_number).round_dp(2);
+    let remainder = amount - (base_amount * installment_number);
+    (base_amount, remainder)
+}
+
+/// (principal, interest) of each installment of an even split.
+fn even_split(amount: Decimal, count: i32) -> Vec<(Decimal, Decimal)> {
+    let (base_amount, remainder) = split_amount(amount, count);
+
+    (1..=count)
+        .map(|i| {
+            let amount = if i == 1 {
+                base_amount + remainder
+            } else {
+                base_amount
+            };
+            (amount, Decimal::ZERO)
+        })
+        .collect()
+}
+
+/// (principal, interest) of each installment under the amortization system.
+/// Fails when the compound factor of the plan does not fit a decimal.
+fn amortize(
+    principal: Decimal,
+    count: i32,
+    terms: &FinancingTerms,
+) -> HttpResult<Vec<(Decimal, Decimal)>> {
+    let rate = terms.monthly_rate();
+    let installment_number = Decimal::from(count);
+
+    let price_amount = if rate.is_zero() {
+        (principal / installment_number).round_dp(2)
+    } else {
+        let overflow = || {
+            Box::new(HttpError::bad_request(
+                "Financing terms are too large to be calculated",
+            ))
+        };
+        let factor = (0..count)
+            .try_fold(Decimal::ONE, |acc, _| acc.checked_mul(Decimal::ONE + rate))
+            .ok_or_else(overflow)?;
+        let amount = principal
+            .checked_mul(rate)
+            .and_then(|amount| amount.checked_mul(factor))
+            .ok_or_else(overflow)?;
+        (amount / (factor - Decimal::ONE)).round_dp(2)
+    };
+    let sac_amortization = (principal / installment_number).round_dp(2);
+
+    let mut balance = principal;
+    let mut components = Vec::new();
+
+    for i in 1..=count {
+        let interest = (balance * rate).round_dp(2);
+        let amortization = if i == count {
+            balance
+        } else {
+            match terms.amortization {
+                AmortizationSystem::Price => price_amount - interest,
+                AmortizationSystem::Sac => sac_amortization,
+            }
+        };
+
+        balance -= amortization;
+        components.push((amortization, interest));
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::modules::finance_manager::domain::debt::Debt;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn schedule(amortization: AmortizationSystem) -> InstallmentSchedule {
+        let terms = FinancingTerms {
+            interest_rate: Decimal::ONE,
+            amortization,
+        };
+
+        InstallmentSchedule::build(Decimal::from(1000), 3, date(11, 10), 10, 1, Some(&terms))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_price_has_fixed_installments() {
+        // The last installment absorbs the rounding of the balance
+        let schedule = schedule(AmortizationSystem::Price);
+        let amounts: Vec<Decimal> = schedule.entries.iter().map(|e| e.amount).collect();
+
+        assert_eq!(
+            amounts,
+            vec![
+                Decimal::new(34002, 2),
+                Decimal::new(34002, 2),
+                Decimal::new(34003, 2)
+            ]
+        );
+        assert_eq!(schedule.entries[0].interest, Decimal::from(10));
+        assert_eq!(schedule.total_interest, Decimal::new(2007, 2));
+        assert_eq!(schedule.entries[2].balance, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_sac_has_constant_amortization() {
+        let schedule = schedule(AmortizationSystem::Sac);
+
+        assert_eq!(schedule.entries[0].principal, Decimal::new(33333, 2));
+        assert_eq!(schedule.entries[0].amount, Decimal::new(34333, 2));
+        assert_eq!(schedule.entries[1].amount, Decimal::new(34000, 2));
+        assert_eq!(schedule.entries[2].principal, Decimal::new(33334, 2));
+        assert_eq!(schedule.total_interest, Decimal::new(2000, 2));
+        assert_eq!(schedule.entries[2].balance, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_oversized_financing_is_rejected_instead_of_overflowing() {
+        let terms = FinancingTerms {
+            interest_rate: Decimal::ONE_HUNDRED,
+            amortization: AmortizationSystem::Price,
+        };
+
+        let result =
+            InstallmentSchedule::build(Decimal::from(1000), 120, date(11, 10), 10, 1, Some(&terms));
+        assert_eq!(result.unwrap_err().status_u16(), 400);
+
+        let too_many = InstallmentSchedule::build(
+            Decimal::from(1000),
+            MAX_INSTALLMENT_COUNT + 1,
+            date(11, 10),
+            10,
+            1,
+            None,
+        );
+        assert!(too_many.is_err());
+
+        let too_expensive = FinancingTerms {
+            interest_rate: Decimal::from(101),
+            ..terms
+        };
+        assert!(too_expensive.validate().is_err());
+    }
+
+    #[test]
+    fn test_even_split_without_terms() {
+        let schedule =
+            InstallmentSchedule::build(Decimal::from(100), 3, date(1, 31), 31, 1, None).unwrap();
+
+        assert_eq!(schedule.entries[0].amount, Decimal::new(3334, 2));
+        assert_eq!(schedule.entries[1].amount, Decimal::new(3333, 2));
+        assert_eq!(schedule.entries[1].due_date, date(2, 28));
+        assert_eq!(schedule.total_interest, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_financed_debt_includes_interest_in_total() {
+        let mut debt = Debt::new(
+            Uuid::new_v4(),
+            "Notebook".to_string(),
+            Decimal::from(1000),
+            None,
+            None,
+            date(11, 10),
+            None,
+            None,
+            None,
+            Some(3),
+        );
+        debt.set_financing(Some(FinancingTerms {
+            interest_rate: Decimal::ONE,
+            amortization: AmortizationSystem::Sac,
+        }));
+
+        let installments = debt.generate_installments(10).unwrap();
+
+        assert_eq!(*debt.total_amount(), Decimal::from(1020));
+        assert_eq!(*debt.remaining_amount(), Decimal::from(1020));
+        assert_eq!(
+            *debt.due_date(),
+            NaiveDate::from_ymd_opt(2027, 1, 10).unwrap()
+        );
+        assert_eq!(*installments[0].interest_amount(), Decimal::from(10));
+        assert_eq!(*installments[0].principal_amount(), Decimal::new(33333, 2));
+    }
+}
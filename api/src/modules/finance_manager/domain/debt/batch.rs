@@ -0,0 +1,385 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use http_error::{problem::ProblemDetails, HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::{
+    installment::Installment, invoice::Invoice, Debt, RegisteredDebt,
+};
+
+/// Most creations and updates a single batch may carry.
+pub const MAX_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchMode {
+    /// Nothing is saved when any item fails.
+    #[default]
+    AllOrNothing,
+    /// Valid items are saved and failed ones are reported.
+    BestEffort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchOperation {
+    Create,
+    Update,
+}
+
+impl BatchOperation {
+    /// Request field holding the items of the operation.
+    pub fn field(&self) -> &'static str {
+        match self {
+            BatchOperation::Create => "create",
+            BatchOperation::Update => "update",
+        }
+    }
+}
+
+/// Everything a debt batch writes, saved in a single transaction.
+#[derive(Debug, Clone, Default)]
+pub struct DebtBatch {
+    pub new_debts: Vec<Debt>,
+    pub installments: Vec<Installment>,
+    pub updated_debts: Vec<Debt>,
+    pub new_invoices: Vec<Invoice>,
+    pub updated_invoices: Vec<Invoice>,
+}
+
+impl DebtBatch {
+    pub fn is_empty(&self) -> bool {
+        self.new_debts.is_empty() && self.updated_debts.is_empty()
+    }
+
+    /// Invoice of the billing cycle, when an earlier item already charged it.
+    pub fn cycle_invoice(
+        &self,
+        instrument_id: Uuid,
+        reference_date: NaiveDate,
+    ) -> Option<&Invoice> {
+        self.new_invoices
+            .iter()
+            .chain(&self.updated_invoices)
+            .find(|invoice| {
+                invoice.financial_instrument_id() == &Some(instrument_id)
+                    && invoice.reference_date() == &reference_date
+            })
+    }
+
+    /// What the batch's new debts charge on the invoices of the card, not
+    /// saved yet and so unknown to its credit limit usage.
+    pub fn card_amount(&self, instrument_id: Uuid) -> Decimal {
+        let charged: HashSet<&Uuid> = self
+            .new_invoices
+            .iter()
+            .chain(&self.updated_invoices)
+            .filter(|invoice| invoice.financial_instrument_id() == &Some(instrument_id))
+            .flat_map(|invoice| invoice.related_debt_ids())
+            .collect();
+
+        self.new_debts
+            .iter()
+            .filter(|debt| charged.contains(debt.id()))
+            .map(|debt| *debt.remaining_amount())
+            .sum()
+    }
+
+    /// Adds what another item prepared. Its invoices replace the batch's
+    /// copies of the same invoice, so every debt charged on it stays linked.
+    pub fn merge(&mut self, other: DebtBatch) {
+        self.new_debts.extend(other.new_debts);
+        self.installments.extend(other.installments);
+        self.updated_debts.extend(other.updated_debts);
+
+        for invoice in other.new_invoices {
+            replace_or_push(&mut self.new_invoices, invoice);
+        }
+        for invoice in other.updated_invoices {
+            if let Some(existing) = self
+                .new_invoices
+                .iter_mut()
+                .find(|existing| existing.id() == invoice.id())
+            {
+                *existing = invoice;
+            } else {
+                replace_or_push(&mut self.updated_invoices, invoice);
+            }
+        }
+    }
+
+    /// Months whose budgets the batch may have changed.
+    pub fn budget_months(&self) -> Vec<NaiveDate> {
+        self.new_debts
+            .iter()
+            .chain(&self.updated_debts)
+            .filter(|debt| !debt.has_installments())
+            .map(|debt| *debt.due_date())
+            .chain(self.installments.iter().map(|i| *i.due_date()))
+            .collect()
+    }
+}
+
+fn replace_or_push(invoices: &mut Vec<Invoice>, invoice: Invoice) {
+    match invoices
+        .iter_mut()
+        .find(|existing| existing.id() == invoice.id())
+    {
+        Some(existing) => *existing = invoice,
+        None => invoices.push(invoice),
+    }
+}
+
+/// What a best-effort batch saved, and the error of each debt that failed to.
+#[derive(Debug, Default)]
+pub struct SavedDebtBatch {
+    pub debts: Vec<Debt>,
+    pub failures: HashMap<Uuid, Box<HttpError>>,
+}
+
+/// Outcome of one item of the batch. `index` is the item's position in its
+/// request field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtBatchItem {
+    pub operation: BatchOperation,
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debt: Option<RegisteredDebt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ProblemDetails>,
+}
+
+impl DebtBatchItem {
+    /// Failed items point at themselves through the problem's `instance`,
+    /// like `/create/3`.
+    pub fn new(
+        operation: BatchOperation,
+        index: usize,
+        outcome: HttpResult<RegisteredDebt>,
+    ) -> Self {
+        match outcome {
+            Ok(debt) => Self {
+                operation,
+                index,
+                debt: Some(debt),
+                error: None,
+            },
+            Err(err) => {
+                let mut problem = err.to_problem_details();
+                problem.instance = Some(format!("/{}/{}", operation.field(), index));
+
+                Self {
+                    operation,
+                    index,
+                    debt: None,
+                    error: Some(problem),
+                }
+            }
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtBatchResult {
+    pub mode: BatchMode,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<DebtBatchItem>,
+}
+
+impl DebtBatchResult {
+    pub fn new(mode: BatchMode, items: Vec<DebtBatchItem>) -> Self {
+        let failed = items.iter().filter(|item| item.is_failed()).count();
+
+        Self {
+            mode,
+            succeeded: items.len() - failed,
+            failed,
+            items,
+        }
+    }
+
+    /// Replaces the items' debts with their saved copies, which carry the
+    /// fields filled in by the database.
+    pub fn with_saved_debts(mut self, saved: Vec<Debt>) -> Self {
+        let mut saved: HashMap<Uuid, Debt> =
+            saved.into_iter().map(|debt| (*debt.id(), debt)).collect();
+
+        for registered in self.items.iter_mut().filter_map(|item| item.debt.as_mut()) {
+            if let Some(debt) = saved.remove(registered.debt.id()) {
+                registered.debt = debt;
+            }
+        }
+
+        self
+    }
+
+    /// Turns the items whose debt failed to save into failed items.
+    pub fn with_failed_debts(mut self, mut failures: HashMap<Uuid, Box<HttpError>>) -> Self {
+        for item in self.items.iter_mut() {
+            let Some(err) = item
+                .debt
+                .as_ref()
+                .and_then(|registered| failures.remove(registered.debt.id()))
+            else {
+                continue;
+            };
+
+            *item = DebtBatchItem::new(item.operation, item.index, Err(err));
+        }
+
+        Self::new(self.mode, self.items)
+    }
+
+    /// In all-or-nothing mode a single failed item rejects the whole batch,
+    /// with the problem of each failed item.
+    pub fn ensure_applicable(&self) -> HttpResult<()> {
+        if self.mode == BatchMode::BestEffort || self.failed == 0 {
+            return Ok(());
+        }
+
+        let problems: Vec<&ProblemDetails> = self
+            .items
+            .iter()
+            .filter_map(|item| item.error.as_ref())
+            .collect();
+
+        Err(Box::new(HttpError::unprocessable(
+            serde_json::to_value(problems).unwrap_or_default(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::domain::financial_instrument::{
+        configuration::InstrumentConfiguration, FinancialInstrument, FinancialInstrumentType,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn debt(client_id: Uuid) -> RegisteredDebt {
+        RegisteredDebt {
+            debt: Debt::new(
+                client_id,
+                "Mercado".to_string(),
+                Decimal::from(100),
+                None,
+                None,
+                date(10, 10),
+                None,
+                None,
+                None,
+                None,
+            ),
+            credit_limit_warning: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_every_debt_linked_to_a_shared_invoice() {
+        let client_id = Uuid::new_v4();
+        let card = FinancialInstrument::new(
+            client_id,
+            "Nubank".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::CreditCard,
+            InstrumentConfiguration {
+                default_due_date: Some(10),
+                closing_day: Some(3),
+                ..Default::default()
+            },
+        );
+        let cycle = card.billing_cycle(date(10, 20)).unwrap();
+        let (first_debt, second_debt) = (debt(client_id).debt, debt(client_id).debt);
+        let (first, second) = (*first_debt.id(), *second_debt.id());
+
+        let mut batch = DebtBatch::default();
+        let mut invoice = Invoice::from_billing_cycle(&card, &cycle);
+        invoice.link_debt(first).unwrap();
+        batch.merge(DebtBatch {
+            new_debts: vec![first_debt],
+            new_invoices: vec![invoice],
+            ..Default::default()
+        });
+
+        let mut invoice = batch
+            .cycle_invoice(*card.id(), cycle.reference_date)
+            .cloned()
+            .unwrap();
+        invoice.link_debt(second).unwrap();
+        batch.merge(DebtBatch {
+            new_debts: vec![second_debt],
+            updated_invoices: vec![invoice],
+            ..Default::default()
+        });
+
+        assert_eq!(batch.new_invoices.len(), 1);
+        assert!(batch.updated_invoices.is_empty());
+        assert!(batch.new_invoices[0].related_debt_ids().contains(&first));
+        assert!(batch.new_invoices[0].related_debt_ids().contains(&second));
+        assert_eq!(batch.card_amount(*card.id()), Decimal::from(200));
+        assert_eq!(batch.card_amount(Uuid::new_v4()), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_failed_items_reject_an_all_or_nothing_batch() {
+        let client_id = Uuid::new_v4();
+        let items = vec![
+            DebtBatchItem::new(BatchOperation::Create, 0, Ok(debt(client_id))),
+            DebtBatchItem::new(
+                BatchOperation::Update,
+                2,
+                Err(Box::new(HttpError::not_found("debt", "x"))),
+            ),
+        ];
+
+        let best_effort = DebtBatchResult::new(BatchMode::BestEffort, items.clone());
+        assert_eq!((best_effort.succeeded, best_effort.failed), (1, 1));
+        assert!(best_effort.ensure_applicable().is_ok());
+
+        let problem = best_effort.items[1].error.as_ref().unwrap();
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.instance.as_deref(), Some("/update/2"));
+
+        let err = DebtBatchResult::new(BatchMode::AllOrNothing, items)
+            .ensure_applicable()
+            .unwrap_err();
+        assert_eq!(err.status_u16(), 422);
+        assert_eq!(err.details.unwrap().as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_debts_failing_to_save_fail_their_item() {
+        let client_id = Uuid::new_v4();
+        let (saved, failed) = (debt(client_id), debt(client_id));
+        let failed_id = *failed.debt.id();
+        let items = vec![
+            DebtBatchItem::new(BatchOperation::Create, 0, Ok(saved)),
+            DebtBatchItem::new(BatchOperation::Create, 1, Ok(failed)),
+        ];
+
+        let result =
+            DebtBatchResult::new(BatchMode::BestEffort, items).with_failed_debts(HashMap::from([
+                (failed_id, Box::new(HttpError::bad_request("duplicate"))),
+            ]));
+
+        assert_eq!((result.succeeded, result.failed), (1, 1));
+        assert!(result.items[1].debt.is_none());
+        let problem = result.items[1].error.as_ref().unwrap();
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.instance.as_deref(), Some("/create/1"));
+    }
+}
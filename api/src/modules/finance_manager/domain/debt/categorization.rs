@@ -0,0 +1,528 @@
+use chrono::{DateTime, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::debt::{Debt, DebtCategory, ExpenseType},
+    handler::debt::categorization::use_cases::{CreateRuleRequest, UpdateRuleRequest},
+};
+
+/// Client rule filling the category, expense type and tags of new debts
+/// registered without a category, like "description contains uber →
+/// TRANSPORT".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorizationRule {
+    id: Uuid,
+    client_id: Uuid,
+    name: String,
+    /// Rules are tried in ascending priority; the first match applies.
+    priority: i32,
+    active: bool,
+    conditions: RuleConditions,
+    actions: RuleActions,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// Every condition set must hold for the rule to match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleConditions {
+    /// Case-insensitive.
+    pub description_contains: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    /// Card or account the purchase was made with.
+    pub financial_instrument_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleActions {
+    pub category: Option<DebtCategory>,
+    pub expense_type: Option<ExpenseType>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Debt as seen by the rule conditions.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleInput<'a> {
+    pub description: &'a str,
+    pub amount: Decimal,
+    pub financial_instrument_id: Option<Uuid>,
+}
+
+/// Fields of a new debt the rules may fill; `None` when not given.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Categorization {
+    pub category: Option<DebtCategory>,
+    pub expense_type: Option<ExpenseType>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Categorization {
+    /// Fills the missing fields with the first matching active rule. Debts
+    /// given a category are left as they are, the rules only categorize the
+    /// ones without it. Returns the id of the rule applied.
+    pub fn apply_rules(&mut self, rules: &[CategorizationRule], input: &RuleInput) -> Option<Uuid> {
+        if self.category.is_some() {
+            return None;
+        }
+
+        let mut rules: Vec<&CategorizationRule> = rules.iter().filter(|rule| rule.active).collect();
+        rules.sort_by_key(|rule| rule.order());
+
+        let rule = rules.into_iter().find(|rule| rule.matches(input))?;
+        let actions = rule.actions.clone();
+
+        self.category = self.category.take().or(actions.category);
+        self.expense_type = self.expense_type.take().or(actions.expense_type);
+        self.tags = self.tags.take().or(actions.tags);
+
+        Some(rule.id)
+    }
+}
+
+/// Category, expense type and tags of a debt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtLabels {
+    pub category: DebtCategory,
+    pub expense_type: ExpenseType,
+    pub tags: Vec<String>,
+}
+
+impl From<&Debt> for DebtLabels {
+    fn from(debt: &Debt) -> Self {
+        Self {
+            category: debt.category().clone(),
+            expense_type: debt.expense_type().clone(),
+            tags: debt.tags().clone(),
+        }
+    }
+}
+
+/// Existing debt a rule changes, or would change on a dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecategorizedDebt {
+    pub debt_id: Uuid,
+    pub description: String,
+    pub total_amount: Decimal,
+    pub before: DebtLabels,
+    pub after: DebtLabels,
+}
+
+impl CategorizationRule {
+    pub fn from_request(client_id: Uuid, request: CreateRuleRequest) -> HttpResult<Self> {
+        let rule = Self {
+            id: Uuid::new_v4(),
+            client_id,
+            name: request.name.trim().to_string(),
+            priority: request.priority.unwrap_or_default(),
+            active: request.active.unwrap_or(true),
+            conditions: request.conditions,
+            actions: request.actions,
+            created_at: Utc::now(),
+            updated_at: None,
+        };
+        rule.validate()?;
+
+        Ok(rule)
+    }
+
+    pub fn update(&mut self, request: UpdateRuleRequest) -> HttpResult<()> {
+        if let Some(name) = request.name {
+            self.name = name.trim().to_string();
+        }
+        if let Some(priority) = request.priority {
+            self.priority = priority;
+        }
+        if let Some(active) = request.active {
+            self.active = active;
+        }
+        if let Some(conditions) = request.conditions {
+            self.conditions = conditions;
+        }
+        if let Some(actions) = request.actions {
+            self.actions = actions;
+        }
+        self.validate()?;
+
+        self.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to manage this rule",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, input: &RuleInput) -> bool {
+        let conditions = &self.conditions;
+
+        conditions.description_contains.as_ref().is_none_or(|text| {
+            input
+                .description
+                .to_lowercase()
+                .contains(&text.to_lowercase())
+        }) && conditions.min_amount.is_none_or(|min| input.amount >= min)
+            && conditions.max_amount.is_none_or(|max| input.amount <= max)
+            && conditions
+                .financial_instrument_id
+                .is_none_or(|id| input.financial_instrument_id == Some(id))
+    }
+
+    /// Applies the rule's actions to an existing uncategorized debt when it
+    /// matches, replacing its category and expense type and adding the
+    /// rule's tags. Debts an active rule of `rules` tried before this one
+    /// also matches are left to that rule, like new debts are. Returns `None`
+    /// when nothing would change.
+    pub fn recategorize(
+        &self,
+        debt: &mut Debt,
+        financial_instrument_id: Option<Uuid>,
+        rules: &[CategorizationRule],
+    ) -> Option<RecategorizedDebt> {
+        if !debt.category().is_unknown() {
+            return None;
+        }
+
+        let input = RuleInput {
+            description: debt.description(),
+            amount: *debt.total_amount(),
+            financial_instrument_id,
+        };
+        let claimed_before = rules.iter().any(|rule| {
+            rule.active && rule.id != self.id && rule.order() < self.order() && rule.matches(&input)
+        });
+        if claimed_before || !self.matches(&input) {
+            return None;
+        }
+
+        let before = DebtLabels::from(&*debt);
+        let mut after = before.clone();
+        if let Some(category) = &self.actions.category {
+            after.category = category.clone();
+        }
+        if let Some(expense_type) = &self.actions.expense_type {
+            after.expense_type = expense_type.clone();
+        }
+        for tag in self.actions.tags.iter().flatten() {
+            if !after.tags.contains(tag) {
+                after.tags.push(tag.clone());
+            }
+        }
+
+        if after == before {
+            return None;
+        }
+
+        debt.set_category(after.category.clone());
+        debt.set_expense_type(after.expense_type.clone());
+        debt.set_tags(after.tags.clone());
+
+        Some(RecategorizedDebt {
+            debt_id: *debt.id(),
+            description: debt.description().clone(),
+            total_amount: *debt.total_amount(),
+            before,
+            after,
+        })
+    }
+
+    /// Position of the rule in the order rules are tried.
+    fn order(&self) -> (i32, DateTime<Utc>) {
+        (self.priority, self.created_at)
+    }
+
+    fn validate(&self) -> HttpResult<()> {
+        if self.name.is_empty() {
+            return Err(Box::new(HttpError::bad_request(
+                "Rule name cannot be empty",
+            )));
+        }
+
+        let conditions = &self.conditions;
+        if conditions == &RuleConditions::default()
+            || conditions
+                .description_contains
+                .as_ref()
+                .is_some_and(|text| text.trim().is_empty())
+        {
+            return Err(Box::new(HttpError::bad_request(
+                "A rule needs at least one condition",
+            )));
+        }
+        if let (Some(min), Some(max)) = (conditions.min_amount, conditions.max_amount) {
+            if min > max {
+                return Err(Box::new(HttpError::bad_request(
+                    "Rule minimum amount must not be greater than its maximum",
+                )));
+            }
+        }
+
+        if self.actions == RuleActions::default() {
+            return Err(Box::new(HttpError::bad_request(
+                "A rule needs at least one action",
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+getters! {
+    CategorizationRule {
+        id: Uuid,
+        client_id: Uuid,
+        name: String,
+        priority: i32,
+        active: bool,
+        conditions: RuleConditions,
+        actions: RuleActions,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for CategorizationRule {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            name: row.get("name"),
+            priority: row.get("priority"),
+            active: row.get("active"),
+            conditions: row.get::<Json<RuleConditions>, _>("conditions").0,
+            actions: row.get::<Json<RuleActions>, _>("actions").0,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn rule(priority: i32, conditions: RuleConditions, actions: RuleActions) -> CategorizationRule {
+        CategorizationRule::from_request(
+            Uuid::new_v4(),
+            CreateRuleRequest {
+                name: "Regra".to_string(),
+                priority: Some(priority),
+                active: None,
+                conditions,
+                actions,
+            },
+        )
+        .unwrap()
+    }
+
+    fn uber() -> CategorizationRule {
+        rule(
+            10,
+            RuleConditions {
+                description_contains: Some("uber".to_string()),
+                ..Default::default()
+            },
+            RuleActions {
+                category: Some(DebtCategory::new("TRANSPORT")),
+                tags: Some(vec!["ride".to_string()]),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn rule_with_priority(priority: i32, conditions: RuleConditions) -> CategorizationRule {
+        rule(
+            priority,
+            conditions,
+            RuleActions {
+                category: Some(DebtCategory::new("FOOD")),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_first_matching_rule_fills_missing_fields() {
+        let card = Uuid::new_v4();
+        let big_card_purchase = rule(
+            1,
+            RuleConditions {
+                min_amount: Some(Decimal::from(1000)),
+                financial_instrument_id: Some(card),
+                ..Default::default()
+            },
+            RuleActions {
+                expense_type: Some(ExpenseType::Fixed),
+                ..Default::default()
+            },
+        );
+        let rules = vec![uber(), big_card_purchase.clone()];
+
+        let mut categorization = Categorization::default();
+        let applied = categorization.apply_rules(
+            &rules,
+            &RuleInput {
+                description: "Uber Viagem",
+                amount: Decimal::from(25),
+                financial_instrument_id: Some(card),
+            },
+        );
+        assert_eq!(applied, Some(*rules[0].id()));
+        assert_eq!(
+            categorization.category,
+            Some(DebtCategory::new("TRANSPORT"))
+        );
+        assert_eq!(categorization.tags, Some(vec!["ride".to_string()]));
+
+        let input = RuleInput {
+            description: "Uber Black",
+            amount: Decimal::from(1500),
+            financial_instrument_id: Some(card),
+        };
+        let mut categorization = Categorization {
+            tags: Some(vec!["viagem".to_string()]),
+            ..Default::default()
+        };
+        let applied = categorization.apply_rules(&rules, &input);
+        assert_eq!(applied, Some(*big_card_purchase.id()));
+        assert_eq!(categorization.category, None);
+        assert_eq!(categorization.expense_type, Some(ExpenseType::Fixed));
+        assert_eq!(categorization.tags, Some(vec!["viagem".to_string()]));
+
+        // Debts given a category are not touched by the rules
+        let mut categorization = Categorization {
+            category: Some(DebtCategory::new("HOME")),
+            ..Default::default()
+        };
+        assert_eq!(categorization.apply_rules(&rules, &input), None);
+        assert_eq!(categorization.expense_type, None);
+        assert_eq!(categorization.tags, None);
+    }
+
+    #[test]
+    fn test_rule_needs_a_condition_and_an_action() {
+        let request = |conditions: RuleConditions, actions: RuleActions| CreateRuleRequest {
+            name: "Regra".to_string(),
+            priority: None,
+            active: None,
+            conditions,
+            actions,
+        };
+        let actions = RuleActions {
+            expense_type: Some(ExpenseType::Fixed),
+            ..Default::default()
+        };
+        let conditions = RuleConditions {
+            min_amount: Some(Decimal::from(10)),
+            ..Default::default()
+        };
+
+        assert!(CategorizationRule::from_request(
+            Uuid::new_v4(),
+            request(Default::default(), actions)
+        )
+        .is_err());
+        assert!(CategorizationRule::from_request(
+            Uuid::new_v4(),
+            request(conditions, Default::default())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_recategorize_reports_only_changed_debts() {
+        let rule = uber();
+        let rules = vec![rule.clone()];
+        let new_debt = |description: &str, category: Option<&str>| {
+            Debt::new(
+                Uuid::new_v4(),
+                description.to_string(),
+                Decimal::from(30),
+                None,
+                None,
+                NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+                category.map(DebtCategory::new),
+                None,
+                Some(vec!["trabalho".to_string()]),
+                None,
+            )
+        };
+
+        let mut ride = new_debt("UBER *TRIP", None);
+        let change = rule.recategorize(&mut ride, None, &rules).unwrap();
+        assert_eq!(change.before.category, DebtCategory::default());
+        assert_eq!(change.after.tags, vec!["trabalho", "ride"]);
+        assert_eq!(ride.category(), &DebtCategory::new("TRANSPORT"));
+
+        assert!(rule.recategorize(&mut ride, None, &rules).is_none());
+        assert!(rule
+            .recategorize(&mut new_debt("Mercado", None), None, &rules)
+            .is_none());
+        // Categorized debts are kept
+        assert!(rule
+            .recategorize(&mut new_debt("Uber Eats", Some("FOOD")), None, &rules)
+            .is_none());
+    }
+
+    #[test]
+    fn test_recategorize_leaves_debts_to_rules_tried_before() {
+        let rule = uber();
+        let eats = rule_with_priority(
+            1,
+            RuleConditions {
+                description_contains: Some("eats".to_string()),
+                ..Default::default()
+            },
+        );
+        let later = rule_with_priority(
+            20,
+            RuleConditions {
+                description_contains: Some("uber".to_string()),
+                ..Default::default()
+            },
+        );
+        let rules = vec![eats.clone(), rule.clone(), later];
+        let new_debt = |description: &str| {
+            Debt::new(
+                Uuid::new_v4(),
+                description.to_string(),
+                Decimal::from(30),
+                None,
+                None,
+                NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        assert!(rule
+            .recategorize(&mut new_debt("Uber Eats"), None, &rules)
+            .is_none());
+        assert!(rule
+            .recategorize(&mut new_debt("Uber Trip"), None, &rules)
+            .is_some());
+        assert!(eats
+            .recategorize(&mut new_debt("Uber Eats"), None, &rules)
+            .is_some());
+    }
+}
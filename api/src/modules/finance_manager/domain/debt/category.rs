@@ -1,33 +1,377 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use http_error::{HttpError, HttpResult};
 use serde::{Deserialize, Serialize};
-use util::{from_row_constructor, getters};
+use util::getters;
 use uuid::Uuid;
 
+use crate::modules::finance_manager::{
+    domain::debt::DebtCategory,
+    handler::debt::category::use_cases::{CreateCategoryRequest, UpdateCategoryRequest},
+};
+
+/// Categories every client starts with, the former fixed ones, with their
+/// colour and icon.
+pub const DEFAULT_CATEGORIES: [(&str, &str, &str); 9] = [
+    (DebtCategory::UNKNOWN, "#9E9E9E", "help-circle"),
+    ("HOME", "#8D6E63", "home"),
+    ("TRANSPORT", "#42A5F5", "car"),
+    ("HEALTH", "#EF5350", "heart-pulse"),
+    ("FOOD", "#FFA726", "utensils"),
+    ("LIFESTYLE", "#AB47BC", "sparkles"),
+    ("EDUCATION", "#5C6BC0", "graduation-cap"),
+    ("GOALS", "#66BB6A", "target"),
+    ("PERSONAL", "#26A69A", "user"),
+];
+
+const MAX_ICON_LENGTH: usize = 50;
+
+/// Debt category of a client. Categories may be nested under a parent, and
+/// filtering by a category also matches its subcategories.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DebtCategory {
+pub struct Category {
     id: Uuid,
-    name: String,
+    client_id: Uuid,
+    parent_id: Option<Uuid>,
+    name: DebtCategory,
+    /// Hex colour, like `#FFA726`.
+    color: Option<String>,
+    icon: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
 }
 
-impl DebtCategory {
-    pub fn new(name: String) -> Self {
-        Self {
+impl Category {
+    /// `categories` are the client's current ones, used to check the name
+    /// and the parent.
+    pub fn from_request(
+        client_id: Uuid,
+        request: CreateCategoryRequest,
+        categories: &[Category],
+    ) -> HttpResult<Self> {
+        let name = validate_name(&request.name, None, categories)?;
+        validate_color(request.color.as_deref())?;
+        validate_icon(request.icon.as_deref())?;
+        if let Some(parent_id) = request.parent_id {
+            find(categories, parent_id)?;
+        }
+
+        Ok(Self {
             id: Uuid::new_v4(),
-            name: name.to_uppercase(),
+            client_id,
+            parent_id: request.parent_id,
+            name,
+            color: request.color,
+            icon: request.icon,
+            created_at: Utc::now(),
+            updated_at: None,
+        })
+    }
+
+    pub fn defaults(client_id: Uuid) -> Vec<Self> {
+        DEFAULT_CATEGORIES
+            .iter()
+            .map(|(name, color, icon)| Self {
+                id: Uuid::new_v4(),
+                client_id,
+                parent_id: None,
+                name: DebtCategory::new(name),
+                color: Some(color.to_string()),
+                icon: Some(icon.to_string()),
+                created_at: Utc::now(),
+                updated_at: None,
+            })
+            .collect()
+    }
+
+    /// Returns the previous name when the category was renamed, so debts,
+    /// recurrences and budgets can follow it.
+    pub fn update(
+        &mut self,
+        request: UpdateCategoryRequest,
+        categories: &[Category],
+    ) -> HttpResult<Option<DebtCategory>> {
+        let mut previous_name = None;
+
+        if let Some(name) = request.name {
+            let name = validate_name(&name, Some(self.id), categories)?;
+            if name != self.name {
+                if self.name.is_unknown() {
+                    return Err(Box::new(HttpError::bad_request(
+                        "The default category cannot be renamed",
+                    )));
+                }
+                previous_name = Some(std::mem::replace(&mut self.name, name));
+            }
+        }
+        if let Some(parent_id) = request.parent_id {
+            if let Some(parent_id) = parent_id {
+                self.ensure_not_ancestor_of(parent_id, categories)?;
+            }
+            self.parent_id = parent_id;
+        }
+        if let Some(color) = request.color {
+            validate_color(color.as_deref())?;
+            self.color = color;
+        }
+        if let Some(icon) = request.icon {
+            validate_icon(icon.as_deref())?;
+            self.icon = icon;
         }
+
+        self.updated_at = Some(Utc::now());
+        Ok(previous_name)
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to manage this category",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects moving the category under itself or one of its descendants.
+    fn ensure_not_ancestor_of(&self, parent_id: Uuid, categories: &[Category]) -> HttpResult<()> {
+        let mut current = Some(find(categories, parent_id)?);
+        let mut visited = HashSet::new();
+
+        while let Some(category) = current {
+            if category.id == self.id {
+                return Err(Box::new(HttpError::bad_request(
+                    "A category cannot be nested under itself or its subcategories",
+                )));
+            }
+            if !visited.insert(category.id) {
+                break;
+            }
+
+            current = category
+                .parent_id
+                .and_then(|id| categories.iter().find(|c| c.id == id));
+        }
+
+        Ok(())
     }
 }
 
 getters! {
-    DebtCategory {
+    Category {
         id: Uuid,
-        name: String,
+        client_id: Uuid,
+        parent_id: Option<Uuid>,
+        name: DebtCategory,
+        color: Option<String>,
+        icon: Option<String>,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
     }
 }
 
-from_row_constructor! {
-    DebtCategory {
-        id: Uuid,
-        name: String,
+impl From<&sqlx::postgres::PgRow> for Category {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            parent_id: row.get("parent_id"),
+            name: DebtCategory::from(row.get::<String, _>("name")),
+            color: row.get("color"),
+            icon: row.get("icon"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+/// The given category names plus the names of all their subcategories, so a
+/// filter by a parent category also matches its children.
+pub fn with_subcategories(categories: &[Category], names: &[String]) -> Vec<String> {
+    let mut matched: Vec<String> = names
+        .iter()
+        .map(|name| DebtCategory::new(name).into())
+        .collect();
+    let mut ids: HashSet<Uuid> = categories
+        .iter()
+        .filter(|category| matched.iter().any(|name| name == category.name.as_str()))
+        .map(|category| category.id)
+        .collect();
+
+    loop {
+        let children: Vec<&Category> = categories
+            .iter()
+            .filter(|category| !ids.contains(&category.id))
+            .filter(|category| category.parent_id.is_some_and(|id| ids.contains(&id)))
+            .collect();
+        if children.is_empty() {
+            return matched;
+        }
+
+        for child in children {
+            ids.insert(child.id);
+            matched.push(child.name.clone().into());
+        }
+    }
+}
+
+/// Rejects names without a category among the client's `categories`. The
+/// unknown category always exists.
+pub fn ensure_exists(categories: &[Category], name: &DebtCategory) -> HttpResult<()> {
+    if name.is_unknown() || categories.iter().any(|category| &category.name == name) {
+        return Ok(());
+    }
+
+    Err(Box::new(HttpError::bad_request(format!(
+        "Category {} does not exist",
+        name.as_str()
+    ))))
+}
+
+fn find(categories: &[Category], id: Uuid) -> HttpResult<&Category> {
+    categories
+        .iter()
+        .find(|category| category.id == id)
+        .ok_or_else(|| Box::new(HttpError::not_found("category", id)))
+}
+
+fn validate_name(
+    name: &str,
+    id: Option<Uuid>,
+    categories: &[Category],
+) -> HttpResult<DebtCategory> {
+    let name = DebtCategory::new(name);
+    if name.as_str().is_empty() {
+        return Err(Box::new(HttpError::bad_request(
+            "Category name cannot be empty",
+        )));
+    }
+
+    if categories
+        .iter()
+        .any(|category| category.name == name && Some(category.id) != id)
+    {
+        return Err(Box::new(HttpError::conflict(format!(
+            "Category {} already exists",
+            name.as_str()
+        ))));
+    }
+
+    Ok(name)
+}
+
+fn validate_color(color: Option<&str>) -> HttpResult<()> {
+    let Some(color) = color else {
+        return Ok(());
+    };
+
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(Box::new(HttpError::bad_request(
+            "Category colour must be a hex colour like #FFA726",
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_icon(icon: Option<&str>) -> HttpResult<()> {
+    if icon.is_some_and(|icon| icon.trim().is_empty() || icon.len() > MAX_ICON_LENGTH) {
+        return Err(Box::new(HttpError::bad_request(format!(
+            "Category icon must have between 1 and {MAX_ICON_LENGTH} characters"
+        ))));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(
+        name: &str,
+        parent_id: Option<Uuid>,
+        categories: &[Category],
+    ) -> HttpResult<Category> {
+        Category::from_request(
+            Uuid::new_v4(),
+            CreateCategoryRequest {
+                name: name.to_string(),
+                parent_id,
+                color: None,
+                icon: None,
+            },
+            categories,
+        )
+    }
+
+    #[test]
+    fn test_category_names_are_unique_and_uppercase() {
+        let mut categories = Category::defaults(Uuid::new_v4());
+        let market = create(" Mercado ", None, &categories).unwrap();
+        assert_eq!(market.name().as_str(), "MERCADO");
+        categories.push(market);
+
+        assert!(create("mercado", None, &categories).is_err());
+        assert!(create("food", None, &categories).is_err());
+        assert!(create("  ", None, &categories).is_err());
+    }
+
+    #[test]
+    fn test_category_cannot_be_nested_under_its_subcategories() {
+        let mut categories = Vec::new();
+        let home = create("Casa", None, &categories).unwrap();
+        categories.push(home.clone());
+        let rent = create("Aluguel", Some(*home.id()), &categories).unwrap();
+        categories.push(rent.clone());
+
+        let mut moved = home.clone();
+        let err = moved
+            .update(
+                UpdateCategoryRequest {
+                    parent_id: Some(Some(*rent.id())),
+                    ..Default::default()
+                },
+                &categories,
+            )
+            .unwrap_err();
+        assert_eq!(err.status_u16(), 400);
+
+        let mut renamed = rent.clone();
+        let previous = renamed
+            .update(
+                UpdateCategoryRequest {
+                    name: Some("moradia".to_string()),
+                    parent_id: Some(None),
+                    ..Default::default()
+                },
+                &categories,
+            )
+            .unwrap();
+        assert_eq!(previous, Some(DebtCategory::new("ALUGUEL")));
+        assert_eq!(renamed.parent_id(), &None);
+    }
+
+    #[test]
+    fn test_filter_names_include_subcategories() {
+        let mut categories = Category::defaults(Uuid::new_v4());
+        let home = categories[1].clone();
+        let rent = create("Aluguel", Some(*home.id()), &categories).unwrap();
+        categories.push(rent.clone());
+        let deposit = create("Caução", Some(*rent.id()), &categories).unwrap();
+        categories.push(deposit);
+
+        let names = with_subcategories(&categories, &["home".to_string()]);
+        assert_eq!(names, vec!["HOME", "ALUGUEL", "CAUÇÃO"]);
+
+        let names = with_subcategories(&categories, &["FOOD".to_string()]);
+        assert_eq!(names, vec!["FOOD"]);
     }
 }
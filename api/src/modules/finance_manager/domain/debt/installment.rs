@@ -7,7 +7,9 @@ use uuid::Uuid;
 
 use util::DeletedBy;
 
-use crate::modules::finance_manager::domain::payment::Payment;
+use crate::modules::finance_manager::domain::{
+    debt::amortization::ScheduleEntry, payment::Payment,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,8 +18,21 @@ pub struct Installment {
     installment_id: i32,
     due_date: NaiveDate,
     amount: Decimal,
+    /// Amortized principal of the debt within `amount`.
+    #[serde(default)]
+    principal_amount: Decimal,
+    /// Financing interest within `amount`.
+    #[serde(default)]
+    interest_amount: Decimal,
+    #[serde(default)]
+    paid_amount: Decimal,
     is_paid: bool,
+    /// Payment that completed the installment.
     payment_id: Option<Uuid>,
+    /// Renegotiation that replaced the installment with a new plan, or
+    /// carry-over debt now billing what was left unpaid on it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    superseded_by: Option<Uuid>,
     created_at: DateTime<Utc>,
     updated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,52 +46,243 @@ impl Installment {
             installment_id,
             due_date,
             amount,
+            principal_amount: amount,
+            interest_amount: Decimal::ZERO,
+            paid_amount: Decimal::ZERO,
             is_paid: false,
             payment_id: None,
+            superseded_by: None,
             created_at: Utc::now(),
             updated_at: None,
             deleted_by: None,
         }
     }
 
-    pub fn process_payment(&mut self, payment: &Payment) -> HttpResult<()> {
-        self.validate_payment(payment)?;
+    /// Creates the installment of a plan entry, keeping its principal and
+    /// interest components.
+    pub fn from_schedule(debt_id: Uuid, entry: &ScheduleEntry) -> Self {
+        Self {
+            principal_amount: entry.principal,
+            interest_amount: entry.interest,
+            ..Self::new(debt_id, entry.installment_id, entry.due_date, entry.amount)
+        }
+    }
 
-        self.set_as_paid(*payment.id());
+    pub fn remaining_amount(&self) -> Decimal {
+        (self.amount - self.paid_amount).max(Decimal::ZERO)
+    }
+
+    /// Unpaid and still part of the debt's current plan.
+    pub fn is_open(&self) -> bool {
+        !self.is_paid && self.superseded_by.is_none()
+    }
+
+    /// Takes the installment out of the debt's plan, keeping it for history.
+    pub fn supersede(&mut self, superseded_by: Uuid) {
+        self.superseded_by = Some(superseded_by);
         self.updated_at = Some(Utc::now());
+    }
 
-        Ok(())
+    /// Open installments a payment goes to: the targeted ones when the
+    /// payment names them, otherwise every open installment, oldest first.
+    pub fn payable(installments: &[Self], targets: Option<&[i32]>) -> HttpResult<Vec<i32>> {
+        let mut unpaid: Vec<&Self> = installments.iter().filter(|i| i.is_open()).collect();
+        unpaid.sort_by_key(|installment| installment.installment_id);
+
+        let Some(targets) = targets else {
+            return Ok(unpaid.iter().map(|i| i.installment_id).collect());
+        };
+
+        if targets.is_empty() {
+            return Err(Box::new(HttpError::bad_request(
+                "At least one installment must be informed",
+            )));
+        }
+
+        for target in targets {
+            if !unpaid.iter().any(|i| i.installment_id == *target) {
+                return Err(Box::new(HttpError::bad_request(format!(
+                    "Installment {} not found or already paid",
+                    target
+                ))));
+            }
+        }
+
+        Ok(unpaid
+            .iter()
+            .map(|i| i.installment_id)
+            .filter(|id| targets.contains(id))
+            .collect())
+    }
+
+    /// Amount still due on the given installments.
+    pub fn amount_due(installments: &[Self], installment_ids: &[i32]) -> Decimal {
+        installments
+            .iter()
+            .filter(|i| installment_ids.contains(&i.installment_id))
+            .map(Self::remaining_amount)
+            .sum()
     }
 
-    pub fn validate_payment(&self, payment: &Payment) -> HttpResult<()> {
-        if *self.is_paid() {
-            return Err(Box::new(HttpError::bad_request("Installment already paid")));
+    /// Checks the payment can be spread over its payable installments. An
+    /// early payment discount is only accepted when the payment settles the
+    /// targeted installments and all of them are due after the payment date.
+    pub fn validate_payment(installments: &[Self], payment: &Payment) -> HttpResult<()> {
+        let payable = Self::payable(installments, payment.installment_ids().as_deref())?;
+        let remaining = Self::amount_due(installments, &payable);
+        let discount = *payment.discount_amount();
+
+        if remaining.is_zero() {
+            return Err(Box::new(HttpError::bad_request(
+                "Installments already paid",
+            )));
+        }
+
+        if *payment.amount() <= Decimal::ZERO {
+            return Err(Box::new(HttpError::bad_request(
+                "Payment amount must be greater than zero",
+            )));
         }
 
-        if *payment.amount() != *self.amount() {
+        if discount < Decimal::ZERO {
             return Err(Box::new(HttpError::bad_request(
-                "Payment amount differs from installment amount",
+                "Early payment discount cannot be negative",
             )));
         }
 
+        if *payment.amount() + discount > remaining {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Payment amount ({:.2}) exceeds remaining installments amount ({:.2})",
+                payment.amount(),
+                remaining
+            ))));
+        }
+
+        if discount > Decimal::ZERO {
+            if payment.installment_ids().is_none() {
+                return Err(Box::new(HttpError::bad_request(
+                    "Early payment discount requires the installments being paid in advance",
+                )));
+            }
+
+            if *payment.amount() + discount != remaining {
+                return Err(Box::new(HttpError::bad_request(
+                    "Early payment discount requires settling the informed installments",
+                )));
+            }
+
+            let not_future = installments.iter().any(|i| {
+                payable.contains(&i.installment_id) && i.due_date <= *payment.payment_date()
+            });
+            if not_future {
+                return Err(Box::new(HttpError::bad_request(
+                    "Early payment discount only applies to future installments",
+                )));
+            }
+        }
+
         Ok(())
     }
 
-    pub fn set_as_paid(&mut self, payment_id: Uuid) {
-        self.is_paid = true;
-        self.payment_id = Some(payment_id);
+    /// Spreads the payment over its payable installments, oldest first: a
+    /// partial payment stays on the current installment and any overpayment
+    /// rolls into the next ones. The early payment discount covers what the
+    /// paid amount leaves open. Returns how much went to each installment.
+    pub fn allocate_payment(
+        installments: &mut [Self],
+        payment: &Payment,
+    ) -> HttpResult<Vec<InstallmentAllocation>> {
+        Self::validate_payment(installments, payment)?;
+        let payable = Self::payable(installments, payment.installment_ids().as_deref())?;
+
+        installments.sort_by_key(|installment| installment.installment_id);
+
+        let mut left = *payment.amount();
+        let mut discount_left = *payment.discount_amount();
+        let mut allocations = Vec::new();
+
+        for installment in installments
+            .iter_mut()
+            .filter(|i| payable.contains(&i.installment_id))
+        {
+            if left.is_zero() && discount_left.is_zero() {
+                break;
+            }
+
+            let applied = left.min(installment.remaining_amount());
+            let discount = discount_left.min(installment.remaining_amount() - applied);
+
+            installment.apply_payment(*payment.id(), applied + discount);
+            allocations.push(InstallmentAllocation::new(
+                *payment.id(),
+                installment,
+                applied,
+                discount,
+            ));
+            left -= applied;
+            discount_left -= discount;
+        }
+
+        Ok(allocations)
+    }
+
+    /// Undoes the allocations of a payment, restoring the paid amount of
+    /// each installment it was spread over.
+    pub fn reverse_allocations(
+        installments: &mut [Self],
+        allocations: &[InstallmentAllocation],
+    ) -> HttpResult<()> {
+        for allocation in allocations {
+            let installment = installments
+                .iter_mut()
+                .find(|i| {
+                    i.debt_id == allocation.debt_id && i.installment_id == allocation.installment_id
+                })
+                .ok_or_else(|| {
+                    Box::new(HttpError::not_found(
+                        "installment",
+                        allocation.installment_id,
+                    ))
+                })?;
+
+            if installment.superseded_by.is_some() {
+                return Err(Box::new(HttpError::bad_request(format!(
+                    "Cannot reverse: installment {} was renegotiated",
+                    installment.installment_id
+                ))));
+            }
+
+            installment.reverse_payment(allocation)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_payment(&mut self, payment_id: Uuid, amount: Decimal) {
+        self.paid_amount += amount;
+
+        if self.paid_amount >= self.amount {
+            self.is_paid = true;
+            self.payment_id = Some(payment_id);
+        }
+
         self.updated_at = Some(Utc::now());
     }
 
-    pub fn reverse_payment(&mut self) -> HttpResult<()> {
-        if !self.is_paid {
-            return Err(Box::new(HttpError::bad_request(
-                "Cannot reverse: installment is not paid",
-            )));
+    fn reverse_payment(&mut self, allocation: &InstallmentAllocation) -> HttpResult<()> {
+        let amount = allocation.amount + allocation.discount_amount;
+        if amount > self.paid_amount {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Cannot reverse: amount ({:.2}) exceeds installment paid amount ({:.2})",
+                amount, self.paid_amount
+            ))));
         }
 
-        self.is_paid = false;
-        self.payment_id = None;
+        self.paid_amount -= amount;
+        self.is_paid = self.paid_amount >= self.amount;
+        if !self.is_paid {
+            self.payment_id = None;
+        }
         self.updated_at = Some(Utc::now());
 
         Ok(())
@@ -85,7 +291,7 @@ impl Installment {
     pub fn get_latest_unpaid(installments: &[Self]) -> Option<&Self> {
         installments
             .iter()
-            .filter(|i| !i.is_paid())
+            .filter(|i| i.is_open())
             .min_by_key(|i| i.installment_id())
     }
 }
@@ -96,8 +302,12 @@ getters!(
         installment_id: i32,
         due_date: NaiveDate,
         amount: Decimal,
+        principal_amount: Decimal,
+        interest_amount: Decimal,
+        paid_amount: Decimal,
         is_paid: bool,
         payment_id: Option<Uuid>,
+        superseded_by: Option<Uuid>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -110,10 +320,229 @@ from_row_constructor! {
         installment_id: i32,
         due_date: NaiveDate,
         amount: Decimal,
+        principal_amount: Decimal,
+        interest_amount: Decimal,
+        paid_amount: Decimal,
         is_paid: bool,
         payment_id: Option<Uuid>,
+        superseded_by: Option<Uuid>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
     }
 }
+
+/// Part of a payment applied to one installment. A payment may be spread over
+/// several installments and an installment may be paid by several payments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallmentAllocation {
+    payment_id: Uuid,
+    debt_id: Uuid,
+    installment_id: i32,
+    amount: Decimal,
+    /// Part of the installment settled by the early payment discount.
+    discount_amount: Decimal,
+    created_at: DateTime<Utc>,
+}
+
+impl InstallmentAllocation {
+    fn new(
+        payment_id: Uuid,
+        installment: &Installment,
+        amount: Decimal,
+        discount_amount: Decimal,
+    ) -> Self {
+        Self {
+            payment_id,
+            debt_id: installment.debt_id,
+            installment_id: installment.installment_id,
+            amount,
+            discount_amount,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Allocation of a payment made before allocations were tracked, when an
+    /// installment could only be paid in full by a single payment.
+    pub fn legacy(payment_id: Uuid, installment: &Installment) -> Self {
+        Self::new(payment_id, installment, installment.amount, Decimal::ZERO)
+    }
+}
+
+getters!(
+    InstallmentAllocation {
+        payment_id: Uuid,
+        debt_id: Uuid,
+        installment_id: i32,
+        amount: Decimal,
+        discount_amount: Decimal,
+        created_at: DateTime<Utc>,
+    }
+);
+
+impl From<&sqlx::postgres::PgRow> for InstallmentAllocation {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            payment_id: row.get("payment_id"),
+            debt_id: row.get("debt_id"),
+            installment_id: row.get("installment_id"),
+            amount: row.get("amount"),
+            discount_amount: row.get("discount_amount"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::debt::Debt, handler::payment::use_cases::PaymentBasicData,
+    };
+
+    fn installments() -> (Debt, Vec<Installment>) {
+        let debt = Debt::new(
+            Uuid::new_v4(),
+            "Notebook".to_string(),
+            Decimal::from(300),
+            None,
+            None,
+            NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+            None,
+            None,
+            None,
+            Some(3),
+        );
+        let installments = (1..=3)
+            .map(|i| {
+                Installment::new(
+                    *debt.id(),
+                    i,
+                    NaiveDate::from_ymd_opt(2026, 9 + i as u32, 10).unwrap(),
+                    Decimal::from(100),
+                )
+            })
+            .collect();
+
+        (debt, installments)
+    }
+
+    fn payment(debt: &Debt, amount: i64) -> Payment {
+        targeted_payment(debt, amount, None, None)
+    }
+
+    fn targeted_payment(
+        debt: &Debt,
+        amount: i64,
+        installment_ids: Option<Vec<i32>>,
+        discount: Option<i64>,
+    ) -> Payment {
+        Payment::new(
+            debt,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date: NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+                amount: Some(Decimal::from(amount)),
+                installment_ids,
+                early_payment_discount: discount.map(Decimal::from),
+            },
+        )
+    }
+
+    #[test]
+    fn test_partial_payment_keeps_installment_open() {
+        let (debt, mut installments) = installments();
+
+        let allocations =
+            Installment::allocate_payment(&mut installments, &payment(&debt, 40)).unwrap();
+
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(*installments[0].paid_amount(), Decimal::from(40));
+        assert_eq!(installments[0].remaining_amount(), Decimal::from(60));
+        assert!(!installments[0].is_paid());
+    }
+
+    #[test]
+    fn test_overpayment_rolls_into_next_installment() {
+        let (debt, mut installments) = installments();
+        let payment = payment(&debt, 150);
+
+        let allocations = Installment::allocate_payment(&mut installments, &payment).unwrap();
+
+        assert_eq!(allocations.len(), 2);
+        assert!(installments[0].is_paid());
+        assert_eq!(*installments[0].payment_id(), Some(*payment.id()));
+        assert_eq!(*installments[1].paid_amount(), Decimal::from(50));
+        assert!(!installments[1].is_paid());
+    }
+
+    #[test]
+    fn test_reversal_restores_installments() {
+        let (debt, mut installments) = installments();
+        Installment::allocate_payment(&mut installments, &payment(&debt, 30)).unwrap();
+        let allocations =
+            Installment::allocate_payment(&mut installments, &payment(&debt, 120)).unwrap();
+
+        Installment::reverse_allocations(&mut installments, &allocations).unwrap();
+
+        assert_eq!(*installments[0].paid_amount(), Decimal::from(30));
+        assert!(!installments[0].is_paid());
+        assert_eq!(*installments[0].payment_id(), None);
+        assert_eq!(*installments[1].paid_amount(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_payment_targets_informed_installments() {
+        let (debt, mut installments) = installments();
+        let payment = targeted_payment(&debt, 200, Some(vec![2, 3]), None);
+
+        let allocations = Installment::allocate_payment(&mut installments, &payment).unwrap();
+
+        assert_eq!(allocations.len(), 2);
+        assert!(!installments[0].is_paid());
+        assert!(installments[1].is_paid());
+        assert!(installments[2].is_paid());
+    }
+
+    #[test]
+    fn test_early_payment_discount_settles_future_installments() {
+        let (debt, mut installments) = installments();
+        let payment = targeted_payment(&debt, 180, Some(vec![2, 3]), Some(20));
+
+        let allocations = Installment::allocate_payment(&mut installments, &payment).unwrap();
+
+        assert!(installments[1].is_paid());
+        assert!(installments[2].is_paid());
+        assert_eq!(*allocations[1].amount(), Decimal::from(80));
+        assert_eq!(*allocations[1].discount_amount(), Decimal::from(20));
+
+        Installment::reverse_allocations(&mut installments, &allocations).unwrap();
+        assert_eq!(*installments[2].paid_amount(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_early_payment_discount_rejected_for_due_installments() {
+        let (debt, installments) = installments();
+
+        let partial = targeted_payment(&debt, 150, Some(vec![2, 3]), Some(20));
+        assert!(Installment::validate_payment(&installments, &partial).is_err());
+
+        let not_future = targeted_payment(&debt, 90, Some(vec![1]), Some(10));
+        assert!(Installment::validate_payment(&installments, &not_future).is_err());
+
+        let unknown = targeted_payment(&debt, 100, Some(vec![4]), None);
+        assert!(Installment::validate_payment(&installments, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_payment_exceeding_remaining_installments_is_rejected() {
+        let (debt, mut installments) = installments();
+
+        assert!(Installment::allocate_payment(&mut installments, &payment(&debt, 301)).is_err());
+    }
+}
@@ -2,14 +2,24 @@ use std::collections::HashSet;
 
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use util::{getters, DeletedBy};
 use uuid::Uuid;
 
-use crate::modules::finance_manager::domain::debt::invoice::use_cases::{
-    CreateInvoiceRequest, ManageInvoiceDebts,
+use crate::modules::finance_manager::domain::{
+    debt::{
+        installment::Installment,
+        invoice::use_cases::{CreateInvoiceRequest, ManageInvoiceDebts},
+        late_fee::LateFeePolicy,
+        Debt,
+    },
+    financial_instrument::{billing_cycle::BillingCycle, FinancialInstrument},
 };
 
+pub mod settlement;
+pub mod summary;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Invoice {
@@ -24,6 +34,19 @@ pub struct Invoice {
     #[serde(default)]
     related_debt_ids: HashSet<Uuid>,
 
+    /// Credit card whose billing cycle generated the invoice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    financial_instrument_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    closing_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due_date: Option<NaiveDate>,
+    /// Amount billed, computed when the invoice is closed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total_amount: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    closed_at: Option<DateTime<Utc>>,
+
     created_at: DateTime<Utc>,
     updated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -37,6 +60,11 @@ getters! {
         name: String,
         reference_date: NaiveDate,
         related_debt_ids: HashSet<Uuid>,
+        financial_instrument_id: Option<Uuid>,
+        closing_date: Option<NaiveDate>,
+        due_date: Option<NaiveDate>,
+        total_amount: Option<Decimal>,
+        closed_at: Option<DateTime<Utc>>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -56,6 +84,11 @@ impl From<&sqlx::postgres::PgRow> for Invoice {
                 .get::<Vec<Uuid>, _>("related_debt_ids")
                 .into_iter()
                 .collect(),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            closing_date: row.get("closing_date"),
+            due_date: row.get("due_date"),
+            total_amount: row.get("total_amount"),
+            closed_at: row.get("closed_at"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
             deleted_by: row
@@ -76,6 +109,7 @@ enum ExpectedDebtLink {
 enum InvoiceValidationError {
     DebtNotFoundInInvoice,
     DebtAlreadyInInvoice,
+    InvoiceAlreadyClosed,
 }
 
 impl InvoiceValidationError {
@@ -83,6 +117,7 @@ impl InvoiceValidationError {
         match self {
             Self::DebtNotFoundInInvoice => "Debt not found in invoice",
             Self::DebtAlreadyInInvoice => "Debt already in invoice",
+            Self::InvoiceAlreadyClosed => "Invoice already closed",
         }
     }
 }
@@ -105,12 +140,140 @@ impl Invoice {
             name: request.name,
             reference_date: reference_month_as_date(request.reference_date),
             related_debt_ids: HashSet::new(),
+            financial_instrument_id: None,
+            closing_date: None,
+            due_date: None,
+            total_amount: None,
+            closed_at: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_by: None,
+        }
+    }
+
+    /// Invoice of a credit card billing cycle.
+    pub fn from_billing_cycle(instrument: &FinancialInstrument, cycle: &BillingCycle) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            client_id: *instrument.client_id(),
+            name: format!(
+                "Fatura {} {:02}/{}",
+                instrument.name(),
+                cycle.reference_date.month(),
+                cycle.reference_date.year()
+            ),
+            reference_date: cycle.reference_date,
+            related_debt_ids: HashSet::new(),
+            financial_instrument_id: Some(*instrument.id()),
+            closing_date: Some(cycle.closing_date),
+            due_date: Some(cycle.due_date),
+            total_amount: None,
+            closed_at: None,
             created_at: Utc::now(),
             updated_at: None,
             deleted_by: None,
         }
     }
 
+    pub fn is_closed(&self) -> bool {
+        self.closed_at.is_some()
+    }
+
+    /// Links a debt charged on the invoice's billing cycle.
+    pub fn link_debt(&mut self, debt_id: Uuid) -> HttpResult<()> {
+        self.ensure_open()?;
+
+        if self.related_debt_ids.insert(debt_id) {
+            self.updated_at = Some(Utc::now());
+        }
+
+        Ok(())
+    }
+
+    /// Unlinks a debt no longer billed on the invoice.
+    pub fn unlink_debt(&mut self, debt_id: Uuid) -> HttpResult<()> {
+        self.ensure_open()?;
+
+        if self.related_debt_ids.remove(&debt_id) {
+            self.updated_at = Some(Utc::now());
+        }
+
+        Ok(())
+    }
+
+    /// Amount the invoice bills for a debt: the installments due on it for
+    /// installment debts, otherwise the debt's total.
+    pub fn billed_amount(&self, debt: &Debt, installments: &[Installment]) -> Decimal {
+        if !debt.has_installments() {
+            return *debt.total_amount();
+        }
+
+        installments
+            .iter()
+            .filter(|installment| installment.debt_id() == debt.id())
+            .filter(|installment| installment.superseded_by().is_none())
+            .filter(|installment| self.bills_on(*installment.due_date()))
+            .map(|installment| *installment.amount())
+            .sum()
+    }
+
+    /// Closes the invoice with the amount billed for its debts. Invoices
+    /// without a billing cycle are due on the latest due date of their debts.
+    pub fn close(&mut self, debts: &[Debt], installments: &[Installment]) -> HttpResult<()> {
+        self.ensure_open()?;
+
+        let linked_debts = debts
+            .iter()
+            .filter(|debt| self.related_debt_ids.contains(debt.id()));
+
+        let mut total_amount = Decimal::ZERO;
+        let mut latest_due_date = None;
+        for debt in linked_debts {
+            total_amount += self.billed_amount(debt, installments);
+            latest_due_date = latest_due_date.max(Some(*debt.due_date()));
+        }
+
+        self.total_amount = Some(total_amount);
+        self.due_date = self.due_date.or(latest_due_date);
+        self.closed_at = Some(Utc::now());
+        self.updated_at = Some(Utc::now());
+
+        Ok(())
+    }
+
+    /// Whether an installment due on `date` belongs to this invoice.
+    pub(crate) fn bills_on(&self, date: NaiveDate) -> bool {
+        match self.due_date {
+            Some(due_date) if self.financial_instrument_id.is_some() => date == due_date,
+            _ => reference_month_as_date(date) == self.reference_date,
+        }
+    }
+
+    fn ensure_open(&self) -> HttpResult<()> {
+        if self.is_closed() {
+            return Err(Box::new(
+                InvoiceValidationError::InvoiceAlreadyClosed.into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Late-fee policy debts billed on the invoice fall back to: the one of
+    /// the card it bills, found among `instruments`, whichever instrument
+    /// pays it.
+    pub fn late_fee_policy<'a>(
+        &self,
+        instruments: &'a [FinancialInstrument],
+    ) -> Option<&'a LateFeePolicy> {
+        let card_id = self.financial_instrument_id?;
+
+        instruments
+            .iter()
+            .find(|instrument| *instrument.id() == card_id)
+            .and_then(|card| card.configuration().late_fee_policy.as_ref())
+    }
+
     pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
         if *self.client_id() != client_id {
             return Err(Box::new(HttpError::forbidden(
@@ -126,6 +289,8 @@ impl Invoice {
             return Err(Box::new(HttpError::bad_request("No changes to apply")));
         }
 
+        self.ensure_open()?;
+
         self.validate_debt_ids(&request.add_debt_ids, ExpectedDebtLink::MustNotBeLinked)?;
         self.validate_debt_ids(&request.remove_debt_ids, ExpectedDebtLink::MustBeLinked)?;
 
@@ -173,6 +338,8 @@ impl Invoice {
 
 pub mod use_cases {
     use chrono::NaiveDate;
+    use http_error::{HttpError, HttpResult};
+    use rust_decimal::Decimal;
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
@@ -191,6 +358,11 @@ pub mod use_cases {
         pub related_debt_ids: Option<Vec<Uuid>>,
         #[serde(default)]
         pub reference_date: Option<NaiveDate>,
+        #[serde(default)]
+        pub financial_instrument_id: Option<Uuid>,
+        /// Includes what each invoice bills for its debts.
+        #[serde(default)]
+        pub expand_debts: bool,
     }
 
     #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -207,6 +379,48 @@ pub mod use_cases {
             self.add_debt_ids.is_empty() && self.remove_debt_ids.is_empty()
         }
     }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PayInvoiceRequest {
+        /// Account the invoice is paid from.
+        pub financial_instrument_id: Uuid,
+        /// Defaults to today.
+        pub payment_date: Option<NaiveDate>,
+        /// Defaults to the amount due on the invoice.
+        pub amount: Option<Decimal>,
+        /// Carries the unpaid balance over to the next invoice as a new debt.
+        pub carry_over: Option<CarryOverRequest>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CarryOverRequest {
+        /// Percentage added to the carried balance ("juros do rotativo").
+        pub interest_rate: Option<Decimal>,
+    }
+
+    impl PayInvoiceRequest {
+        pub fn validate(&self) -> HttpResult<()> {
+            if self.amount.is_some_and(|amount| amount <= Decimal::ZERO) {
+                return Err(Box::new(HttpError::bad_request(
+                    "Payment amount must be greater than zero",
+                )));
+            }
+
+            let interest_rate = self
+                .carry_over
+                .as_ref()
+                .and_then(|carry_over| carry_over.interest_rate);
+            if interest_rate.is_some_and(|rate| rate < Decimal::ZERO) {
+                return Err(Box::new(HttpError::bad_request(
+                    "Interest rate cannot be negative",
+                )));
+            }
+
+            Ok(())
+        }
+    }
 }
 
 pub mod filters {
@@ -220,6 +434,7 @@ pub mod filters {
         pub client_id: Uuid,
         pub related_debt_ids: Option<Vec<Uuid>>,
         pub reference_date: Option<NaiveDate>,
+        pub financial_instrument_id: Option<Uuid>,
     }
 
     impl InvoiceFilters {
@@ -241,5 +456,134 @@ pub mod filters {
             self.reference_date = reference_date;
             self
         }
+
+        pub fn with_financial_instrument_id(
+            mut self,
+            financial_instrument_id: Option<Uuid>,
+        ) -> Self {
+            self.financial_instrument_id = financial_instrument_id;
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::modules::finance_manager::domain::{
+        debt::late_fee::InterestPeriod,
+        financial_instrument::{configuration::InstrumentConfiguration, FinancialInstrumentType},
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn card(client_id: Uuid, late_fee_policy: Option<LateFeePolicy>) -> FinancialInstrument {
+        FinancialInstrument::new(
+            client_id,
+            "Nubank".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::CreditCard,
+            InstrumentConfiguration {
+                default_due_date: Some(10),
+                closing_day: Some(3),
+                late_fee_policy,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn card_invoice(client_id: Uuid) -> Invoice {
+        invoice_of(&card(client_id, None))
+    }
+
+    fn invoice_of(card: &FinancialInstrument) -> Invoice {
+        let cycle = card.billing_cycle(date(10, 20)).unwrap();
+
+        Invoice::from_billing_cycle(card, &cycle)
+    }
+
+    fn debt(client_id: Uuid, amount: i64, installment_count: Option<i32>) -> Debt {
+        Debt::new(
+            client_id,
+            "Compra".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            date(11, 10),
+            None,
+            None,
+            None,
+            installment_count,
+        )
+    }
+
+    #[test]
+    fn test_close_bills_only_installments_due_on_the_invoice() {
+        let client_id = Uuid::new_v4();
+        let mut invoice = card_invoice(client_id);
+        let single = debt(client_id, 50, None);
+        let mut parcelled = debt(client_id, 300, Some(3));
+        let installments = parcelled.generate_installments(10).unwrap();
+
+        invoice.link_debt(*single.id()).unwrap();
+        invoice.link_debt(*parcelled.id()).unwrap();
+        invoice.close(&[single, parcelled], &installments).unwrap();
+
+        assert_eq!(*invoice.due_date(), Some(date(11, 10)));
+        assert_eq!(*invoice.total_amount(), Some(Decimal::from(150)));
+        assert!(invoice.is_closed());
+    }
+
+    #[test]
+    fn test_closed_invoice_rejects_changes() {
+        let client_id = Uuid::new_v4();
+        let mut invoice = card_invoice(client_id);
+        invoice.close(&[], &[]).unwrap();
+
+        assert!(invoice.link_debt(Uuid::new_v4()).is_err());
+        assert!(invoice.close(&[], &[]).is_err());
+        assert!(invoice
+            .validate_changes(&ManageInvoiceDebts {
+                add_debt_ids: vec![Uuid::new_v4()],
+                remove_debt_ids: vec![],
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_debts_accrue_under_the_card_policy_not_the_payer_one() {
+        let client_id = Uuid::new_v4();
+        let card_policy = LateFeePolicy {
+            fine: Decimal::from(10),
+            interest_rate: Decimal::ZERO,
+            interest_period: InterestPeriod::Daily,
+        };
+        let card = card(client_id, Some(card_policy.clone()));
+        let checking_account = FinancialInstrument::new(
+            client_id,
+            "Conta corrente".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::DebitAccount,
+            InstrumentConfiguration {
+                late_fee_policy: Some(LateFeePolicy {
+                    fine: Decimal::from(50),
+                    interest_rate: Decimal::ONE,
+                    interest_period: InterestPeriod::Daily,
+                }),
+                ..Default::default()
+            },
+        );
+        let invoice = invoice_of(&card);
+        let instruments = [checking_account, card];
+
+        let mut purchase = debt(client_id, 100, None);
+        purchase.accrue_late_fees(date(11, 15), invoice.late_fee_policy(&instruments));
+
+        assert_eq!(invoice.late_fee_policy(&instruments), Some(&card_policy));
+        assert_eq!(*purchase.total_amount(), Decimal::from(110));
     }
 }
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        debt::{
+            installment::{Installment, InstallmentAllocation},
+            invoice::{use_cases::PayInvoiceRequest, Invoice},
+            Debt, DebtStatus,
+        },
+        payment::Payment,
+    },
+    handler::payment::use_cases::PaymentBasicData,
+};
+
+/// What the invoice settlement did for one of its debts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementItem {
+    pub debt_id: Uuid,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installment_ids: Option<Vec<i32>>,
+    pub amount_due: Decimal,
+    pub paid_amount: Decimal,
+    pub carried_over_amount: Decimal,
+    pub payment_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementSummary {
+    pub invoice_id: Uuid,
+    pub financial_instrument_id: Uuid,
+    pub payment_date: NaiveDate,
+    pub total_due: Decimal,
+    pub paid_amount: Decimal,
+    pub carried_over_amount: Decimal,
+    /// Still due on the invoice's debts after the settlement.
+    pub remaining_amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carry_over_debt_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carry_over_invoice_id: Option<Uuid>,
+    pub carry_over_interest_amount: Decimal,
+    pub items: Vec<SettlementItem>,
+    /// Set when paying with a card exceeded the card's credit limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_limit_warning: Option<String>,
+}
+
+/// Debt opened on the next invoice with the balance left unpaid.
+#[derive(Debug, Clone)]
+pub struct CarryOver {
+    pub debt: Debt,
+    pub invoice: Invoice,
+    pub is_new_invoice: bool,
+}
+
+/// Everything an invoice payment changes, saved in a single transaction.
+#[derive(Debug, Clone)]
+pub struct InvoiceSettlement {
+    pub payments: Vec<Payment>,
+    pub debts: Vec<Debt>,
+    pub installments: Vec<Installment>,
+    pub allocations: Vec<InstallmentAllocation>,
+    pub carry_over: Option<CarryOver>,
+    pub summary: SettlementSummary,
+}
+
+impl InvoiceSettlement {
+    /// Pays what the invoice bills for each of its debts, earliest due date
+    /// first, until the informed amount runs out. With carry-over, the part
+    /// left unpaid is set aside to be billed again on the next invoice (see
+    /// `attach_carry_over`).
+    pub fn plan(
+        invoice: &Invoice,
+        debts: Vec<Debt>,
+        installments: Vec<Installment>,
+        request: &PayInvoiceRequest,
+        today: NaiveDate,
+    ) -> HttpResult<Self> {
+        request.validate()?;
+
+        if request.carry_over.is_some() && invoice.financial_instrument_id().is_none() {
+            return Err(Box::new(HttpError::bad_request(
+                "Carry-over is only available for credit card invoices",
+            )));
+        }
+
+        if invoice.financial_instrument_id() == &Some(request.financial_instrument_id) {
+            return Err(Box::new(HttpError::bad_request(
+                "An invoice cannot be paid with its own credit card",
+            )));
+        }
+
+        let mut debts: Vec<Debt> = debts
+            .into_iter()
+            .filter(|debt| invoice.related_debt_ids().contains(debt.id()))
+            .collect();
+        debts.sort_by_key(|debt| (*debt.due_date(), *debt.created_at()));
+
+        let mut installments_by_debt: HashMap<Uuid, Vec<Installment>> = HashMap::new();
+        for installment in installments {
+            installments_by_debt
+                .entry(*installment.debt_id())
+                .or_default()
+                .push(installment);
+        }
+
+        let billed: Vec<(usize, Option<Vec<i32>>, Decimal)> = debts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, debt)| {
+                let debt_installments = installments_by_debt
+                    .get(debt.id())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let (targets, amount_due) = billed_on(invoice, debt, debt_installments)?;
+                Some((index, targets, amount_due))
+            })
+            .collect();
+
+        let total_due: Decimal = billed.iter().map(|(_, _, amount_due)| *amount_due).sum();
+        if total_due.is_zero() {
+            return Err(Box::new(HttpError::bad_request("Invoice already paid")));
+        }
+
+        let amount = request.amount.unwrap_or(total_due);
+        if amount > total_due {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Payment amount ({:.2}) exceeds invoice amount due ({:.2})",
+                amount, total_due
+            ))));
+        }
+
+        let payment_date = request.payment_date.unwrap_or(today);
+        let mut budget = amount;
+        let mut payments = Vec::new();
+        let mut allocations = Vec::new();
+        let mut items = Vec::new();
+
+        for (index, targets, amount_due) in billed {
+            let debt = &mut debts[index];
+            let paid_amount = budget.min(amount_due);
+            let carried_over_amount = match request.carry_over {
+                Some(_) => amount_due - paid_amount,
+                None => Decimal::ZERO,
+            };
+            budget -= paid_amount;
+
+            let mut payment_ids = Vec::new();
+            if !paid_amount.is_zero() {
+                let debt_installments = installments_by_debt.entry(*debt.id()).or_default();
+                let payment = Payment::new(
+                    debt,
+                    &request.financial_instrument_id,
+                    &PaymentBasicData {
+                        payment_date,
+                        amount: Some(paid_amount),
+                        installment_ids: targets.clone(),
+                        early_payment_discount: None,
+                    },
+                );
+
+                if debt.has_installments() {
+                    allocations.extend(Installment::allocate_payment(debt_installments, &payment)?);
+                }
+                debt.process_payment(&payment)?;
+
+                payment_ids.push(*payment.id());
+                payments.push(payment);
+            }
+
+            items.push(SettlementItem {
+                debt_id: *debt.id(),
+                description: debt.description().clone(),
+                installment_ids: targets,
+                amount_due,
+                paid_amount,
+                carried_over_amount,
+                payment_ids,
+            });
+        }
+
+        let carried_over_amount: Decimal = items.iter().map(|item| item.carried_over_amount).sum();
+        let changed_debt_ids: Vec<Uuid> = items
+            .iter()
+            .filter(|item| !item.payment_ids.is_empty() || !item.carried_over_amount.is_zero())
+            .map(|item| item.debt_id)
+            .collect();
+
+        // Installments left open are kept for the carry-over to supersede
+        let installments = installments_by_debt
+            .into_values()
+            .flatten()
+            .filter(|installment| {
+                let allocated = allocations.iter().any(|allocation| {
+                    allocation.debt_id() == installment.debt_id()
+                        && allocation.installment_id() == installment.installment_id()
+                });
+                let carried_over = items.iter().any(|item| {
+                    !item.carried_over_amount.is_zero()
+                        && item.debt_id == *installment.debt_id()
+                        && item
+                            .installment_ids
+                            .as_ref()
+                            .is_some_and(|ids| ids.contains(installment.installment_id()))
+                });
+                allocated || carried_over
+            })
+            .collect();
+
+        Ok(Self {
+            payments,
+            debts: debts
+                .into_iter()
+                .filter(|debt| changed_debt_ids.contains(debt.id()))
+                .collect(),
+            installments,
+            allocations,
+            carry_over: None,
+            summary: SettlementSummary {
+                invoice_id: *invoice.id(),
+                financial_instrument_id: request.financial_instrument_id,
+                payment_date,
+                total_due,
+                paid_amount: amount,
+                carried_over_amount,
+                remaining_amount: total_due - amount - carried_over_amount,
+                carry_over_debt_id: None,
+                carry_over_invoice_id: None,
+                carry_over_interest_amount: Decimal::ZERO,
+                items,
+                credit_limit_warning: None,
+            },
+        })
+    }
+
+    /// Bills the carried over balance, plus the optional interest percentage,
+    /// as a new debt on `next_invoice`. The invoice's debts are closed by the
+    /// carried over amount and their open installments superseded by the new
+    /// debt, without any payment: no money left an account.
+    pub fn attach_carry_over(
+        &mut self,
+        invoice: &Invoice,
+        mut next_invoice: Invoice,
+        is_new_invoice: bool,
+        interest_rate: Option<Decimal>,
+    ) -> HttpResult<()> {
+        let balance = self.summary.carried_over_amount;
+        if balance.is_zero() {
+            return Ok(());
+        }
+
+        let interest_amount =
+            (balance * interest_rate.unwrap_or_default() / Decimal::ONE_HUNDRED).round_dp(2);
+        let due_date = next_invoice.due_date().unwrap_or(self.summary.payment_date);
+
+        let mut debt = Debt::new(
+            *invoice.client_id(),
+            format!("Saldo anterior - {}", invoice.name()),
+            balance + interest_amount,
+            None,
+            None,
+            due_date,
+            None,
+            None,
+            None,
+            None,
+        );
+        debt.bill_carry_over(*invoice.id());
+        next_invoice.link_debt(*debt.id())?;
+
+        for item in &self.summary.items {
+            if item.carried_over_amount.is_zero() {
+                continue;
+            }
+
+            if let Some(original) = self.debts.iter_mut().find(|d| d.id() == &item.debt_id) {
+                original.carry_over(item.carried_over_amount, *debt.id())?;
+            }
+
+            for installment in self.installments.iter_mut().filter(|installment| {
+                installment.debt_id() == &item.debt_id
+                    && installment.is_open()
+                    && item
+                        .installment_ids
+                        .as_ref()
+                        .is_some_and(|ids| ids.contains(installment.installment_id()))
+            }) {
+                installment.supersede(*debt.id());
+            }
+        }
+
+        self.summary.carry_over_debt_id = Some(*debt.id());
+        self.summary.carry_over_invoice_id = Some(*next_invoice.id());
+        self.summary.carry_over_interest_amount = interest_amount;
+        self.carry_over = Some(CarryOver {
+            debt,
+            invoice: next_invoice,
+            is_new_invoice,
+        });
+
+        Ok(())
+    }
+}
+
+/// Installments the invoice bills for the debt, with the amount still due.
+/// Invoices without a billing cycle fall back to the next open installment.
+/// Returns None when nothing is due.
+fn billed_on(
+    invoice: &Invoice,
+    debt: &Debt,
+    installments: &[Installment],
+) -> Option<(Option<Vec<i32>>, Decimal)> {
+    if !debt.has_installments() {
+        let amount_due = *debt.remaining_amount();
+        return (*debt.status() != DebtStatus::Settled && amount_due > Decimal::ZERO)
+            .then_some((None, amount_due));
+    }
+
+    let mut open: Vec<&Installment> = installments.iter().filter(|i| i.is_open()).collect();
+    open.sort_by_key(|installment| *installment.installment_id());
+
+    let mut targets: Vec<i32> = open
+        .iter()
+        .filter(|installment| invoice.bills_on(*installment.due_date()))
+        .map(|installment| *installment.installment_id())
+        .collect();
+
+    if targets.is_empty() && invoice.financial_instrument_id().is_none() {
+        targets.extend(
+            open.first()
+                .map(|installment| *installment.installment_id()),
+        );
+    }
+
+    let amount_due = Installment::amount_due(installments, &targets);
+    (amount_due > Decimal::ZERO).then_some((Some(targets), amount_due))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::domain::{
+        debt::invoice::use_cases::CarryOverRequest,
+        financial_instrument::{
+            configuration::InstrumentConfiguration, FinancialInstrument, FinancialInstrumentType,
+        },
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn card(client_id: Uuid) -> FinancialInstrument {
+        FinancialInstrument::new(
+            client_id,
+            "Nubank".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::CreditCard,
+            InstrumentConfiguration {
+                default_due_date: Some(10),
+                closing_day: Some(3),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Invoice due on 2026-11-10 with a 100.00 purchase and the first
+    /// installment (100.00) of a 300.00 purchase.
+    fn fixture() -> (FinancialInstrument, Invoice, Vec<Debt>, Vec<Installment>) {
+        let client_id = Uuid::new_v4();
+        let card = card(client_id);
+        let cycle = card.billing_cycle(date(10, 20)).unwrap();
+        let mut invoice = Invoice::from_billing_cycle(&card, &cycle);
+
+        let new_debt = |amount: i64, installment_count: Option<i32>| {
+            Debt::new(
+                client_id,
+                "Compra".to_string(),
+                Decimal::from(amount),
+                None,
+                None,
+                cycle.due_date,
+                None,
+                None,
+                None,
+                installment_count,
+            )
+        };
+        let single = new_debt(100, None);
+        let mut parcelled = new_debt(300, Some(3));
+        let installments = parcelled.generate_installments(10).unwrap();
+
+        invoice.link_debt(*single.id()).unwrap();
+        invoice.link_debt(*parcelled.id()).unwrap();
+
+        (card, invoice, vec![single, parcelled], installments)
+    }
+
+    fn request(amount: Option<i64>, carry_over: bool) -> PayInvoiceRequest {
+        PayInvoiceRequest {
+            financial_instrument_id: Uuid::new_v4(),
+            payment_date: Some(date(11, 5)),
+            amount: amount.map(Decimal::from),
+            carry_over: carry_over.then_some(CarryOverRequest {
+                interest_rate: Some(Decimal::TEN),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_full_payment_settles_billed_items() {
+        let (_, invoice, debts, installments) = fixture();
+
+        let settlement = InvoiceSettlement::plan(
+            &invoice,
+            debts,
+            installments,
+            &request(None, false),
+            date(11, 5),
+        )
+        .unwrap();
+
+        assert_eq!(settlement.summary.total_due, Decimal::from(200));
+        assert_eq!(settlement.summary.remaining_amount, Decimal::ZERO);
+        assert_eq!(settlement.payments.len(), 2);
+        assert_eq!(settlement.installments.len(), 1);
+        assert!(*settlement.installments[0].is_paid());
+        assert_eq!(*settlement.debts[0].status(), DebtStatus::Settled);
+    }
+
+    #[test]
+    fn test_partial_payment_keeps_the_rest_due() {
+        let (_, invoice, debts, installments) = fixture();
+
+        let settlement = InvoiceSettlement::plan(
+            &invoice,
+            debts,
+            installments,
+            &request(Some(150), false),
+            date(11, 5),
+        )
+        .unwrap();
+
+        assert_eq!(settlement.summary.paid_amount, Decimal::from(150));
+        assert_eq!(settlement.summary.remaining_amount, Decimal::from(50));
+        assert_eq!(*settlement.installments[0].paid_amount(), Decimal::from(50));
+    }
+
+    #[test]
+    fn test_carry_over_moves_the_balance_to_the_next_invoice() {
+        let (card, invoice, debts, installments) = fixture();
+        let next_cycle = card.billing_cycle(date(11, 20)).unwrap();
+        let next_invoice = Invoice::from_billing_cycle(&card, &next_cycle);
+
+        let mut settlement = InvoiceSettlement::plan(
+            &invoice,
+            debts,
+            installments,
+            &request(Some(150), true),
+            date(11, 5),
+        )
+        .unwrap();
+        settlement
+            .attach_carry_over(&invoice, next_invoice, true, Some(Decimal::TEN))
+            .unwrap();
+
+        let carry_over = settlement.carry_over.unwrap();
+        assert_eq!(settlement.summary.carried_over_amount, Decimal::from(50));
+        assert_eq!(settlement.summary.remaining_amount, Decimal::ZERO);
+        assert_eq!(*carry_over.debt.total_amount(), Decimal::from(55));
+
+        // Only the informed amount is paid; the rest is carried over
+        let paid: Decimal = settlement.payments.iter().map(|p| *p.amount()).sum();
+        assert_eq!(paid, Decimal::from(150));
+        let parcelled = settlement
+            .debts
+            .iter()
+            .find(|debt| debt.has_installments())
+            .unwrap();
+        assert_eq!(*parcelled.carried_over_amount(), Decimal::from(50));
+        assert_eq!(parcelled.carried_over_to(), &Some(*carry_over.debt.id()));
+        assert_eq!(*parcelled.remaining_amount(), Decimal::from(200));
+        assert_eq!(
+            settlement.installments[0].superseded_by(),
+            &Some(*carry_over.debt.id())
+        );
+
+        assert_eq!(*carry_over.debt.due_date(), date(12, 10));
+        assert!(carry_over
+            .invoice
+            .related_debt_ids()
+            .contains(carry_over.debt.id()));
+    }
+
+    #[test]
+    fn test_invoice_cannot_be_paid_with_its_own_card() {
+        let (card, invoice, debts, installments) = fixture();
+        let mut request = request(None, false);
+        request.financial_instrument_id = *card.id();
+
+        assert!(
+            InvoiceSettlement::plan(&invoice, debts, installments, &request, date(11, 5)).is_err()
+        );
+    }
+}
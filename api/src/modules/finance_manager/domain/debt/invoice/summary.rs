@@ -0,0 +1,318 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::{
+    installment::Installment, invoice::Invoice, Debt, DebtCategory, ExpenseType,
+};
+
+/// Payment status of an invoice, derived from its debts. Never persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvoiceStatus {
+    /// Nothing paid yet. A.k.a. "Em aberto"
+    Open,
+    /// A.k.a. "Parcialmente paga"
+    PartiallyPaid,
+    /// A.k.a. "Paga"
+    Paid,
+    /// Past its due date with an amount still due. A.k.a. "Vencida"
+    Overdue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTotal {
+    pub category: DebtCategory,
+    pub total_amount: Decimal,
+    pub remaining_amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseTypeTotal {
+    pub expense_type: ExpenseType,
+    pub total_amount: Decimal,
+    pub remaining_amount: Decimal,
+}
+
+/// What the invoice bills for one of its debts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceDebt {
+    pub debt_id: Uuid,
+    pub description: String,
+    pub category: DebtCategory,
+    pub expense_type: ExpenseType,
+    pub due_date: NaiveDate,
+    /// Installments billed on the invoice, for installment debts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installment_ids: Option<Vec<i32>>,
+    pub total_amount: Decimal,
+    /// Settled by payments, early payment discounts included.
+    pub paid_amount: Decimal,
+    /// Moved to the debt billed on the next invoice.
+    pub carried_over_amount: Decimal,
+    pub remaining_amount: Decimal,
+}
+
+impl InvoiceDebt {
+    fn new(invoice: &Invoice, debt: &Debt, installments: &[Installment]) -> Self {
+        if !debt.has_installments() {
+            return Self::build(
+                debt,
+                None,
+                *debt.total_amount(),
+                *debt.paid_amount() + *debt.discount_amount(),
+                *debt.carried_over_amount(),
+                *debt.remaining_amount(),
+            );
+        }
+
+        // Installments carried over to the next invoice are superseded by the
+        // carry-over debt but still belong to this invoice.
+        let billed: Vec<&Installment> = installments
+            .iter()
+            .filter(|installment| installment.debt_id() == debt.id())
+            .filter(|installment| {
+                installment.superseded_by().is_none()
+                    || installment.superseded_by() == debt.carried_over_to()
+            })
+            .filter(|installment| invoice.bills_on(*installment.due_date()))
+            .collect();
+        let (carried, open): (Vec<&Installment>, Vec<&Installment>) = billed
+            .iter()
+            .partition(|installment| installment.superseded_by().is_some());
+
+        Self::build(
+            debt,
+            Some(billed.iter().map(|i| *i.installment_id()).collect()),
+            billed.iter().map(|i| *i.amount()).sum(),
+            billed.iter().map(|i| *i.paid_amount()).sum(),
+            carried.iter().map(|i| i.remaining_amount()).sum(),
+            open.iter().map(|i| i.remaining_amount()).sum(),
+        )
+    }
+
+    fn build(
+        debt: &Debt,
+        installment_ids: Option<Vec<i32>>,
+        total_amount: Decimal,
+        paid_amount: Decimal,
+        carried_over_amount: Decimal,
+        remaining_amount: Decimal,
+    ) -> Self {
+        Self {
+            debt_id: *debt.id(),
+            description: debt.description().clone(),
+            category: debt.category().clone(),
+            expense_type: debt.expense_type().clone(),
+            due_date: *debt.due_date(),
+            installment_ids,
+            total_amount,
+            paid_amount,
+            carried_over_amount,
+            remaining_amount,
+        }
+    }
+}
+
+/// Invoice with the amounts of its linked debts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceSummary {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    pub status: InvoiceStatus,
+    pub total_due: Decimal,
+    pub paid_amount: Decimal,
+    pub carried_over_amount: Decimal,
+    pub remaining_amount: Decimal,
+    pub categories: Vec<CategoryTotal>,
+    pub expense_types: Vec<ExpenseTypeTotal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debts: Option<Vec<InvoiceDebt>>,
+}
+
+impl InvoiceSummary {
+    /// Builds the summary from the debts linked to the invoice. `debts` and
+    /// `installments` may hold records of other invoices, which are ignored.
+    pub fn build(
+        invoice: Invoice,
+        debts: &[Debt],
+        installments: &[Installment],
+        expand_debts: bool,
+        today: NaiveDate,
+    ) -> Self {
+        let items: Vec<InvoiceDebt> = debts
+            .iter()
+            .filter(|debt| invoice.related_debt_ids().contains(debt.id()))
+            .map(|debt| InvoiceDebt::new(&invoice, debt, installments))
+            .collect();
+
+        let total_due: Decimal = items.iter().map(|item| item.total_amount).sum();
+        let paid_amount: Decimal = items.iter().map(|item| item.paid_amount).sum();
+        let carried_over_amount: Decimal = items.iter().map(|item| item.carried_over_amount).sum();
+        let remaining_amount: Decimal = items.iter().map(|item| item.remaining_amount).sum();
+
+        let due_date = invoice
+            .due_date()
+            .or_else(|| items.iter().map(|item| item.due_date).max());
+
+        let status = if remaining_amount.is_zero() && !total_due.is_zero() {
+            InvoiceStatus::Paid
+        } else if due_date.is_some_and(|due_date| due_date < today) && !remaining_amount.is_zero() {
+            InvoiceStatus::Overdue
+        } else if paid_amount.is_zero() {
+            InvoiceStatus::Open
+        } else {
+            InvoiceStatus::PartiallyPaid
+        };
+
+        let mut categories: Vec<CategoryTotal> = Vec::new();
+        let mut expense_types: Vec<ExpenseTypeTotal> = Vec::new();
+        for item in &items {
+            match categories.iter_mut().find(|c| c.category == item.category) {
+                Some(total) => {
+                    total.total_amount += item.total_amount;
+                    total.remaining_amount += item.remaining_amount;
+                }
+                None => categories.push(CategoryTotal {
+                    category: item.category.clone(),
+                    total_amount: item.total_amount,
+                    remaining_amount: item.remaining_amount,
+                }),
+            }
+
+            match expense_types
+                .iter_mut()
+                .find(|e| e.expense_type == item.expense_type)
+            {
+                Some(total) => {
+                    total.total_amount += item.total_amount;
+                    total.remaining_amount += item.remaining_amount;
+                }
+                None => expense_types.push(ExpenseTypeTotal {
+                    expense_type: item.expense_type.clone(),
+                    total_amount: item.total_amount,
+                    remaining_amount: item.remaining_amount,
+                }),
+            }
+        }
+        categories.sort_by_key(|total| std::cmp::Reverse(total.total_amount));
+        expense_types.sort_by_key(|total| std::cmp::Reverse(total.total_amount));
+
+        Self {
+            invoice,
+            status,
+            total_due,
+            paid_amount,
+            carried_over_amount,
+            remaining_amount,
+            categories,
+            expense_types,
+            debts: expand_debts.then_some(items),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::domain::debt::invoice::use_cases::CreateInvoiceRequest;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn debt(client_id: Uuid, amount: i64, paid: i64, category: DebtCategory) -> Debt {
+        Debt::new(
+            client_id,
+            "Compra".to_string(),
+            Decimal::from(amount),
+            Some(Decimal::from(paid)),
+            None,
+            date(11, 10),
+            Some(category),
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn invoice(client_id: Uuid, debts: &[&Debt]) -> Invoice {
+        let mut invoice = Invoice::from_request(
+            CreateInvoiceRequest {
+                name: "Novembro".to_string(),
+                reference_date: date(11, 1),
+            },
+            client_id,
+        );
+        for debt in debts {
+            invoice.link_debt(*debt.id()).unwrap();
+        }
+        invoice
+    }
+
+    #[test]
+    fn test_summary_totals_and_breakdown() {
+        let client_id = Uuid::new_v4();
+        let food = debt(client_id, 100, 40, DebtCategory::new("FOOD"));
+        let home = debt(client_id, 300, 0, DebtCategory::new("HOME"));
+        let other = debt(client_id, 50, 0, DebtCategory::new("FOOD"));
+        let invoice = invoice(client_id, &[&food, &home]);
+
+        let summary = InvoiceSummary::build(invoice, &[food, home, other], &[], true, date(11, 1));
+
+        assert_eq!(summary.total_due, Decimal::from(400));
+        assert_eq!(summary.paid_amount, Decimal::from(40));
+        assert_eq!(summary.remaining_amount, Decimal::from(360));
+        assert_eq!(summary.status, InvoiceStatus::PartiallyPaid);
+        assert_eq!(summary.categories[0].category, DebtCategory::new("HOME"));
+        assert_eq!(summary.categories[1].remaining_amount, Decimal::from(60));
+        assert_eq!(summary.expense_types.len(), 1);
+        assert_eq!(summary.debts.map(|debts| debts.len()), Some(2));
+    }
+
+    #[test]
+    fn test_summary_status() {
+        let client_id = Uuid::new_v4();
+        let paid = debt(client_id, 100, 100, DebtCategory::new("FOOD"));
+        let open = debt(client_id, 100, 0, DebtCategory::new("FOOD"));
+
+        let paid_invoice = invoice(client_id, &[&paid]);
+        let open_invoice = invoice(client_id, &[&open]);
+        let debts = [paid, open];
+
+        let summary = InvoiceSummary::build(paid_invoice, &debts, &[], false, date(12, 1));
+        assert_eq!(summary.status, InvoiceStatus::Paid);
+        assert!(summary.debts.is_none());
+
+        let summary = InvoiceSummary::build(open_invoice.clone(), &debts, &[], false, date(11, 1));
+        assert_eq!(summary.status, InvoiceStatus::Open);
+
+        let summary = InvoiceSummary::build(open_invoice, &debts, &[], false, date(12, 1));
+        assert_eq!(summary.status, InvoiceStatus::Overdue);
+    }
+
+    #[test]
+    fn test_carried_over_balance_is_not_reported_as_paid() {
+        let client_id = Uuid::new_v4();
+        let mut carried = debt(client_id, 100, 40, DebtCategory::new("FOOD"));
+        carried
+            .carry_over(Decimal::from(60), Uuid::new_v4())
+            .unwrap();
+        let invoice = invoice(client_id, &[&carried]);
+
+        let summary = InvoiceSummary::build(invoice, &[carried], &[], true, date(11, 1));
+
+        assert_eq!(summary.total_due, Decimal::from(100));
+        assert_eq!(summary.paid_amount, Decimal::from(40));
+        assert_eq!(summary.carried_over_amount, Decimal::from(60));
+        assert_eq!(summary.remaining_amount, Decimal::ZERO);
+        let item = &summary.debts.unwrap()[0];
+        assert_eq!(item.paid_amount, Decimal::from(40));
+        assert_eq!(item.carried_over_amount, Decimal::from(60));
+    }
+}
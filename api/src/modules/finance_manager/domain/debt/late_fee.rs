@@ -0,0 +1,323 @@
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::payment::Payment;
+
+/// Period the interest rate of a [`LateFeePolicy`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InterestPeriod {
+    Daily,
+    /// Charged pro rata die, considering 30-day months.
+    #[default]
+    Monthly,
+}
+
+/// Charges applied once a debt is paid after its due date: a fixed fine plus
+/// simple interest over the principal still outstanding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LateFeePolicy {
+    /// Fixed amount charged once the debt is late.
+    #[serde(default)]
+    pub fine: Decimal,
+    /// Interest percentage per `interest_period` (e.g. `1` = 1%).
+    #[serde(default)]
+    pub interest_rate: Decimal,
+    #[serde(default)]
+    pub interest_period: InterestPeriod,
+}
+
+impl LateFeePolicy {
+    pub fn validate(&self) -> HttpResult<()> {
+        if self.fine < Decimal::ZERO || self.interest_rate < Decimal::ZERO {
+            return Err(Box::new(HttpError::bad_request(
+                "Late fee fine and interest rate cannot be negative",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Computes the charges over `principal` for a debt due on `due_date`
+    /// and paid on `until`. Debts paid on time have no charges. Payments
+    /// reduce the principal first, so each period only bears interest over
+    /// what was still outstanding then.
+    pub fn calculate(
+        &self,
+        principal: Decimal,
+        due_date: NaiveDate,
+        mut payments: Vec<LateFeePayment>,
+        until: NaiveDate,
+    ) -> LateFeeBreakdown {
+        payments.sort_by_key(|payment| payment.payment_date);
+        let days_late = (until - due_date).num_days().max(0);
+
+        let (fine, interest) = if days_late == 0 {
+            (Decimal::ZERO, Decimal::ZERO)
+        } else {
+            let mut outstanding = principal;
+            let mut interest = Decimal::ZERO;
+            let mut from = due_date;
+
+            for payment in payments.iter().filter(|p| p.payment_date <= until) {
+                if payment.payment_date > from {
+                    interest += self.interest(outstanding, from, payment.payment_date);
+                    from = payment.payment_date;
+                }
+                outstanding = (outstanding - payment.amount).max(Decimal::ZERO);
+            }
+            interest += self.interest(outstanding, from, until);
+
+            (self.fine, interest.round_dp(2))
+        };
+
+        LateFeeBreakdown {
+            policy: self.clone(),
+            principal,
+            fine,
+            interest,
+            days_late,
+            calculated_until: until,
+            payments,
+        }
+    }
+
+    fn interest(&self, outstanding: Decimal, from: NaiveDate, until: NaiveDate) -> Decimal {
+        let days = Decimal::from((until - from).num_days().max(0));
+        let periods = match self.interest_period {
+            InterestPeriod::Daily => days,
+            InterestPeriod::Monthly => days / Decimal::from(30),
+        };
+
+        outstanding * self.interest_rate / Decimal::ONE_HUNDRED * periods
+    }
+}
+
+/// Amount paid towards a debt with late fees, as interest stops running on it
+/// from its payment date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LateFeePayment {
+    /// `None` for what was paid before the fees were first accrued.
+    pub payment_id: Option<Uuid>,
+    pub payment_date: NaiveDate,
+    pub amount: Decimal,
+}
+
+impl From<&Payment> for LateFeePayment {
+    fn from(payment: &Payment) -> Self {
+        Self {
+            payment_id: Some(*payment.id()),
+            payment_date: *payment.payment_date(),
+            amount: *payment.amount() + *payment.discount_amount(),
+        }
+    }
+}
+
+/// Composition of a debt's total once late fees were accrued. Keeps the
+/// policy it was calculated with, so later payments accrue the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LateFeeBreakdown {
+    pub policy: LateFeePolicy,
+    pub principal: Decimal,
+    pub fine: Decimal,
+    pub interest: Decimal,
+    pub days_late: i64,
+    pub calculated_until: NaiveDate,
+    /// Payments made since the fees started running, so the charges can be
+    /// recalculated when one of them is reversed.
+    #[serde(default)]
+    pub payments: Vec<LateFeePayment>,
+}
+
+impl LateFeeBreakdown {
+    pub fn total(&self) -> Decimal {
+        self.principal + self.fine + self.interest
+    }
+
+    /// Recalculates the charges without the reversed payment, up to the last
+    /// payment still standing. Returns `None` when no payment is left to have
+    /// been charged late fees.
+    pub fn without_payment(&self, payment: &Payment, due_date: NaiveDate) -> Option<Self> {
+        let mut payments = self.payments.clone();
+        match payments
+            .iter()
+            .position(|p| p.payment_id == Some(*payment.id()))
+        {
+            Some(index) => {
+                payments.remove(index);
+            }
+            // Paid before the fees were accrued, so part of the opening amount
+            None => {
+                if let Some(opening) = payments.iter_mut().find(|p| p.payment_id.is_none()) {
+                    let amount = *payment.amount() + *payment.discount_amount();
+                    opening.amount = (opening.amount - amount).max(Decimal::ZERO);
+                }
+            }
+        }
+
+        let until = payments
+            .iter()
+            .filter(|p| p.payment_id.is_some())
+            .map(|p| p.payment_date)
+            .max()?;
+
+        Some(
+            self.policy
+                .calculate(self.principal, due_date, payments, until),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::{
+            debt::{Debt, DebtStatus},
+            payment::Payment,
+        },
+        handler::payment::use_cases::PaymentBasicData,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn policy(interest_period: InterestPeriod) -> LateFeePolicy {
+        LateFeePolicy {
+            fine: Decimal::new(1000, 2),
+            interest_rate: Decimal::ONE,
+            interest_period,
+        }
+    }
+
+    #[test]
+    fn test_no_charges_when_paid_on_time() {
+        let breakdown = policy(InterestPeriod::Daily).calculate(
+            Decimal::from(300),
+            date(10, 10),
+            Vec::new(),
+            date(10, 10),
+        );
+
+        assert_eq!(breakdown.fine, Decimal::ZERO);
+        assert_eq!(breakdown.interest, Decimal::ZERO);
+        assert_eq!(breakdown.total(), Decimal::from(300));
+    }
+
+    #[test]
+    fn test_daily_interest() {
+        let breakdown = policy(InterestPeriod::Daily).calculate(
+            Decimal::from(300),
+            date(10, 10),
+            Vec::new(),
+            date(10, 15),
+        );
+
+        assert_eq!(breakdown.days_late, 5);
+        assert_eq!(breakdown.fine, Decimal::new(1000, 2));
+        assert_eq!(breakdown.interest, Decimal::from(15));
+        assert_eq!(breakdown.total(), Decimal::new(32500, 2));
+    }
+
+    #[test]
+    fn test_monthly_interest_is_pro_rata() {
+        let breakdown = policy(InterestPeriod::Monthly).calculate(
+            Decimal::from(300),
+            date(10, 10),
+            Vec::new(),
+            date(10, 25),
+        );
+
+        assert_eq!(breakdown.interest, Decimal::new(150, 2));
+    }
+
+    fn internet_debt() -> Debt {
+        let mut debt = Debt::new(
+            Uuid::new_v4(),
+            "Internet".to_string(),
+            Decimal::from(300),
+            None,
+            None,
+            date(10, 10),
+            None,
+            None,
+            None,
+            None,
+        );
+        debt.set_late_fee_policy(Some(policy(InterestPeriod::Daily)));
+        debt
+    }
+
+    fn pay(debt: &mut Debt, payment_date: NaiveDate, amount: Option<Decimal>) -> Payment {
+        let payment_data = PaymentBasicData {
+            payment_date,
+            amount,
+            installment_ids: None,
+            early_payment_discount: None,
+        };
+        debt.accrue_late_fees(payment_date, None);
+        let payment = Payment::new(debt, &Uuid::new_v4(), &payment_data);
+        debt.process_payment(&payment).unwrap();
+
+        payment
+    }
+
+    #[test]
+    fn test_debt_payment_accrues_late_fees_until_payment_date() {
+        let mut debt = internet_debt();
+        pay(&mut debt, date(10, 15), None);
+
+        assert_eq!(*debt.total_amount(), Decimal::new(32500, 2));
+        assert_eq!(debt.principal_amount(), Decimal::from(300));
+        assert_eq!(*debt.remaining_amount(), Decimal::ZERO);
+        assert_eq!(*debt.status(), DebtStatus::Settled);
+    }
+
+    #[test]
+    fn test_interest_only_runs_over_outstanding_principal() {
+        let mut debt = internet_debt();
+        pay(&mut debt, date(10, 15), Some(Decimal::from(200)));
+
+        // 5 days over 300, then 5 days over the 100 left
+        debt.accrue_late_fees(date(10, 20), None);
+
+        let late_fee = debt.late_fee().as_ref().unwrap();
+        assert_eq!(late_fee.interest, Decimal::from(20));
+        assert_eq!(*debt.total_amount(), Decimal::from(330));
+        assert_eq!(*debt.remaining_amount(), Decimal::from(130));
+    }
+
+    #[test]
+    fn test_reversing_payment_rolls_late_fees_back() {
+        let mut debt = internet_debt();
+        let first = pay(&mut debt, date(10, 15), Some(Decimal::from(100)));
+        let second = pay(&mut debt, date(10, 20), Some(Decimal::from(100)));
+        assert_eq!(*debt.total_amount(), Decimal::from(335));
+
+        debt.reverse_payment(&second).unwrap();
+        assert_eq!(*debt.total_amount(), Decimal::from(325));
+        assert_eq!(*debt.remaining_amount(), Decimal::from(225));
+
+        debt.reverse_payment(&first).unwrap();
+        assert_eq!(*debt.total_amount(), Decimal::from(300));
+        assert!(debt.late_fee().is_none());
+        assert_eq!(*debt.remaining_amount(), Decimal::from(300));
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_values() {
+        let mut policy = policy(InterestPeriod::Daily);
+        policy.fine = Decimal::NEGATIVE_ONE;
+
+        assert!(policy.validate().is_err());
+    }
+}
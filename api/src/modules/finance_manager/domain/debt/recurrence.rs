@@ -1,7 +1,10 @@
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use util::{date::date_with_day_or_last, from_row_constructor, getters};
+use util::{
+    date::{date_with_day_or_last, first_day_of_month},
+    from_row_constructor, getters,
+};
 use uuid::Uuid;
 
 use crate::modules::finance_manager::{
@@ -9,6 +12,8 @@ use crate::modules::finance_manager::{
     handler::debt::use_cases::CreateRecurrenceRequest,
 };
 
+pub mod generation;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Recurrence {
@@ -32,9 +37,20 @@ pub struct Recurrence {
 #[serde(rename_all = "camelCase")]
 pub struct RecurrenceExecutionLog {
     run_date: NaiveDate,
+    /// First day of the month the debt was generated for. Older logs don't
+    /// have it and refer to the month of `run_date`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference_month: Option<NaiveDate>,
     debt_id: Uuid,
 }
 
+impl RecurrenceExecutionLog {
+    pub fn reference_month(&self) -> NaiveDate {
+        self.reference_month
+            .unwrap_or_else(|| first_day_of_month(self.run_date))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RecurrenceFilters {
@@ -110,9 +126,53 @@ impl Recurrence {
 
     /// Checks if this recurrence was already executed for the given year/month
     pub fn was_executed_in_month(&self, year: i32, month: u32) -> bool {
-        self.execution_logs
+        self.execution_logs.iter().any(|log| {
+            let reference_month = log.reference_month();
+            reference_month.year() == year && reference_month.month() == month
+        })
+    }
+
+    /// Months (as their first day) that still need a debt: from the month
+    /// after the last execution, or the first month due on or after the start
+    /// date when it never ran, up to `until`, limited to the recurrence's date
+    /// range.
+    pub fn pending_months(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut month = match self
+            .execution_logs
             .iter()
-            .any(|log| log.run_date.year() == year && log.run_date.month() == month)
+            .map(|log| log.reference_month())
+            .max()
+        {
+            Some(last) => last + Months::new(1),
+            None => self.first_month(),
+        };
+        let last_month = match self.end_date {
+            Some(end_date) => first_day_of_month(until.min(end_date)),
+            None => first_day_of_month(until),
+        };
+
+        let mut months = Vec::new();
+        while month <= last_month {
+            if !self.was_executed_in_month(month.year(), month.month()) {
+                months.push(month);
+            }
+            month = month + Months::new(1);
+        }
+
+        months
+    }
+
+    /// Month of the first debt: the start month, unless its due day is already
+    /// past on the start date.
+    fn first_month(&self) -> NaiveDate {
+        let start_month = first_day_of_month(self.start_date);
+        let due_date = self.calculate_due_date(start_month.year(), start_month.month());
+
+        if due_date < self.start_date {
+            start_month + Months::new(1)
+        } else {
+            start_month
+        }
     }
 
     /// Calculates the due date for a given year/month using the recurrence's day_of_month.
@@ -138,10 +198,18 @@ impl Recurrence {
         )
     }
 
-    /// Adds an execution log entry
-    pub fn add_execution_log(&mut self, run_date: NaiveDate, debt_id: Uuid) {
-        self.execution_logs
-            .push(RecurrenceExecutionLog { run_date, debt_id });
+    /// Adds an execution log entry for the debt generated for `reference_month`
+    pub fn add_execution_log(
+        &mut self,
+        run_date: NaiveDate,
+        reference_month: NaiveDate,
+        debt_id: Uuid,
+    ) {
+        self.execution_logs.push(RecurrenceExecutionLog {
+            run_date,
+            reference_month: Some(first_day_of_month(reference_month)),
+            debt_id,
+        });
         self.updated_at = Some(Utc::now());
     }
 
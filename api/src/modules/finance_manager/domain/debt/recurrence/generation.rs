@@ -0,0 +1,505 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use util::{date::first_day_of_month, getters};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::{
+    debt::{recurrence::Recurrence, Debt},
+    income::{recurrence::IncomeRecurrence, Income},
+};
+
+/// How many past months a run generates debts for. Older pending months are
+/// reported as skipped instead of flooding the client with debts.
+pub const MAX_CATCH_UP_MONTHS: u32 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GenerationTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl GenerationTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenerationTrigger::Scheduled => "SCHEDULED",
+            GenerationTrigger::Manual => "MANUAL",
+        }
+    }
+}
+
+impl From<String> for GenerationTrigger {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "MANUAL" => GenerationTrigger::Manual,
+            _ => GenerationTrigger::Scheduled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GenerationItemStatus {
+    Created,
+    Skipped,
+    Failed,
+}
+
+/// What a recurrence generates every month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GeneratedKind {
+    #[default]
+    Debt,
+    Income,
+}
+
+/// A recurrence handled by the generation runs.
+pub trait MonthlyRecurrence {
+    /// The debt or income created for each month.
+    type Generated;
+
+    fn kind(&self) -> GeneratedKind;
+
+    fn recurrence_id(&self) -> Uuid;
+
+    fn recurrence_description(&self) -> String;
+
+    fn months_to_generate(&self, until: NaiveDate) -> Vec<NaiveDate>;
+
+    fn generate_for_month(&self, month: NaiveDate) -> Self::Generated;
+
+    fn generated_id(generated: &Self::Generated) -> Uuid;
+
+    fn log_execution(&mut self, run_date: NaiveDate, month: NaiveDate, generated_id: Uuid);
+}
+
+impl MonthlyRecurrence for Recurrence {
+    type Generated = Debt;
+
+    fn kind(&self) -> GeneratedKind {
+        GeneratedKind::Debt
+    }
+
+    fn recurrence_id(&self) -> Uuid {
+        *self.id()
+    }
+
+    fn recurrence_description(&self) -> String {
+        self.description().clone()
+    }
+
+    fn months_to_generate(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        self.pending_months(until)
+    }
+
+    fn generate_for_month(&self, month: NaiveDate) -> Debt {
+        self.generate_debt_for_month(month.year(), month.month())
+    }
+
+    fn generated_id(generated: &Debt) -> Uuid {
+        *generated.id()
+    }
+
+    fn log_execution(&mut self, run_date: NaiveDate, month: NaiveDate, generated_id: Uuid) {
+        self.add_execution_log(run_date, month, generated_id);
+    }
+}
+
+impl MonthlyRecurrence for IncomeRecurrence {
+    type Generated = Income;
+
+    fn kind(&self) -> GeneratedKind {
+        GeneratedKind::Income
+    }
+
+    fn recurrence_id(&self) -> Uuid {
+        *self.id()
+    }
+
+    fn recurrence_description(&self) -> String {
+        self.description().clone()
+    }
+
+    fn months_to_generate(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        self.pending_months(until)
+    }
+
+    fn generate_for_month(&self, month: NaiveDate) -> Income {
+        self.generate_income_for_month(month.year(), month.month())
+    }
+
+    fn generated_id(generated: &Income) -> Uuid {
+        *generated.id()
+    }
+
+    fn log_execution(&mut self, run_date: NaiveDate, month: NaiveDate, generated_id: Uuid) {
+        self.add_execution_log(run_date, month, generated_id);
+    }
+}
+
+/// Outcome of generating one recurrence for one month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceGenerationItem {
+    /// Older runs only generated debts.
+    #[serde(default)]
+    kind: GeneratedKind,
+    recurrence_id: Uuid,
+    description: String,
+    reference_month: NaiveDate,
+    status: GenerationItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    debt_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    income_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl RecurrenceGenerationItem {
+    /// `generated_id` is the id of the debt or income created for the month.
+    pub fn created(
+        recurrence: &impl MonthlyRecurrence,
+        reference_month: NaiveDate,
+        generated_id: Uuid,
+    ) -> Self {
+        Self::new(
+            recurrence,
+            reference_month,
+            GenerationItemStatus::Created,
+            Some(generated_id),
+            None,
+        )
+    }
+
+    pub fn skipped(
+        recurrence: &impl MonthlyRecurrence,
+        reference_month: NaiveDate,
+        reason: &str,
+    ) -> Self {
+        Self::new(
+            recurrence,
+            reference_month,
+            GenerationItemStatus::Skipped,
+            None,
+            Some(reason.to_string()),
+        )
+    }
+
+    pub fn failed(
+        recurrence: &impl MonthlyRecurrence,
+        reference_month: NaiveDate,
+        reason: String,
+    ) -> Self {
+        Self::new(
+            recurrence,
+            reference_month,
+            GenerationItemStatus::Failed,
+            None,
+            Some(reason),
+        )
+    }
+
+    fn new(
+        recurrence: &impl MonthlyRecurrence,
+        reference_month: NaiveDate,
+        status: GenerationItemStatus,
+        generated_id: Option<Uuid>,
+        reason: Option<String>,
+    ) -> Self {
+        let kind = recurrence.kind();
+
+        Self {
+            kind,
+            recurrence_id: recurrence.recurrence_id(),
+            description: recurrence.recurrence_description(),
+            reference_month,
+            status,
+            debt_id: generated_id.filter(|_| kind == GeneratedKind::Debt),
+            income_id: generated_id.filter(|_| kind == GeneratedKind::Income),
+            reason,
+        }
+    }
+}
+
+getters! {
+    RecurrenceGenerationItem {
+        kind: GeneratedKind,
+        recurrence_id: Uuid,
+        description: String,
+        reference_month: NaiveDate,
+        status: GenerationItemStatus,
+        debt_id: Option<Uuid>,
+        income_id: Option<Uuid>,
+        reason: Option<String>,
+    }
+}
+
+/// Months a recurrence must be generated for in a run, split into the ones
+/// within the catch-up window and the ones too old to be generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationPlan {
+    pub to_generate: Vec<NaiveDate>,
+    pub too_old: Vec<NaiveDate>,
+}
+
+impl GenerationPlan {
+    pub fn for_recurrence(recurrence: &impl MonthlyRecurrence, until: NaiveDate) -> Self {
+        let oldest_allowed = first_day_of_month(until) - Months::new(MAX_CATCH_UP_MONTHS - 1);
+        let (to_generate, too_old) = recurrence
+            .months_to_generate(until)
+            .into_iter()
+            .partition(|month| *month >= oldest_allowed);
+
+        Self {
+            to_generate,
+            too_old,
+        }
+    }
+}
+
+/// Last month a run on `today` generates debts for: the current month once
+/// the configured generation day is reached, the previous one before that.
+/// Running daily, this makes a missed generation day catch up on the next run.
+pub fn generation_cutoff(today: NaiveDate, generation_day: u32) -> NaiveDate {
+    if today.day() >= generation_day {
+        first_day_of_month(today)
+    } else {
+        first_day_of_month(today) - Months::new(1)
+    }
+}
+
+/// History of one generation run for a client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceGenerationRun {
+    id: Uuid,
+    #[serde(skip_serializing)]
+    client_id: Uuid,
+    trigger: GenerationTrigger,
+    run_date: NaiveDate,
+    created_count: i32,
+    skipped_count: i32,
+    failed_count: i32,
+    items: Vec<RecurrenceGenerationItem>,
+    created_at: DateTime<Utc>,
+}
+
+impl RecurrenceGenerationRun {
+    pub fn new(
+        client_id: Uuid,
+        trigger: GenerationTrigger,
+        run_date: NaiveDate,
+        items: Vec<RecurrenceGenerationItem>,
+    ) -> Self {
+        let count = |status: GenerationItemStatus| {
+            items.iter().filter(|item| item.status == status).count() as i32
+        };
+
+        Self {
+            id: Uuid::new_v4(),
+            client_id,
+            trigger,
+            run_date,
+            created_count: count(GenerationItemStatus::Created),
+            skipped_count: count(GenerationItemStatus::Skipped),
+            failed_count: count(GenerationItemStatus::Failed),
+            items,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+getters! {
+    RecurrenceGenerationRun {
+        id: Uuid,
+        client_id: Uuid,
+        trigger: GenerationTrigger,
+        run_date: NaiveDate,
+        created_count: i32,
+        skipped_count: i32,
+        failed_count: i32,
+        items: Vec<RecurrenceGenerationItem>,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for RecurrenceGenerationRun {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            trigger: row.get::<String, _>("trigger").into(),
+            run_date: row.get("run_date"),
+            created_count: row.get("created_count"),
+            skipped_count: row.get("skipped_count"),
+            failed_count: row.get("failed_count"),
+            items: row.get::<Json<Vec<RecurrenceGenerationItem>>, _>("items").0,
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceGenerationRunFilters {
+    client_id: Option<Uuid>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+}
+
+impl RecurrenceGenerationRunFilters {
+    pub fn with_client_id(mut self, client_id: Uuid) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+}
+
+getters! {
+    RecurrenceGenerationRunFilters {
+        client_id: Option<Uuid>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::modules::finance_manager::handler::{
+        debt::use_cases::CreateRecurrenceRequest, income::use_cases::CreateIncomeRecurrenceRequest,
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn recurrence(start_date: NaiveDate, end_date: Option<NaiveDate>) -> Recurrence {
+        Recurrence::from_request(
+            Uuid::new_v4(),
+            CreateRecurrenceRequest {
+                description: "Aluguel".to_string(),
+                amount: Decimal::new(150000, 2),
+                category: None,
+                start_date,
+                end_date,
+                day_of_month: 10,
+            },
+        )
+    }
+
+    #[test]
+    fn test_generation_cutoff_before_and_after_generation_day() {
+        assert_eq!(generation_cutoff(date(2026, 10, 4), 5), date(2026, 9, 1));
+        assert_eq!(generation_cutoff(date(2026, 10, 5), 5), date(2026, 10, 1));
+        assert_eq!(generation_cutoff(date(2026, 1, 1), 5), date(2025, 12, 1));
+    }
+
+    #[test]
+    fn test_pending_months_since_start_date() {
+        let recurrence = recurrence(date(2026, 8, 5), None);
+
+        assert_eq!(
+            recurrence.pending_months(date(2026, 10, 1)),
+            vec![date(2026, 8, 1), date(2026, 9, 1), date(2026, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_pending_months_skip_start_month_due_before_start_date() {
+        let recurrence = recurrence(date(2026, 8, 20), None);
+
+        assert_eq!(
+            recurrence.pending_months(date(2026, 10, 1)),
+            vec![date(2026, 9, 1), date(2026, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_pending_months_resumes_after_last_execution() {
+        let mut recurrence = recurrence(date(2026, 1, 1), None);
+        recurrence.add_execution_log(date(2026, 8, 5), date(2026, 8, 1), Uuid::new_v4());
+
+        assert_eq!(
+            recurrence.pending_months(date(2026, 10, 1)),
+            vec![date(2026, 9, 1), date(2026, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_pending_months_stops_at_end_date() {
+        let recurrence = recurrence(date(2026, 8, 1), Some(date(2026, 9, 15)));
+
+        assert_eq!(
+            recurrence.pending_months(date(2026, 12, 1)),
+            vec![date(2026, 8, 1), date(2026, 9, 1)]
+        );
+    }
+
+    #[test]
+    fn test_plan_skips_months_older_than_catch_up_window() {
+        let recurrence = recurrence(date(2025, 8, 1), None);
+
+        let plan = GenerationPlan::for_recurrence(&recurrence, date(2026, 10, 1));
+
+        assert_eq!(plan.to_generate.len(), MAX_CATCH_UP_MONTHS as usize);
+        assert_eq!(plan.to_generate.first(), Some(&date(2025, 11, 1)));
+        assert_eq!(
+            plan.too_old,
+            vec![date(2025, 8, 1), date(2025, 9, 1), date(2025, 10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_run_counts_items_by_status() {
+        let recurrence = recurrence(date(2026, 1, 1), None);
+        let items = vec![
+            RecurrenceGenerationItem::created(&recurrence, date(2026, 9, 1), Uuid::new_v4()),
+            RecurrenceGenerationItem::created(&recurrence, date(2026, 10, 1), Uuid::new_v4()),
+            RecurrenceGenerationItem::failed(&recurrence, date(2026, 8, 1), "erro".to_string()),
+        ];
+
+        let run = RecurrenceGenerationRun::new(
+            *recurrence.client_id(),
+            GenerationTrigger::Scheduled,
+            date(2026, 10, 16),
+            items,
+        );
+
+        assert_eq!(*run.created_count(), 2);
+        assert_eq!(*run.skipped_count(), 0);
+        assert_eq!(*run.failed_count(), 1);
+    }
+
+    #[test]
+    fn test_income_items_reference_the_generated_income() {
+        let salary = IncomeRecurrence::from_request(
+            Uuid::new_v4(),
+            CreateIncomeRecurrenceRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(5000),
+                start_date: date(2026, 1, 1),
+                end_date: None,
+                day_of_month: 5,
+            },
+        )
+        .unwrap();
+        let income_id = Uuid::new_v4();
+
+        let item = RecurrenceGenerationItem::created(&salary, date(2026, 10, 1), income_id);
+
+        assert_eq!(*item.kind(), GeneratedKind::Income);
+        assert_eq!(*item.income_id(), Some(income_id));
+        assert_eq!(*item.debt_id(), None);
+        assert_eq!(
+            GenerationPlan::for_recurrence(&salary, date(2026, 3, 1)).to_generate,
+            vec![date(2026, 1, 1), date(2026, 2, 1), date(2026, 3, 1)]
+        );
+    }
+}
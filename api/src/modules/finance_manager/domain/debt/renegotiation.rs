@@ -0,0 +1,227 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::Debt;
+
+/// Conditions of a renegotiation, as computed by [`Debt::renegotiate`].
+#[derive(Debug, Clone)]
+pub struct RenegotiationTerms {
+    pub previous_remaining_amount: Decimal,
+    pub interest_rate: Decimal,
+    pub interest_amount: Decimal,
+    pub installment_count: i32,
+    pub due_day: u32,
+    pub first_due_date: NaiveDate,
+    pub first_installment_id: i32,
+    pub superseded_installment_ids: Vec<i32>,
+}
+
+/// History entry of a debt's remaining balance being re-split into a new
+/// installment plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtRenegotiation {
+    id: Uuid,
+    debt_id: Uuid,
+    #[serde(skip_serializing)]
+    client_id: Uuid,
+    previous_remaining_amount: Decimal,
+    interest_rate: Decimal,
+    interest_amount: Decimal,
+    renegotiated_amount: Decimal,
+    installment_count: i32,
+    due_day: i32,
+    first_due_date: NaiveDate,
+    first_installment_id: i32,
+    superseded_installment_ids: Vec<i32>,
+    created_at: DateTime<Utc>,
+}
+
+impl DebtRenegotiation {
+    pub fn new(id: Uuid, debt: &Debt, terms: RenegotiationTerms) -> Self {
+        Self {
+            id,
+            debt_id: *debt.id(),
+            client_id: *debt.client_id(),
+            previous_remaining_amount: terms.previous_remaining_amount,
+            interest_rate: terms.interest_rate,
+            interest_amount: terms.interest_amount,
+            renegotiated_amount: terms.previous_remaining_amount + terms.interest_amount,
+            installment_count: terms.installment_count,
+            due_day: terms.due_day as i32,
+            first_due_date: terms.first_due_date,
+            first_installment_id: terms.first_installment_id,
+            superseded_installment_ids: terms.superseded_installment_ids,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+getters! {
+    DebtRenegotiation {
+        id: Uuid,
+        debt_id: Uuid,
+        client_id: Uuid,
+        previous_remaining_amount: Decimal,
+        interest_rate: Decimal,
+        interest_amount: Decimal,
+        renegotiated_amount: Decimal,
+        installment_count: i32,
+        due_day: i32,
+        first_due_date: NaiveDate,
+        first_installment_id: i32,
+        superseded_installment_ids: Vec<i32>,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for DebtRenegotiation {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            debt_id: row.get("debt_id"),
+            client_id: row.get("client_id"),
+            previous_remaining_amount: row.get("previous_remaining_amount"),
+            interest_rate: row.get("interest_rate"),
+            interest_amount: row.get("interest_amount"),
+            renegotiated_amount: row.get("renegotiated_amount"),
+            installment_count: row.get("installment_count"),
+            due_day: row.get("due_day"),
+            first_due_date: row.get("first_due_date"),
+            first_installment_id: row.get("first_installment_id"),
+            superseded_installment_ids: row.get("superseded_installment_ids"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::{
+            debt::{installment::Installment, DebtStatus},
+            payment::Payment,
+        },
+        handler::{debt::use_cases::RenegotiateDebtRequest, payment::use_cases::PaymentBasicData},
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn debt_with_installments() -> (Debt, Vec<Installment>) {
+        let mut debt = Debt::new(
+            Uuid::new_v4(),
+            "Empréstimo".to_string(),
+            Decimal::from(400),
+            None,
+            None,
+            date(2026, 8, 10),
+            None,
+            None,
+            None,
+            Some(4),
+        );
+        let mut installments = debt.generate_installments(10).unwrap();
+
+        let payment = Payment::new(
+            &debt,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date: date(2026, 8, 10),
+                amount: Some(Decimal::from(100)),
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        );
+        Installment::allocate_payment(&mut installments, &payment).unwrap();
+        debt.process_payment(&payment).unwrap();
+
+        (debt, installments)
+    }
+
+    fn request(installment_count: i32, interest_rate: Option<Decimal>) -> RenegotiateDebtRequest {
+        RenegotiateDebtRequest {
+            installment_count,
+            due_day: Some(20),
+            first_due_date: None,
+            interest_rate,
+        }
+    }
+
+    #[test]
+    fn test_renegotiation_supersedes_open_installments() {
+        let (mut debt, mut installments) = debt_with_installments();
+
+        let (renegotiation, new_installments) = debt
+            .renegotiate(&mut installments, &request(2, None), date(2026, 10, 16))
+            .unwrap();
+
+        assert_eq!(renegotiation.superseded_installment_ids, vec![2, 3, 4]);
+        assert!(installments.iter().all(|i| !i.is_open()));
+        assert!(installments[0].superseded_by().is_none());
+        assert_eq!(
+            new_installments
+                .iter()
+                .map(|i| (*i.installment_id(), *i.due_date()))
+                .collect::<Vec<_>>(),
+            vec![(5, date(2026, 11, 20)), (6, date(2026, 12, 20))]
+        );
+        assert_eq!(*debt.installment_count(), Some(2));
+        assert_eq!(*debt.due_date(), date(2026, 12, 20));
+        assert_eq!(*debt.status(), DebtStatus::Installment);
+    }
+
+    #[test]
+    fn test_renegotiation_adds_interest_to_balance() {
+        let (mut debt, mut installments) = debt_with_installments();
+
+        let (renegotiation, new_installments) = debt
+            .renegotiate(
+                &mut installments,
+                &request(3, Some(Decimal::from(10))),
+                date(2026, 10, 16),
+            )
+            .unwrap();
+
+        assert_eq!(renegotiation.interest_amount, Decimal::from(30));
+        assert_eq!(renegotiation.renegotiated_amount, Decimal::from(330));
+        assert_eq!(*new_installments[0].amount(), Decimal::from(110));
+        assert_eq!(*debt.total_amount(), Decimal::from(430));
+        assert_eq!(*debt.remaining_amount(), Decimal::from(330));
+    }
+
+    #[test]
+    fn test_rounding_difference_goes_to_first_installment() {
+        let (mut debt, mut installments) = debt_with_installments();
+
+        let (_, new_installments) = debt
+            .renegotiate(&mut installments, &request(7, None), date(2026, 10, 16))
+            .unwrap();
+
+        assert_eq!(*new_installments[0].amount(), Decimal::new(4284, 2));
+        assert_eq!(*new_installments[1].amount(), Decimal::new(4286, 2));
+        assert_eq!(
+            new_installments
+                .iter()
+                .map(|i| *i.amount())
+                .sum::<Decimal>(),
+            Decimal::from(300)
+        );
+    }
+
+    #[test]
+    fn test_renegotiation_rejects_invalid_count() {
+        let (mut debt, mut installments) = debt_with_installments();
+
+        assert!(debt
+            .renegotiate(&mut installments, &request(0, None), date(2026, 10, 16))
+            .is_err());
+    }
+}
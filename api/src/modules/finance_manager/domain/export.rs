@@ -0,0 +1,385 @@
+use std::{
+    io::{Seek, SeekFrom, Write},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use http_error::{HttpError, HttpResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    #[default]
+    Json,
+}
+
+/// Data exported for a client, one file per entity in CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportEntity {
+    FinancialInstruments,
+    Debts,
+    Installments,
+    Payments,
+    Incomes,
+    Invoices,
+    Recurrences,
+    IncomeRecurrences,
+}
+
+impl ExportEntity {
+    pub const ALL: [ExportEntity; 8] = [
+        ExportEntity::FinancialInstruments,
+        ExportEntity::Debts,
+        ExportEntity::Installments,
+        ExportEntity::Payments,
+        ExportEntity::Incomes,
+        ExportEntity::Invoices,
+        ExportEntity::Recurrences,
+        ExportEntity::IncomeRecurrences,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportEntity::FinancialInstruments => "financial_instruments",
+            ExportEntity::Debts => "debts",
+            ExportEntity::Installments => "installments",
+            ExportEntity::Payments => "payments",
+            ExportEntity::Incomes => "incomes",
+            ExportEntity::Invoices => "invoices",
+            ExportEntity::Recurrences => "recurrences",
+            ExportEntity::IncomeRecurrences => "income_recurrences",
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "export.zip",
+            ExportFormat::Json => "export.json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "application/zip",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// Writes the exported rows as they are read, one entity after the other:
+/// a single JSON object with the rows of each entity under its name, or a
+/// zip with one CSV per entity. Rows are stored as they are, soft-deleted
+/// ones flagged by their `deleted_by` column.
+pub struct ExportWriter {
+    output: ExportOutput,
+    format: FormatWriter,
+    entities: usize,
+    /// CSV columns of the current entity, from its first row.
+    columns: Option<Vec<String>>,
+    rows: usize,
+    /// Bytes before this offset are final.
+    ready_until: u64,
+}
+
+enum FormatWriter {
+    Json(ExportOutput),
+    CsvZip(Box<ZipWriter<ExportOutput>>),
+}
+
+impl ExportWriter {
+    pub fn new(format: ExportFormat) -> Self {
+        let output = ExportOutput::default();
+
+        Self {
+            format: match format {
+                ExportFormat::Json => FormatWriter::Json(output.clone()),
+                ExportFormat::Csv => FormatWriter::CsvZip(Box::new(ZipWriter::new(output.clone()))),
+            },
+            output,
+            entities: 0,
+            columns: None,
+            rows: 0,
+            ready_until: 0,
+        }
+    }
+
+    pub fn start_entity(&mut self, entity: ExportEntity) -> HttpResult<()> {
+        self.end_entity()?;
+
+        match &mut self.format {
+            FormatWriter::Json(json) => {
+                let separator = if self.entities == 0 { "{" } else { "," };
+                write!(json, "{separator}\"{}\":[", entity.as_str()).map_err(export_error)?;
+                self.ready_until = json.position();
+            }
+            FormatWriter::CsvZip(zip) => {
+                // Starting a file completes the previous one, which zip
+                // rewrites from its header on.
+                let entry_start = self.output.position();
+                zip.start_file(
+                    format!("{}.csv", entity.as_str()),
+                    SimpleFileOptions::default(),
+                )
+                .map_err(export_error)?;
+                self.ready_until = entry_start;
+            }
+        }
+
+        self.entities += 1;
+        self.columns = None;
+        self.rows = 0;
+
+        Ok(())
+    }
+
+    /// The columns of an entity's CSV are the keys of its first row.
+    /// Nested values, like JSONB columns, are written as JSON.
+    pub fn write_row(&mut self, row: &Value) -> HttpResult<()> {
+        match &mut self.format {
+            FormatWriter::Json(json) => {
+                if self.rows > 0 {
+                    json.write_all(b",").map_err(export_error)?;
+                }
+                serde_json::to_writer(&mut *json, row).map_err(export_error)?;
+                self.ready_until = json.position();
+            }
+            FormatWriter::CsvZip(zip) => {
+                let Some(row) = row.as_object() else {
+                    return Ok(());
+                };
+                let columns = self
+                    .columns
+                    .get_or_insert_with(|| row.keys().cloned().collect());
+                if self.rows == 0 {
+                    zip.write_all(csv_line(columns.iter().map(|column| escape(column))).as_bytes())
+                        .map_err(export_error)?;
+                }
+
+                let values = columns.iter().map(|column| match row.get(column) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(value)) => escape(value),
+                    Some(value) => escape(&value.to_string()),
+                });
+                zip.write_all(csv_line(values).as_bytes())
+                    .map_err(export_error)?;
+            }
+        }
+
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Takes the bytes that are already final, once there are at least
+    /// `min_len` of them.
+    pub fn take_ready(&mut self, min_len: usize) -> Option<Vec<u8>> {
+        let ready = self.output.take_until(self.ready_until, min_len);
+        (!ready.is_empty()).then_some(ready)
+    }
+
+    /// Completes the export, returning the bytes not taken yet.
+    pub fn finish(mut self) -> HttpResult<Vec<u8>> {
+        self.end_entity()?;
+
+        match self.format {
+            FormatWriter::Json(mut json) => {
+                let end = if self.entities == 0 { "{}" } else { "}" };
+                json.write_all(end.as_bytes()).map_err(export_error)?;
+            }
+            FormatWriter::CsvZip(zip) => {
+                zip.finish().map_err(export_error)?;
+            }
+        }
+
+        Ok(self.output.take_until(u64::MAX, 0))
+    }
+
+    fn end_entity(&mut self) -> HttpResult<()> {
+        if self.entities == 0 {
+            return Ok(());
+        }
+
+        match &mut self.format {
+            FormatWriter::Json(json) => json.write_all(b"]").map_err(export_error),
+            // Entities without rows still get their file
+            FormatWriter::CsvZip(zip) if self.rows == 0 => {
+                zip.write_all(b"\n").map_err(export_error)
+            }
+            FormatWriter::CsvZip(_) => Ok(()),
+        }
+    }
+}
+
+fn csv_line(values: impl Iterator<Item = String>) -> String {
+    let mut line = values.collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+/// Output of an export, handed over in chunks while it is written. Zip seeks
+/// back to complete each file's header, so only the bytes before the current
+/// file are taken.
+#[derive(Clone, Default)]
+struct ExportOutput(Arc<Mutex<OutputBuffer>>);
+
+#[derive(Default)]
+struct OutputBuffer {
+    bytes: Vec<u8>,
+    /// Offset of the first byte not taken yet.
+    offset: u64,
+    position: u64,
+}
+
+impl ExportOutput {
+    fn buffer(&self) -> MutexGuard<'_, OutputBuffer> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn position(&self) -> u64 {
+        self.buffer().position
+    }
+
+    fn take_until(&self, until: u64, min_len: usize) -> Vec<u8> {
+        let mut buffer = self.buffer();
+        let len = (until.saturating_sub(buffer.offset) as usize).min(buffer.bytes.len());
+        if len == 0 || len < min_len {
+            return Vec::new();
+        }
+
+        buffer.offset += len as u64;
+        buffer.bytes.drain(..len).collect()
+    }
+}
+
+impl Write for ExportOutput {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        let mut buffer = self.buffer();
+        let start = (buffer.position - buffer.offset) as usize;
+        let overwritten = data.len().min(buffer.bytes.len().saturating_sub(start));
+
+        buffer.bytes[start..start + overwritten].copy_from_slice(&data[..overwritten]);
+        buffer.bytes.extend_from_slice(&data[overwritten..]);
+        buffer.position += data.len() as u64;
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for ExportOutput {
+    fn seek(&mut self, position: SeekFrom) -> std::io::Result<u64> {
+        let mut buffer = self.buffer();
+        let end = buffer.offset + buffer.bytes.len() as u64;
+        let target = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => end.checked_add_signed(delta),
+            SeekFrom::Current(delta) => buffer.position.checked_add_signed(delta),
+        };
+
+        match target {
+            Some(target) if target >= buffer.offset && target <= end => {
+                buffer.position = target;
+                Ok(target)
+            }
+            _ => Err(std::io::Error::other(
+                "Export output was already sent up to this position",
+            )),
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn export_error(err: impl std::fmt::Display) -> Box<HttpError> {
+    Box::new(HttpError::internal(format!(
+        "Failed to build export: {err}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use serde_json::json;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    /// Writes the export taking every chunk as soon as it is ready, like the
+    /// streamed response does.
+    fn export(format: ExportFormat) -> Vec<u8> {
+        let entities = vec![
+            (
+                ExportEntity::Debts,
+                vec![
+                    json!({"id": "a", "description": "Luz, água", "total_amount": 150.9, "deleted_by": null}),
+                    json!({"id": "b", "description": "Aluguel", "total_amount": 1200, "deleted_by": {"user_id": "u"}}),
+                ],
+            ),
+            (ExportEntity::Incomes, vec![]),
+        ];
+
+        let mut writer = ExportWriter::new(format);
+        let mut content = Vec::new();
+        for (entity, rows) in entities {
+            writer.start_entity(entity).unwrap();
+            for row in rows {
+                writer.write_row(&row).unwrap();
+                content.extend(writer.take_ready(1).unwrap_or_default());
+            }
+        }
+        content.extend(writer.finish().unwrap());
+
+        content
+    }
+
+    #[test]
+    fn test_csv_export_zips_one_file_per_entity() {
+        let content = export(ExportFormat::Csv);
+
+        let mut archive = ZipArchive::new(std::io::Cursor::new(content)).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut debts = String::new();
+        archive
+            .by_name("debts.csv")
+            .unwrap()
+            .read_to_string(&mut debts)
+            .unwrap();
+        assert_eq!(
+            debts,
+            "deleted_by,description,id,total_amount\n\
+             ,\"Luz, água\",a,150.9\n\
+             \"{\"\"user_id\"\":\"\"u\"\"}\",Aluguel,b,1200\n"
+        );
+
+        let mut incomes = String::new();
+        archive
+            .by_name("incomes.csv")
+            .unwrap()
+            .read_to_string(&mut incomes)
+            .unwrap();
+        assert_eq!(incomes, "\n");
+    }
+
+    #[test]
+    fn test_json_export_groups_rows_by_entity() {
+        let document: Value = serde_json::from_slice(&export(ExportFormat::Json)).unwrap();
+
+        assert_eq!(document["debts"].as_array().unwrap().len(), 2);
+        assert_eq!(document["incomes"], json!([]));
+    }
+}
@@ -1,12 +1,22 @@
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use util::{from_row_constructor, getters};
 use uuid::Uuid;
 
+pub mod adjustment;
+pub mod billing_cycle;
 pub mod configuration;
+pub mod credit_limit;
+pub mod goal;
+pub mod investment;
+pub mod ledger;
 
 use crate::modules::finance_manager::{
-    domain::financial_instrument::configuration::InstrumentConfiguration,
+    domain::financial_instrument::{
+        billing_cycle::BillingCycle, configuration::InstrumentConfiguration,
+        credit_limit::CreditLimitUsage,
+    },
     handler::financial_instrument::use_cases::UpdateFinancialInstrumentRequest,
 };
 
@@ -67,9 +77,10 @@ impl FinancialInstrument {
         name: String,
         owner: String,
         instrument_type: FinancialInstrumentType,
-        configuration: InstrumentConfiguration,
+        mut configuration: InstrumentConfiguration,
     ) -> Self {
         let uuid = Uuid::new_v4();
+        configuration.date_yield_policy(None, Utc::now().date_naive());
 
         Self {
             id: uuid,
@@ -88,6 +99,23 @@ impl FinancialInstrument {
         self.configuration.default_due_date()
     }
 
+    /// Billing cycle a purchase is charged on. Only credit cards with a
+    /// closing day have one.
+    pub fn billing_cycle(&self, purchase_date: NaiveDate) -> Option<BillingCycle> {
+        match self.instrument_type {
+            FinancialInstrumentType::CreditCard => self.configuration.billing_cycle(purchase_date),
+            _ => None,
+        }
+    }
+
+    /// Only credit cards have a credit limit.
+    pub fn credit_limit(&self) -> Option<Decimal> {
+        match self.instrument_type {
+            FinancialInstrumentType::CreditCard => self.configuration.credit_limit,
+            _ => None,
+        }
+    }
+
     pub fn update(&mut self, request: &UpdateFinancialInstrumentRequest) {
         if let Some(name) = &request.name {
             self.name = name.clone();
@@ -99,13 +127,26 @@ impl FinancialInstrument {
             self.instrument_type = instrument_type.clone();
         }
         if let Some(configuration) = &request.configuration {
-            self.configuration = configuration.clone();
+            let mut configuration = configuration.clone();
+            configuration.date_yield_policy(Some(&self.configuration), Utc::now().date_naive());
+            self.configuration = configuration;
         }
 
         self.updated_at = Some(Utc::now());
     }
 }
 
+/// Financial instrument as listed to the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancialInstrumentSummary {
+    #[serde(flatten)]
+    pub instrument: FinancialInstrument,
+    /// Only for credit cards with a credit limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_limit_usage: Option<CreditLimitUsage>,
+}
+
 getters! {
     FinancialInstrument {
         id: Uuid,
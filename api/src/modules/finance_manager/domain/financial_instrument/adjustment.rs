@@ -0,0 +1,69 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::handler::ledger::use_cases::CreateAdjustmentRequest;
+
+/// Manual correction of a financial instrument balance. Positive amounts
+/// credit the account, negative amounts debit it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAdjustment {
+    id: Uuid,
+    client_id: Uuid,
+    financial_instrument_id: Uuid,
+    description: String,
+    amount: Decimal,
+    adjustment_date: NaiveDate,
+    created_at: DateTime<Utc>,
+}
+
+impl AccountAdjustment {
+    pub fn from_request(
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        request: CreateAdjustmentRequest,
+    ) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            client_id,
+            financial_instrument_id,
+            description: request.description,
+            amount: request.amount,
+            adjustment_date: request.adjustment_date.unwrap_or(now.date_naive()),
+            created_at: now,
+        }
+    }
+}
+
+getters! {
+    AccountAdjustment {
+        id: Uuid,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        description: String,
+        amount: Decimal,
+        adjustment_date: NaiveDate,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for AccountAdjustment {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            description: row.get("description"),
+            amount: row.get("amount"),
+            adjustment_date: row.get("adjustment_date"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
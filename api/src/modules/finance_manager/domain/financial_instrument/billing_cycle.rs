@@ -0,0 +1,122 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use util::date::date_with_day_or_last;
+
+use crate::modules::finance_manager::domain::debt::invoice::reference_month_as_date;
+
+/// Statement period of a credit card. Purchases made from the previous closing
+/// date up to the day before `closing_date` are billed on the invoice of
+/// `reference_date`, which is due on `due_date`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BillingCycle {
+    /// Mês de competência, always the first day of the closing month.
+    pub reference_date: NaiveDate,
+    pub start_date: NaiveDate,
+    pub closing_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub closing_day: u32,
+    pub due_day: u32,
+}
+
+impl BillingCycle {
+    /// Cycle closing in the month of `reference_date`. When the due day is not
+    /// after the closing day the invoice is due in the following month.
+    pub fn for_reference(closing_day: u32, due_day: u32, reference_date: NaiveDate) -> Self {
+        let reference_date = reference_month_as_date(reference_date);
+        let previous_month = reference_date - Months::new(1);
+        let due_month = if due_day > closing_day {
+            reference_date
+        } else {
+            reference_date + Months::new(1)
+        };
+
+        Self {
+            reference_date,
+            start_date: date_with_day_or_last(
+                previous_month.year(),
+                previous_month.month(),
+                closing_day,
+            ),
+            closing_date: date_with_day_or_last(
+                reference_date.year(),
+                reference_date.month(),
+                closing_day,
+            ),
+            due_date: date_with_day_or_last(due_month.year(), due_month.month(), due_day),
+            closing_day,
+            due_day,
+        }
+    }
+
+    /// Cycle the purchase is billed on. Purchases on the closing day already
+    /// go to the next invoice.
+    pub fn for_purchase(closing_day: u32, due_day: u32, purchase_date: NaiveDate) -> Self {
+        let cycle = Self::for_reference(closing_day, due_day, purchase_date);
+
+        if purchase_date >= cycle.closing_date {
+            cycle.next()
+        } else {
+            cycle
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        Self::for_reference(
+            self.closing_day,
+            self.due_day,
+            self.reference_date + Months::new(1),
+        )
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.start_date && date < self.closing_date
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_purchase_before_closing_day_is_billed_this_month() {
+        let cycle = BillingCycle::for_purchase(3, 10, date(10, 2));
+
+        assert_eq!(cycle.reference_date, date(10, 1));
+        assert_eq!(cycle.start_date, date(9, 3));
+        assert_eq!(cycle.closing_date, date(10, 3));
+        assert_eq!(cycle.due_date, date(10, 10));
+        assert!(cycle.contains(date(10, 2)));
+    }
+
+    #[test]
+    fn test_purchase_on_closing_day_is_billed_next_month() {
+        let cycle = BillingCycle::for_purchase(3, 10, date(10, 3));
+
+        assert_eq!(cycle.reference_date, date(11, 1));
+        assert_eq!(cycle.due_date, date(11, 10));
+        assert_eq!(cycle.next().due_date, date(12, 10));
+    }
+
+    #[test]
+    fn test_due_day_before_closing_day_is_due_next_month() {
+        let cycle = BillingCycle::for_purchase(25, 5, date(1, 31));
+
+        assert_eq!(cycle.reference_date, date(2, 1));
+        assert_eq!(cycle.closing_date, date(2, 25));
+        assert_eq!(cycle.due_date, date(3, 5));
+    }
+
+    #[test]
+    fn test_closing_day_is_clamped_to_month_length() {
+        let cycle = BillingCycle::for_reference(31, 10, date(2, 1));
+
+        assert_eq!(cycle.start_date, date(1, 31));
+        assert_eq!(cycle.closing_date, date(2, 28));
+        assert_eq!(cycle.due_date, date(3, 10));
+    }
+}
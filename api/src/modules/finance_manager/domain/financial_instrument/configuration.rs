@@ -1,10 +1,43 @@
 use chrono::{Datelike, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::modules::finance_manager::domain::{
+    debt::late_fee::LateFeePolicy,
+    financial_instrument::{
+        billing_cycle::BillingCycle, credit_limit::CreditLimitEnforcement, investment::YieldPolicy,
+    },
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct InstrumentConfiguration {
     pub default_due_date: Option<u32>,
+    /// Day the credit card statement closes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closing_day: Option<u32>,
+    /// Applied to late payments of debts without a policy of their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_fee_policy: Option<LateFeePolicy>,
+    /// Balance of the account before its first ledger entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opening_balance: Option<Decimal>,
+    /// How far below zero a debit account may go ("cheque especial").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overdraft_limit: Option<Decimal>,
+    /// Limit of a credit card, taken by its unpaid debts and installments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_limit: Option<Decimal>,
+    /// Whether charges over the credit limit are only warned about or rejected.
+    #[serde(default)]
+    pub credit_limit_enforcement: CreditLimitEnforcement,
+    /// How an investment box yields, accrued daily by the yield job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yield_policy: Option<YieldPolicy>,
+    /// First day yielding under the policy. Defaults to the day it was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yield_since: Option<NaiveDate>,
 }
 
 impl InstrumentConfiguration {
@@ -18,4 +51,79 @@ impl InstrumentConfiguration {
             }
         })
     }
+
+    /// Billing cycle of a purchase, when both closing and due days are set.
+    pub fn billing_cycle(&self, purchase_date: NaiveDate) -> Option<BillingCycle> {
+        let closing_day = self.closing_day?;
+        let due_day = self.default_due_date?;
+
+        Some(BillingCycle::for_purchase(
+            closing_day,
+            due_day,
+            purchase_date,
+        ))
+    }
+
+    /// Billing cycle closing in the month of `reference_date`.
+    pub fn reference_billing_cycle(&self, reference_date: NaiveDate) -> Option<BillingCycle> {
+        Some(BillingCycle::for_reference(
+            self.closing_day?,
+            self.default_due_date?,
+            reference_date,
+        ))
+    }
+
+    /// Keeps the day the yield policy started from the `previous`
+    /// configuration, or starts it `today` for a new policy.
+    pub fn date_yield_policy(&mut self, previous: Option<&Self>, today: NaiveDate) {
+        if self.yield_policy.is_none() {
+            self.yield_since = None;
+            return;
+        }
+
+        self.yield_since = self
+            .yield_since
+            .or(previous
+                .filter(|previous| previous.yield_policy.is_some())
+                .and_then(|previous| previous.yield_since))
+            .or(Some(today));
+    }
+
+    pub fn validate(&self) -> HttpResult<()> {
+        for day in [self.default_due_date, self.closing_day]
+            .into_iter()
+            .flatten()
+        {
+            if !(1..=31).contains(&day) {
+                return Err(Box::new(HttpError::bad_request(
+                    "Due and closing days must be between 1 and 31",
+                )));
+            }
+        }
+
+        if self
+            .overdraft_limit
+            .is_some_and(|limit| limit < Decimal::ZERO)
+        {
+            return Err(Box::new(HttpError::bad_request(
+                "Overdraft limit cannot be negative",
+            )));
+        }
+
+        if self.credit_limit.is_some_and(|limit| limit < Decimal::ZERO) {
+            return Err(Box::new(HttpError::bad_request(
+                "Credit limit cannot be negative",
+            )));
+        }
+
+        if let Some(late_fee_policy) = &self.late_fee_policy {
+            late_fee_policy.validate()?;
+        }
+
+        if let Some(yield_policy) = &self.yield_policy {
+            yield_policy.validate()?;
+        }
+
+        Ok(())
+    }
 }
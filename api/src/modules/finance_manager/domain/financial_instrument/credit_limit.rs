@@ -0,0 +1,127 @@
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::finance_manager::domain::debt::Debt;
+
+/// What happens when a charge exceeds the available limit of a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CreditLimitEnforcement {
+    /// The charge is accepted with a warning.
+    #[default]
+    Warn,
+    Reject,
+}
+
+/// How much of a credit card limit is taken by unpaid debts charged to it.
+/// Installments still to be billed count in full, as they do on the card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditLimitUsage {
+    pub credit_limit: Decimal,
+    pub used_amount: Decimal,
+    /// Negative once the limit was exceeded.
+    pub available_amount: Decimal,
+    pub usage_percentage: Decimal,
+}
+
+impl CreditLimitUsage {
+    pub fn new(credit_limit: Decimal, debts: &[Debt]) -> Self {
+        let used_amount: Decimal = debts
+            .iter()
+            .map(|debt| (*debt.remaining_amount()).max(Decimal::ZERO))
+            .sum();
+
+        Self::from_used_amount(credit_limit, used_amount)
+    }
+
+    /// Usage of an amount already summed up, e.g. by the database.
+    pub fn from_used_amount(credit_limit: Decimal, used_amount: Decimal) -> Self {
+        let usage_percentage = if credit_limit.is_zero() {
+            Decimal::ZERO
+        } else {
+            (used_amount / credit_limit * Decimal::ONE_HUNDRED).round_dp(2)
+        };
+
+        Self {
+            credit_limit,
+            used_amount,
+            available_amount: credit_limit - used_amount,
+            usage_percentage,
+        }
+    }
+
+    /// Checks a new charge against the available limit. Returns a warning
+    /// when it exceeds the limit and the card only warns about it.
+    pub fn check_charge(
+        &self,
+        amount: Decimal,
+        enforcement: CreditLimitEnforcement,
+    ) -> HttpResult<Option<String>> {
+        if amount <= self.available_amount {
+            return Ok(None);
+        }
+
+        let message = format!(
+            "Charge of {:.2} exceeds the available credit limit ({:.2})",
+            amount, self.available_amount
+        );
+
+        match enforcement {
+            CreditLimitEnforcement::Warn => Ok(Some(message)),
+            CreditLimitEnforcement::Reject => Err(Box::new(HttpError::bad_request(message))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn debt(amount: i64, paid: i64) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Compra".to_string(),
+            Decimal::from(amount),
+            Some(Decimal::from(paid)),
+            None,
+            NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_usage_counts_unpaid_amounts() {
+        let usage = CreditLimitUsage::new(Decimal::from(1000), &[debt(600, 200), debt(300, 0)]);
+
+        assert_eq!(usage.used_amount, Decimal::from(700));
+        assert_eq!(usage.available_amount, Decimal::from(300));
+        assert_eq!(usage.usage_percentage, Decimal::from(70));
+    }
+
+    #[test]
+    fn test_charge_over_limit_warns_or_rejects() {
+        let usage = CreditLimitUsage::new(Decimal::from(1000), &[debt(900, 0)]);
+
+        assert_eq!(
+            usage
+                .check_charge(Decimal::from(100), CreditLimitEnforcement::Reject)
+                .unwrap(),
+            None
+        );
+        assert!(usage
+            .check_charge(Decimal::from(101), CreditLimitEnforcement::Warn)
+            .unwrap()
+            .is_some());
+        assert!(usage
+            .check_charge(Decimal::from(101), CreditLimitEnforcement::Reject)
+            .is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::{getters, DeletedBy};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::financial_instrument::ledger::{Ledger, LedgerEntryKind},
+    handler::investment::use_cases::{CreateGoalRequest, UpdateGoalRequest},
+};
+
+/// Amount the client wants to have saved in an investment box by a date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavingsGoal {
+    id: Uuid,
+    client_id: Uuid,
+    financial_instrument_id: Uuid,
+    name: String,
+    target_amount: Decimal,
+    target_date: NaiveDate,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_by: Option<DeletedBy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GoalStatus {
+    InProgress,
+    Achieved,
+    /// Target date passed without reaching the target amount.
+    Missed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalProgress {
+    #[serde(flatten)]
+    pub goal: SavingsGoal,
+    pub status: GoalStatus,
+    /// Balance of the investment box.
+    pub current_amount: Decimal,
+    /// Part of the balance that came from yields.
+    pub yield_amount: Decimal,
+    pub remaining_amount: Decimal,
+    pub progress_percentage: Decimal,
+    /// Monthly saving needed to reach the target on time.
+    pub monthly_contribution_needed: Decimal,
+}
+
+impl SavingsGoal {
+    pub fn from_request(client_id: Uuid, request: CreateGoalRequest) -> HttpResult<Self> {
+        validate_target(request.target_amount)?;
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            client_id,
+            financial_instrument_id: request.financial_instrument_id,
+            name: request.name,
+            target_amount: request.target_amount,
+            target_date: request.target_date,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_by: None,
+        })
+    }
+
+    pub fn update(&mut self, request: UpdateGoalRequest) -> HttpResult<()> {
+        if let Some(target_amount) = request.target_amount {
+            validate_target(target_amount)?;
+            self.target_amount = target_amount;
+        }
+        if let Some(name) = request.name {
+            self.name = name;
+        }
+        if let Some(target_date) = request.target_date {
+            self.target_date = target_date;
+        }
+
+        self.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to manage this goal",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Progress of the goal given the ledger of its investment box.
+    pub fn progress(&self, ledger: &Ledger, today: NaiveDate) -> GoalProgress {
+        let current_amount = ledger.balance_at(today);
+        let yield_amount = ledger
+            .entries()
+            .iter()
+            .filter(|entry| entry.kind == LedgerEntryKind::Yield && entry.date <= today)
+            .map(|entry| entry.amount)
+            .sum();
+        let remaining_amount = (self.target_amount - current_amount).max(Decimal::ZERO);
+
+        let status = if remaining_amount.is_zero() {
+            GoalStatus::Achieved
+        } else if today > self.target_date {
+            GoalStatus::Missed
+        } else {
+            GoalStatus::InProgress
+        };
+
+        let monthly_contribution_needed = match status {
+            GoalStatus::InProgress => {
+                (remaining_amount / Decimal::from(self.months_left(today))).round_dp(2)
+            }
+            _ => Decimal::ZERO,
+        };
+
+        GoalProgress {
+            goal: self.clone(),
+            status,
+            current_amount,
+            yield_amount,
+            remaining_amount,
+            progress_percentage: (current_amount.max(Decimal::ZERO) / self.target_amount
+                * Decimal::ONE_HUNDRED)
+                .min(Decimal::ONE_HUNDRED)
+                .round_dp(2),
+            monthly_contribution_needed,
+        }
+    }
+
+    /// Months of contributions left until the target date, counting the
+    /// current one.
+    fn months_left(&self, today: NaiveDate) -> i32 {
+        let months = (self.target_date.year() - today.year()) * 12
+            + self.target_date.month() as i32
+            - today.month() as i32;
+
+        months.max(1)
+    }
+}
+
+fn validate_target(target_amount: Decimal) -> HttpResult<()> {
+    if target_amount <= Decimal::ZERO {
+        return Err(Box::new(HttpError::bad_request(
+            "Goal target amount must be greater than zero",
+        )));
+    }
+
+    Ok(())
+}
+
+getters! {
+    SavingsGoal {
+        id: Uuid,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        name: String,
+        target_amount: Decimal,
+        target_date: NaiveDate,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+        deleted_by: Option<DeletedBy>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for SavingsGoal {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            name: row.get("name"),
+            target_amount: row.get("target_amount"),
+            target_date: row.get("target_date"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            deleted_by: row
+                .get::<Option<Json<DeletedBy>>, _>("deleted_by")
+                .map(|j| j.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::financial_instrument::{
+            adjustment::AccountAdjustment, configuration::InstrumentConfiguration,
+            FinancialInstrument, FinancialInstrumentType,
+        },
+        handler::ledger::use_cases::CreateAdjustmentRequest,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn ledger_with_balance(amount: i64) -> (FinancialInstrument, Ledger) {
+        let instrument = FinancialInstrument::new(
+            Uuid::new_v4(),
+            "Caixinha".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::InvestmentBox,
+            InstrumentConfiguration::default(),
+        );
+        let deposit = AccountAdjustment::from_request(
+            *instrument.client_id(),
+            *instrument.id(),
+            CreateAdjustmentRequest {
+                amount: Decimal::from(amount),
+                description: "Depósito".to_string(),
+                adjustment_date: Some(date(10, 1)),
+            },
+        );
+        let ledger = Ledger::new(&instrument, &[], &[], &[deposit], &[], &[]);
+
+        (instrument, ledger)
+    }
+
+    fn goal(instrument: &FinancialInstrument, target: i64, target_date: NaiveDate) -> SavingsGoal {
+        SavingsGoal::from_request(
+            *instrument.client_id(),
+            CreateGoalRequest {
+                financial_instrument_id: *instrument.id(),
+                name: "Viagem".to_string(),
+                target_amount: Decimal::from(target),
+                target_date,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let (instrument, ledger) = ledger_with_balance(1000);
+        let progress = goal(&instrument, 4000, date(12, 20)).progress(&ledger, date(10, 17));
+
+        assert_eq!(progress.status, GoalStatus::InProgress);
+        assert_eq!(progress.remaining_amount, Decimal::from(3000));
+        assert_eq!(progress.progress_percentage, Decimal::from(25));
+        assert_eq!(progress.monthly_contribution_needed, Decimal::from(1500));
+    }
+
+    #[test]
+    fn test_goal_status() {
+        let (instrument, ledger) = ledger_with_balance(1000);
+
+        let achieved = goal(&instrument, 1000, date(12, 20)).progress(&ledger, date(10, 17));
+        assert_eq!(achieved.status, GoalStatus::Achieved);
+        assert_eq!(achieved.progress_percentage, Decimal::ONE_HUNDRED);
+
+        let missed = goal(&instrument, 2000, date(10, 10)).progress(&ledger, date(10, 17));
+        assert_eq!(missed.status, GoalStatus::Missed);
+        assert_eq!(missed.monthly_contribution_needed, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,308 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::financial_instrument::{
+    ledger::Ledger, FinancialInstrument,
+};
+
+/// How an investment box yields. Rates are annual percentages (e.g. `12` =
+/// 12% a.a.), accrued daily as simple interest over 365 days.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum YieldPolicy {
+    #[serde(rename_all = "camelCase")]
+    FixedRate { annual_rate: Decimal },
+    /// Percentage of a reference rate such as the CDI, whose current value is
+    /// kept up to date by hand (e.g. 100% of 10.65% a.a.).
+    #[serde(rename_all = "camelCase")]
+    ReferenceRate {
+        reference_annual_rate: Decimal,
+        percentage: Decimal,
+    },
+}
+
+impl YieldPolicy {
+    pub fn validate(&self) -> HttpResult<()> {
+        let negative = match self {
+            YieldPolicy::FixedRate { annual_rate } => *annual_rate < Decimal::ZERO,
+            YieldPolicy::ReferenceRate {
+                reference_annual_rate,
+                percentage,
+            } => *reference_annual_rate < Decimal::ZERO || *percentage < Decimal::ZERO,
+        };
+
+        if negative {
+            return Err(Box::new(HttpError::bad_request(
+                "Yield rates cannot be negative",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Effective annual percentage of the box.
+    pub fn annual_rate(&self) -> Decimal {
+        match self {
+            YieldPolicy::FixedRate { annual_rate } => *annual_rate,
+            YieldPolicy::ReferenceRate {
+                reference_annual_rate,
+                percentage,
+            } => *reference_annual_rate * *percentage / Decimal::ONE_HUNDRED,
+        }
+    }
+
+    /// Yield of one day over `balance`, at full precision. Negative balances
+    /// don't yield.
+    pub fn daily_yield(&self, balance: Decimal) -> Decimal {
+        if balance <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        balance * self.annual_rate() / Decimal::ONE_HUNDRED / Decimal::from(365)
+    }
+}
+
+/// Yield accrued by an investment box for one day. Only whole cents are
+/// credited; the rest is carried to the next days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YieldEntry {
+    id: Uuid,
+    client_id: Uuid,
+    financial_instrument_id: Uuid,
+    accrual_date: NaiveDate,
+    /// Balance the yield was calculated over.
+    balance: Decimal,
+    /// Annual percentage in effect on the accrual date.
+    annual_rate: Decimal,
+    /// Yield of the day, at full precision.
+    accrued_amount: Decimal,
+    /// Whole cents credited on the day, out of what was accrued so far.
+    amount: Decimal,
+    created_at: DateTime<Utc>,
+}
+
+impl YieldEntry {
+    /// Yields of every day after the last accrual up to `until`, inclusive.
+    /// The first accrual starts on the day the policy was configured, so the
+    /// history of the account before it doesn't yield. Each day yields over
+    /// the balance at its end, including the yields credited before it.
+    /// `yields` are the ones accrued so far, whose fractions of a cent are
+    /// still to be credited.
+    pub fn accrue(
+        instrument: &FinancialInstrument,
+        ledger: &Ledger,
+        yields: &[YieldEntry],
+        until: NaiveDate,
+    ) -> Vec<Self> {
+        let Some(policy) = &instrument.configuration().yield_policy else {
+            return Vec::new();
+        };
+
+        let last_accrual = yields.iter().map(|entry| entry.accrual_date).max();
+        let start = match last_accrual {
+            Some(date) => date.succ_opt(),
+            None => Some(instrument.configuration().yield_since.unwrap_or(until)),
+        };
+        let Some(start) = start else {
+            return Vec::new();
+        };
+
+        let now = Utc::now();
+        let mut credited = Decimal::ZERO;
+        let mut pending: Decimal = yields
+            .iter()
+            .map(|entry| entry.accrued_amount - entry.amount)
+            .sum();
+        let mut accrued = Vec::new();
+
+        for date in start.iter_days().take_while(|date| *date <= until) {
+            let balance = ledger.balance_at(date) + credited;
+            let accrued_amount = policy.daily_yield(balance);
+            pending += accrued_amount;
+
+            let amount = pending.round_dp_with_strategy(2, RoundingStrategy::ToZero);
+            pending -= amount;
+            credited += amount;
+            accrued.push(Self {
+                id: Uuid::new_v4(),
+                client_id: *instrument.client_id(),
+                financial_instrument_id: *instrument.id(),
+                accrual_date: date,
+                balance,
+                annual_rate: policy.annual_rate(),
+                accrued_amount,
+                amount,
+                created_at: now,
+            });
+        }
+
+        accrued
+    }
+}
+
+getters! {
+    YieldEntry {
+        id: Uuid,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        accrual_date: NaiveDate,
+        balance: Decimal,
+        annual_rate: Decimal,
+        accrued_amount: Decimal,
+        amount: Decimal,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for YieldEntry {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            accrual_date: row.get("accrual_date"),
+            balance: row.get("balance"),
+            annual_rate: row.get("annual_rate"),
+            accrued_amount: row.get("accrued_amount"),
+            amount: row.get("amount"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::financial_instrument::{
+            adjustment::AccountAdjustment, configuration::InstrumentConfiguration,
+            FinancialInstrumentType,
+        },
+        handler::ledger::use_cases::CreateAdjustmentRequest,
+    };
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn investment_box(policy: YieldPolicy, since: u32) -> FinancialInstrument {
+        FinancialInstrument::new(
+            Uuid::new_v4(),
+            "Caixinha".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::InvestmentBox,
+            InstrumentConfiguration {
+                yield_policy: Some(policy),
+                yield_since: Some(date(since)),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn deposit(instrument: &FinancialInstrument, amount: i64, day: u32) -> AccountAdjustment {
+        AccountAdjustment::from_request(
+            *instrument.client_id(),
+            *instrument.id(),
+            CreateAdjustmentRequest {
+                amount: Decimal::from(amount),
+                description: "Depósito".to_string(),
+                adjustment_date: Some(date(day)),
+            },
+        )
+    }
+
+    #[test]
+    fn test_reference_rate_policy() {
+        let policy = YieldPolicy::ReferenceRate {
+            reference_annual_rate: Decimal::new(1000, 2),
+            percentage: Decimal::from(110),
+        };
+
+        assert_eq!(policy.annual_rate(), Decimal::from(11));
+        assert_eq!(policy.daily_yield(Decimal::from(36_500)), Decimal::from(11));
+        assert_eq!(policy.daily_yield(Decimal::from(-100)), Decimal::ZERO);
+        assert!(YieldPolicy::FixedRate {
+            annual_rate: Decimal::from(-1)
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_accrual_compounds_and_resumes_after_last_entry() {
+        let instrument = investment_box(
+            YieldPolicy::FixedRate {
+                annual_rate: Decimal::from(365),
+            },
+            1,
+        );
+        let adjustments = [deposit(&instrument, 100, 1)];
+
+        let ledger = Ledger::new(&instrument, &[], &[], &adjustments, &[], &[]);
+        let yields = YieldEntry::accrue(&instrument, &ledger, &[], date(2));
+
+        assert_eq!(yields.len(), 2);
+        assert_eq!(*yields[0].amount(), Decimal::ONE);
+        assert_eq!(*yields[1].balance(), Decimal::from(101));
+        assert_eq!(*yields[1].amount(), Decimal::new(101, 2));
+
+        let ledger = Ledger::new(&instrument, &[], &[], &adjustments, &[], &yields);
+        let next = YieldEntry::accrue(&instrument, &ledger, &yields, date(3));
+
+        assert_eq!(next.len(), 1);
+        assert_eq!(*next[0].accrual_date(), date(3));
+        assert!(YieldEntry::accrue(&instrument, &ledger, &yields, date(2)).is_empty());
+    }
+
+    #[test]
+    fn test_accrual_starts_when_the_policy_is_configured() {
+        let instrument = investment_box(
+            YieldPolicy::FixedRate {
+                annual_rate: Decimal::from(365),
+            },
+            5,
+        );
+        let adjustments = [deposit(&instrument, 100, 1)];
+
+        let ledger = Ledger::new(&instrument, &[], &[], &adjustments, &[], &[]);
+        let yields = YieldEntry::accrue(&instrument, &ledger, &[], date(6));
+
+        assert_eq!(yields.len(), 2);
+        assert_eq!(*yields[0].accrual_date(), date(5));
+        assert_eq!(*yields[0].balance(), Decimal::from(100));
+    }
+
+    #[test]
+    fn test_small_balances_yield_once_accruals_reach_a_cent() {
+        let instrument = investment_box(
+            YieldPolicy::FixedRate {
+                annual_rate: Decimal::from(10),
+            },
+            1,
+        );
+        let adjustments = [deposit(&instrument, 10, 1)];
+
+        let ledger = Ledger::new(&instrument, &[], &[], &adjustments, &[], &[]);
+        let yields = YieldEntry::accrue(&instrument, &ledger, &[], date(30));
+
+        assert_eq!(yields.len(), 30);
+        assert!(yields[0].amount().is_zero());
+        let credited: Decimal = yields.iter().map(|entry| *entry.amount()).sum();
+        assert_eq!(credited, Decimal::new(8, 2));
+
+        // Fractions of a cent carry over between runs of the job
+        let first = YieldEntry::accrue(&instrument, &ledger, &[], date(15));
+        let ledger = Ledger::new(&instrument, &[], &[], &adjustments, &[], &first);
+        let rest = YieldEntry::accrue(&instrument, &ledger, &first, date(30));
+
+        let credited: Decimal = first.iter().chain(&rest).map(|entry| *entry.amount()).sum();
+        assert_eq!(credited, Decimal::new(8, 2));
+    }
+}
@@ -0,0 +1,404 @@
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::{
+    financial_instrument::{
+        adjustment::AccountAdjustment, investment::YieldEntry, FinancialInstrument,
+        FinancialInstrumentType,
+    },
+    income::Income,
+    payment::Payment,
+    transfer::Transfer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LedgerEntryKind {
+    Income,
+    Payment,
+    Refund,
+    Adjustment,
+    TransferIn,
+    TransferOut,
+    Yield,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntry {
+    pub date: NaiveDate,
+    pub kind: LedgerEntryKind,
+    /// Income, payment, adjustment, transfer or yield that originated the entry.
+    pub reference_id: Uuid,
+    pub description: String,
+    /// Positive for credits, negative for debits.
+    pub amount: Decimal,
+    /// Running balance after the entry.
+    pub balance: Decimal,
+}
+
+/// Entries of a financial instrument in a period, with the balances around it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    pub financial_instrument_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub opening_balance: Decimal,
+    pub closing_balance: Decimal,
+    pub total_credits: Decimal,
+    pub total_debits: Decimal,
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// Balance history of a financial instrument, derived from the incomes it
+/// received, the payments made from it, their refunds, manual adjustments,
+/// transfers to or from other instruments and investment yields.
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    financial_instrument_id: Uuid,
+    opening_balance: Decimal,
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    /// Records not related to the instrument are ignored. Refunded payments
+    /// show up as a debit on the payment date and a credit on the refund date.
+    pub fn new(
+        instrument: &FinancialInstrument,
+        incomes: &[Income],
+        payments: &[Payment],
+        adjustments: &[AccountAdjustment],
+        transfers: &[Transfer],
+        yields: &[YieldEntry],
+    ) -> Self {
+        let instrument_id = *instrument.id();
+        let mut entries = Vec::new();
+
+        for income in incomes
+            .iter()
+            .filter(|income| income.financial_instrument_id() == &instrument_id)
+        {
+            entries.push(entry(
+                *income.reference(),
+                LedgerEntryKind::Income,
+                *income.id(),
+                income.description().clone(),
+                *income.amount(),
+            ));
+        }
+
+        for payment in payments
+            .iter()
+            .filter(|payment| payment.account_id() == &instrument_id)
+        {
+            entries.push(entry(
+                *payment.payment_date(),
+                LedgerEntryKind::Payment,
+                *payment.id(),
+                "Pagamento de dívida".to_string(),
+                -*payment.amount(),
+            ));
+
+            if let Some(refunded_at) = payment.refunded_at() {
+                entries.push(entry(
+                    *refunded_at,
+                    LedgerEntryKind::Refund,
+                    *payment.id(),
+                    "Estorno de pagamento".to_string(),
+                    *payment.amount(),
+                ));
+            }
+        }
+
+        for adjustment in adjustments
+            .iter()
+            .filter(|adjustment| adjustment.financial_instrument_id() == &instrument_id)
+        {
+            entries.push(entry(
+                *adjustment.adjustment_date(),
+                LedgerEntryKind::Adjustment,
+                *adjustment.id(),
+                adjustment.description().clone(),
+                *adjustment.amount(),
+            ));
+        }
+
+        for transfer in transfers {
+            let Some(amount) = transfer.amount_for(&instrument_id) else {
+                continue;
+            };
+            let kind = if amount < Decimal::ZERO {
+                LedgerEntryKind::TransferOut
+            } else {
+                LedgerEntryKind::TransferIn
+            };
+
+            entries.push(entry(
+                *transfer.transfer_date(),
+                kind,
+                *transfer.id(),
+                transfer.description().clone(),
+                amount,
+            ));
+        }
+
+        // Days whose accrual didn't reach a cent credit nothing
+        for yield_entry in yields.iter().filter(|yield_entry| {
+            yield_entry.financial_instrument_id() == &instrument_id
+                && !yield_entry.amount().is_zero()
+        }) {
+            entries.push(entry(
+                *yield_entry.accrual_date(),
+                LedgerEntryKind::Yield,
+                *yield_entry.id(),
+                "Rendimento".to_string(),
+                *yield_entry.amount(),
+            ));
+        }
+
+        // Credits first within a day, so an income received on the day of a
+        // payment covers it.
+        entries.sort_by_key(|entry| (entry.date, entry.amount < Decimal::ZERO));
+
+        let opening_balance = instrument
+            .configuration()
+            .opening_balance
+            .unwrap_or_default();
+        let mut balance = opening_balance;
+        for entry in entries.iter_mut() {
+            balance += entry.amount;
+            entry.balance = balance;
+        }
+
+        Self {
+            financial_instrument_id: instrument_id,
+            opening_balance,
+            entries,
+        }
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    pub fn balance(&self) -> Decimal {
+        self.entries
+            .last()
+            .map(|entry| entry.balance)
+            .unwrap_or(self.opening_balance)
+    }
+
+    /// Balance at the end of `date`.
+    pub fn balance_at(&self, date: NaiveDate) -> Decimal {
+        self.entries
+            .iter()
+            .take_while(|entry| entry.date <= date)
+            .last()
+            .map(|entry| entry.balance)
+            .unwrap_or(self.opening_balance)
+    }
+
+    pub fn statement(&self, from: NaiveDate, to: NaiveDate) -> HttpResult<Statement> {
+        if from > to {
+            return Err(Box::new(HttpError::bad_request(
+                "Statement start date must not be after its end date",
+            )));
+        }
+
+        let entries: Vec<LedgerEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.date >= from && entry.date <= to)
+            .cloned()
+            .collect();
+
+        let opening_balance = from
+            .pred_opt()
+            .map(|day_before| self.balance_at(day_before))
+            .unwrap_or(self.opening_balance);
+
+        Ok(Statement {
+            financial_instrument_id: self.financial_instrument_id,
+            from,
+            to,
+            opening_balance,
+            closing_balance: self.balance_at(to),
+            total_credits: entries
+                .iter()
+                .map(|entry| entry.amount.max(Decimal::ZERO))
+                .sum(),
+            total_debits: entries
+                .iter()
+                .map(|entry| (-entry.amount).max(Decimal::ZERO))
+                .sum(),
+            entries,
+        })
+    }
+
+    /// Debit accounts can't be overdrawn beyond their overdraft limit.
+    pub fn ensure_can_debit(
+        &self,
+        instrument: &FinancialInstrument,
+        amount: Decimal,
+    ) -> HttpResult<()> {
+        Self::ensure_balance_covers(instrument, self.balance(), amount)
+    }
+
+    /// Same as `ensure_can_debit`, for a balance computed elsewhere.
+    pub fn ensure_balance_covers(
+        instrument: &FinancialInstrument,
+        balance: Decimal,
+        amount: Decimal,
+    ) -> HttpResult<()> {
+        if instrument.instrument_type() != &FinancialInstrumentType::DebitAccount {
+            return Ok(());
+        }
+
+        let available = balance
+            + instrument
+                .configuration()
+                .overdraft_limit
+                .unwrap_or_default();
+
+        if amount > available {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Insufficient balance: payment amount ({:.2}) exceeds available balance ({:.2})",
+                amount, available
+            ))));
+        }
+
+        Ok(())
+    }
+}
+
+fn entry(
+    date: NaiveDate,
+    kind: LedgerEntryKind,
+    reference_id: Uuid,
+    description: String,
+    amount: Decimal,
+) -> LedgerEntry {
+    LedgerEntry {
+        date,
+        kind,
+        reference_id,
+        description,
+        amount,
+        balance: Decimal::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use util::DeletedBy;
+
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::{debt::Debt, financial_instrument::configuration::InstrumentConfiguration},
+        handler::{income::use_cases::CreateIncomeRequest, payment::use_cases::PaymentBasicData},
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn account(configuration: InstrumentConfiguration) -> FinancialInstrument {
+        FinancialInstrument::new(
+            Uuid::new_v4(),
+            "Conta".to_string(),
+            "Gabriel".to_string(),
+            FinancialInstrumentType::DebitAccount,
+            configuration,
+        )
+    }
+
+    fn income(account: &FinancialInstrument, amount: i64, day: NaiveDate) -> Income {
+        Income::from_request(
+            CreateIncomeRequest {
+                financial_instrument_id: *account.id(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(amount),
+                date_reference: day,
+            },
+            *account.client_id(),
+        )
+    }
+
+    fn payment(account: &FinancialInstrument, amount: i64, day: NaiveDate) -> Payment {
+        let debt = Debt::new(
+            *account.client_id(),
+            "Aluguel".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            day,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        Payment::new(
+            &debt,
+            account.id(),
+            &PaymentBasicData {
+                payment_date: day,
+                amount: None,
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_statement_running_balance() {
+        let account = account(InstrumentConfiguration {
+            opening_balance: Some(Decimal::from(100)),
+            ..Default::default()
+        });
+        let incomes = [income(&account, 1000, date(10, 5))];
+        let mut refunded = payment(&account, 50, date(10, 8));
+        refunded.refund(DeletedBy::new(Uuid::new_v4()));
+        let payments = [payment(&account, 300, date(9, 20)), refunded];
+
+        let ledger = Ledger::new(&account, &incomes, &payments, &[], &[], &[]);
+        let statement = ledger.statement(date(10, 1), date(10, 8)).unwrap();
+
+        assert_eq!(statement.opening_balance, Decimal::from(-200));
+        assert_eq!(statement.entries[0].balance, Decimal::from(800));
+        assert_eq!(statement.entries[1].balance, Decimal::from(750));
+        assert_eq!(statement.closing_balance, Decimal::from(750));
+        assert_eq!(statement.total_debits, Decimal::from(50));
+        assert_eq!(ledger.balance(), Decimal::from(800));
+    }
+
+    #[test]
+    fn test_debit_account_cannot_be_overdrawn() {
+        let account = account(InstrumentConfiguration::default());
+        let incomes = [income(&account, 100, date(10, 5))];
+        let ledger = Ledger::new(&account, &incomes, &[], &[], &[], &[]);
+
+        assert!(ledger
+            .ensure_can_debit(&account, Decimal::from(100))
+            .is_ok());
+        assert!(ledger
+            .ensure_can_debit(&account, Decimal::from(101))
+            .is_err());
+
+        let account = self::account(InstrumentConfiguration {
+            overdraft_limit: Some(Decimal::from(500)),
+            ..Default::default()
+        });
+        let ledger = Ledger::new(&account, &[], &[], &[], &[], &[]);
+        assert!(ledger
+            .ensure_can_debit(&account, Decimal::from(500))
+            .is_ok());
+        assert!(ledger
+            .ensure_can_debit(&account, Decimal::from(501))
+            .is_err());
+    }
+}
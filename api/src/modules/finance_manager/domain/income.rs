@@ -6,6 +6,8 @@ use uuid::Uuid;
 
 use crate::modules::finance_manager::handler::income::use_cases::CreateIncomeRequest;
 
+pub mod recurrence;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Income {
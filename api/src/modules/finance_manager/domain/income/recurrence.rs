@@ -0,0 +1,321 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::{
+    date::{date_with_day_or_last, first_day_of_month},
+    getters,
+};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::income::Income,
+    handler::income::use_cases::{
+        CreateIncomeRecurrenceRequest, CreateIncomeRequest, UpdateIncomeRecurrenceRequest,
+    },
+};
+
+/// Income received every month, such as a salary, generated by the same
+/// scheduled runs as debt recurrences.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeRecurrence {
+    id: Uuid,
+    #[serde(skip_serializing)]
+    client_id: Uuid,
+    /// Instrument the generated incomes are received in.
+    financial_instrument_id: Uuid,
+    description: String,
+    amount: Decimal,
+    active: bool,
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    day_of_month: i32,
+    #[serde(default)]
+    execution_logs: Vec<IncomeRecurrenceExecutionLog>,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeRecurrenceExecutionLog {
+    run_date: NaiveDate,
+    /// First day of the month the income was generated for.
+    reference_month: NaiveDate,
+    income_id: Uuid,
+}
+
+impl IncomeRecurrence {
+    pub fn from_request(
+        client_id: Uuid,
+        request: CreateIncomeRecurrenceRequest,
+    ) -> HttpResult<Self> {
+        validate_amount(request.amount)?;
+        validate_day_of_month(request.day_of_month)?;
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            client_id,
+            financial_instrument_id: request.financial_instrument_id,
+            description: request.description,
+            amount: request.amount,
+            active: true,
+            start_date: request.start_date,
+            end_date: request.end_date,
+            day_of_month: request.day_of_month,
+            execution_logs: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        })
+    }
+
+    /// Applies partial updates to the recurrence. Incomes already generated
+    /// are kept as they are.
+    pub fn update(&mut self, request: UpdateIncomeRecurrenceRequest) -> HttpResult<()> {
+        if let Some(amount) = request.amount {
+            validate_amount(amount)?;
+            self.amount = amount;
+        }
+        if let Some(day_of_month) = request.day_of_month {
+            validate_day_of_month(day_of_month)?;
+            self.day_of_month = day_of_month;
+        }
+        if let Some(financial_instrument_id) = request.financial_instrument_id {
+            self.financial_instrument_id = financial_instrument_id;
+        }
+        if let Some(description) = request.description {
+            self.description = description;
+        }
+        if let Some(end_date) = request.end_date {
+            self.end_date = Some(end_date);
+        }
+        if let Some(active) = request.active {
+            self.active = active;
+        }
+
+        self.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
+    pub fn was_executed_in_month(&self, year: i32, month: u32) -> bool {
+        self.execution_logs
+            .iter()
+            .any(|log| log.reference_month.year() == year && log.reference_month.month() == month)
+    }
+
+    /// Months (as their first day) that still need an income, like
+    /// `Recurrence::pending_months`.
+    pub fn pending_months(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut month = match self
+            .execution_logs
+            .iter()
+            .map(|log| log.reference_month)
+            .max()
+        {
+            Some(last) => last + Months::new(1),
+            None => self.first_month(),
+        };
+        let last_month = match self.end_date {
+            Some(end_date) => first_day_of_month(until.min(end_date)),
+            None => first_day_of_month(until),
+        };
+
+        let mut months = Vec::new();
+        while month <= last_month {
+            if !self.was_executed_in_month(month.year(), month.month()) {
+                months.push(month);
+            }
+            month = month + Months::new(1);
+        }
+
+        months
+    }
+
+    /// Month of the first income: the start month, unless its day is already
+    /// past on the start date.
+    fn first_month(&self) -> NaiveDate {
+        let start_month = first_day_of_month(self.start_date);
+        let reference_date = self.calculate_reference_date(start_month.year(), start_month.month());
+
+        if reference_date < self.start_date {
+            start_month + Months::new(1)
+        } else {
+            start_month
+        }
+    }
+
+    pub fn calculate_reference_date(&self, year: i32, month: u32) -> NaiveDate {
+        date_with_day_or_last(year, month, self.day_of_month as u32)
+    }
+
+    pub fn generate_income_for_month(&self, year: i32, month: u32) -> Income {
+        Income::from_request(
+            CreateIncomeRequest {
+                financial_instrument_id: self.financial_instrument_id,
+                description: self.description.clone(),
+                amount: self.amount,
+                date_reference: self.calculate_reference_date(year, month),
+            },
+            self.client_id,
+        )
+    }
+
+    pub fn add_execution_log(
+        &mut self,
+        run_date: NaiveDate,
+        reference_month: NaiveDate,
+        income_id: Uuid,
+    ) {
+        self.execution_logs.push(IncomeRecurrenceExecutionLog {
+            run_date,
+            reference_month,
+            income_id,
+        });
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn is_within_date_range(&self, date: NaiveDate) -> bool {
+        date >= self.start_date && self.end_date.is_none_or(|end_date| date <= end_date)
+    }
+}
+
+getters! {
+    IncomeRecurrence {
+        id: Uuid,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        description: String,
+        amount: Decimal,
+        active: bool,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+        day_of_month: i32,
+        execution_logs: Vec<IncomeRecurrenceExecutionLog>,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+    }
+}
+
+getters! {
+    IncomeRecurrenceExecutionLog {
+        run_date: NaiveDate,
+        reference_month: NaiveDate,
+        income_id: Uuid,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for IncomeRecurrence {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            description: row.get("description"),
+            amount: row.get("amount"),
+            active: row.get("active"),
+            start_date: row.get("start_date"),
+            end_date: row.get("end_date"),
+            day_of_month: row.get("day_of_month"),
+            execution_logs: row
+                .get::<Json<Vec<IncomeRecurrenceExecutionLog>>, _>("execution_logs")
+                .0,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+fn validate_amount(amount: Decimal) -> HttpResult<()> {
+    if amount <= Decimal::ZERO {
+        return Err(Box::new(HttpError::bad_request(
+            "Income recurrence amount must be positive",
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_day_of_month(day_of_month: i32) -> HttpResult<()> {
+    if !(1..=31).contains(&day_of_month) {
+        return Err(Box::new(HttpError::bad_request(
+            "Day of month must be between 1 and 31",
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn salary(day_of_month: i32, end_date: Option<NaiveDate>) -> IncomeRecurrence {
+        IncomeRecurrence::from_request(
+            Uuid::new_v4(),
+            CreateIncomeRecurrenceRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(5000),
+                start_date: date(8, 1),
+                end_date,
+                day_of_month,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pending_months_resume_after_last_execution() {
+        let mut recurrence = salary(5, Some(date(11, 30)));
+        assert_eq!(
+            recurrence.pending_months(date(12, 1)),
+            vec![date(8, 1), date(9, 1), date(10, 1), date(11, 1)]
+        );
+
+        recurrence.add_execution_log(date(9, 5), date(9, 1), Uuid::new_v4());
+        assert_eq!(recurrence.pending_months(date(10, 1)), vec![date(10, 1)]);
+    }
+
+    #[test]
+    fn test_pending_months_skip_start_month_when_its_day_has_passed() {
+        let mut recurrence = salary(5, None);
+        recurrence.start_date = date(8, 10);
+
+        assert_eq!(
+            recurrence.pending_months(date(10, 1)),
+            vec![date(9, 1), date(10, 1)]
+        );
+    }
+
+    #[test]
+    fn test_generated_income_is_received_on_the_day_of_month() {
+        let recurrence = salary(31, None);
+        let income = recurrence.generate_income_for_month(2026, 9);
+
+        assert_eq!(*income.reference(), date(9, 30));
+        assert_eq!(
+            income.financial_instrument_id(),
+            recurrence.financial_instrument_id()
+        );
+        assert_eq!(*income.amount(), Decimal::from(5000));
+        assert!(IncomeRecurrence::from_request(
+            Uuid::new_v4(),
+            CreateIncomeRecurrenceRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Aluguel recebido".to_string(),
+                amount: Decimal::from(1200),
+                start_date: date(8, 1),
+                end_date: None,
+                day_of_month: 32,
+            },
+        )
+        .is_err());
+    }
+}
@@ -18,13 +18,33 @@ pub struct Payment {
     debt_id: Uuid,
     account_id: Uuid,
     amount: Decimal,
+    /// Early payment discount granted when settling future installments.
+    #[serde(default)]
+    discount_amount: Decimal,
+    /// Installments explicitly targeted by the payment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    installment_ids: Option<Vec<i32>>,
     payment_date: NaiveDate,
+    /// Day the payment was refunded back to its account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refunded_at: Option<NaiveDate>,
     created_at: DateTime<Utc>,
     updated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     deleted_by: Option<DeletedBy>,
 }
 
+/// Payment as returned to the client when it is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredPayment {
+    #[serde(flatten)]
+    pub payment: Payment,
+    /// Set when a payment made with a card exceeded the card's credit limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_limit_warning: Option<String>,
+}
+
 impl Payment {
     pub fn new(debt: &Debt, account_id: &Uuid, payment_data: &PaymentBasicData) -> Self {
         Self {
@@ -33,12 +53,25 @@ impl Payment {
             debt_id: *debt.id(),
             account_id: *account_id,
             amount: payment_data.amount(debt),
+            discount_amount: payment_data.early_payment_discount.unwrap_or(Decimal::ZERO),
+            installment_ids: payment_data.installment_ids.clone(),
             payment_date: payment_data.payment_date,
+            refunded_at: None,
             created_at: Utc::now(),
             updated_at: None,
             deleted_by: None,
         }
     }
+
+    /// Refunds the payment. It is kept, deleted, so the account statement
+    /// still shows the debit and its refund.
+    pub fn refund(&mut self, deleted_by: DeletedBy) {
+        let now = Utc::now();
+
+        self.refunded_at = Some(now.date_naive());
+        self.deleted_by = Some(deleted_by);
+        self.updated_at = Some(now);
+    }
 }
 
 getters! {
@@ -48,7 +81,10 @@ getters! {
         debt_id: Uuid,
         account_id: Uuid,
         amount: Decimal,
+        discount_amount: Decimal,
+        installment_ids: Option<Vec<i32>>,
         payment_date: NaiveDate,
+        refunded_at: Option<NaiveDate>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -62,7 +98,10 @@ from_row_constructor! {
         debt_id: Uuid,
         account_id: Uuid,
         amount: Decimal,
+        discount_amount: Decimal,
+        installment_ids: Option<Vec<i32>>,
         payment_date: NaiveDate,
+        refunded_at: Option<NaiveDate>,
         created_at: DateTime<Utc>,
         updated_at: Option<DateTime<Utc>>,
         deleted_by: Option<DeletedBy>,
@@ -0,0 +1,320 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::{
+    debt::{installment::Installment, Debt},
+    telegram::format_currency,
+};
+
+/// How far ahead a client wants to be reminded of upcoming due dates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderSetting {
+    client_id: Uuid,
+    horizon_days: i32,
+    active: bool,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl ReminderSetting {
+    pub const DEFAULT_HORIZON_DAYS: i32 = 3;
+    pub const MAX_HORIZON_DAYS: i32 = 60;
+
+    /// Setting used for clients that never configured their reminders.
+    pub fn default_for(client_id: Uuid) -> Self {
+        Self {
+            client_id,
+            horizon_days: Self::DEFAULT_HORIZON_DAYS,
+            active: true,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    pub fn update(&mut self, horizon_days: Option<i32>, active: Option<bool>) -> HttpResult<()> {
+        if let Some(horizon_days) = horizon_days {
+            if !(0..=Self::MAX_HORIZON_DAYS).contains(&horizon_days) {
+                return Err(Box::new(HttpError::bad_request(format!(
+                    "Horizon days must be between 0 and {}",
+                    Self::MAX_HORIZON_DAYS
+                ))));
+            }
+            self.horizon_days = horizon_days;
+        }
+        if let Some(active) = active {
+            self.active = active;
+        }
+        self.updated_at = Some(Utc::now());
+
+        Ok(())
+    }
+
+    /// Inclusive range of due dates covered by the reminder sent on `today`.
+    pub fn window(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        (today, today + Duration::days(self.horizon_days as i64))
+    }
+}
+
+getters! {
+    ReminderSetting {
+        client_id: Uuid,
+        horizon_days: i32,
+        active: bool,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for ReminderSetting {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            client_id: row.get("client_id"),
+            horizon_days: row.get("horizon_days"),
+            active: row.get("active"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+/// A debt or installment that is due inside the reminder window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueItem {
+    debt_id: Uuid,
+    installment_id: Option<i32>,
+    /// Number of the installment within the debt's current plan.
+    installment_number: Option<i32>,
+    installment_count: Option<i32>,
+    identification: String,
+    description: String,
+    amount: Decimal,
+    due_date: NaiveDate,
+}
+
+impl DueItem {
+    pub fn from_debt(debt: &Debt) -> Self {
+        Self {
+            debt_id: *debt.id(),
+            installment_id: None,
+            installment_number: None,
+            installment_count: None,
+            identification: debt.identification().clone(),
+            description: debt.description().clone(),
+            amount: *debt.remaining_amount(),
+            due_date: *debt.due_date(),
+        }
+    }
+
+    pub fn from_installment(installment: &Installment, debt: &Debt) -> Self {
+        Self {
+            debt_id: *debt.id(),
+            installment_id: Some(*installment.installment_id()),
+            installment_number: Some(debt.installment_number(*installment.installment_id())),
+            installment_count: *debt.installment_count(),
+            identification: debt.identification().clone(),
+            description: debt.description().clone(),
+            amount: installment.remaining_amount(),
+            due_date: *installment.due_date(),
+        }
+    }
+
+    pub fn was_reminded(&self, sent: &[DebtReminder]) -> bool {
+        sent.iter().any(|reminder| {
+            reminder.debt_id == self.debt_id
+                && reminder.installment_id == self.installment_id.unwrap_or_default()
+                && reminder.due_date == self.due_date
+        })
+    }
+
+    fn format_line(&self) -> String {
+        let installment = match (self.installment_number, self.installment_count) {
+            (Some(number), Some(count)) => format!(" (parcela {number}/{count})"),
+            (Some(number), None) => format!(" (parcela {number})"),
+            _ => String::new(),
+        };
+
+        format!(
+            "• {} — #{} {}{}: {}",
+            self.due_date.format("%d/%m"),
+            self.identification,
+            self.description,
+            installment,
+            format_currency(self.amount),
+        )
+    }
+}
+
+getters! {
+    DueItem {
+        debt_id: Uuid,
+        installment_id: Option<i32>,
+        installment_number: Option<i32>,
+        installment_count: Option<i32>,
+        identification: String,
+        description: String,
+        amount: Decimal,
+        due_date: NaiveDate,
+    }
+}
+
+/// Builds the message sent to the client with everything due in the window,
+/// ordered by due date.
+pub fn build_digest(items: &[DueItem], today: NaiveDate) -> String {
+    let mut items = items.to_vec();
+    items.sort_by_key(|item| item.due_date);
+
+    let lines = items
+        .iter()
+        .map(|item| {
+            let line = item.format_line();
+            if item.due_date == today {
+                format!("{line} ⚠️ vence hoje")
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("🔔 Vencimentos próximos:\n{lines}")
+}
+
+/// Record of a reminder already sent, so it is never sent twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtReminder {
+    debt_id: Uuid,
+    installment_id: i32,
+    due_date: NaiveDate,
+    client_id: Uuid,
+    sent_at: DateTime<Utc>,
+}
+
+impl DebtReminder {
+    pub fn new(client_id: Uuid, item: &DueItem) -> Self {
+        Self {
+            debt_id: item.debt_id,
+            installment_id: item.installment_id.unwrap_or_default(),
+            due_date: item.due_date,
+            client_id,
+            sent_at: Utc::now(),
+        }
+    }
+}
+
+getters! {
+    DebtReminder {
+        debt_id: Uuid,
+        installment_id: i32,
+        due_date: NaiveDate,
+        client_id: Uuid,
+        sent_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for DebtReminder {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            debt_id: row.get("debt_id"),
+            installment_id: row.get("installment_id"),
+            due_date: row.get("due_date"),
+            client_id: row.get("client_id"),
+            sent_at: row.get("sent_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn debt(due_date: NaiveDate) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Internet".to_string(),
+            Decimal::new(9990, 2),
+            None,
+            None,
+            due_date,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_window_uses_horizon_days() {
+        let mut setting = ReminderSetting::default_for(Uuid::new_v4());
+        setting.update(Some(5), None).unwrap();
+
+        assert_eq!(setting.window(date(16)), (date(16), date(21)));
+    }
+
+    #[test]
+    fn test_update_rejects_horizon_out_of_range() {
+        let mut setting = ReminderSetting::default_for(Uuid::new_v4());
+
+        assert!(setting.update(Some(-1), None).is_err());
+        assert!(setting
+            .update(Some(ReminderSetting::MAX_HORIZON_DAYS + 1), None)
+            .is_err());
+        assert_eq!(
+            *setting.horizon_days(),
+            ReminderSetting::DEFAULT_HORIZON_DAYS
+        );
+    }
+
+    #[test]
+    fn test_was_reminded_matches_debt_installment_and_due_date() {
+        let debt = debt(date(18));
+        let installment = Installment::new(*debt.id(), 2, date(18), Decimal::new(5000, 2));
+        let debt_item = DueItem::from_debt(&debt);
+        let installment_item = DueItem::from_installment(&installment, &debt);
+
+        let sent = vec![DebtReminder::new(*debt.client_id(), &debt_item)];
+
+        assert!(debt_item.was_reminded(&sent));
+        assert!(!installment_item.was_reminded(&sent));
+    }
+
+    #[test]
+    fn test_was_reminded_is_false_when_due_date_changed() {
+        let mut debt = debt(date(18));
+        let sent = vec![DebtReminder::new(
+            *debt.client_id(),
+            &DueItem::from_debt(&debt),
+        )];
+
+        debt.set_due_date(date(20));
+
+        assert!(!DueItem::from_debt(&debt).was_reminded(&sent));
+    }
+
+    #[test]
+    fn test_build_digest_orders_by_due_date_and_flags_today() {
+        let later = DueItem::from_debt(&debt(date(20)));
+        let today = DueItem::from_debt(&debt(date(16)));
+
+        let digest = build_digest(&[later, today], date(16));
+        let lines: Vec<&str> = digest.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("• 16/10"));
+        assert!(lines[1].ends_with("R$ 99,90 ⚠️ vence hoje"));
+        assert!(lines[2].starts_with("• 20/10"));
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use http_error::{HttpError, HttpResult};
+use serde::{Deserialize, Serialize};
+
+pub mod cash_flow;
+pub mod forecast;
+pub mod spending;
+
+/// Longest range a report can be grouped over, in months.
+pub const MAX_REPORT_MONTHS: u32 = 60;
+
+/// Size of the periods a report is grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Granularity {
+    #[default]
+    #[serde(alias = "month")]
+    Month,
+    /// Weeks start on Monday.
+    #[serde(alias = "week")]
+    Week,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportPeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl ReportPeriod {
+    /// The whole calendar month of `date`.
+    pub fn month(date: NaiveDate) -> Self {
+        let start = date.with_day(1).unwrap_or(date);
+        let next = start + Months::new(1);
+
+        Self {
+            start,
+            end: next.pred_opt().unwrap_or(next),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.start && date <= self.end
+    }
+}
+
+impl Granularity {
+    fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Month => date.with_day(1).unwrap_or(date),
+            Granularity::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+        }
+    }
+
+    fn next_period_start(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Month => start + Months::new(1),
+            Granularity::Week => start + Duration::days(7),
+        }
+    }
+
+    /// Periods covering `from` to `to`. The first and last ones are cut at
+    /// the range limits.
+    pub fn periods(&self, from: NaiveDate, to: NaiveDate) -> HttpResult<Vec<ReportPeriod>> {
+        if from > to {
+            return Err(Box::new(HttpError::bad_request(
+                "Report start date must not be after its end date",
+            )));
+        }
+
+        if !from
+            .checked_add_months(Months::new(MAX_REPORT_MONTHS))
+            .is_some_and(|limit| to < limit)
+        {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Report must cover at most {MAX_REPORT_MONTHS} months"
+            ))));
+        }
+
+        let mut periods = Vec::new();
+        let mut start = self.period_start(from);
+
+        while start <= to {
+            let next = self.next_period_start(start);
+            periods.push(ReportPeriod {
+                start: start.max(from),
+                end: next.pred_opt().unwrap_or(next).min(to),
+            });
+            start = next;
+        }
+
+        Ok(periods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_monthly_periods_are_cut_at_range_limits() {
+        let periods = Granularity::Month
+            .periods(date(1, 15), date(3, 10))
+            .unwrap();
+
+        assert_eq!(periods.len(), 3);
+        assert_eq!(periods[0].start, date(1, 15));
+        assert_eq!(periods[0].end, date(1, 31));
+        assert_eq!(periods[1].end, date(2, 28));
+        assert_eq!(periods[2].end, date(3, 10));
+    }
+
+    #[test]
+    fn test_weekly_periods_start_on_monday() {
+        // 2026-10-01 is a Thursday
+        let periods = Granularity::Week
+            .periods(date(10, 1), date(10, 12))
+            .unwrap();
+
+        assert_eq!(periods.len(), 3);
+        assert_eq!(periods[0].end, date(10, 4));
+        assert_eq!(periods[1].start, date(10, 5));
+        assert_eq!(periods[2].end, date(10, 12));
+        assert!(Granularity::Week.periods(date(10, 2), date(10, 1)).is_err());
+    }
+
+    #[test]
+    fn test_periods_range_is_capped() {
+        let from = date(1, 1);
+        let last_day = from + Months::new(MAX_REPORT_MONTHS) - Duration::days(1);
+
+        assert_eq!(
+            Granularity::Month.periods(from, last_day).unwrap().len(),
+            MAX_REPORT_MONTHS as usize
+        );
+        assert!(Granularity::Week.periods(from, NaiveDate::MAX).is_err());
+    }
+}
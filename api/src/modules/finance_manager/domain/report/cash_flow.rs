@@ -0,0 +1,265 @@
+use chrono::NaiveDate;
+use http_error::HttpResult;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::finance_manager::domain::{
+    debt::{installment::Installment, Debt},
+    income::Income,
+    payment::Payment,
+    report::{Granularity, ReportPeriod},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowPeriod {
+    #[serde(flatten)]
+    pub period: ReportPeriod,
+    /// Incomes referenced in the period.
+    pub expected_inflow: Decimal,
+    /// Incomes referenced in the period up to today.
+    pub realized_inflow: Decimal,
+    /// Debts and installments due in the period. A balance carried over to
+    /// the next invoice counts on the debt billing it.
+    pub expected_outflow: Decimal,
+    /// Payments made in the period.
+    pub realized_outflow: Decimal,
+    pub expected_net_result: Decimal,
+    pub net_result: Decimal,
+    /// Realized net result accumulated since the start of the report.
+    pub cumulative_balance: Decimal,
+}
+
+impl CashFlowPeriod {
+    fn new(period: ReportPeriod) -> Self {
+        Self {
+            period,
+            expected_inflow: Decimal::ZERO,
+            realized_inflow: Decimal::ZERO,
+            expected_outflow: Decimal::ZERO,
+            realized_outflow: Decimal::ZERO,
+            expected_net_result: Decimal::ZERO,
+            net_result: Decimal::ZERO,
+            cumulative_balance: Decimal::ZERO,
+        }
+    }
+}
+
+/// Expected and realized money in and out of a client's accounts, per period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub granularity: Granularity,
+    pub expected_net_result: Decimal,
+    pub net_result: Decimal,
+    pub periods: Vec<CashFlowPeriod>,
+}
+
+impl CashFlowReport {
+    /// Debts paid in installments are expected through their installments
+    /// only. Records outside the range are ignored.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        from: NaiveDate,
+        to: NaiveDate,
+        granularity: Granularity,
+        today: NaiveDate,
+        incomes: &[Income],
+        debts: &[Debt],
+        installments: &[Installment],
+        payments: &[Payment],
+    ) -> HttpResult<Self> {
+        let mut periods: Vec<CashFlowPeriod> = granularity
+            .periods(from, to)?
+            .into_iter()
+            .map(CashFlowPeriod::new)
+            .collect();
+
+        for income in incomes {
+            if let Some(cash_flow) = period_of(&mut periods, *income.reference()) {
+                cash_flow.expected_inflow += income.amount();
+                if income.reference() <= &today {
+                    cash_flow.realized_inflow += income.amount();
+                }
+            }
+        }
+
+        for debt in debts.iter().filter(|debt| !debt.has_installments()) {
+            if let Some(cash_flow) = period_of(&mut periods, *debt.due_date()) {
+                cash_flow.expected_outflow += debt.expected_amount();
+            }
+        }
+
+        for installment in installments {
+            if let Some(cash_flow) = period_of(&mut periods, *installment.due_date()) {
+                cash_flow.expected_outflow += installment.amount();
+            }
+        }
+
+        for payment in payments {
+            if let Some(cash_flow) = period_of(&mut periods, *payment.payment_date()) {
+                cash_flow.realized_outflow += payment.amount();
+            }
+        }
+
+        let mut cumulative_balance = Decimal::ZERO;
+        for cash_flow in periods.iter_mut() {
+            cash_flow.expected_net_result = cash_flow.expected_inflow - cash_flow.expected_outflow;
+            cash_flow.net_result = cash_flow.realized_inflow - cash_flow.realized_outflow;
+            cumulative_balance += cash_flow.net_result;
+            cash_flow.cumulative_balance = cumulative_balance;
+        }
+
+        Ok(Self {
+            from,
+            to,
+            granularity,
+            expected_net_result: periods.iter().map(|p| p.expected_net_result).sum(),
+            net_result: cumulative_balance,
+            periods,
+        })
+    }
+}
+
+fn period_of(periods: &mut [CashFlowPeriod], date: NaiveDate) -> Option<&mut CashFlowPeriod> {
+    periods
+        .iter_mut()
+        .find(|cash_flow| cash_flow.period.contains(date))
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::modules::finance_manager::handler::{
+        income::use_cases::CreateIncomeRequest, payment::use_cases::PaymentBasicData,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn income(amount: i64, reference: NaiveDate) -> Income {
+        Income::from_request(
+            CreateIncomeRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(amount),
+                date_reference: reference,
+            },
+            Uuid::new_v4(),
+        )
+    }
+
+    fn debt(amount: i64, due_date: NaiveDate, installment_count: Option<i32>) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Aluguel".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            due_date,
+            None,
+            None,
+            None,
+            installment_count,
+        )
+    }
+
+    fn payment(debt: &Debt, payment_date: NaiveDate) -> Payment {
+        Payment::new(
+            debt,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date,
+                amount: None,
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_cash_flow_per_month() {
+        let rent = debt(1500, date(9, 10), None);
+        let notebook = debt(3000, date(11, 5), Some(2));
+        let installments = [
+            Installment::new(*notebook.id(), 1, date(10, 5), Decimal::from(1500)),
+            Installment::new(*notebook.id(), 2, date(11, 5), Decimal::from(1500)),
+        ];
+
+        let report = CashFlowReport::build(
+            date(9, 1),
+            date(11, 30),
+            Granularity::Month,
+            date(10, 17),
+            &[
+                income(5000, date(9, 5)),
+                income(5000, date(10, 5)),
+                income(5000, date(11, 5)),
+            ],
+            &[rent.clone(), notebook],
+            &installments,
+            &[payment(&rent, date(9, 12))],
+        )
+        .unwrap();
+
+        assert_eq!(report.periods.len(), 3);
+
+        let september = &report.periods[0];
+        assert_eq!(september.expected_outflow, Decimal::from(1500));
+        assert_eq!(september.realized_outflow, Decimal::from(1500));
+        assert_eq!(september.net_result, Decimal::from(3500));
+
+        let october = &report.periods[1];
+        assert_eq!(october.expected_outflow, Decimal::from(1500));
+        assert_eq!(october.realized_outflow, Decimal::ZERO);
+        assert_eq!(october.cumulative_balance, Decimal::from(8500));
+
+        let november = &report.periods[2];
+        assert_eq!(november.expected_inflow, Decimal::from(5000));
+        assert_eq!(november.realized_inflow, Decimal::ZERO);
+        assert_eq!(november.expected_net_result, Decimal::from(3500));
+        assert_eq!(report.expected_net_result, Decimal::from(10500));
+    }
+
+    #[test]
+    fn test_carried_over_balance_counts_once() {
+        let mut market = debt(500, date(9, 10), None);
+        let paid = Payment::new(
+            &market,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date: date(9, 10),
+                amount: Some(Decimal::from(300)),
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        );
+        market.process_payment(&paid).unwrap();
+        let mut carry_over = debt(220, date(10, 10), None);
+        carry_over.bill_carry_over(Uuid::new_v4());
+        market
+            .carry_over(Decimal::from(200), *carry_over.id())
+            .unwrap();
+
+        let report = CashFlowReport::build(
+            date(9, 1),
+            date(10, 31),
+            Granularity::Month,
+            date(10, 17),
+            &[],
+            &[market, carry_over],
+            &[],
+            &[paid],
+        )
+        .unwrap();
+
+        assert_eq!(report.periods[0].expected_outflow, Decimal::from(300));
+        assert_eq!(report.periods[1].expected_outflow, Decimal::from(220));
+        assert_eq!(report.expected_net_result, Decimal::from(-520));
+    }
+}
@@ -0,0 +1,338 @@
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::finance_manager::domain::{
+    debt::{installment::Installment, recurrence::Recurrence, Debt},
+    income::{recurrence::IncomeRecurrence, Income},
+    report::ReportPeriod,
+};
+
+/// Longest projection a client can ask for, in months.
+pub const MAX_FORECAST_MONTHS: u32 = 24;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastMonth {
+    #[serde(flatten)]
+    pub period: ReportPeriod,
+    /// Incomes registered for the month plus income recurrences not
+    /// generated for it yet.
+    pub expected_income: Decimal,
+    /// Unpaid part of the installments due in the month.
+    pub installments_amount: Decimal,
+    /// Unpaid part of the debts without installments due in the month.
+    pub debts_amount: Decimal,
+    /// Unpaid debts and installments that fell due before the forecast. Only
+    /// the first month carries them.
+    pub overdue_amount: Decimal,
+    /// Recurrences not generated yet for the month.
+    pub recurrences_amount: Decimal,
+    pub committed_amount: Decimal,
+    /// Income left after the commitments. Negative when they exceed it.
+    pub free_amount: Decimal,
+    /// Percentage of the income already committed. Not set for months
+    /// without income.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_percentage: Option<Decimal>,
+}
+
+impl ForecastMonth {
+    fn new(period: ReportPeriod) -> Self {
+        Self {
+            period,
+            expected_income: Decimal::ZERO,
+            installments_amount: Decimal::ZERO,
+            debts_amount: Decimal::ZERO,
+            overdue_amount: Decimal::ZERO,
+            recurrences_amount: Decimal::ZERO,
+            committed_amount: Decimal::ZERO,
+            free_amount: Decimal::ZERO,
+            committed_percentage: None,
+        }
+    }
+}
+
+/// Money already committed in the coming months, against the income known
+/// for them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentForecast {
+    pub expected_income: Decimal,
+    pub committed_amount: Decimal,
+    pub free_amount: Decimal,
+    pub months: Vec<ForecastMonth>,
+}
+
+impl CommitmentForecast {
+    /// Projects `months` calendar months starting at the month of `from`.
+    /// Recurrences count only for months they weren't generated for yet, as
+    /// the generated debts and incomes are already among the records.
+    /// Debts and installments due before the range are overdue and committed
+    /// in its first month; other records outside the range are ignored.
+    pub fn build(
+        from: NaiveDate,
+        months: u32,
+        incomes: &[Income],
+        debts: &[Debt],
+        installments: &[Installment],
+        recurrences: &[Recurrence],
+        income_recurrences: &[IncomeRecurrence],
+    ) -> Self {
+        let mut forecast: Vec<ForecastMonth> = (0..months)
+            .map(|offset| ForecastMonth::new(ReportPeriod::month(from + Months::new(offset))))
+            .collect();
+
+        if let Some(first) = forecast.first_mut() {
+            let start = first.period.start;
+            first.overdue_amount = installments
+                .iter()
+                .filter(|installment| !installment.is_paid() && *installment.due_date() < start)
+                .map(|installment| installment.remaining_amount())
+                .sum::<Decimal>()
+                + debts
+                    .iter()
+                    .filter(|debt| !debt.has_installments() && *debt.due_date() < start)
+                    .map(|debt| (*debt.remaining_amount()).max(Decimal::ZERO))
+                    .sum::<Decimal>();
+        }
+
+        for month in forecast.iter_mut() {
+            let period = month.period;
+            let (year, number) = (period.start.year(), period.start.month());
+
+            let recurring_income: Decimal = income_recurrences
+                .iter()
+                .filter(|recurrence| *recurrence.active())
+                .filter(|recurrence| {
+                    !recurrence.was_executed_in_month(year, number)
+                        && recurrence
+                            .is_within_date_range(recurrence.calculate_reference_date(year, number))
+                })
+                .map(|recurrence| *recurrence.amount())
+                .sum();
+            month.expected_income = recurring_income
+                + incomes
+                    .iter()
+                    .filter(|income| period.contains(*income.reference()))
+                    .map(|income| *income.amount())
+                    .sum::<Decimal>();
+
+            month.installments_amount = installments
+                .iter()
+                .filter(|installment| !installment.is_paid())
+                .filter(|installment| period.contains(*installment.due_date()))
+                .map(|installment| {
+                    (*installment.amount() - *installment.paid_amount()).max(Decimal::ZERO)
+                })
+                .sum();
+
+            month.debts_amount = debts
+                .iter()
+                .filter(|debt| !debt.has_installments() && period.contains(*debt.due_date()))
+                .map(|debt| (*debt.remaining_amount()).max(Decimal::ZERO))
+                .sum();
+
+            month.recurrences_amount = recurrences
+                .iter()
+                .filter(|recurrence| *recurrence.active())
+                .filter(|recurrence| {
+                    !recurrence.was_executed_in_month(year, number)
+                        && recurrence
+                            .is_within_date_range(recurrence.calculate_due_date(year, number))
+                })
+                .map(|recurrence| *recurrence.amount())
+                .sum();
+
+            month.committed_amount = month.installments_amount
+                + month.debts_amount
+                + month.overdue_amount
+                + month.recurrences_amount;
+            month.free_amount = month.expected_income - month.committed_amount;
+            month.committed_percentage = (!month.expected_income.is_zero()).then(|| {
+                (month.committed_amount / month.expected_income * Decimal::ONE_HUNDRED).round_dp(2)
+            });
+        }
+
+        let expected_income: Decimal = forecast.iter().map(|month| month.expected_income).sum();
+        let committed_amount: Decimal = forecast.iter().map(|month| month.committed_amount).sum();
+
+        Self {
+            expected_income,
+            committed_amount,
+            free_amount: expected_income - committed_amount,
+            months: forecast,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::modules::finance_manager::handler::{
+        debt::use_cases::CreateRecurrenceRequest,
+        income::use_cases::{CreateIncomeRecurrenceRequest, CreateIncomeRequest},
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn income(amount: i64, reference: NaiveDate) -> Income {
+        Income::from_request(
+            CreateIncomeRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(amount),
+                date_reference: reference,
+            },
+            Uuid::new_v4(),
+        )
+    }
+
+    fn rent(end_date: Option<NaiveDate>) -> Recurrence {
+        Recurrence::from_request(
+            Uuid::new_v4(),
+            CreateRecurrenceRequest {
+                description: "Aluguel".to_string(),
+                amount: Decimal::from(1500),
+                category: None,
+                start_date: date(1, 1),
+                end_date,
+                day_of_month: 10,
+            },
+        )
+    }
+
+    #[test]
+    fn test_forecast_commits_income_month_by_month() {
+        let notebook = Debt::new(
+            Uuid::new_v4(),
+            "Notebook".to_string(),
+            Decimal::from(3000),
+            None,
+            None,
+            date(12, 5),
+            None,
+            None,
+            None,
+            Some(2),
+        );
+        let installments = [
+            Installment::new(*notebook.id(), 1, date(11, 5), Decimal::from(1500)),
+            Installment::new(*notebook.id(), 2, date(12, 5), Decimal::from(1500)),
+        ];
+
+        let mut generated = rent(None);
+        generated.add_execution_log(date(10, 1), date(10, 1), Uuid::new_v4());
+        let generated_rent = generated.generate_debt_for_month(2026, 10);
+
+        let forecast = CommitmentForecast::build(
+            date(10, 17),
+            3,
+            &[income(5000, date(10, 5)), income(5000, date(11, 5))],
+            &[notebook, generated_rent],
+            &installments,
+            &[generated, rent(Some(date(11, 30)))],
+            &[],
+        );
+
+        assert_eq!(forecast.months.len(), 3);
+
+        let october = &forecast.months[0];
+        assert_eq!(october.period.start, date(10, 1));
+        assert_eq!(october.debts_amount, Decimal::from(1500));
+        // The first recurrence was already generated for October.
+        assert_eq!(october.recurrences_amount, Decimal::from(1500));
+        assert_eq!(october.committed_percentage, Some(Decimal::from(60)));
+
+        let november = &forecast.months[1];
+        assert_eq!(november.installments_amount, Decimal::from(1500));
+        assert_eq!(november.recurrences_amount, Decimal::from(3000));
+        assert_eq!(november.free_amount, Decimal::from(500));
+
+        let december = &forecast.months[2];
+        assert_eq!(december.recurrences_amount, Decimal::from(1500));
+        assert_eq!(december.committed_percentage, None);
+        assert_eq!(december.free_amount, Decimal::from(-3000));
+        assert_eq!(forecast.committed_amount, Decimal::from(10500));
+    }
+
+    #[test]
+    fn test_forecast_counts_income_recurrences_not_generated_yet() {
+        let mut salary = IncomeRecurrence::from_request(
+            Uuid::new_v4(),
+            CreateIncomeRecurrenceRequest {
+                financial_instrument_id: Uuid::new_v4(),
+                description: "Salário".to_string(),
+                amount: Decimal::from(5000),
+                start_date: date(1, 1),
+                end_date: None,
+                day_of_month: 5,
+            },
+        )
+        .unwrap();
+        let october_salary = salary.generate_income_for_month(2026, 10);
+        salary.add_execution_log(date(10, 5), date(10, 1), *october_salary.id());
+
+        let forecast =
+            CommitmentForecast::build(date(10, 17), 2, &[october_salary], &[], &[], &[], &[salary]);
+
+        assert_eq!(forecast.months[0].expected_income, Decimal::from(5000));
+        assert_eq!(forecast.months[1].expected_income, Decimal::from(5000));
+        assert_eq!(forecast.free_amount, Decimal::from(10000));
+    }
+
+    #[test]
+    fn test_forecast_commits_overdue_debts_in_the_first_month() {
+        let overdue = Debt::new(
+            Uuid::new_v4(),
+            "Conta de luz".to_string(),
+            Decimal::from(200),
+            Some(Decimal::from(50)),
+            None,
+            date(9, 10),
+            None,
+            None,
+            None,
+            None,
+        );
+        let phone = Debt::new(
+            Uuid::new_v4(),
+            "Celular".to_string(),
+            Decimal::from(1200),
+            None,
+            None,
+            date(8, 20),
+            None,
+            None,
+            None,
+            Some(3),
+        );
+        let installments = [Installment::new(
+            *phone.id(),
+            3,
+            date(9, 20),
+            Decimal::from(400),
+        )];
+
+        let forecast = CommitmentForecast::build(
+            date(10, 17),
+            2,
+            &[income(1000, date(10, 5))],
+            &[overdue, phone],
+            &installments,
+            &[],
+            &[],
+        );
+
+        let october = &forecast.months[0];
+        assert_eq!(october.overdue_amount, Decimal::from(550));
+        assert_eq!(october.committed_amount, Decimal::from(550));
+        assert_eq!(october.free_amount, Decimal::from(450));
+        assert_eq!(forecast.months[1].overdue_amount, Decimal::ZERO);
+        assert_eq!(forecast.committed_amount, Decimal::from(550));
+    }
+}
@@ -0,0 +1,524 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, Months};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::{
+    debt::{
+        category::{with_subcategories, Category},
+        installment::Installment,
+        Debt, DebtCategory,
+    },
+    payment::Payment,
+    report::ReportPeriod,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingAmounts {
+    /// Paid in the period.
+    pub spent: Decimal,
+    /// Due in the period, paid or not.
+    pub committed: Decimal,
+}
+
+/// Spent and committed amounts of a period, grouped by the attributes of the
+/// debts they belong to.
+#[derive(Debug, Clone, Default)]
+pub struct SpendingTotals {
+    total: SpendingAmounts,
+    categories: BTreeMap<String, SpendingAmounts>,
+    tags: BTreeMap<String, SpendingAmounts>,
+    expense_types: BTreeMap<String, SpendingAmounts>,
+}
+
+impl SpendingTotals {
+    /// `debts` must hold every debt referenced by the installments and
+    /// payments. Debts paid in installments are committed through their
+    /// installments only, and records outside the period are ignored.
+    /// Carry-over debts are left out: the purchases they bill again already
+    /// count in their own categories.
+    pub fn collect(
+        period: &ReportPeriod,
+        debts: &[Debt],
+        installments: &[Installment],
+        payments: &[Payment],
+    ) -> Self {
+        let debts: HashMap<&Uuid, &Debt> = debts
+            .iter()
+            .filter(|debt| !debt.is_carry_over())
+            .map(|debt| (debt.id(), debt))
+            .collect();
+        let mut totals = Self::default();
+
+        for debt in debts.values() {
+            if !debt.has_installments() && period.contains(*debt.due_date()) {
+                totals.add(debt, Decimal::ZERO, *debt.total_amount());
+            }
+        }
+
+        for installment in installments
+            .iter()
+            .filter(|installment| period.contains(*installment.due_date()))
+        {
+            if let Some(debt) = debts.get(installment.debt_id()) {
+                totals.add(debt, Decimal::ZERO, *installment.amount());
+            }
+        }
+
+        for payment in payments
+            .iter()
+            .filter(|payment| period.contains(*payment.payment_date()))
+        {
+            if let Some(debt) = debts.get(payment.debt_id()) {
+                totals.add(debt, *payment.amount(), Decimal::ZERO);
+            }
+        }
+
+        totals
+    }
+
+    /// Adds the amounts of each subcategory to its parent categories, so a
+    /// parent covers everything filed under it.
+    pub fn roll_up_subcategories(mut self, categories: &[Category]) -> Self {
+        let own = self.categories.clone();
+
+        for category in categories {
+            let name = String::from(category.name().clone());
+            let subtree: Vec<&SpendingAmounts> =
+                with_subcategories(categories, std::slice::from_ref(&name))
+                    .iter()
+                    .filter_map(|name| own.get(name))
+                    .collect();
+            if subtree.is_empty() {
+                continue;
+            }
+
+            let mut amounts = SpendingAmounts::default();
+            for subcategory in subtree {
+                add_amounts(&mut amounts, subcategory.spent, subcategory.committed);
+            }
+            self.categories.insert(name, amounts);
+        }
+
+        self
+    }
+
+    pub fn category(&self, category: &DebtCategory) -> SpendingAmounts {
+        self.categories
+            .get(&String::from(category.clone()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn tag(&self, tag: &str) -> SpendingAmounts {
+        self.tags.get(tag).copied().unwrap_or_default()
+    }
+
+    fn add(&mut self, debt: &Debt, spent: Decimal, committed: Decimal) {
+        let keys = [
+            (&mut self.categories, String::from(debt.category().clone())),
+            (
+                &mut self.expense_types,
+                debt.expense_type().as_str().to_string(),
+            ),
+        ];
+        for (groups, key) in keys {
+            add_amounts(groups.entry(key).or_default(), spent, committed);
+        }
+
+        for tag in debt.tags() {
+            add_amounts(self.tags.entry(tag.clone()).or_default(), spent, committed);
+        }
+
+        add_amounts(&mut self.total, spent, committed);
+    }
+}
+
+fn add_amounts(amounts: &mut SpendingAmounts, spent: Decimal, committed: Decimal) {
+    amounts.spent += spent;
+    amounts.committed += committed;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingGroup {
+    /// Category, tag or expense type.
+    pub key: String,
+    #[serde(flatten)]
+    pub amounts: SpendingAmounts,
+    /// Percentage of the total spent in the period.
+    pub spent_share: Decimal,
+    /// Percentage of the total committed in the period.
+    pub committed_share: Decimal,
+    pub previous_period: SpendingAmounts,
+    pub same_period_last_year: SpendingAmounts,
+    /// Percentage change of the spent amount. Not set when nothing was spent
+    /// in the compared period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spent_change_from_previous_period: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spent_change_from_last_year: Option<Decimal>,
+}
+
+impl SpendingGroup {
+    fn new(
+        key: String,
+        amounts: SpendingAmounts,
+        total: &SpendingAmounts,
+        previous_period: SpendingAmounts,
+        same_period_last_year: SpendingAmounts,
+    ) -> Self {
+        Self {
+            key,
+            amounts,
+            spent_share: percentage(amounts.spent, total.spent).unwrap_or_default(),
+            committed_share: percentage(amounts.committed, total.committed).unwrap_or_default(),
+            previous_period,
+            same_period_last_year,
+            spent_change_from_previous_period: change(amounts.spent, previous_period.spent),
+            spent_change_from_last_year: change(amounts.spent, same_period_last_year.spent),
+        }
+    }
+}
+
+/// Where money went in a period, compared with the period right before it
+/// and with the same period of the previous year. A debt with several tags
+/// counts for each of them, and a debt of a subcategory also counts for its
+/// parents, so tag and category shares may add up to more than 100%.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingBreakdown {
+    pub period: ReportPeriod,
+    pub previous_period: ReportPeriod,
+    pub same_period_last_year: ReportPeriod,
+    pub total: SpendingGroup,
+    pub categories: Vec<SpendingGroup>,
+    pub tags: Vec<SpendingGroup>,
+    pub expense_types: Vec<SpendingGroup>,
+}
+
+impl SpendingBreakdown {
+    /// Period of the same length right before `period`, and the same period
+    /// one year earlier. A whole calendar month is compared with whole months.
+    pub fn comparison_periods(period: &ReportPeriod) -> (ReportPeriod, ReportPeriod) {
+        if *period == ReportPeriod::month(period.start) {
+            return (
+                ReportPeriod::month(period.start - Months::new(1)),
+                ReportPeriod::month(period.start - Months::new(12)),
+            );
+        }
+
+        let length = period.end - period.start + Duration::days(1);
+        let previous = ReportPeriod {
+            start: period.start - length,
+            end: period.start - Duration::days(1),
+        };
+        let last_year = ReportPeriod {
+            start: period.start - Months::new(12),
+            end: period.end - Months::new(12),
+        };
+
+        (previous, last_year)
+    }
+
+    pub fn build(
+        period: ReportPeriod,
+        current: &SpendingTotals,
+        previous: &SpendingTotals,
+        last_year: &SpendingTotals,
+    ) -> Self {
+        let (previous_period, same_period_last_year) = Self::comparison_periods(&period);
+
+        let groups = |select: fn(&SpendingTotals) -> &BTreeMap<String, SpendingAmounts>| {
+            let mut keys: Vec<&String> = select(current)
+                .keys()
+                .chain(select(previous).keys())
+                .chain(select(last_year).keys())
+                .collect();
+            keys.sort();
+            keys.dedup();
+
+            let mut groups: Vec<SpendingGroup> = keys
+                .into_iter()
+                .map(|key| {
+                    let amounts = |totals: &SpendingTotals| {
+                        select(totals).get(key).copied().unwrap_or_default()
+                    };
+                    SpendingGroup::new(
+                        key.clone(),
+                        amounts(current),
+                        &current.total,
+                        amounts(previous),
+                        amounts(last_year),
+                    )
+                })
+                .collect();
+            groups.sort_by_key(|group| std::cmp::Reverse(group.amounts.spent));
+            groups
+        };
+
+        Self {
+            period,
+            previous_period,
+            same_period_last_year,
+            total: SpendingGroup::new(
+                "TOTAL".to_string(),
+                current.total,
+                &current.total,
+                previous.total,
+                last_year.total,
+            ),
+            categories: groups(|totals| &totals.categories),
+            tags: groups(|totals| &totals.tags),
+            expense_types: groups(|totals| &totals.expense_types),
+        }
+    }
+}
+
+fn percentage(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    if whole.is_zero() {
+        return None;
+    }
+
+    Some((part / whole * Decimal::ONE_HUNDRED).round_dp(2))
+}
+
+fn change(current: Decimal, past: Decimal) -> Option<Decimal> {
+    percentage(current - past, past)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::modules::finance_manager::{
+        domain::debt::ExpenseType,
+        handler::{
+            debt::category::use_cases::CreateCategoryRequest, payment::use_cases::PaymentBasicData,
+        },
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn october(year: i32) -> ReportPeriod {
+        ReportPeriod {
+            start: date(year, 10, 1),
+            end: date(year, 10, 31),
+        }
+    }
+
+    fn debt(
+        amount: i64,
+        due_date: NaiveDate,
+        category: DebtCategory,
+        expense_type: ExpenseType,
+        tags: &[&str],
+    ) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Compra".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            due_date,
+            Some(category),
+            Some(expense_type),
+            Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            None,
+        )
+    }
+
+    fn payment(debt: &Debt, payment_date: NaiveDate) -> Payment {
+        Payment::new(
+            debt,
+            &Uuid::new_v4(),
+            &PaymentBasicData {
+                payment_date,
+                amount: None,
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_comparison_periods() {
+        let (previous, last_year) = SpendingBreakdown::comparison_periods(&october(2026));
+
+        assert_eq!(previous.start, date(2026, 9, 1));
+        assert_eq!(previous.end, date(2026, 9, 30));
+        assert_eq!(last_year, october(2025));
+
+        let march = ReportPeriod::month(date(2026, 3, 1));
+        let (previous, _) = SpendingBreakdown::comparison_periods(&march);
+        assert_eq!(previous, ReportPeriod::month(date(2026, 2, 1)));
+
+        // Other periods are compared with the same number of days before them
+        let fortnight = ReportPeriod {
+            start: date(2026, 10, 1),
+            end: date(2026, 10, 14),
+        };
+        let (previous, _) = SpendingBreakdown::comparison_periods(&fortnight);
+        assert_eq!(previous.start, date(2026, 9, 17));
+        assert_eq!(previous.end, date(2026, 9, 30));
+    }
+
+    #[test]
+    fn test_breakdown_groups_and_compares() {
+        let rent = debt(
+            1500,
+            date(2026, 10, 10),
+            DebtCategory::new("HOME"),
+            ExpenseType::Fixed,
+            &["casa"],
+        );
+        let market = debt(
+            500,
+            date(2026, 10, 15),
+            DebtCategory::new("FOOD"),
+            ExpenseType::Variable,
+            &["casa", "mercado"],
+        );
+        let old_market = debt(
+            250,
+            date(2025, 10, 15),
+            DebtCategory::new("FOOD"),
+            ExpenseType::Variable,
+            &["mercado"],
+        );
+
+        let current = SpendingTotals::collect(
+            &october(2026),
+            &[rent.clone(), market.clone()],
+            &[],
+            &[payment(&rent, date(2026, 10, 10))],
+        );
+        let old_payment = payment(&old_market, date(2025, 10, 15));
+        let last_year = SpendingTotals::collect(&october(2025), &[old_market], &[], &[old_payment]);
+        let breakdown = SpendingBreakdown::build(
+            october(2026),
+            &current,
+            &SpendingTotals::default(),
+            &last_year,
+        );
+
+        assert_eq!(breakdown.total.amounts.spent, Decimal::from(1500));
+        assert_eq!(breakdown.total.amounts.committed, Decimal::from(2000));
+
+        let home = &breakdown.categories[0];
+        assert_eq!(home.key, "HOME");
+        assert_eq!(home.spent_share, Decimal::ONE_HUNDRED);
+        assert_eq!(home.committed_share, Decimal::from(75));
+
+        let food = &breakdown.categories[1];
+        assert_eq!(food.same_period_last_year.spent, Decimal::from(250));
+        assert_eq!(food.spent_change_from_last_year, Some(Decimal::from(-100)));
+        assert_eq!(food.spent_change_from_previous_period, None);
+
+        let house_tag = breakdown.tags.iter().find(|tag| tag.key == "casa").unwrap();
+        assert_eq!(house_tag.amounts.committed, Decimal::from(2000));
+        assert_eq!(breakdown.expense_types.len(), 2);
+    }
+
+    #[test]
+    fn test_parent_categories_include_their_subcategories() {
+        let mut categories = Category::defaults(Uuid::new_v4());
+        let home = categories
+            .iter()
+            .find(|category| category.name().as_str() == "HOME")
+            .unwrap()
+            .clone();
+        let rent = Category::from_request(
+            *home.client_id(),
+            CreateCategoryRequest {
+                name: "Aluguel".to_string(),
+                parent_id: Some(*home.id()),
+                color: None,
+                icon: None,
+            },
+            &categories,
+        )
+        .unwrap();
+        categories.push(rent);
+
+        let totals = SpendingTotals::collect(
+            &october(2026),
+            &[
+                debt(
+                    300,
+                    date(2026, 10, 5),
+                    DebtCategory::new("HOME"),
+                    ExpenseType::Fixed,
+                    &[],
+                ),
+                debt(
+                    1500,
+                    date(2026, 10, 10),
+                    DebtCategory::new("ALUGUEL"),
+                    ExpenseType::Fixed,
+                    &[],
+                ),
+            ],
+            &[],
+            &[],
+        )
+        .roll_up_subcategories(&categories);
+
+        assert_eq!(
+            totals.category(&DebtCategory::new("HOME")).committed,
+            Decimal::from(1800)
+        );
+        assert_eq!(
+            totals.category(&DebtCategory::new("ALUGUEL")).committed,
+            Decimal::from(1500)
+        );
+        assert_eq!(totals.total.committed, Decimal::from(1800));
+        assert_eq!(
+            totals.category(&DebtCategory::new("FOOD")),
+            SpendingAmounts::default()
+        );
+    }
+
+    #[test]
+    fn test_carry_over_debts_are_left_out() {
+        let market = debt(
+            500,
+            date(2026, 10, 15),
+            DebtCategory::new("FOOD"),
+            ExpenseType::Variable,
+            &[],
+        );
+        let mut carry_over = debt(
+            200,
+            date(2026, 10, 10),
+            DebtCategory::default(),
+            ExpenseType::default(),
+            &[],
+        );
+        carry_over.bill_carry_over(Uuid::new_v4());
+
+        let totals = SpendingTotals::collect(
+            &october(2026),
+            &[market, carry_over.clone()],
+            &[],
+            &[payment(&carry_over, date(2026, 10, 10))],
+        );
+
+        assert_eq!(totals.total.committed, Decimal::from(500));
+        assert_eq!(totals.total.spent, Decimal::ZERO);
+        assert_eq!(
+            totals.category(&DebtCategory::new("FOOD")).committed,
+            Decimal::from(500)
+        );
+        assert_eq!(
+            totals.category(&DebtCategory::default()),
+            SpendingAmounts::default()
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::{installment::Installment, Debt};
+
+pub mod csv;
+pub mod ofx;
+
+/// Days a statement line may be away from the due date of what it pays.
+pub const MATCH_WINDOW_DAYS: i64 = 5;
+
+/// Largest difference between a line and the amount due, as a fraction of
+/// the latter, for them to match. Differences are reconciled on confirmation.
+pub const MATCH_AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatementFormat {
+    Ofx,
+    Csv,
+}
+
+impl StatementFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementFormat::Ofx => "OFX",
+            StatementFormat::Csv => "CSV",
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> HttpResult<Vec<ParsedStatementLine>> {
+        match self {
+            StatementFormat::Ofx => ofx::parse(content),
+            StatementFormat::Csv => csv::parse(content),
+        }
+    }
+}
+
+impl From<String> for StatementFormat {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "CSV" => StatementFormat::Csv,
+            _ => StatementFormat::Ofx,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatementTransactionStatus {
+    /// Waiting for the client to confirm what the line is.
+    #[default]
+    Pending,
+    /// Claimed by a confirmation that is running its action. A line left here
+    /// had the action done without being recorded, so it is never redone.
+    Processing,
+    /// Turned into a payment, a debt or an income.
+    Confirmed,
+    Ignored,
+}
+
+impl StatementTransactionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementTransactionStatus::Pending => "PENDING",
+            StatementTransactionStatus::Processing => "PROCESSING",
+            StatementTransactionStatus::Confirmed => "CONFIRMED",
+            StatementTransactionStatus::Ignored => "IGNORED",
+        }
+    }
+}
+
+impl From<String> for StatementTransactionStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "PROCESSING" => StatementTransactionStatus::Processing,
+            "CONFIRMED" => StatementTransactionStatus::Confirmed,
+            "IGNORED" => StatementTransactionStatus::Ignored,
+            _ => StatementTransactionStatus::Pending,
+        }
+    }
+}
+
+/// Line read from a statement file. Negative amounts left the instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedStatementLine {
+    pub date: NaiveDate,
+    pub description: String,
+    pub amount: Decimal,
+    /// Identifier given by the bank, used to skip lines already imported.
+    pub external_id: Option<String>,
+}
+
+/// Bank or card statement of a financial instrument, staged until the client
+/// confirms each of its lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementImport {
+    id: Uuid,
+    #[serde(skip_serializing)]
+    client_id: Uuid,
+    financial_instrument_id: Uuid,
+    format: StatementFormat,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    transactions: Vec<StatementTransaction>,
+}
+
+impl StatementImport {
+    pub fn new(
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        format: StatementFormat,
+        lines: Vec<ParsedStatementLine>,
+    ) -> HttpResult<Self> {
+        if lines.is_empty() {
+            return Err(Box::new(HttpError::bad_request(
+                "Statement has no transactions to import",
+            )));
+        }
+
+        let id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let transactions = lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| StatementTransaction {
+                id: Uuid::new_v4(),
+                import_id: id,
+                line_number: index as i32 + 1,
+                date: line.date,
+                description: line.description,
+                amount: line.amount,
+                external_id: line.external_id,
+                status: StatementTransactionStatus::Pending,
+                debt_id: None,
+                installment_id: None,
+                payment_id: None,
+                income_id: None,
+                created_at,
+                updated_at: None,
+            })
+            .collect();
+
+        Ok(Self {
+            id,
+            client_id,
+            financial_instrument_id,
+            format,
+            created_at,
+            transactions,
+        })
+    }
+
+    pub fn with_transactions(mut self, transactions: Vec<StatementTransaction>) -> Self {
+        self.transactions = transactions;
+        self
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to access this statement",
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn transaction_mut(
+        &mut self,
+        transaction_id: Uuid,
+    ) -> HttpResult<&mut StatementTransaction> {
+        self.transactions
+            .iter_mut()
+            .find(|transaction| transaction.id == transaction_id)
+            .or_not_found("statement_transaction", transaction_id.to_string())
+    }
+
+    /// First and last dates of the statement lines.
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let first = self.transactions.iter().map(|t| t.date).min()?;
+        let last = self.transactions.iter().map(|t| t.date).max()?;
+
+        Some((first, last))
+    }
+
+    /// Suggests the open installment or debt each pending outgoing line
+    /// pays: the closest amount within the tolerance, then the closest due
+    /// date within the window. Each one is suggested for a single line.
+    pub fn match_open_debts(&mut self, debts: &[Debt], installments: &[Installment]) {
+        let mut candidates: Vec<(Uuid, Option<i32>, NaiveDate, Decimal)> = installments
+            .iter()
+            .filter(|installment| installment.is_open())
+            .map(|installment| {
+                (
+                    *installment.debt_id(),
+                    Some(*installment.installment_id()),
+                    *installment.due_date(),
+                    installment.remaining_amount(),
+                )
+            })
+            .chain(
+                debts
+                    .iter()
+                    .filter(|debt| !debt.has_installments())
+                    .map(|debt| (*debt.id(), None, *debt.due_date(), *debt.remaining_amount())),
+            )
+            .filter(|(_, _, _, amount)| *amount > Decimal::ZERO)
+            .collect();
+
+        for transaction in self.transactions.iter_mut() {
+            if !transaction.is_pending()
+                || !transaction.is_outflow()
+                || transaction.debt_id.is_some()
+            {
+                continue;
+            }
+
+            let amount = transaction.absolute_amount();
+            let best = candidates
+                .iter()
+                .enumerate()
+                .filter_map(|(index, (_, _, due_date, due_amount))| {
+                    let days = (*due_date - transaction.date).num_days().abs();
+                    let difference = (*due_amount - amount).abs();
+
+                    (days <= MATCH_WINDOW_DAYS
+                        && difference <= *due_amount * MATCH_AMOUNT_TOLERANCE)
+                        .then_some((index, difference, days))
+                })
+                .min_by(|a, b| (a.1, a.2).cmp(&(b.1, b.2)))
+                .map(|(index, _, _)| index);
+
+            if let Some(index) = best {
+                let (debt_id, installment_id, _, _) = candidates.remove(index);
+                transaction.debt_id = Some(debt_id);
+                transaction.installment_id = installment_id;
+            }
+        }
+    }
+}
+
+getters! {
+    StatementImport {
+        id: Uuid,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        format: StatementFormat,
+        created_at: DateTime<Utc>,
+        transactions: Vec<StatementTransaction>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for StatementImport {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            financial_instrument_id: row.get("financial_instrument_id"),
+            format: StatementFormat::from(row.get::<String, _>("format")),
+            created_at: row.get("created_at"),
+            transactions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementTransaction {
+    id: Uuid,
+    import_id: Uuid,
+    line_number: i32,
+    date: NaiveDate,
+    description: String,
+    /// Negative when the money left the instrument.
+    amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    status: StatementTransactionStatus,
+    /// Debt the line pays, suggested on import or set on confirmation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    debt_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installment_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    income_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl StatementTransaction {
+    pub fn is_pending(&self) -> bool {
+        self.status == StatementTransactionStatus::Pending
+    }
+
+    pub fn is_outflow(&self) -> bool {
+        self.amount < Decimal::ZERO
+    }
+
+    pub fn absolute_amount(&self) -> Decimal {
+        self.amount.abs()
+    }
+
+    pub fn ensure_pending(&self) -> HttpResult<()> {
+        if !self.is_pending() {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Statement line {} was already processed",
+                self.line_number
+            ))));
+        }
+
+        Ok(())
+    }
+
+    /// Outgoing lines pay debts, incoming ones become incomes.
+    pub fn ensure_outflow(&self, outflow: bool) -> HttpResult<()> {
+        if self.is_outflow() != outflow {
+            let expected = if outflow { "outgoing" } else { "incoming" };
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Statement line {} is not an {} transaction",
+                self.line_number, expected
+            ))));
+        }
+
+        Ok(())
+    }
+
+    /// Records the debt the line was confirmed as, with its payment when
+    /// one was made.
+    pub fn confirm_debt(
+        &mut self,
+        debt_id: Uuid,
+        installment_id: Option<i32>,
+        payment_id: Option<Uuid>,
+    ) {
+        self.debt_id = Some(debt_id);
+        self.installment_id = installment_id;
+        self.payment_id = payment_id;
+        self.confirm(StatementTransactionStatus::Confirmed);
+    }
+
+    pub fn confirm_income(&mut self, income_id: Uuid) {
+        self.debt_id = None;
+        self.installment_id = None;
+        self.income_id = Some(income_id);
+        self.confirm(StatementTransactionStatus::Confirmed);
+    }
+
+    /// Claims the pending line before its action runs.
+    pub fn start_processing(&mut self) -> HttpResult<()> {
+        self.ensure_pending()?;
+        self.confirm(StatementTransactionStatus::Processing);
+
+        Ok(())
+    }
+
+    /// Gives the line back after its action failed.
+    pub fn release(&mut self) {
+        self.confirm(StatementTransactionStatus::Pending);
+    }
+
+    pub fn ignore(&mut self) {
+        self.confirm(StatementTransactionStatus::Ignored);
+    }
+
+    fn confirm(&mut self, status: StatementTransactionStatus) {
+        self.status = status;
+        self.updated_at = Some(Utc::now());
+    }
+}
+
+getters! {
+    StatementTransaction {
+        id: Uuid,
+        import_id: Uuid,
+        line_number: i32,
+        date: NaiveDate,
+        description: String,
+        amount: Decimal,
+        external_id: Option<String>,
+        status: StatementTransactionStatus,
+        debt_id: Option<Uuid>,
+        installment_id: Option<i32>,
+        payment_id: Option<Uuid>,
+        income_id: Option<Uuid>,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for StatementTransaction {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            id: row.get("id"),
+            import_id: row.get("import_id"),
+            line_number: row.get("line_number"),
+            date: row.get("transaction_date"),
+            description: row.get("description"),
+            amount: row.get("amount"),
+            external_id: row.get("external_id"),
+            status: StatementTransactionStatus::from(row.get::<String, _>("status")),
+            debt_id: row.get("debt_id"),
+            installment_id: row.get("installment_id"),
+            payment_id: row.get("payment_id"),
+            income_id: row.get("income_id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn line(day: u32, amount: i64) -> ParsedStatementLine {
+        ParsedStatementLine {
+            date: date(10, day),
+            description: "Linha".to_string(),
+            amount: Decimal::from(amount),
+            external_id: None,
+        }
+    }
+
+    fn debt(amount: i64, due_day: u32) -> Debt {
+        Debt::new(
+            Uuid::new_v4(),
+            "Conta".to_string(),
+            Decimal::from(amount),
+            None,
+            None,
+            date(10, due_day),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_outflows_match_open_debts_by_amount_and_date() {
+        let energy = debt(200, 10);
+        let internet = debt(100, 12);
+        let far_away = debt(300, 28);
+        let notebook = debt(1000, 5);
+        let installment = Installment::new(*notebook.id(), 1, date(10, 15), Decimal::from(500));
+
+        let mut import = StatementImport::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            StatementFormat::Csv,
+            vec![
+                line(11, -203),
+                line(11, -100),
+                line(14, -500),
+                line(15, -300),
+                line(16, 200),
+            ],
+        )
+        .unwrap();
+        import.match_open_debts(
+            &[energy.clone(), internet.clone(), far_away],
+            &[installment],
+        );
+
+        let matched: Vec<_> = import
+            .transactions()
+            .iter()
+            .map(|transaction| (*transaction.debt_id(), *transaction.installment_id()))
+            .collect();
+        assert_eq!(
+            matched,
+            vec![
+                (Some(*energy.id()), None),
+                (Some(*internet.id()), None),
+                (Some(*notebook.id()), Some(1)),
+                (None, None),
+                (None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lines_are_confirmed_only_once() {
+        let mut import = StatementImport::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            StatementFormat::Ofx,
+            vec![line(1, 5000)],
+        )
+        .unwrap();
+        let id = *import.transactions()[0].id();
+
+        let transaction = import.transaction_mut(id).unwrap();
+        assert!(transaction.ensure_outflow(true).is_err());
+        transaction.start_processing().unwrap();
+        assert!(transaction.start_processing().is_err());
+        transaction.release();
+        transaction.start_processing().unwrap();
+        transaction.confirm_income(Uuid::new_v4());
+
+        assert_eq!(*transaction.status(), StatementTransactionStatus::Confirmed);
+        assert!(transaction.ensure_pending().is_err());
+        assert!(import.transaction_mut(Uuid::new_v4()).is_err());
+        assert!(
+            StatementImport::new(Uuid::new_v4(), Uuid::new_v4(), StatementFormat::Csv, vec![])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+
+use crate::modules::finance_manager::domain::statement::ParsedStatementLine;
+
+const DATE_COLUMNS: [&str; 2] = ["data", "date"];
+const DESCRIPTION_COLUMNS: [&str; 6] = [
+    "descricao",
+    "description",
+    "historico",
+    "lancamento",
+    "memo",
+    "title",
+];
+const AMOUNT_COLUMNS: [&str; 3] = ["valor", "amount", "value"];
+const ID_COLUMNS: [&str; 2] = ["identificador", "id"];
+const DATE_FORMATS: [&str; 3] = ["%d/%m/%Y", "%Y-%m-%d", "%d-%m-%Y"];
+
+/// Reads a CSV statement with a header row naming its date, description and
+/// amount columns, in Portuguese or English. Fields may be separated by
+/// commas or semicolons and amounts may use either decimal separator.
+pub fn parse(content: &str) -> HttpResult<Vec<ParsedStatementLine>> {
+    let mut rows = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let Some((_, header)) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let delimiter = if header.contains(';') { ';' } else { ',' };
+    let header: Vec<String> = split_fields(header, delimiter)
+        .iter()
+        .map(|column| normalize(column))
+        .collect();
+
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|column| names.contains(&column.as_str()))
+    };
+    let (Some(date_column), Some(description_column), Some(amount_column)) = (
+        column(&DATE_COLUMNS),
+        column(&DESCRIPTION_COLUMNS),
+        column(&AMOUNT_COLUMNS),
+    ) else {
+        return Err(Box::new(HttpError::bad_request(
+            "CSV header must name the date, description and amount columns",
+        )));
+    };
+    let id_column = column(&ID_COLUMNS);
+
+    rows.map(|(index, row)| {
+        let fields = split_fields(row, delimiter);
+        let field = |column: usize| fields.get(column).map(|field| field.trim()).unwrap_or("");
+        let line_number = index + 1;
+
+        let date = parse_date(field(date_column))
+            .ok_or_else(|| invalid(line_number, "date", field(date_column)))?;
+        let amount = parse_amount(field(amount_column))
+            .ok_or_else(|| invalid(line_number, "amount", field(amount_column)))?;
+
+        Ok(ParsedStatementLine {
+            date,
+            description: field(description_column).to_string(),
+            amount,
+            external_id: id_column
+                .map(field)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+        })
+    })
+    .collect()
+}
+
+/// Splits a row on `delimiter`, except inside double quotes.
+fn split_fields(row: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            char if char == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            char => field.push(char),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Lowercase column name without accents, e.g. "Descrição" as "descricao".
+fn normalize(column: &str) -> String {
+    column
+        .trim()
+        .trim_start_matches('\u{feff}')
+        .to_lowercase()
+        .chars()
+        .map(|char| match char {
+            'á' | 'à' | 'â' | 'ã' => 'a',
+            'é' | 'ê' => 'e',
+            'í' => 'i',
+            'ó' | 'ô' | 'õ' => 'o',
+            'ú' => 'u',
+            'ç' => 'c',
+            char => char,
+        })
+        .collect()
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// Accepts "1.234,56", "1,234.56", "-150,90" and "R$ 10". The last
+/// separator is taken as the decimal one.
+fn parse_amount(value: &str) -> Option<Decimal> {
+    let value: String = value
+        .replace("R$", "")
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect();
+
+    let normalized = match (value.rfind(','), value.rfind('.')) {
+        (Some(comma), Some(dot)) if comma > dot => value.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => value.replace(',', ""),
+        (Some(_), None) => value.replace(',', "."),
+        _ => value,
+    };
+
+    Decimal::from_str(&normalized).ok()
+}
+
+fn invalid(line_number: usize, field: &str, value: &str) -> Box<HttpError> {
+    Box::new(HttpError::bad_request(format!(
+        "Invalid {field} '{value}' on CSV line {line_number}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_bank_and_card_layouts() {
+        let bank = "Data;Valor;Identificador;Descrição\n\
+                    05/10/2026;5.000,00;id-1;Salário\n\
+                    \n\
+                    10/10/2026;-150,90;;\"Conta; luz\"\n";
+        let lines = parse(bank).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].date, date(5));
+        assert_eq!(lines[0].amount, Decimal::from(5000));
+        assert_eq!(lines[0].external_id.as_deref(), Some("id-1"));
+        assert_eq!(lines[1].description, "Conta; luz");
+        assert_eq!(lines[1].amount, Decimal::new(-15090, 2));
+        assert_eq!(lines[1].external_id, None);
+
+        let card = "date,title,amount\n2026-10-12,\"Mercado, centro\",\"1,234.50\"\n";
+        let lines = parse(card).unwrap();
+        assert_eq!(lines[0].description, "Mercado, centro");
+        assert_eq!(lines[0].amount, Decimal::new(123450, 2));
+    }
+
+    #[test]
+    fn test_rejects_unknown_layouts() {
+        assert!(parse("when,what,how much\n2026-10-12,Mercado,10").is_err());
+        assert!(parse("data,descricao,valor\n32/10/2026,Mercado,10").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+
+use crate::modules::finance_manager::domain::statement::ParsedStatementLine;
+
+/// Reads the `STMTTRN` entries of an OFX file. Both the SGML (1.x) and XML
+/// (2.x) flavours are accepted, as only the tag values are looked at.
+pub fn parse(content: &str) -> HttpResult<Vec<ParsedStatementLine>> {
+    content
+        .split("<STMTTRN>")
+        .skip(1)
+        .enumerate()
+        .map(|(index, entry)| {
+            let entry = entry.split("</STMTTRN>").next().unwrap_or(entry);
+            parse_transaction(entry, index + 1)
+        })
+        .collect()
+}
+
+fn parse_transaction(entry: &str, position: usize) -> HttpResult<ParsedStatementLine> {
+    let posted =
+        tag_value(entry, "DTPOSTED").ok_or_else(|| invalid(position, "DTPOSTED is missing"))?;
+    // Dates come as YYYYMMDD, optionally followed by time and timezone.
+    let date = posted
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| invalid(position, &format!("invalid DTPOSTED '{posted}'")))?;
+
+    let raw_amount =
+        tag_value(entry, "TRNAMT").ok_or_else(|| invalid(position, "TRNAMT is missing"))?;
+    let amount = Decimal::from_str(&raw_amount.replace(',', "."))
+        .map_err(|_| invalid(position, &format!("invalid TRNAMT '{raw_amount}'")))?;
+
+    let description = tag_value(entry, "MEMO")
+        .or_else(|| tag_value(entry, "NAME"))
+        .unwrap_or_default();
+
+    Ok(ParsedStatementLine {
+        date,
+        description,
+        amount,
+        external_id: tag_value(entry, "FITID"),
+    })
+}
+
+/// Text after `<TAG>` up to the next tag or line break.
+fn tag_value(entry: &str, tag: &str) -> Option<String> {
+    let start = entry.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let value = entry[start..]
+        .split(['<', '\n', '\r'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn invalid(position: usize, reason: &str) -> Box<HttpError> {
+    Box::new(HttpError::bad_request(format!(
+        "Invalid OFX transaction {position}: {reason}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sgml_and_xml_transactions() {
+        let content = r#"OFXHEADER:100
+DATA:OFXSGML
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20261010120000[-3:BRT]
+<TRNAMT>-150,90
+<FITID>abc-1
+<MEMO>Conta de luz
+</STMTTRN>
+<STMTTRN><TRNTYPE>CREDIT</TRNTYPE><DTPOSTED>20261005</DTPOSTED><TRNAMT>5000.00</TRNAMT><NAME>Salario</NAME></STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>"#;
+
+        let lines = parse(content).unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                ParsedStatementLine {
+                    date: NaiveDate::from_ymd_opt(2026, 10, 10).unwrap(),
+                    description: "Conta de luz".to_string(),
+                    amount: Decimal::new(-15090, 2),
+                    external_id: Some("abc-1".to_string()),
+                },
+                ParsedStatementLine {
+                    date: NaiveDate::from_ymd_opt(2026, 10, 5).unwrap(),
+                    description: "Salario".to_string(),
+                    amount: Decimal::from(5000),
+                    external_id: None,
+                },
+            ]
+        );
+        assert!(parse("<STMTTRN><DTPOSTED>2026<TRNAMT>1</STMTTRN>").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Duration, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::getters;
+use uuid::Uuid;
+
+pub mod command;
+
+/// A Telegram chat linked to a user. Every command received from the chat
+/// runs on behalf of that user's client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelegramChat {
+    chat_id: i64,
+    client_id: Uuid,
+    user_id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl TelegramChat {
+    pub fn new(chat_id: i64, client_id: Uuid, user_id: Uuid) -> Self {
+        Self {
+            chat_id,
+            client_id,
+            user_id,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+}
+
+getters! {
+    TelegramChat {
+        chat_id: i64,
+        client_id: Uuid,
+        user_id: Uuid,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for TelegramChat {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            chat_id: row.get("chat_id"),
+            client_id: row.get("client_id"),
+            user_id: row.get("user_id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+/// One-time code generated by an authenticated user and sent to the bot
+/// (`/vincular <code>`) to prove the chat belongs to that user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelegramLinkCode {
+    code: String,
+    #[serde(skip_serializing)]
+    client_id: Uuid,
+    #[serde(skip_serializing)]
+    user_id: Uuid,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl TelegramLinkCode {
+    const CODE_LENGTH: usize = 6;
+    const VALIDITY_MINUTES: i64 = 15;
+
+    pub fn new(client_id: Uuid, user_id: Uuid) -> Self {
+        let code = Uuid::new_v4()
+            .simple()
+            .to_string()
+            .chars()
+            .take(Self::CODE_LENGTH)
+            .collect::<String>()
+            .to_uppercase();
+        let now = Utc::now();
+
+        Self {
+            code,
+            client_id,
+            user_id,
+            expires_at: now + Duration::minutes(Self::VALIDITY_MINUTES),
+            created_at: now,
+        }
+    }
+
+    pub fn validate_not_expired(&self, now: DateTime<Utc>) -> HttpResult<()> {
+        if now > self.expires_at {
+            return Err(Box::new(HttpError::bad_request(
+                "Código de vinculação expirado",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the code, producing the chat link it authorizes.
+    pub fn link(self, chat_id: i64) -> TelegramChat {
+        TelegramChat::new(chat_id, self.client_id, self.user_id)
+    }
+}
+
+getters! {
+    TelegramLinkCode {
+        code: String,
+        client_id: Uuid,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for TelegramLinkCode {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::Row;
+
+        Self {
+            code: row.get("code"),
+            client_id: row.get("client_id"),
+            user_id: row.get("user_id"),
+            expires_at: row.get("expires_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+/// Formats an amount the way it is shown in chat messages: `R$ 120,50`.
+pub fn format_currency(amount: Decimal) -> String {
+    format!("R$ {:.2}", amount).replace('.', ",")
+}
@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+
+pub const HELP_MESSAGE: &str = "Comandos disponíveis:\n\
+/divida <valor> <descrição> [AAAA-MM-DD] — registra uma dívida\n\
+/pagar <identificação da dívida> <identificação do instrumento> [valor] — registra um pagamento\n\
+/saldo — resumo do mês atual\n\
+/vincular <código> — vincula este chat à sua conta";
+
+const REGISTER_DEBT_USAGE: &str = "Uso: /divida <valor> <descrição> [AAAA-MM-DD]";
+const PAY_DEBT_USAGE: &str =
+    "Uso: /pagar <identificação da dívida> <identificação do instrumento> [valor]";
+const LINK_USAGE: &str = "Uso: /vincular <código>";
+
+/// A command sent to the bot through a chat message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelegramCommand {
+    /// `/divida 120.50 mercado 2026-11-10` — the due date defaults to today.
+    RegisterDebt {
+        amount: Decimal,
+        description: String,
+        due_date: NaiveDate,
+    },
+    /// `/pagar <debt identification> <instrument identification> [amount]`
+    PayDebt {
+        debt_identification: String,
+        financial_instrument_identification: String,
+        amount: Option<Decimal>,
+    },
+    /// `/saldo`
+    Balance,
+    /// `/vincular <code>`
+    Link { code: String },
+    /// `/start`, `/ajuda`
+    Help,
+}
+
+impl TelegramCommand {
+    /// Parses a chat message into a command. Errors carry the usage message
+    /// so they can be replied to the chat as they are.
+    pub fn parse(text: &str, today: NaiveDate) -> HttpResult<Self> {
+        let mut tokens = text.split_whitespace();
+        let command = tokens.next().map(command_name).unwrap_or_default();
+        let args: Vec<&str> = tokens.collect();
+
+        match command.as_str() {
+            "/divida" => Self::parse_register_debt(&args, today),
+            "/pagar" => Self::parse_pay_debt(&args),
+            "/saldo" => Ok(Self::Balance),
+            "/vincular" => match args.as_slice() {
+                [code] => Ok(Self::Link {
+                    code: code.to_uppercase(),
+                }),
+                _ => Err(usage(LINK_USAGE)),
+            },
+            "/start" | "/ajuda" | "/help" => Ok(Self::Help),
+            _ => Err(usage(HELP_MESSAGE)),
+        }
+    }
+
+    fn parse_register_debt(args: &[&str], today: NaiveDate) -> HttpResult<Self> {
+        let Some((amount, rest)) = args.split_first() else {
+            return Err(usage(REGISTER_DEBT_USAGE));
+        };
+        let amount = parse_amount(amount).ok_or_else(|| usage(REGISTER_DEBT_USAGE))?;
+
+        let (due_date, description) = match rest.split_last() {
+            Some((last, description)) => match parse_date(last) {
+                Some(date) => (date, description),
+                None => (today, rest),
+            },
+            None => (today, rest),
+        };
+
+        if description.is_empty() {
+            return Err(usage(REGISTER_DEBT_USAGE));
+        }
+
+        Ok(Self::RegisterDebt {
+            amount,
+            description: description.join(" "),
+            due_date,
+        })
+    }
+
+    fn parse_pay_debt(args: &[&str]) -> HttpResult<Self> {
+        let (debt, instrument, amount) = match args {
+            [debt, instrument] => (debt, instrument, None),
+            [debt, instrument, amount] => (
+                debt,
+                instrument,
+                Some(parse_amount(amount).ok_or_else(|| usage(PAY_DEBT_USAGE))?),
+            ),
+            _ => return Err(usage(PAY_DEBT_USAGE)),
+        };
+
+        if debt.parse::<i32>().is_err() || instrument.parse::<i32>().is_err() {
+            return Err(usage(PAY_DEBT_USAGE));
+        }
+
+        Ok(Self::PayDebt {
+            debt_identification: debt.to_string(),
+            financial_instrument_identification: instrument.to_string(),
+            amount,
+        })
+    }
+}
+
+/// Strips the `@BotName` suffix Telegram appends to commands in group chats.
+fn command_name(token: &str) -> String {
+    token.split('@').next().unwrap_or_default().to_lowercase()
+}
+
+/// Accepts both `120.50` and `120,50`; only positive amounts are valid.
+fn parse_amount(value: &str) -> Option<Decimal> {
+    Decimal::from_str(&value.replace(',', "."))
+        .ok()
+        .filter(|amount| *amount > Decimal::ZERO)
+}
+
+/// Accepts `AAAA-MM-DD` and `DD/MM/AAAA`.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%d/%m/%Y"))
+        .ok()
+}
+
+fn usage(message: &'static str) -> Box<HttpError> {
+    Box::new(HttpError::bad_request(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+    }
+
+    #[test]
+    fn test_parse_register_debt_with_due_date() {
+        let command = TelegramCommand::parse("/divida 120.50 mercado 2026-11-10", today()).unwrap();
+
+        assert_eq!(
+            command,
+            TelegramCommand::RegisterDebt {
+                amount: Decimal::new(12050, 2),
+                description: "mercado".to_string(),
+                due_date: NaiveDate::from_ymd_opt(2026, 11, 10).unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_register_debt_defaults_due_date_to_today() {
+        let command = TelegramCommand::parse("/divida 35,90 padaria da esquina", today()).unwrap();
+
+        assert_eq!(
+            command,
+            TelegramCommand::RegisterDebt {
+                amount: Decimal::new(3590, 2),
+                description: "padaria da esquina".to_string(),
+                due_date: today(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_register_debt_rejects_missing_description() {
+        assert!(TelegramCommand::parse("/divida 10", today()).is_err());
+        assert!(TelegramCommand::parse("/divida 10 2026-11-10", today()).is_err());
+    }
+
+    #[test]
+    fn test_parse_register_debt_rejects_non_positive_amount() {
+        assert!(TelegramCommand::parse("/divida -10 mercado", today()).is_err());
+        assert!(TelegramCommand::parse("/divida abc mercado", today()).is_err());
+    }
+
+    #[test]
+    fn test_parse_pay_debt() {
+        let command = TelegramCommand::parse("/pagar 42 3", today()).unwrap();
+
+        assert_eq!(
+            command,
+            TelegramCommand::PayDebt {
+                debt_identification: "42".to_string(),
+                financial_instrument_identification: "3".to_string(),
+                amount: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_pay_debt_rejects_non_numeric_identification() {
+        assert!(TelegramCommand::parse("/pagar abc 3", today()).is_err());
+    }
+
+    #[test]
+    fn test_parse_strips_bot_name_suffix() {
+        let command = TelegramCommand::parse("/saldo@FinanceBot", today()).unwrap();
+
+        assert_eq!(command, TelegramCommand::Balance);
+    }
+
+    #[test]
+    fn test_parse_link_uppercases_code() {
+        let command = TelegramCommand::parse("/vincular ab12cd", today()).unwrap();
+
+        assert_eq!(
+            command,
+            TelegramCommand::Link {
+                code: "AB12CD".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_command_replies_help() {
+        let err = TelegramCommand::parse("oi", today()).unwrap_err();
+
+        assert_eq!(err.message, HELP_MESSAGE);
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use util::{getters, DeletedBy};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::handler::transfer::use_cases::CreateTransferRequest;
+
+/// Money moved between two financial instruments of the same client. It is
+/// neither an income nor an expense, only a debit on the source and a credit
+/// on the destination ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    id: Uuid,
+    client_id: Uuid,
+    source_instrument_id: Uuid,
+    destination_instrument_id: Uuid,
+    amount: Decimal,
+    description: String,
+    transfer_date: NaiveDate,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_by: Option<DeletedBy>,
+}
+
+impl Transfer {
+    pub fn from_request(client_id: Uuid, request: CreateTransferRequest) -> HttpResult<Self> {
+        if request.amount <= Decimal::ZERO {
+            return Err(Box::new(HttpError::bad_request(
+                "Transfer amount must be greater than zero",
+            )));
+        }
+
+        if request.source_instrument_id == request.destination_instrument_id {
+            return Err(Box::new(HttpError::bad_request(
+                "Source and destination instruments must be different",
+            )));
+        }
+
+        let now = Utc::now();
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            client_id,
+            source_instrument_id: request.source_instrument_id,
+            destination_instrument_id: request.destination_instrument_id,
+            amount: request.amount,
+            description: request
+                .description
+                .unwrap_or_else(|| "Transferência".to_string()),
+            transfer_date: request.transfer_date.unwrap_or(now.date_naive()),
+            created_at: now,
+            updated_at: None,
+            deleted_by: None,
+        })
+    }
+
+    pub fn belongs_to_client(&self, client_id: Uuid) -> HttpResult<()> {
+        if self.client_id != client_id {
+            return Err(Box::new(HttpError::forbidden(
+                "You don't have permission to manage this transfer",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Signed amount of the transfer on the instrument's ledger, if it is one
+    /// of its ends.
+    pub fn amount_for(&self, instrument_id: &Uuid) -> Option<Decimal> {
+        if &self.source_instrument_id == instrument_id {
+            Some(-self.amount)
+        } else if &self.destination_instrument_id == instrument_id {
+            Some(self.amount)
+        } else {
+            None
+        }
+    }
+}
+
+getters! {
+    Transfer {
+        id: Uuid,
+        client_id: Uuid,
+        source_instrument_id: Uuid,
+        destination_instrument_id: Uuid,
+        amount: Decimal,
+        description: String,
+        transfer_date: NaiveDate,
+        created_at: DateTime<Utc>,
+        updated_at: Option<DateTime<Utc>>,
+        deleted_by: Option<DeletedBy>,
+    }
+}
+
+impl From<&sqlx::postgres::PgRow> for Transfer {
+    fn from(row: &sqlx::postgres::PgRow) -> Self {
+        use sqlx::{types::Json, Row};
+
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            source_instrument_id: row.get("source_instrument_id"),
+            destination_instrument_id: row.get("destination_instrument_id"),
+            amount: row.get("amount"),
+            description: row.get("description"),
+            transfer_date: row.get("transfer_date"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            deleted_by: row
+                .get::<Option<Json<DeletedBy>>, _>("deleted_by")
+                .map(|j| j.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(source: Uuid, destination: Uuid, amount: i64) -> CreateTransferRequest {
+        CreateTransferRequest {
+            source_instrument_id: source,
+            destination_instrument_id: destination,
+            amount: Decimal::from(amount),
+            description: None,
+            transfer_date: None,
+        }
+    }
+
+    #[test]
+    fn test_transfer_debits_source_and_credits_destination() {
+        let (source, destination) = (Uuid::new_v4(), Uuid::new_v4());
+        let transfer =
+            Transfer::from_request(Uuid::new_v4(), request(source, destination, 200)).unwrap();
+
+        assert_eq!(transfer.amount_for(&source), Some(Decimal::from(-200)));
+        assert_eq!(transfer.amount_for(&destination), Some(Decimal::from(200)));
+        assert_eq!(transfer.amount_for(&Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_invalid_transfers_are_rejected() {
+        let instrument = Uuid::new_v4();
+
+        assert!(
+            Transfer::from_request(Uuid::new_v4(), request(instrument, instrument, 10)).is_err()
+        );
+        assert!(
+            Transfer::from_request(Uuid::new_v4(), request(instrument, Uuid::new_v4(), 0)).is_err()
+        );
+    }
+}
@@ -1,5 +1,14 @@
+pub mod budget;
 pub mod debt;
+pub mod export;
 pub mod financial_instrument;
 pub mod income;
+pub mod investment;
+pub mod ledger;
 pub mod payment;
 pub mod pubsub;
+pub mod reminder;
+pub mod report;
+pub mod statement;
+pub mod telegram;
+pub mod transfer;
@@ -0,0 +1,295 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{Months, NaiveDate, Utc};
+use http_error::{ext::OptionHttpExt, HttpResult};
+use telegram_api::{domain::send_message::SendMessageRequest, telegram_api::DynTelegramApiGateway};
+use util::{date::first_day_of_month, DeletedBy};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        budget::{Budget, BudgetAlert, BudgetSummary, BudgetThreshold},
+        report::ReportPeriod,
+    },
+    handler::{
+        budget::use_cases::{BudgetQuery, CreateBudgetRequest, UpdateBudgetRequest},
+        debt::category::DynCategoryHandler,
+        report::DynReportHandler,
+    },
+    repository::{budget::DynBudgetRepository, telegram::DynTelegramChatRepository},
+};
+
+pub type DynBudgetHandler = dyn BudgetHandler + Send + Sync;
+
+#[async_trait]
+pub trait BudgetHandler {
+    async fn create_budget(
+        &self,
+        client_id: Uuid,
+        request: CreateBudgetRequest,
+    ) -> HttpResult<Budget>;
+
+    /// Budgets of the client with their usage in the queried month.
+    async fn list_budgets(
+        &self,
+        client_id: Uuid,
+        query: BudgetQuery,
+    ) -> HttpResult<Vec<BudgetSummary>>;
+
+    async fn update_budget(
+        &self,
+        client_id: Uuid,
+        budget_id: Uuid,
+        request: UpdateBudgetRequest,
+    ) -> HttpResult<Budget>;
+
+    async fn delete_budget(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        budget_id: Uuid,
+    ) -> HttpResult<()>;
+
+    async fn list_alerts(
+        &self,
+        client_id: Uuid,
+        query: BudgetQuery,
+    ) -> HttpResult<Vec<BudgetAlert>>;
+
+    /// Raises the alerts of every threshold the client's budgets reached in
+    /// the given months, and sends them to Telegram when the budget asks for
+    /// it. Returns only the alerts raised now.
+    async fn check_budgets(
+        &self,
+        client_id: Uuid,
+        months: Vec<NaiveDate>,
+    ) -> HttpResult<Vec<BudgetAlert>>;
+
+    /// Same as `check_budgets`, after the debts that may raise the alerts
+    /// were saved: a failed check is only logged.
+    async fn check_budgets_or_log(&self, client_id: Uuid, months: Vec<NaiveDate>);
+}
+
+#[derive(Clone)]
+pub struct BudgetHandlerImpl {
+    pub budget_repository: Arc<DynBudgetRepository>,
+    pub telegram_chat_repository: Arc<DynTelegramChatRepository>,
+    pub report: Arc<DynReportHandler>,
+    pub telegram_gateway: Arc<DynTelegramApiGateway>,
+    pub category: Arc<DynCategoryHandler>,
+}
+
+impl BudgetHandlerImpl {
+    async fn get_client_budget(&self, client_id: Uuid, budget_id: Uuid) -> HttpResult<Budget> {
+        let budget = self
+            .budget_repository
+            .get_by_id(&budget_id)
+            .await?
+            .or_not_found("budget", budget_id.to_string())?;
+
+        budget.belongs_to_client(client_id)?;
+
+        Ok(budget)
+    }
+
+    /// Usage of each budget in the month of `month`. Rollover only looks at
+    /// the month before, see `Budget::usage`.
+    async fn summarize(
+        &self,
+        client_id: Uuid,
+        budgets: Vec<Budget>,
+        month: NaiveDate,
+    ) -> HttpResult<Vec<BudgetSummary>> {
+        if budgets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let current = self
+            .report
+            .get_spending_totals(client_id, &ReportPeriod::month(month))
+            .await?;
+        let previous = if budgets.iter().any(|budget| *budget.rollover()) {
+            self.report
+                .get_spending_totals(client_id, &ReportPeriod::month(month - Months::new(1)))
+                .await?
+        } else {
+            Default::default()
+        };
+
+        Ok(budgets
+            .into_iter()
+            .map(|budget| BudgetSummary {
+                usage: budget.usage(month, &current, &previous),
+                budget,
+            })
+            .collect())
+    }
+
+    async fn notify(&self, client_id: Uuid, messages: Vec<String>) -> HttpResult<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let text = messages.join("\n");
+        for chat in self
+            .telegram_chat_repository
+            .list_by_client_id(client_id)
+            .await?
+        {
+            self.telegram_gateway
+                .send_message(SendMessageRequest {
+                    chat_id: *chat.chat_id(),
+                    text: text.clone(),
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BudgetHandler for BudgetHandlerImpl {
+    async fn create_budget(
+        &self,
+        client_id: Uuid,
+        request: CreateBudgetRequest,
+    ) -> HttpResult<Budget> {
+        if let Some(category) = &request.category {
+            self.category.ensure_category(client_id, category).await?;
+        }
+
+        let budget = Budget::from_request(client_id, request)?;
+        self.budget_repository.insert(budget).await
+    }
+
+    async fn list_budgets(
+        &self,
+        client_id: Uuid,
+        query: BudgetQuery,
+    ) -> HttpResult<Vec<BudgetSummary>> {
+        let budgets = self.budget_repository.list(client_id).await?;
+        let month = query.month.unwrap_or_else(|| Utc::now().date_naive());
+
+        self.summarize(client_id, budgets, month).await
+    }
+
+    async fn update_budget(
+        &self,
+        client_id: Uuid,
+        budget_id: Uuid,
+        request: UpdateBudgetRequest,
+    ) -> HttpResult<Budget> {
+        let mut budget = self.get_client_budget(client_id, budget_id).await?;
+        budget.update(request)?;
+
+        self.budget_repository.update(budget).await
+    }
+
+    async fn delete_budget(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        budget_id: Uuid,
+    ) -> HttpResult<()> {
+        self.get_client_budget(client_id, budget_id).await?;
+
+        self.budget_repository
+            .soft_delete(&budget_id, DeletedBy::new(user_id))
+            .await
+    }
+
+    async fn list_alerts(
+        &self,
+        client_id: Uuid,
+        query: BudgetQuery,
+    ) -> HttpResult<Vec<BudgetAlert>> {
+        self.budget_repository
+            .list_alerts(client_id, query.month.map(first_day_of_month))
+            .await
+    }
+
+    async fn check_budgets(
+        &self,
+        client_id: Uuid,
+        months: Vec<NaiveDate>,
+    ) -> HttpResult<Vec<BudgetAlert>> {
+        let budgets = self.budget_repository.list(client_id).await?;
+        if budgets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let months: BTreeSet<NaiveDate> = months.into_iter().map(first_day_of_month).collect();
+        let mut raised = Vec::new();
+        let mut messages = Vec::new();
+
+        for month in months {
+            for summary in self.summarize(client_id, budgets.clone(), month).await? {
+                for threshold in BudgetThreshold::reached(&summary.usage) {
+                    let alert = BudgetAlert::new(&summary.budget, &summary.usage, threshold);
+                    let Some(alert) = self.budget_repository.insert_alert(alert).await? else {
+                        continue;
+                    };
+
+                    if *summary.budget.notify_telegram() {
+                        messages.push(alert.message(&summary.budget));
+                    }
+                    raised.push(alert);
+                }
+            }
+        }
+
+        self.notify(client_id, messages).await?;
+
+        Ok(raised)
+    }
+
+    async fn check_budgets_or_log(&self, client_id: Uuid, months: Vec<NaiveDate>) {
+        if months.is_empty() {
+            return;
+        }
+
+        if let Err(err) = self.check_budgets(client_id, months).await {
+            tracing::error!(
+                "failed to check budgets of client {}: {}",
+                client_id,
+                err.message
+            );
+        }
+    }
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+
+    use crate::modules::finance_manager::domain::debt::DebtCategory;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateBudgetRequest {
+        /// Either a category or a tag, not both.
+        pub category: Option<DebtCategory>,
+        pub tag: Option<String>,
+        pub monthly_limit: Decimal,
+        pub rollover: Option<bool>,
+        pub notify_telegram: Option<bool>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateBudgetRequest {
+        pub monthly_limit: Option<Decimal>,
+        pub rollover: Option<bool>,
+        pub notify_telegram: Option<bool>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BudgetQuery {
+        /// Any day of the month; defaults to the current one.
+        pub month: Option<NaiveDate>,
+    }
+}
@@ -1,29 +1,54 @@
+use std::collections::HashSet;
+
 use async_trait::async_trait;
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate, Utc};
 use http_error::{ext::OptionHttpExt, HttpResult};
 use uuid::Uuid;
 
 use util::DeletedBy;
 
 use crate::modules::finance_manager::{
-    domain::debt::{
-        installment::Installment,
-        recurrence::{Recurrence, RecurrenceFilters},
-        Debt, DebtFilters,
+    domain::{
+        debt::{
+            amortization::InstallmentSchedule,
+            batch::{BatchMode, BatchOperation, DebtBatch, DebtBatchItem, DebtBatchResult},
+            categorization::{Categorization, CategorizationRule, RuleInput},
+            category::{ensure_exists, with_subcategories, Category},
+            installment::Installment,
+            invoice::{filters::InvoiceFilters, Invoice},
+            recurrence::{Recurrence, RecurrenceFilters},
+            renegotiation::DebtRenegotiation,
+            Debt, DebtFilters, RegisteredDebt,
+        },
+        financial_instrument::{billing_cycle::BillingCycle, FinancialInstrument},
     },
-    handler::debt::use_cases::{
-        CreateDebtRequest, CreateRecurrenceRequest, DebtGeneratorRequest,
-        ListDebtInstallmentsRequest, UpdateDebtRequest, UpdateRecurrenceRequest,
+    handler::{
+        budget::DynBudgetHandler,
+        debt::categorization::DynCategorizationHandler,
+        debt::category::DynCategoryHandler,
+        debt::use_cases::{
+            CreateDebtRequest, CreateRecurrenceRequest, DebtBatchRequest,
+            ListDebtInstallmentsRequest, RenegotiateDebtRequest, SimulateInstallmentsRequest,
+            UpdateDebtRequest, UpdateRecurrenceRequest,
+        },
+        ledger::DynLedgerHandler,
     },
     repository::debt::installment::use_cases::InstallmentFilters,
     repository::{
-        debt::{installment::DynInstallmentRepository, DynDebtRepository},
+        debt::{
+            installment::DynInstallmentRepository, invoice::DynInvoiceRepository,
+            renegotiation::DynRenegotiationRepository, DynDebtRepository,
+        },
+        financial_instrument::DynFinancialInstrumentRepository,
         recurrence::DynRecurrenceRepository,
     },
 };
 use std::sync::Arc;
 
+pub mod categorization;
+pub mod category;
 pub mod invoice;
+pub mod recurrence;
 
 pub type DynDebtHandler = dyn DebtHandler + Send + Sync;
 
@@ -42,7 +67,16 @@ pub trait DebtHandler {
         &self,
         client_id: Uuid,
         request: CreateDebtRequest,
-    ) -> HttpResult<Debt>;
+    ) -> HttpResult<RegisteredDebt>;
+
+    /// Creates and updates debts in a single transaction. In best-effort mode
+    /// the valid items are saved and each failed one is reported as a problem
+    /// detail; otherwise any failure rejects the whole batch.
+    async fn apply_debt_batch(
+        &self,
+        client_id: Uuid,
+        request: DebtBatchRequest,
+    ) -> HttpResult<DebtBatchResult>;
 
     async fn list_debt_installments(
         &self,
@@ -50,7 +84,10 @@ pub trait DebtHandler {
         request: &ListDebtInstallmentsRequest,
     ) -> HttpResult<Vec<Installment>>;
 
-    async fn generate_current_recurrences(&self, request: DebtGeneratorRequest) -> HttpResult<()>;
+    async fn simulate_installments(
+        &self,
+        request: SimulateInstallmentsRequest,
+    ) -> HttpResult<InstallmentSchedule>;
 
     async fn create_debt_recurrence(
         &self,
@@ -77,6 +114,19 @@ pub trait DebtHandler {
         user_id: Uuid,
         debt_id: Uuid,
     ) -> HttpResult<()>;
+
+    async fn renegotiate_debt(
+        &self,
+        client_id: Uuid,
+        debt_id: Uuid,
+        request: RenegotiateDebtRequest,
+    ) -> HttpResult<DebtRenegotiation>;
+
+    async fn list_debt_renegotiations(
+        &self,
+        client_id: Uuid,
+        debt_id: Uuid,
+    ) -> HttpResult<Vec<DebtRenegotiation>>;
 }
 
 #[derive(Clone)]
@@ -84,56 +134,280 @@ pub struct DebtHandlerImpl {
     pub debt_repository: Arc<DynDebtRepository>,
     pub installment_repository: Arc<DynInstallmentRepository>,
     pub recurrence_repository: Arc<DynRecurrenceRepository>,
+    pub renegotiation_repository: Arc<DynRenegotiationRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub invoice_repository: Arc<DynInvoiceRepository>,
+    pub ledger: Arc<DynLedgerHandler>,
+    pub budget: Arc<DynBudgetHandler>,
+    pub category: Arc<DynCategoryHandler>,
+    pub categorization: Arc<DynCategorizationHandler>,
+}
+
+/// Client data shared by every debt a request prepares, loaded once.
+struct DebtContext {
+    categories: Vec<Category>,
+    rules: Vec<CategorizationRule>,
 }
 
 impl DebtHandlerImpl {
+    async fn load_context(&self, client_id: Uuid) -> HttpResult<DebtContext> {
+        Ok(DebtContext {
+            categories: self.category.list_categories(client_id).await?,
+            rules: self.categorization.list_active_rules(client_id).await?,
+        })
+    }
+
     /// Processes installments for a debt if applicable.
     /// Returns None if no installments, or the generated installments.
     /// Also updates the debt's due_date to the last installment date.
-    fn process_installments(&self, debt: &mut Debt) -> HttpResult<Option<Vec<Installment>>> {
+    fn process_installments(
+        &self,
+        debt: &mut Debt,
+        billing_cycle: Option<&BillingCycle>,
+    ) -> HttpResult<Option<Vec<Installment>>> {
         if !debt.has_installments() {
             return Ok(None);
         }
 
-        let due_day = debt.due_date().day();
+        let due_day = billing_cycle
+            .map(|cycle| cycle.due_day)
+            .unwrap_or_else(|| debt.due_date().day());
         let installments = debt.generate_installments(due_day)?;
         Ok(Some(installments))
     }
-}
 
-#[async_trait]
-impl DebtHandler for DebtHandlerImpl {
-    async fn generate_current_recurrences(&self, request: DebtGeneratorRequest) -> HttpResult<()> {
-        let current_date = request.get_date();
-        let current_year = current_date.year();
-        let current_month = current_date.month();
+    /// Billing cycle a card purchase is charged on. Cycles whose invoice is
+    /// already closed are skipped, like late charges on a real statement.
+    async fn open_billing_cycle(
+        &self,
+        client_id: Uuid,
+        instrument_id: Uuid,
+        purchase_date: NaiveDate,
+    ) -> HttpResult<Option<(FinancialInstrument, BillingCycle)>> {
+        let instrument = self
+            .financial_instrument_repository
+            .get_by_id(instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", instrument_id.to_string())?;
 
-        let recurrences = self
-            .recurrence_repository
-            .list(&RecurrenceFilters::new().with_active(true))
-            .await?;
+        let Some(mut cycle) = instrument.billing_cycle(purchase_date) else {
+            return Ok(None);
+        };
 
-        for mut recurrence in recurrences {
-            // Skip if already executed this month
-            if recurrence.was_executed_in_month(current_year, current_month) {
-                continue;
+        while self
+            .find_cycle_invoice(&instrument, &cycle)
+            .await?
+            .is_some_and(|invoice| invoice.is_closed())
+        {
+            cycle = cycle.next();
+        }
+
+        Ok(Some((instrument, cycle)))
+    }
+
+    async fn find_cycle_invoice(
+        &self,
+        instrument: &FinancialInstrument,
+        cycle: &BillingCycle,
+    ) -> HttpResult<Option<Invoice>> {
+        self.invoice_repository
+            .get_by_billing_cycle(*instrument.id(), cycle.reference_date)
+            .await
+    }
+
+    /// Links the debt to the invoice of each cycle it is billed on: one per
+    /// installment, starting at `cycle`. Invoices already charged in `batch`
+    /// are reused, so each one is saved once with all its debts.
+    async fn charge_to_invoices(
+        &self,
+        instrument: &FinancialInstrument,
+        cycle: BillingCycle,
+        debt: &Debt,
+        batch: &DebtBatch,
+        prepared: &mut DebtBatch,
+    ) -> HttpResult<()> {
+        let mut cycle = cycle;
+
+        for _ in 0..debt.installment_count().unwrap_or(1).max(1) {
+            let invoice = match batch.cycle_invoice(*instrument.id(), cycle.reference_date) {
+                Some(invoice) => Some(invoice.clone()),
+                None => self.find_cycle_invoice(instrument, &cycle).await?,
+            };
+
+            match invoice {
+                Some(mut invoice) => {
+                    invoice.link_debt(*debt.id())?;
+                    prepared.updated_invoices.push(invoice);
+                }
+                None => {
+                    let mut invoice = Invoice::from_billing_cycle(instrument, &cycle);
+                    invoice.link_debt(*debt.id())?;
+                    prepared.new_invoices.push(invoice);
+                }
             }
 
-            // Skip if outside the valid date range
-            if !recurrence.is_within_date_range(current_date) {
-                continue;
+            cycle = cycle.next();
+        }
+
+        Ok(())
+    }
+
+    /// Builds the debt of the request, its installments and the invoices it
+    /// is charged on, on top of what `batch` already holds. Nothing is saved.
+    async fn prepare_new_debt(
+        &self,
+        client_id: Uuid,
+        mut request: CreateDebtRequest,
+        context: &DebtContext,
+        batch: &DebtBatch,
+    ) -> HttpResult<(RegisteredDebt, DebtBatch)> {
+        request.validate()?;
+        Self::categorize(&mut request, &context.rules);
+        if let Some(category) = &request.category {
+            ensure_exists(&context.categories, category)?;
+        }
+
+        // Card purchases are due with the invoice of their billing cycle
+        let billing = match request.financial_instrument_id {
+            Some(instrument_id) => {
+                let purchase_date = request
+                    .purchase_date
+                    .unwrap_or_else(|| Utc::now().date_naive());
+                self.open_billing_cycle(client_id, instrument_id, purchase_date)
+                    .await?
             }
+            None => None,
+        };
+        let due_date = billing
+            .as_ref()
+            .map(|(_, cycle)| cycle.due_date)
+            .unwrap_or(request.due_date);
+
+        let mut debt = Debt::new(
+            client_id,
+            request.description,
+            request.total_amount,
+            request.paid_amount,
+            request.discount_amount,
+            due_date,
+            request.category,
+            request.expense_type,
+            request.tags,
+            request.installment_count,
+        );
+        debt.set_late_fee_policy(request.late_fee_policy);
+        debt.set_financing(request.financing);
 
-            let debt = recurrence.generate_debt_for_month(current_year, current_month);
-            let saved_debt = self.debt_repository.insert(debt).await?;
+        let installments =
+            self.process_installments(&mut debt, billing.as_ref().map(|(_, cycle)| cycle))?;
 
-            recurrence.add_execution_log(current_date, *saved_debt.id());
-            self.recurrence_repository.update(recurrence).await?;
+        // Earlier purchases of the batch are not in the card's usage yet
+        let credit_limit_warning = match &billing {
+            Some((instrument, _)) => {
+                let amount = *debt.remaining_amount() + batch.card_amount(*instrument.id());
+                self.ledger.check_credit_limit(instrument, amount).await?
+            }
+            None => None,
+        };
+
+        let mut prepared = DebtBatch::default();
+        if let Some((instrument, cycle)) = billing {
+            self.charge_to_invoices(&instrument, cycle, &debt, batch, &mut prepared)
+                .await?;
         }
+        prepared.installments = installments.unwrap_or_default();
+        prepared.new_debts.push(debt.clone());
 
-        Ok(())
+        Ok((
+            RegisteredDebt {
+                debt,
+                credit_limit_warning,
+            },
+            prepared,
+        ))
+    }
+
+    /// Fills the expense type and tags the request left out with the client's
+    /// categorization rules, along with the category. Requests given a
+    /// category keep it and are not matched against the rules.
+    fn categorize(request: &mut CreateDebtRequest, rules: &[CategorizationRule]) {
+        let mut categorization = Categorization {
+            category: request.category.take(),
+            expense_type: request.expense_type.take(),
+            tags: request.tags.take(),
+        };
+
+        categorization.apply_rules(
+            rules,
+            &RuleInput {
+                description: &request.description,
+                amount: request.total_amount,
+                financial_instrument_id: request.financial_instrument_id,
+            },
+        );
+
+        request.category = categorization.category;
+        request.expense_type = categorization.expense_type;
+        request.tags = categorization.tags;
+    }
+
+    /// Applies the changes of the request to the client's debt. Nothing is
+    /// saved.
+    async fn prepare_debt_update(
+        &self,
+        client_id: Uuid,
+        debt_id: Uuid,
+        request: UpdateDebtRequest,
+        categories: &[Category],
+    ) -> HttpResult<Debt> {
+        let mut debt = self
+            .debt_repository
+            .get_by_id(&debt_id)
+            .await?
+            .or_not_found("debt", debt_id.to_string())?;
+
+        if debt.client_id() != &client_id {
+            return Err(Box::new(http_error::HttpError::forbidden(
+                "You don't have permission to update this debt",
+            )));
+        }
+
+        if let Some(category) = request.category {
+            ensure_exists(categories, &category)?;
+            debt.set_category(category);
+        }
+        if let Some(expense_type) = request.expense_type {
+            debt.set_expense_type(expense_type);
+        }
+        if let Some(tags) = request.tags {
+            debt.set_tags(tags);
+        }
+        if let Some(description) = request.description {
+            debt.set_description(description);
+        }
+        if let Some(due_date) = request.due_date {
+            debt.set_due_date(due_date);
+        }
+        if let Some(late_fee_policy) = request.late_fee_policy {
+            late_fee_policy.validate()?;
+            debt.set_late_fee_policy(Some(late_fee_policy));
+        }
+
+        Ok(debt)
     }
 
+    async fn get_client_debt(&self, client_id: Uuid, debt_id: Uuid) -> HttpResult<Debt> {
+        self.debt_repository
+            .get_by_id(&debt_id)
+            .await?
+            .filter(|debt| debt.client_id() == &client_id)
+            .or_not_found("debt", debt_id.to_string())
+    }
+}
+
+#[async_trait]
+impl DebtHandler for DebtHandlerImpl {
     async fn list_debt_recurrences(
         &self,
         client_id: Uuid,
@@ -182,6 +456,10 @@ impl DebtHandler for DebtHandlerImpl {
         client_id: Uuid,
         request: CreateRecurrenceRequest,
     ) -> HttpResult<Recurrence> {
+        if let Some(category) = &request.category {
+            self.category.ensure_category(client_id, category).await?;
+        }
+
         let recurrence = Recurrence::from_request(client_id, request);
         self.recurrence_repository.insert(recurrence).await
     }
@@ -192,33 +470,10 @@ impl DebtHandler for DebtHandlerImpl {
         debt_id: Uuid,
         request: UpdateDebtRequest,
     ) -> HttpResult<Debt> {
-        let mut debt = self
-            .debt_repository
-            .get_by_id(&debt_id)
-            .await?
-            .or_not_found("debt", debt_id.to_string())?;
-
-        if debt.client_id() != &client_id {
-            return Err(Box::new(http_error::HttpError::forbidden(
-                "You don't have permission to update this debt",
-            )));
-        }
-
-        if let Some(category) = request.category {
-            debt.set_category(category);
-        }
-        if let Some(expense_type) = request.expense_type {
-            debt.set_expense_type(expense_type);
-        }
-        if let Some(tags) = request.tags {
-            debt.set_tags(tags);
-        }
-        if let Some(description) = request.description {
-            debt.set_description(description);
-        }
-        if let Some(due_date) = request.due_date {
-            debt.set_due_date(due_date);
-        }
+        let categories = self.category.list_categories(client_id).await?;
+        let debt = self
+            .prepare_debt_update(client_id, debt_id, request, &categories)
+            .await?;
 
         self.debt_repository.update(debt).await
     }
@@ -234,51 +489,139 @@ impl DebtHandler for DebtHandlerImpl {
             .with_is_paid(request.is_paid)
             .with_start_date(request.start_date)
             .with_end_date(request.end_date)
-            .with_payment_id(request.payment_id);
+            .with_payment_id(request.payment_id)
+            .with_include_superseded(request.include_superseded);
 
         self.installment_repository.list(&filters).await
     }
 
+    async fn simulate_installments(
+        &self,
+        request: SimulateInstallmentsRequest,
+    ) -> HttpResult<InstallmentSchedule> {
+        request.validate()?;
+
+        InstallmentSchedule::build(
+            request.total_amount,
+            request.installment_count,
+            request.due_date,
+            request.due_day.unwrap_or_else(|| request.due_date.day()),
+            1,
+            request.financing.as_ref(),
+        )
+    }
+
     async fn register_new_debt(
         &self,
         client_id: Uuid,
         request: CreateDebtRequest,
-    ) -> HttpResult<Debt> {
+    ) -> HttpResult<RegisteredDebt> {
+        let context = self.load_context(client_id).await?;
+        let (registered, batch) = self
+            .prepare_new_debt(client_id, request, &context, &DebtBatch::default())
+            .await?;
+
+        let budget_months = batch.budget_months();
+        let debt = self
+            .debt_repository
+            .save_batch(batch)
+            .await?
+            .pop()
+            .or_not_found("debt", registered.debt.id().to_string())?;
+
+        self.budget
+            .check_budgets_or_log(client_id, budget_months)
+            .await;
+
+        Ok(RegisteredDebt { debt, ..registered })
+    }
+
+    async fn apply_debt_batch(
+        &self,
+        client_id: Uuid,
+        request: DebtBatchRequest,
+    ) -> HttpResult<DebtBatchResult> {
         request.validate()?;
 
-        let mut debt = Debt::new(
-            client_id,
-            request.description,
-            request.total_amount,
-            request.paid_amount,
-            request.discount_amount,
-            request.due_date,
-            request.category,
-            request.expense_type,
-            request.tags,
-            request.installment_count,
-        );
+        let context = self.load_context(client_id).await?;
+        let mut batch = DebtBatch::default();
+        let mut items = Vec::with_capacity(request.len());
 
-        let installments = self.process_installments(&mut debt)?;
+        for (index, create) in request.create.into_iter().enumerate() {
+            let outcome = match self
+                .prepare_new_debt(client_id, create, &context, &batch)
+                .await
+            {
+                Ok((registered, prepared)) => {
+                    batch.merge(prepared);
+                    Ok(registered)
+                }
+                Err(err) => Err(err),
+            };
+            items.push(DebtBatchItem::new(BatchOperation::Create, index, outcome));
+        }
 
-        let debt = self.debt_repository.insert(debt).await?;
+        let mut updated_ids = HashSet::new();
+        for (index, update) in request.update.into_iter().enumerate() {
+            let outcome = if updated_ids.insert(update.id) {
+                self.prepare_debt_update(client_id, update.id, update.changes, &context.categories)
+                    .await
+            } else {
+                Err(Box::new(http_error::HttpError::bad_request(format!(
+                    "Debt {} is updated more than once in the batch",
+                    update.id
+                ))))
+            };
+            let outcome = outcome.map(|debt| {
+                batch.updated_debts.push(debt.clone());
+                RegisteredDebt {
+                    debt,
+                    credit_limit_warning: None,
+                }
+            });
+            items.push(DebtBatchItem::new(BatchOperation::Update, index, outcome));
+        }
 
-        if let Some(installments) = installments {
-            self.installment_repository
-                .insert_many(installments)
-                .await?;
+        let result = DebtBatchResult::new(request.mode, items);
+        result.ensure_applicable()?;
+
+        if batch.is_empty() {
+            return Ok(result);
         }
 
-        Ok(debt)
+        let budget_months = batch.budget_months();
+        let result = match result.mode {
+            BatchMode::AllOrNothing => {
+                result.with_saved_debts(self.debt_repository.save_batch(batch).await?)
+            }
+            BatchMode::BestEffort => {
+                let saved = self.debt_repository.save_batch_best_effort(batch).await?;
+                result
+                    .with_saved_debts(saved.debts)
+                    .with_failed_debts(saved.failures)
+            }
+        };
+
+        self.budget
+            .check_budgets_or_log(client_id, budget_months)
+            .await;
+
+        Ok(result)
     }
 
     async fn list_debts(&self, client_id: Uuid, filters: &DebtFilters) -> HttpResult<Vec<Debt>> {
         let built = DebtFilters::new(client_id)
             .with_optional_statuses(filters.statuses().clone())
             .with_optional_ids(filters.ids().clone())
-            .with_optional_start_date(filters.start_date().clone())
-            .with_optional_end_date(filters.end_date().clone())
-            .with_optional_category_names(filters.category_names().clone());
+            .with_optional_start_date(*filters.start_date())
+            .with_optional_end_date(*filters.end_date())
+            .with_optional_category_names(match filters.category_names() {
+                Some(names) => {
+                    let categories = self.category.list_categories(client_id).await?;
+                    Some(with_subcategories(&categories, names))
+                }
+                None => None,
+            });
 
         let debts = self.debt_repository.list(&built).await?;
 
@@ -307,6 +650,104 @@ impl DebtHandler for DebtHandlerImpl {
             .soft_delete_cascade(client_id, debt_id, DeletedBy::new(user_id))
             .await
     }
+
+    async fn renegotiate_debt(
+        &self,
+        client_id: Uuid,
+        debt_id: Uuid,
+        request: RenegotiateDebtRequest,
+    ) -> HttpResult<DebtRenegotiation> {
+        let mut debt = self.get_client_debt(client_id, debt_id).await?;
+        let mut installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_debt_ids(Some(vec![debt_id]))
+                    .with_include_superseded(true),
+            )
+            .await?;
+        let linked_invoices = self
+            .invoice_repository
+            .list(&InvoiceFilters::new(client_id).with_related_debt_ids(Some(vec![debt_id])))
+            .await?;
+
+        // Card debts stay due with the card's invoices, from the one open today
+        let today = Utc::now().date_naive();
+        let card_id = linked_invoices
+            .iter()
+            .find_map(|invoice| *invoice.financial_instrument_id());
+        let billing = match card_id {
+            Some(card_id) => self.open_billing_cycle(client_id, card_id, today).await?,
+            None => None,
+        };
+        let request = match &billing {
+            Some((_, cycle)) => RenegotiateDebtRequest {
+                due_day: Some(cycle.due_day),
+                first_due_date: Some(cycle.due_date),
+                ..request
+            },
+            None => request,
+        };
+
+        let (renegotiation, new_installments) =
+            debt.renegotiate(&mut installments, &request, today)?;
+
+        let mut invoices = DebtBatch::default();
+        if let Some((instrument, cycle)) = billing {
+            self.charge_to_invoices(
+                &instrument,
+                cycle,
+                &debt,
+                &DebtBatch::default(),
+                &mut invoices,
+            )
+            .await?;
+        }
+
+        // Open invoices left without any installment of the debt stop
+        // billing it
+        let charged: HashSet<Uuid> = invoices
+            .updated_invoices
+            .iter()
+            .map(|invoice| *invoice.id())
+            .collect();
+        let plan: Vec<Installment> = installments
+            .iter()
+            .chain(&new_installments)
+            .cloned()
+            .collect();
+        for mut invoice in linked_invoices {
+            if invoice.is_closed()
+                || charged.contains(invoice.id())
+                || !invoice.billed_amount(&debt, &plan).is_zero()
+            {
+                continue;
+            }
+
+            invoice.unlink_debt(debt_id)?;
+            invoices.updated_invoices.push(invoice);
+        }
+
+        let superseded = installments
+            .into_iter()
+            .filter(|installment| installment.superseded_by() == &Some(*renegotiation.id()))
+            .collect();
+
+        self.renegotiation_repository
+            .save(debt, superseded, new_installments, invoices, renegotiation)
+            .await
+    }
+
+    async fn list_debt_renegotiations(
+        &self,
+        client_id: Uuid,
+        debt_id: Uuid,
+    ) -> HttpResult<Vec<DebtRenegotiation>> {
+        let debt = self.get_client_debt(client_id, debt_id).await?;
+        self.renegotiation_repository
+            .list_by_debt_id(*debt.id())
+            .await
+    }
 }
 
 pub mod use_cases {
@@ -316,7 +757,12 @@ pub mod use_cases {
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
-    use crate::modules::finance_manager::domain::debt::{DebtCategory, DebtStatus, ExpenseType};
+    use crate::modules::finance_manager::domain::debt::{
+        amortization::{validate_installment_count, FinancingTerms},
+        batch::{BatchMode, MAX_BATCH_SIZE},
+        late_fee::LateFeePolicy,
+        DebtCategory, DebtStatus, ExpenseType,
+    };
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
@@ -344,6 +790,14 @@ pub mod use_cases {
         pub discount_amount: Option<Decimal>,
         pub status: Option<DebtStatus>,
         pub installment_count: Option<i32>,
+        pub late_fee_policy: Option<LateFeePolicy>,
+        /// Interest charged over the installments.
+        pub financing: Option<FinancingTerms>,
+        /// Credit card the purchase was made with. Its billing cycle defines
+        /// the invoice and the due date of the debt.
+        pub financial_instrument_id: Option<Uuid>,
+        /// Defaults to today.
+        pub purchase_date: Option<NaiveDate>,
     }
 
     impl CreateDebtRequest {
@@ -366,6 +820,10 @@ pub mod use_cases {
                 due_date,
                 status: Some(DebtStatus::Open),
                 installment_count,
+                late_fee_policy: None,
+                financing: None,
+                financial_instrument_id: None,
+                purchase_date: None,
             }
         }
 
@@ -376,6 +834,24 @@ pub mod use_cases {
                 )));
             }
 
+            if let Some(late_fee_policy) = &self.late_fee_policy {
+                late_fee_policy.validate()?;
+            }
+
+            if let Some(count) = self.installment_count.filter(|count| *count > 0) {
+                validate_installment_count(count)?;
+            }
+
+            if let Some(financing) = &self.financing {
+                if self.installment_count.unwrap_or(0) <= 0 {
+                    return Err(Box::new(HttpError::bad_request(
+                        "Financing terms require an installment count",
+                    )));
+                }
+
+                financing.validate()?;
+            }
+
             Ok(())
         }
 
@@ -384,12 +860,6 @@ pub mod use_cases {
         }
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    pub struct CreateCategoryRequest {
-        pub name: String,
-    }
-
     #[derive(Debug, Clone, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct UpdateDebtRequest {
@@ -398,6 +868,52 @@ pub mod use_cases {
         pub tags: Option<Vec<String>>,
         pub description: Option<String>,
         pub due_date: Option<NaiveDate>,
+        pub late_fee_policy: Option<LateFeePolicy>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct DebtBatchRequest {
+        #[serde(default)]
+        pub mode: BatchMode,
+        #[serde(default)]
+        pub create: Vec<CreateDebtRequest>,
+        #[serde(default)]
+        pub update: Vec<BatchUpdateDebtRequest>,
+    }
+
+    impl DebtBatchRequest {
+        pub fn len(&self) -> usize {
+            self.create.len() + self.update.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn validate(&self) -> HttpResult<()> {
+            if self.is_empty() {
+                return Err(Box::new(HttpError::bad_request(
+                    "Batch must have at least one debt to create or update",
+                )));
+            }
+
+            if self.len() > MAX_BATCH_SIZE {
+                return Err(Box::new(HttpError::bad_request(format!(
+                    "Batch cannot have more than {MAX_BATCH_SIZE} items"
+                ))));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BatchUpdateDebtRequest {
+        pub id: Uuid,
+        #[serde(flatten)]
+        pub changes: UpdateDebtRequest,
     }
 
     #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -428,5 +944,81 @@ pub mod use_cases {
         pub start_date: Option<NaiveDate>,
         pub end_date: Option<NaiveDate>,
         pub payment_id: Option<Uuid>,
+        #[serde(default)]
+        pub include_superseded: bool,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SimulateInstallmentsRequest {
+        pub total_amount: Decimal,
+        pub installment_count: i32,
+        /// Due date of the first installment.
+        pub due_date: NaiveDate,
+        /// Defaults to the day of the due date.
+        pub due_day: Option<u32>,
+        pub financing: Option<FinancingTerms>,
+    }
+
+    impl SimulateInstallmentsRequest {
+        pub fn validate(&self) -> HttpResult<()> {
+            if self.total_amount <= Decimal::ZERO {
+                return Err(Box::new(HttpError::bad_request(
+                    "Total amount must be greater than zero",
+                )));
+            }
+
+            validate_installment_count(self.installment_count)?;
+
+            if let Some(due_day) = self.due_day {
+                if !(1..=31).contains(&due_day) {
+                    return Err(Box::new(HttpError::bad_request(
+                        "Due day must be between 1 and 31",
+                    )));
+                }
+            }
+
+            if let Some(financing) = &self.financing {
+                financing.validate()?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RenegotiateDebtRequest {
+        pub installment_count: i32,
+        /// Defaults to the day of the debt's current due date. Card debts are
+        /// always due with the card's invoices.
+        pub due_day: Option<u32>,
+        /// Defaults to the due day of the next month. Card debts start on the
+        /// invoice open on the renegotiation date.
+        pub first_due_date: Option<NaiveDate>,
+        /// Flat percentage added to the renegotiated balance.
+        pub interest_rate: Option<Decimal>,
+    }
+
+    impl RenegotiateDebtRequest {
+        pub fn validate(&self) -> HttpResult<()> {
+            validate_installment_count(self.installment_count)?;
+
+            if let Some(due_day) = self.due_day {
+                if !(1..=31).contains(&due_day) {
+                    return Err(Box::new(HttpError::bad_request(
+                        "Due day must be between 1 and 31",
+                    )));
+                }
+            }
+
+            if self.interest_rate.is_some_and(|rate| rate < Decimal::ZERO) {
+                return Err(Box::new(HttpError::bad_request(
+                    "Interest rate cannot be negative",
+                )));
+            }
+
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,249 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use http_error::{ext::OptionHttpExt, HttpResult};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::debt::{
+        categorization::{CategorizationRule, RecategorizedDebt},
+        invoice::filters::InvoiceFilters,
+        Debt, DebtCategory, DebtFilters,
+    },
+    handler::debt::{
+        categorization::use_cases::{CreateRuleRequest, UpdateRuleRequest},
+        category::DynCategoryHandler,
+    },
+    repository::debt::{
+        categorization::DynCategorizationRuleRepository, invoice::DynInvoiceRepository,
+        DynDebtRepository,
+    },
+};
+
+pub type DynCategorizationHandler = dyn CategorizationHandler + Send + Sync;
+
+#[async_trait]
+pub trait CategorizationHandler {
+    async fn create_rule(
+        &self,
+        client_id: Uuid,
+        request: CreateRuleRequest,
+    ) -> HttpResult<CategorizationRule>;
+
+    /// Rules of the client in the order they are tried.
+    async fn list_rules(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>>;
+
+    async fn update_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+        request: UpdateRuleRequest,
+    ) -> HttpResult<CategorizationRule>;
+
+    async fn delete_rule(&self, client_id: Uuid, rule_id: Uuid) -> HttpResult<()>;
+
+    /// Rules applied to new debts, in the order they are tried.
+    async fn list_active_rules(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>>;
+
+    /// Dry run: the existing debts the rule would recategorize. Only debts
+    /// without a category that no rule tried before this one claims are
+    /// recategorized.
+    async fn preview_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+    ) -> HttpResult<Vec<RecategorizedDebt>>;
+
+    /// Applies the rule retroactively to the existing debts it matches.
+    async fn apply_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+    ) -> HttpResult<Vec<RecategorizedDebt>>;
+}
+
+#[derive(Clone)]
+pub struct CategorizationHandlerImpl {
+    pub rule_repository: Arc<DynCategorizationRuleRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub invoice_repository: Arc<DynInvoiceRepository>,
+    pub category: Arc<DynCategoryHandler>,
+}
+
+impl CategorizationHandlerImpl {
+    async fn get_client_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+    ) -> HttpResult<CategorizationRule> {
+        let rule = self
+            .rule_repository
+            .get_by_id(&rule_id)
+            .await?
+            .or_not_found("categorization rule", rule_id.to_string())?;
+
+        rule.belongs_to_client(client_id)?;
+
+        Ok(rule)
+    }
+
+    async fn ensure_actions(&self, rule: &CategorizationRule) -> HttpResult<()> {
+        if let Some(category) = &rule.actions().category {
+            self.category
+                .ensure_category(*rule.client_id(), category)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the rule to the client's uncategorized debts, without saving
+    /// them.
+    async fn recategorize(
+        &self,
+        rule: &CategorizationRule,
+    ) -> HttpResult<(Vec<RecategorizedDebt>, Vec<Debt>)> {
+        let client_id = *rule.client_id();
+        let debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(client_id)
+                    .with_category_names(vec![DebtCategory::UNKNOWN.to_string()]),
+            )
+            .await?;
+        let rules = self.list_active_rules(client_id).await?;
+
+        // Debts only know their card through the invoices charging them
+        let needs_cards = rules
+            .iter()
+            .chain([rule])
+            .any(|candidate| candidate.conditions().financial_instrument_id.is_some());
+        let debt_cards: HashMap<Uuid, Uuid> = if needs_cards {
+            self.invoice_repository
+                .list(&InvoiceFilters::new(client_id))
+                .await?
+                .iter()
+                .filter_map(|invoice| {
+                    let card = (*invoice.financial_instrument_id())?;
+                    Some(invoice.related_debt_ids().iter().map(move |id| (*id, card)))
+                })
+                .flatten()
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let mut changes = Vec::new();
+        let mut changed_debts = Vec::new();
+        for mut debt in debts {
+            let card = debt_cards.get(debt.id()).copied();
+            if let Some(change) = rule.recategorize(&mut debt, card, &rules) {
+                changes.push(change);
+                changed_debts.push(debt);
+            }
+        }
+
+        Ok((changes, changed_debts))
+    }
+}
+
+#[async_trait]
+impl CategorizationHandler for CategorizationHandlerImpl {
+    async fn create_rule(
+        &self,
+        client_id: Uuid,
+        request: CreateRuleRequest,
+    ) -> HttpResult<CategorizationRule> {
+        let rule = CategorizationRule::from_request(client_id, request)?;
+        self.ensure_actions(&rule).await?;
+
+        self.rule_repository.insert(rule).await
+    }
+
+    async fn list_rules(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>> {
+        self.rule_repository.list(client_id).await
+    }
+
+    async fn update_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+        request: UpdateRuleRequest,
+    ) -> HttpResult<CategorizationRule> {
+        let mut rule = self.get_client_rule(client_id, rule_id).await?;
+        rule.update(request)?;
+        self.ensure_actions(&rule).await?;
+
+        self.rule_repository.update(rule).await
+    }
+
+    async fn delete_rule(&self, client_id: Uuid, rule_id: Uuid) -> HttpResult<()> {
+        self.get_client_rule(client_id, rule_id).await?;
+
+        self.rule_repository.delete(&rule_id).await
+    }
+
+    async fn list_active_rules(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>> {
+        let rules = self.rule_repository.list(client_id).await?;
+
+        Ok(rules.into_iter().filter(|rule| *rule.active()).collect())
+    }
+
+    async fn preview_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+    ) -> HttpResult<Vec<RecategorizedDebt>> {
+        let rule = self.get_client_rule(client_id, rule_id).await?;
+        let (changes, _) = self.recategorize(&rule).await?;
+
+        Ok(changes)
+    }
+
+    async fn apply_rule(
+        &self,
+        client_id: Uuid,
+        rule_id: Uuid,
+    ) -> HttpResult<Vec<RecategorizedDebt>> {
+        let rule = self.get_client_rule(client_id, rule_id).await?;
+        let (changes, debts) = self.recategorize(&rule).await?;
+
+        if !debts.is_empty() {
+            self.debt_repository.update_labels(debts).await?;
+        }
+
+        Ok(changes)
+    }
+}
+
+pub mod use_cases {
+    use serde::{Deserialize, Serialize};
+
+    use crate::modules::finance_manager::domain::debt::categorization::{
+        RuleActions, RuleConditions,
+    };
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateRuleRequest {
+        pub name: String,
+        /// Defaults to 0; lower priorities are tried first.
+        pub priority: Option<i32>,
+        /// Defaults to true.
+        pub active: Option<bool>,
+        pub conditions: RuleConditions,
+        pub actions: RuleActions,
+    }
+
+    /// Missing fields are left unchanged; conditions and actions are replaced
+    /// as a whole.
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateRuleRequest {
+        pub name: Option<String>,
+        pub priority: Option<i32>,
+        pub active: Option<bool>,
+        pub conditions: Option<RuleConditions>,
+        pub actions: Option<RuleActions>,
+    }
+}
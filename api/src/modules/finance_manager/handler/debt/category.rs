@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::debt::{
+        category::{ensure_exists, Category},
+        DebtCategory,
+    },
+    handler::debt::category::use_cases::{CreateCategoryRequest, UpdateCategoryRequest},
+    repository::debt::category::DynCategoryRepository,
+};
+
+pub type DynCategoryHandler = dyn CategoryHandler + Send + Sync;
+
+#[async_trait]
+pub trait CategoryHandler {
+    async fn create_category(
+        &self,
+        client_id: Uuid,
+        request: CreateCategoryRequest,
+    ) -> HttpResult<Category>;
+
+    /// Categories of the client, seeded with the default ones on first use.
+    async fn list_categories(&self, client_id: Uuid) -> HttpResult<Vec<Category>>;
+
+    /// Renaming a category also renames it on its debts, recurrences, budgets
+    /// and categorization rules.
+    async fn update_category(
+        &self,
+        client_id: Uuid,
+        category_id: Uuid,
+        request: UpdateCategoryRequest,
+    ) -> HttpResult<Category>;
+
+    /// Only categories nothing refers to anymore can be deleted.
+    async fn delete_category(&self, client_id: Uuid, category_id: Uuid) -> HttpResult<()>;
+
+    /// Rejects names the client has no category for.
+    async fn ensure_category(&self, client_id: Uuid, category: &DebtCategory) -> HttpResult<()>;
+}
+
+#[derive(Clone)]
+pub struct CategoryHandlerImpl {
+    pub category_repository: Arc<DynCategoryRepository>,
+}
+
+impl CategoryHandlerImpl {
+    async fn get_client_category(
+        &self,
+        client_id: Uuid,
+        category_id: Uuid,
+    ) -> HttpResult<Category> {
+        let category = self
+            .category_repository
+            .get_by_id(&category_id)
+            .await?
+            .or_not_found("category", category_id.to_string())?;
+
+        category.belongs_to_client(client_id)?;
+
+        Ok(category)
+    }
+}
+
+#[async_trait]
+impl CategoryHandler for CategoryHandlerImpl {
+    async fn create_category(
+        &self,
+        client_id: Uuid,
+        request: CreateCategoryRequest,
+    ) -> HttpResult<Category> {
+        let categories = self.list_categories(client_id).await?;
+        let category = Category::from_request(client_id, request, &categories)?;
+
+        self.category_repository.insert(category).await
+    }
+
+    async fn list_categories(&self, client_id: Uuid) -> HttpResult<Vec<Category>> {
+        let categories = self.category_repository.list(client_id).await?;
+        if !categories.is_empty() {
+            return Ok(categories);
+        }
+
+        self.category_repository
+            .insert_many(Category::defaults(client_id))
+            .await?;
+        self.category_repository.list(client_id).await
+    }
+
+    async fn update_category(
+        &self,
+        client_id: Uuid,
+        category_id: Uuid,
+        request: UpdateCategoryRequest,
+    ) -> HttpResult<Category> {
+        let mut category = self.get_client_category(client_id, category_id).await?;
+        let categories = self.category_repository.list(client_id).await?;
+        let previous_name = category.update(request, &categories)?;
+
+        self.category_repository
+            .update(category, previous_name)
+            .await
+    }
+
+    async fn delete_category(&self, client_id: Uuid, category_id: Uuid) -> HttpResult<()> {
+        let category = self.get_client_category(client_id, category_id).await?;
+
+        if category.name().is_unknown() {
+            return Err(Box::new(HttpError::bad_request(
+                "The default category cannot be deleted",
+            )));
+        }
+
+        if self.category_repository.count_usages(&category).await? > 0 {
+            return Err(Box::new(HttpError::conflict(
+                "Category still has subcategories, debts, recurrences, budgets or rules",
+            )));
+        }
+
+        self.category_repository.delete(&category_id).await
+    }
+
+    async fn ensure_category(&self, client_id: Uuid, category: &DebtCategory) -> HttpResult<()> {
+        if category.is_unknown()
+            || self
+                .category_repository
+                .get_by_name(client_id, category)
+                .await?
+                .is_some()
+        {
+            return Ok(());
+        }
+
+        // Clients that never listed their categories have none seeded yet
+        let categories = self.list_categories(client_id).await?;
+        ensure_exists(&categories, category)
+    }
+}
+
+pub mod use_cases {
+    use serde::{Deserialize, Deserializer, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateCategoryRequest {
+        pub name: String,
+        pub parent_id: Option<Uuid>,
+        /// Hex colour, like `#FFA726`.
+        pub color: Option<String>,
+        pub icon: Option<String>,
+    }
+
+    /// Fields set to `null` are cleared; missing fields are left unchanged.
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateCategoryRequest {
+        pub name: Option<String>,
+        #[serde(default, deserialize_with = "nullable")]
+        pub parent_id: Option<Option<Uuid>>,
+        #[serde(default, deserialize_with = "nullable")]
+        pub color: Option<Option<String>>,
+        #[serde(default, deserialize_with = "nullable")]
+        pub icon: Option<Option<String>>,
+    }
+
+    /// Tells an explicit `null` apart from a missing field.
+    fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Some)
+    }
+}
@@ -1,17 +1,41 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
-use http_error::{ext::OptionHttpExt, HttpResult};
+use chrono::{NaiveDate, Utc};
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
 use uuid::Uuid;
 
-use crate::modules::finance_manager::{
-    domain::debt::invoice::{
-        filters::InvoiceFilters,
-        reference_month_as_date,
-        use_cases::{CreateInvoiceRequest, ListInvoicesFilters, ManageInvoiceDebts},
-        Invoice,
+use crate::modules::{
+    finance_manager::{
+        domain::{
+            debt::{
+                installment::Installment,
+                invoice::{
+                    filters::InvoiceFilters,
+                    reference_month_as_date,
+                    settlement::{InvoiceSettlement, SettlementSummary},
+                    summary::InvoiceSummary,
+                    use_cases::{
+                        CreateInvoiceRequest, ListInvoicesFilters, ManageInvoiceDebts,
+                        PayInvoiceRequest,
+                    },
+                    Invoice,
+                },
+                Debt, DebtFilters,
+            },
+            financial_instrument::FinancialInstrument,
+        },
+        handler::ledger::DynLedgerHandler,
+        repository::{
+            debt::{
+                installment::{use_cases::InstallmentFilters, DynInstallmentRepository},
+                invoice::DynInvoiceRepository,
+                DynDebtRepository,
+            },
+            financial_instrument::DynFinancialInstrumentRepository,
+        },
     },
-    repository::debt::invoice::DynInvoiceRepository,
+    shared::scheduler::DailyJob,
 };
 
 #[async_trait]
@@ -26,7 +50,7 @@ pub trait InvoiceHandler {
         &self,
         client_id: Uuid,
         request: ListInvoicesFilters,
-    ) -> HttpResult<Vec<Invoice>>;
+    ) -> HttpResult<Vec<InvoiceSummary>>;
 
     async fn manage_invoice(
         &self,
@@ -34,6 +58,15 @@ pub trait InvoiceHandler {
         invoice_id: Uuid,
         request: ManageInvoiceDebts,
     ) -> HttpResult<()>;
+
+    async fn close_invoice(&self, client_id: Uuid, invoice_id: Uuid) -> HttpResult<Invoice>;
+
+    async fn pay_invoice(
+        &self,
+        client_id: Uuid,
+        invoice_id: Uuid,
+        request: PayInvoiceRequest,
+    ) -> HttpResult<SettlementSummary>;
 }
 
 pub type DynInvoiceHandler = dyn InvoiceHandler + Send + Sync;
@@ -41,6 +74,133 @@ pub type DynInvoiceHandler = dyn InvoiceHandler + Send + Sync;
 #[derive(Clone)]
 pub struct InvoiceHandlerImpl {
     pub invoice_repository: Arc<DynInvoiceRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub installment_repository: Arc<DynInstallmentRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub ledger: Arc<DynLedgerHandler>,
+}
+
+impl InvoiceHandlerImpl {
+    async fn get_client_invoice(&self, client_id: Uuid, invoice_id: Uuid) -> HttpResult<Invoice> {
+        let invoice = self
+            .invoice_repository
+            .get(&invoice_id)
+            .await?
+            .or_not_found("invoice", invoice_id.to_string())?;
+
+        invoice.belongs_to_client(client_id)?;
+
+        Ok(invoice)
+    }
+
+    async fn get_client_instrument(
+        &self,
+        client_id: Uuid,
+        instrument_id: Uuid,
+    ) -> HttpResult<FinancialInstrument> {
+        self.financial_instrument_repository
+            .get_by_id(instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", instrument_id.to_string())
+    }
+
+    /// Debts linked to the invoice with their installments.
+    async fn list_invoice_debts(
+        &self,
+        invoice: &Invoice,
+    ) -> HttpResult<(Vec<Debt>, Vec<Installment>)> {
+        self.list_debts(*invoice.client_id(), std::slice::from_ref(invoice))
+            .await
+    }
+
+    /// Debts linked to any of the invoices with their installments.
+    async fn list_debts(
+        &self,
+        client_id: Uuid,
+        invoices: &[Invoice],
+    ) -> HttpResult<(Vec<Debt>, Vec<Installment>)> {
+        let debt_ids: Vec<Uuid> = invoices
+            .iter()
+            .flat_map(|invoice| invoice.related_debt_ids().iter().copied())
+            .collect::<HashSet<Uuid>>()
+            .into_iter()
+            .collect();
+        if debt_ids.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let debts = self
+            .debt_repository
+            .list(&DebtFilters::new(client_id).with_ids(debt_ids.clone()))
+            .await?;
+        let installments = self
+            .installment_repository
+            .list(&InstallmentFilters::new().with_debt_ids(Some(debt_ids)))
+            .await?;
+
+        Ok((debts, installments))
+    }
+
+    /// Next open invoice of the card that billed `invoice`, created when it
+    /// does not exist yet. Returns whether it is new.
+    async fn next_card_invoice(&self, invoice: &Invoice) -> HttpResult<(Invoice, bool)> {
+        let card_id = invoice
+            .financial_instrument_id()
+            .or_not_found("financial_instrument", invoice.id().to_string())?;
+        let card = self
+            .get_client_instrument(*invoice.client_id(), card_id)
+            .await?;
+
+        let mut cycle = card
+            .configuration()
+            .reference_billing_cycle(*invoice.reference_date())
+            .ok_or_else(|| {
+                Box::new(HttpError::bad_request(
+                    "Credit card has no billing cycle configured",
+                ))
+            })?
+            .next();
+
+        loop {
+            match self
+                .invoice_repository
+                .get_by_billing_cycle(card_id, cycle.reference_date)
+                .await?
+            {
+                Some(next) if next.is_closed() => cycle = cycle.next(),
+                Some(next) => return Ok((next, false)),
+                None => return Ok((Invoice::from_billing_cycle(&card, &cycle), true)),
+            }
+        }
+    }
+
+    /// Closes the invoice with the total billed for its linked debts.
+    async fn close(&self, mut invoice: Invoice) -> HttpResult<Invoice> {
+        let (debts, installments) = self.list_invoice_debts(&invoice).await?;
+
+        invoice.close(&debts, &installments)?;
+        self.invoice_repository.update(invoice).await
+    }
+
+    /// Closes every billing cycle invoice whose closing date has passed.
+    /// An invoice failing to close is logged and left for the next run.
+    pub async fn close_due_invoices(&self, today: NaiveDate) -> HttpResult<Vec<Invoice>> {
+        let invoices = self.invoice_repository.list_to_close(today).await?;
+
+        let mut closed = Vec::with_capacity(invoices.len());
+        for invoice in invoices {
+            let invoice_id = *invoice.id();
+            match self.close(invoice).await {
+                Ok(invoice) => closed.push(invoice),
+                Err(err) => {
+                    tracing::error!("failed to close invoice {}: {}", invoice_id, err.message)
+                }
+            }
+        }
+
+        Ok(closed)
+    }
 }
 
 #[async_trait]
@@ -60,12 +220,22 @@ impl InvoiceHandler for InvoiceHandlerImpl {
         &self,
         client_id: Uuid,
         request: ListInvoicesFilters,
-    ) -> HttpResult<Vec<Invoice>> {
+    ) -> HttpResult<Vec<InvoiceSummary>> {
         let filters = InvoiceFilters::new(client_id)
             .with_related_debt_ids(request.related_debt_ids)
-            .with_reference_date(request.reference_date.map(reference_month_as_date));
+            .with_reference_date(request.reference_date.map(reference_month_as_date))
+            .with_financial_instrument_id(request.financial_instrument_id);
+
+        let invoices = self.invoice_repository.list(&filters).await?;
+        let (debts, installments) = self.list_debts(client_id, &invoices).await?;
 
-        self.invoice_repository.list(&filters).await
+        let today = Utc::now().date_naive();
+        Ok(invoices
+            .into_iter()
+            .map(|invoice| {
+                InvoiceSummary::build(invoice, &debts, &installments, request.expand_debts, today)
+            })
+            .collect())
     }
 
     async fn manage_invoice(
@@ -93,4 +263,78 @@ impl InvoiceHandler for InvoiceHandlerImpl {
 
         Ok(())
     }
+
+    async fn close_invoice(&self, client_id: Uuid, invoice_id: Uuid) -> HttpResult<Invoice> {
+        let invoice = self.get_client_invoice(client_id, invoice_id).await?;
+
+        self.close(invoice).await
+    }
+
+    async fn pay_invoice(
+        &self,
+        client_id: Uuid,
+        invoice_id: Uuid,
+        request: PayInvoiceRequest,
+    ) -> HttpResult<SettlementSummary> {
+        let invoice = self.get_client_invoice(client_id, invoice_id).await?;
+        let instrument = self
+            .get_client_instrument(client_id, request.financial_instrument_id)
+            .await?;
+        let (mut debts, installments) = self.list_invoice_debts(&invoice).await?;
+        let cards: Vec<FinancialInstrument> = match invoice.financial_instrument_id() {
+            Some(card_id) => self
+                .financial_instrument_repository
+                .get_by_id(*card_id)
+                .await?
+                .into_iter()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let today = Utc::now().date_naive();
+        let payment_date = request.payment_date.unwrap_or(today);
+        for debt in debts.iter_mut() {
+            debt.accrue_late_fees(payment_date, invoice.late_fee_policy(&cards));
+        }
+
+        let mut settlement =
+            InvoiceSettlement::plan(&invoice, debts, installments, &request, today)?;
+
+        if let Some(carry_over) = &request.carry_over {
+            if !settlement.summary.carried_over_amount.is_zero() {
+                let (next_invoice, is_new_invoice) = self.next_card_invoice(&invoice).await?;
+                settlement.attach_carry_over(
+                    &invoice,
+                    next_invoice,
+                    is_new_invoice,
+                    carry_over.interest_rate,
+                )?;
+            }
+        }
+
+        let (lock, credit_limit_warning) = self
+            .ledger
+            .ensure_can_debit(&instrument, settlement.summary.paid_amount)
+            .await?;
+        settlement.summary.credit_limit_warning = credit_limit_warning;
+
+        let summary = settlement.summary.clone();
+        self.invoice_repository
+            .save_settlement(settlement, lock)
+            .await?;
+
+        Ok(summary)
+    }
+}
+
+#[async_trait]
+impl DailyJob for InvoiceHandlerImpl {
+    fn name(&self) -> &'static str {
+        "invoice-closing"
+    }
+
+    async fn run(&self, today: NaiveDate) -> HttpResult<()> {
+        self.close_due_invoices(today).await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,370 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate, Utc};
+use http_error::{HttpErrorKind, HttpResult};
+use util::date::first_day_of_month;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::{
+        domain::{
+            debt::{
+                categorization::{Categorization, CategorizationRule, RuleInput},
+                recurrence::{
+                    generation::{
+                        generation_cutoff, GeneratedKind, GenerationItemStatus, GenerationPlan,
+                        GenerationTrigger, MonthlyRecurrence, RecurrenceGenerationItem,
+                        RecurrenceGenerationRun, RecurrenceGenerationRunFilters,
+                    },
+                    Recurrence, RecurrenceFilters,
+                },
+                Debt,
+            },
+            income::recurrence::IncomeRecurrence,
+        },
+        handler::{budget::DynBudgetHandler, debt::categorization::DynCategorizationHandler},
+        repository::recurrence::{
+            generation_run::DynRecurrenceGenerationRunRepository,
+            income::DynIncomeRecurrenceRepository, DynRecurrenceRepository,
+        },
+    },
+    shared::scheduler::DailyJob,
+};
+
+pub type DynRecurrenceGenerationHandler = dyn RecurrenceGenerationHandler + Send + Sync;
+
+#[async_trait]
+pub trait RecurrenceGenerationHandler {
+    /// Generates the pending months of every active debt and income
+    /// recurrence, up to the month allowed by the configured generation day.
+    async fn generate_scheduled(
+        &self,
+        today: NaiveDate,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>>;
+
+    /// Generates the client's pending months up to the month of
+    /// `reference_date`, or the current month when it is in the future.
+    async fn generate_for_client(
+        &self,
+        client_id: Uuid,
+        reference_date: NaiveDate,
+    ) -> HttpResult<RecurrenceGenerationRun>;
+
+    async fn list_runs(
+        &self,
+        client_id: Uuid,
+        filters: RecurrenceGenerationRunFilters,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>>;
+}
+
+#[derive(Clone)]
+pub struct RecurrenceGenerationHandlerImpl {
+    pub recurrence_repository: Arc<DynRecurrenceRepository>,
+    pub income_recurrence_repository: Arc<DynIncomeRecurrenceRepository>,
+    pub generation_run_repository: Arc<DynRecurrenceGenerationRunRepository>,
+    /// Day of the month from which the current month's debts are generated.
+    pub generation_day: u32,
+    pub budget: Arc<DynBudgetHandler>,
+    pub categorization: Arc<DynCategorizationHandler>,
+}
+
+#[async_trait]
+impl RecurrenceGenerationHandler for RecurrenceGenerationHandlerImpl {
+    async fn generate_scheduled(
+        &self,
+        today: NaiveDate,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>> {
+        let until = generation_cutoff(today, self.generation_day);
+        let active = RecurrenceFilters::new().with_active(true);
+        let recurrences = self.recurrence_repository.list(&active).await?;
+        let income_recurrences = self.income_recurrence_repository.list(&active).await?;
+
+        let mut by_client: HashMap<Uuid, (Vec<Recurrence>, Vec<IncomeRecurrence>)> = HashMap::new();
+        for recurrence in recurrences {
+            by_client
+                .entry(*recurrence.client_id())
+                .or_default()
+                .0
+                .push(recurrence);
+        }
+        for recurrence in income_recurrences {
+            by_client
+                .entry(*recurrence.client_id())
+                .or_default()
+                .1
+                .push(recurrence);
+        }
+
+        let mut runs = Vec::new();
+        for (client_id, (recurrences, income_recurrences)) in by_client {
+            let mut items = self
+                .generate_debts(client_id, recurrences, today, until)
+                .await;
+            items.extend(
+                self.generate_incomes(income_recurrences, today, until)
+                    .await,
+            );
+
+            // Days without anything to generate are not worth a history entry.
+            if items.is_empty() {
+                continue;
+            }
+
+            self.check_budgets(client_id, &items).await;
+
+            let run =
+                RecurrenceGenerationRun::new(client_id, GenerationTrigger::Scheduled, today, items);
+            runs.push(self.generation_run_repository.insert(run).await?);
+        }
+
+        Ok(runs)
+    }
+
+    async fn generate_for_client(
+        &self,
+        client_id: Uuid,
+        reference_date: NaiveDate,
+    ) -> HttpResult<RecurrenceGenerationRun> {
+        // Future months are generated by the schedule when they arrive
+        let reference_date = reference_date.min(Utc::now().date_naive());
+        let filters = RecurrenceFilters::new()
+            .with_client_id(client_id)
+            .with_active(true);
+        let recurrences = self.recurrence_repository.list(&filters).await?;
+        let income_recurrences = self.income_recurrence_repository.list(&filters).await?;
+
+        let (year, month) = (reference_date.year(), reference_date.month());
+        let mut items = Vec::new();
+        for recurrence in &recurrences {
+            if recurrence.was_executed_in_month(year, month) {
+                items.push(RecurrenceGenerationItem::skipped(
+                    recurrence,
+                    first_day_of_month(reference_date),
+                    "Já gerada para o mês",
+                ));
+            }
+        }
+        for recurrence in &income_recurrences {
+            if recurrence.was_executed_in_month(year, month) {
+                items.push(RecurrenceGenerationItem::skipped(
+                    recurrence,
+                    first_day_of_month(reference_date),
+                    "Já gerada para o mês",
+                ));
+            }
+        }
+        items.extend(
+            self.generate_debts(client_id, recurrences, reference_date, reference_date)
+                .await,
+        );
+        items.extend(
+            self.generate_incomes(income_recurrences, reference_date, reference_date)
+                .await,
+        );
+        self.check_budgets(client_id, &items).await;
+
+        let run = RecurrenceGenerationRun::new(
+            client_id,
+            GenerationTrigger::Manual,
+            reference_date,
+            items,
+        );
+
+        self.generation_run_repository.insert(run).await
+    }
+
+    async fn list_runs(
+        &self,
+        client_id: Uuid,
+        filters: RecurrenceGenerationRunFilters,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>> {
+        let filters = filters.with_client_id(client_id);
+        self.generation_run_repository.list(&filters).await
+    }
+}
+
+impl RecurrenceGenerationHandlerImpl {
+    /// Checks the client's budgets for the months that got new debts.
+    async fn check_budgets(&self, client_id: Uuid, items: &[RecurrenceGenerationItem]) {
+        let months: Vec<NaiveDate> = items
+            .iter()
+            .filter(|item| {
+                *item.kind() == GeneratedKind::Debt
+                    && *item.status() == GenerationItemStatus::Created
+            })
+            .map(|item| *item.reference_month())
+            .collect();
+
+        self.budget.check_budgets_or_log(client_id, months).await;
+    }
+
+    async fn generate_debts(
+        &self,
+        client_id: Uuid,
+        recurrences: Vec<Recurrence>,
+        run_date: NaiveDate,
+        until: NaiveDate,
+    ) -> Vec<RecurrenceGenerationItem> {
+        let rules = self.active_rules(client_id, &recurrences).await;
+
+        generate(
+            recurrences,
+            run_date,
+            until,
+            |debt| categorize(debt, &rules),
+            |recurrence, month, debt| {
+                self.recurrence_repository
+                    .save_generated_debt(recurrence, month, debt)
+            },
+        )
+        .await
+    }
+
+    async fn generate_incomes(
+        &self,
+        recurrences: Vec<IncomeRecurrence>,
+        run_date: NaiveDate,
+        until: NaiveDate,
+    ) -> Vec<RecurrenceGenerationItem> {
+        generate(
+            recurrences,
+            run_date,
+            until,
+            |_| {},
+            |recurrence, month, income| {
+                self.income_recurrence_repository
+                    .save_generated_income(recurrence, month, income)
+            },
+        )
+        .await
+    }
+
+    /// Categorization rules of the client. Generation goes on without them
+    /// when they cannot be loaded, so the failure is only logged.
+    async fn active_rules(
+        &self,
+        client_id: Uuid,
+        recurrences: &[Recurrence],
+    ) -> Vec<CategorizationRule> {
+        if recurrences.is_empty() {
+            return Vec::new();
+        }
+
+        self.categorization
+            .list_active_rules(client_id)
+            .await
+            .unwrap_or_else(|err| {
+                tracing::error!(
+                    "failed to load rules of client {}: {}",
+                    client_id,
+                    err.message
+                );
+                Vec::new()
+            })
+    }
+}
+
+#[async_trait]
+impl DailyJob for RecurrenceGenerationHandlerImpl {
+    fn name(&self) -> &'static str {
+        "recurrence-generation"
+    }
+
+    async fn run(&self, today: NaiveDate) -> HttpResult<()> {
+        self.generate_scheduled(today).await?;
+        Ok(())
+    }
+}
+
+/// Generates every pending month of each recurrence, oldest first. Each
+/// month is saved in its own transaction; a failure stops that recurrence so
+/// the missing month is retried on the next run instead of being jumped over
+/// by the later ones.
+async fn generate<R, Saving, Saved>(
+    recurrences: Vec<R>,
+    run_date: NaiveDate,
+    until: NaiveDate,
+    prepare: impl Fn(&mut R::Generated),
+    save: impl Fn(R, NaiveDate, R::Generated) -> Saving,
+) -> Vec<RecurrenceGenerationItem>
+where
+    R: MonthlyRecurrence + Clone,
+    Saving: Future<Output = HttpResult<Saved>>,
+{
+    let mut items = Vec::new();
+
+    for mut recurrence in recurrences {
+        let plan = GenerationPlan::for_recurrence(&recurrence, until);
+
+        items.extend(plan.too_old.iter().map(|month| {
+            RecurrenceGenerationItem::skipped(&recurrence, *month, "Fora da janela de recuperação")
+        }));
+
+        for month in plan.to_generate {
+            let mut generated = recurrence.generate_for_month(month);
+            prepare(&mut generated);
+            let generated_id = R::generated_id(&generated);
+
+            let mut updated = recurrence.clone();
+            updated.log_execution(run_date, month, generated_id);
+
+            match save(updated.clone(), month, generated).await {
+                Ok(_) => {
+                    items.push(RecurrenceGenerationItem::created(
+                        &recurrence,
+                        month,
+                        generated_id,
+                    ));
+                    recurrence = updated;
+                }
+                Err(err) if err.kind == HttpErrorKind::Conflict => {
+                    items.push(RecurrenceGenerationItem::skipped(
+                        &recurrence,
+                        month,
+                        "Já gerada para o mês",
+                    ));
+                    break;
+                }
+                Err(err) => {
+                    items.push(RecurrenceGenerationItem::failed(
+                        &recurrence,
+                        month,
+                        err.message.to_string(),
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    items
+}
+
+/// Recurrences without a category generate uncategorized debts; the rules
+/// fill their category and tags. Their expense type is always fixed.
+fn categorize(debt: &mut Debt, rules: &[CategorizationRule]) {
+    if !debt.category().is_unknown() {
+        return;
+    }
+
+    let mut categorization = Categorization {
+        expense_type: Some(debt.expense_type().clone()),
+        ..Default::default()
+    };
+    let input = RuleInput {
+        description: debt.description(),
+        amount: *debt.total_amount(),
+        financial_instrument_id: None,
+    };
+    if categorization.apply_rules(rules, &input).is_none() {
+        return;
+    }
+
+    if let Some(category) = categorization.category {
+        debt.set_category(category);
+    }
+    if let Some(tags) = categorization.tags {
+        debt.set_tags(tags);
+    }
+}
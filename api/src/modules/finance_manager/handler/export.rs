@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http_error::{HttpError, HttpResult};
+use serde_json::Value;
+use tokio::sync::mpsc::{self, Sender};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::export::{ExportEntity, ExportWriter},
+    handler::export::use_cases::{ExportChunk, ExportFile, ExportQuery},
+    repository::export::{DynExportRepository, ExportRowSink},
+};
+
+/// Size of the chunks sent to the client while the export is written.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+pub type DynExportHandler = dyn ExportHandler + Send + Sync;
+
+#[async_trait]
+pub trait ExportHandler {
+    /// Every debt, installment, payment, income, invoice, recurrence and
+    /// financial instrument of the client, as a JSON file or as zipped CSVs.
+    /// The file is streamed while the rows are read; a failure midway aborts
+    /// the response.
+    async fn export_data(&self, client_id: Uuid, query: ExportQuery) -> HttpResult<ExportFile>;
+}
+
+#[derive(Clone)]
+pub struct ExportHandlerImpl {
+    pub export_repository: Arc<DynExportRepository>,
+}
+
+#[async_trait]
+impl ExportHandler for ExportHandlerImpl {
+    async fn export_data(&self, client_id: Uuid, query: ExportQuery) -> HttpResult<ExportFile> {
+        if let (Some(from), Some(to)) = (query.from, query.to) {
+            if from > to {
+                return Err(Box::new(HttpError::bad_request(
+                    "Export start date must not be after its end date",
+                )));
+            }
+        }
+
+        let (sender, content) = mpsc::channel(4);
+        let export_repository = self.export_repository.clone();
+        let file = ExportFile {
+            file_name: query.format.file_name(),
+            content_type: query.format.content_type(),
+            content,
+        };
+
+        tokio::spawn(async move {
+            let streamer = ExportStreamer {
+                writer: ExportWriter::new(query.format),
+                sender: sender.clone(),
+            };
+            if let Err(err) = streamer
+                .stream(export_repository.as_ref(), client_id, &query)
+                .await
+            {
+                // A closed channel means the client went away
+                if !sender.is_closed() {
+                    tracing::error!(
+                        "failed to export data of client {}: {}",
+                        client_id,
+                        err.message
+                    );
+                    let _ = sender
+                        .send(Err(std::io::Error::other(err.message.to_string())))
+                        .await;
+                }
+            }
+        });
+
+        Ok(file)
+    }
+}
+
+/// Writes the rows of each entity and sends the finished bytes to the client.
+struct ExportStreamer {
+    writer: ExportWriter,
+    sender: Sender<ExportChunk>,
+}
+
+impl ExportStreamer {
+    async fn stream(
+        mut self,
+        export_repository: &DynExportRepository,
+        client_id: Uuid,
+        query: &ExportQuery,
+    ) -> HttpResult<()> {
+        for entity in ExportEntity::ALL {
+            self.writer.start_entity(entity)?;
+            export_repository
+                .export_rows(
+                    entity,
+                    client_id,
+                    query.from,
+                    query.to,
+                    query.include_deleted,
+                    &mut self,
+                )
+                .await?;
+        }
+
+        send_chunk(&self.sender, self.writer.finish()?).await
+    }
+}
+
+#[async_trait]
+impl ExportRowSink for ExportStreamer {
+    async fn write_row(&mut self, row: Value) -> HttpResult<()> {
+        self.writer.write_row(&row)?;
+
+        match self.writer.take_ready(EXPORT_CHUNK_SIZE) {
+            Some(chunk) => send_chunk(&self.sender, chunk).await,
+            None => Ok(()),
+        }
+    }
+}
+
+async fn send_chunk(sender: &Sender<ExportChunk>, chunk: Vec<u8>) -> HttpResult<()> {
+    sender
+        .send(Ok(chunk))
+        .await
+        .map_err(|_| Box::new(HttpError::internal("Export was cancelled")))
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::modules::finance_manager::domain::export::ExportFormat;
+
+    pub type ExportChunk = std::io::Result<Vec<u8>>;
+
+    /// File sent back to the client, received in chunks while it is written.
+    pub struct ExportFile {
+        pub file_name: &'static str,
+        pub content_type: &'static str,
+        pub content: Receiver<ExportChunk>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExportQuery {
+        #[serde(default)]
+        pub format: ExportFormat,
+        /// Filters dated entities, like debts by due date and payments by
+        /// payment date. Instruments and recurrences are always exported.
+        pub from: Option<NaiveDate>,
+        pub to: Option<NaiveDate>,
+        /// Also exports soft-deleted rows, flagged by `deleted_by`.
+        #[serde(default)]
+        pub include_deleted: bool,
+    }
+}
@@ -1,11 +1,13 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
 use uuid::Uuid;
 
 use crate::modules::finance_manager::{
-    domain::financial_instrument::FinancialInstrument,
+    domain::financial_instrument::{
+        credit_limit::CreditLimitUsage, FinancialInstrument, FinancialInstrumentSummary,
+    },
     handler::financial_instrument::use_cases::{
         CreateFinancialInstrumentRequest, FinancialInstrumentListFilters,
         UpdateFinancialInstrumentRequest,
@@ -27,7 +29,7 @@ pub trait FinancialInstrumentHandler {
         &self,
         client_id: Uuid,
         filters: FinancialInstrumentListFilters,
-    ) -> HttpResult<Vec<FinancialInstrument>>;
+    ) -> HttpResult<Vec<FinancialInstrumentSummary>>;
 
     async fn update_financial_instrument(
         &self,
@@ -54,6 +56,10 @@ impl FinancialInstrumentHandler for FinancialInstrumentHandlerImpl {
             .await?
             .or_not_found("financial_instrument", &request.identification)?;
 
+        if let Some(configuration) = &request.configuration {
+            configuration.validate()?;
+        }
+
         instrument.update(&request);
         self.financial_instrument_repository
             .update(instrument.clone())
@@ -77,6 +83,7 @@ impl FinancialInstrumentHandler for FinancialInstrumentHandlerImpl {
                 "Credit card requires due date configuration",
             )));
         }
+        configuration.validate()?;
 
         let financial_instrument = FinancialInstrument::new(
             client_id,
@@ -95,9 +102,35 @@ impl FinancialInstrumentHandler for FinancialInstrumentHandlerImpl {
         &self,
         client_id: Uuid,
         filters: FinancialInstrumentListFilters,
-    ) -> HttpResult<Vec<FinancialInstrument>> {
+    ) -> HttpResult<Vec<FinancialInstrumentSummary>> {
         let filters = filters.with_client_id(client_id);
-        self.financial_instrument_repository.list(filters).await
+        let instruments = self.financial_instrument_repository.list(filters).await?;
+
+        let used_amounts = if instruments.iter().any(|i| i.credit_limit().is_some()) {
+            self.financial_instrument_repository
+                .credit_used_amounts(client_id)
+                .await?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(instruments
+            .into_iter()
+            .map(|instrument| {
+                let credit_limit_usage = instrument.credit_limit().map(|credit_limit| {
+                    let used_amount = used_amounts.get(instrument.id()).copied();
+                    CreditLimitUsage::from_used_amount(
+                        credit_limit,
+                        used_amount.unwrap_or_default(),
+                    )
+                });
+
+                FinancialInstrumentSummary {
+                    instrument,
+                    credit_limit_usage,
+                }
+            })
+            .collect())
     }
 }
 
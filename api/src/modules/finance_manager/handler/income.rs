@@ -1,13 +1,23 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use http_error::HttpResult;
+use http_error::{ext::OptionHttpExt, HttpResult};
 use uuid::Uuid;
 
 use crate::modules::finance_manager::{
-    domain::income::Income,
-    handler::income::use_cases::{CreateIncomeRequest, ListIncomesRequest},
-    repository::income::{use_cases::IncomeListFilters, DynIncomeRepository},
+    domain::{
+        debt::recurrence::RecurrenceFilters,
+        income::{recurrence::IncomeRecurrence, Income},
+    },
+    handler::income::use_cases::{
+        CreateIncomeRecurrenceRequest, CreateIncomeRequest, ListIncomesRequest,
+        UpdateIncomeRecurrenceRequest,
+    },
+    repository::{
+        financial_instrument::DynFinancialInstrumentRepository,
+        income::{use_cases::IncomeListFilters, DynIncomeRepository},
+        recurrence::income::DynIncomeRecurrenceRepository,
+    },
 };
 
 #[async_trait]
@@ -22,6 +32,26 @@ pub trait IncomeHandler {
         client_id: Uuid,
         request: CreateIncomeRequest,
     ) -> HttpResult<Income>;
+
+    async fn create_income_recurrence(
+        &self,
+        client_id: Uuid,
+        request: CreateIncomeRecurrenceRequest,
+    ) -> HttpResult<IncomeRecurrence>;
+
+    async fn list_income_recurrences(
+        &self,
+        client_id: Uuid,
+        filters: &RecurrenceFilters,
+    ) -> HttpResult<Vec<IncomeRecurrence>>;
+
+    /// Also deactivates the recurrence when `active` is false.
+    async fn update_income_recurrence(
+        &self,
+        client_id: Uuid,
+        recurrence_id: Uuid,
+        request: UpdateIncomeRecurrenceRequest,
+    ) -> HttpResult<IncomeRecurrence>;
 }
 
 pub type DynIncomeHandler = dyn IncomeHandler + Send + Sync;
@@ -29,6 +59,24 @@ pub type DynIncomeHandler = dyn IncomeHandler + Send + Sync;
 #[derive(Clone)]
 pub struct IncomeHandlerImpl {
     pub income_repository: Arc<DynIncomeRepository>,
+    pub income_recurrence_repository: Arc<DynIncomeRecurrenceRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+}
+
+impl IncomeHandlerImpl {
+    async fn ensure_client_instrument(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<()> {
+        self.financial_instrument_repository
+            .get_by_id(financial_instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", financial_instrument_id.to_string())?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -56,6 +104,49 @@ impl IncomeHandler for IncomeHandlerImpl {
 
         Ok(income)
     }
+
+    async fn create_income_recurrence(
+        &self,
+        client_id: Uuid,
+        request: CreateIncomeRecurrenceRequest,
+    ) -> HttpResult<IncomeRecurrence> {
+        self.ensure_client_instrument(client_id, request.financial_instrument_id)
+            .await?;
+
+        let recurrence = IncomeRecurrence::from_request(client_id, request)?;
+        self.income_recurrence_repository.insert(recurrence).await
+    }
+
+    async fn list_income_recurrences(
+        &self,
+        client_id: Uuid,
+        filters: &RecurrenceFilters,
+    ) -> HttpResult<Vec<IncomeRecurrence>> {
+        let filters = filters.clone().with_client_id(client_id);
+        self.income_recurrence_repository.list(&filters).await
+    }
+
+    async fn update_income_recurrence(
+        &self,
+        client_id: Uuid,
+        recurrence_id: Uuid,
+        request: UpdateIncomeRecurrenceRequest,
+    ) -> HttpResult<IncomeRecurrence> {
+        let mut recurrence = self
+            .income_recurrence_repository
+            .get_by_id(recurrence_id)
+            .await?
+            .filter(|recurrence| recurrence.client_id() == &client_id)
+            .or_not_found("income_recurrence", recurrence_id.to_string())?;
+
+        if let Some(financial_instrument_id) = request.financial_instrument_id {
+            self.ensure_client_instrument(client_id, financial_instrument_id)
+                .await?;
+        }
+
+        recurrence.update(request)?;
+        self.income_recurrence_repository.update(recurrence).await
+    }
 }
 
 pub mod use_cases {
@@ -73,6 +164,29 @@ pub mod use_cases {
         pub date_reference: NaiveDate,
     }
 
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateIncomeRecurrenceRequest {
+        /// Instrument the incomes are received in.
+        pub financial_instrument_id: Uuid,
+        pub description: String,
+        pub amount: Decimal,
+        pub start_date: NaiveDate,
+        pub end_date: Option<NaiveDate>,
+        pub day_of_month: i32,
+    }
+
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateIncomeRecurrenceRequest {
+        pub financial_instrument_id: Option<Uuid>,
+        pub description: Option<String>,
+        pub amount: Option<Decimal>,
+        pub day_of_month: Option<i32>,
+        pub end_date: Option<NaiveDate>,
+        pub active: Option<bool>,
+    }
+
     #[derive(Debug, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct ListIncomesRequest {
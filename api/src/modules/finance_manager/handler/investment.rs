@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use util::DeletedBy;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::{
+        domain::financial_instrument::{
+            goal::{GoalProgress, SavingsGoal},
+            investment::YieldEntry,
+            FinancialInstrument, FinancialInstrumentType,
+        },
+        handler::{
+            financial_instrument::use_cases::FinancialInstrumentListFilters,
+            investment::use_cases::{CreateGoalRequest, UpdateGoalRequest},
+            ledger::DynLedgerHandler,
+        },
+        repository::financial_instrument::{
+            goal::DynSavingsGoalRepository, investment::DynYieldRepository,
+            DynFinancialInstrumentRepository,
+        },
+    },
+    shared::scheduler::DailyJob,
+};
+
+pub type DynInvestmentHandler = dyn InvestmentHandler + Send + Sync;
+
+#[async_trait]
+pub trait InvestmentHandler {
+    async fn create_goal(
+        &self,
+        client_id: Uuid,
+        request: CreateGoalRequest,
+    ) -> HttpResult<SavingsGoal>;
+
+    /// Goals of the client with their current progress.
+    async fn list_goals(&self, client_id: Uuid) -> HttpResult<Vec<GoalProgress>>;
+
+    async fn update_goal(
+        &self,
+        client_id: Uuid,
+        goal_id: Uuid,
+        request: UpdateGoalRequest,
+    ) -> HttpResult<SavingsGoal>;
+
+    async fn delete_goal(&self, client_id: Uuid, user_id: Uuid, goal_id: Uuid) -> HttpResult<()>;
+
+    async fn list_yields(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<YieldEntry>>;
+}
+
+#[derive(Clone)]
+pub struct InvestmentHandlerImpl {
+    pub goal_repository: Arc<DynSavingsGoalRepository>,
+    pub yield_repository: Arc<DynYieldRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub ledger: Arc<DynLedgerHandler>,
+}
+
+impl InvestmentHandlerImpl {
+    async fn get_client_investment_box(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<FinancialInstrument> {
+        let instrument = self
+            .financial_instrument_repository
+            .get_by_id(financial_instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", financial_instrument_id.to_string())?;
+
+        if instrument.instrument_type() != &FinancialInstrumentType::InvestmentBox {
+            return Err(Box::new(HttpError::bad_request(
+                "Financial instrument is not an investment box",
+            )));
+        }
+
+        Ok(instrument)
+    }
+
+    async fn get_client_goal(&self, client_id: Uuid, goal_id: Uuid) -> HttpResult<SavingsGoal> {
+        let goal = self
+            .goal_repository
+            .get_by_id(&goal_id)
+            .await?
+            .or_not_found("savings_goal", goal_id.to_string())?;
+
+        goal.belongs_to_client(client_id)?;
+
+        Ok(goal)
+    }
+
+    /// Credits the yields of every investment box with a yield policy up to
+    /// the day before `today`, catching up on days the job didn't run. A box
+    /// failing to accrue is logged and caught up on the next run.
+    pub async fn accrue_yields(&self, today: NaiveDate) -> HttpResult<()> {
+        let Some(until) = today.pred_opt() else {
+            return Ok(());
+        };
+
+        let investment_boxes = self
+            .financial_instrument_repository
+            .list(
+                FinancialInstrumentListFilters::new()
+                    .with_instrument_types(vec![FinancialInstrumentType::InvestmentBox]),
+            )
+            .await?;
+
+        for instrument in investment_boxes
+            .iter()
+            .filter(|instrument| instrument.configuration().yield_policy.is_some())
+        {
+            if let Err(err) = self.accrue_instrument_yields(instrument, until).await {
+                tracing::error!(
+                    "failed to accrue yields of instrument {}: {}",
+                    instrument.id(),
+                    err.message
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn accrue_instrument_yields(
+        &self,
+        instrument: &FinancialInstrument,
+        until: NaiveDate,
+    ) -> HttpResult<()> {
+        let ledger = self.ledger.get_ledger(instrument).await?;
+        let accrued = self
+            .yield_repository
+            .list_by_instrument(*instrument.id())
+            .await?;
+        let yields = YieldEntry::accrue(instrument, &ledger, &accrued, until);
+
+        if !yields.is_empty() {
+            self.yield_repository.insert_many(yields).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InvestmentHandler for InvestmentHandlerImpl {
+    async fn create_goal(
+        &self,
+        client_id: Uuid,
+        request: CreateGoalRequest,
+    ) -> HttpResult<SavingsGoal> {
+        self.get_client_investment_box(client_id, request.financial_instrument_id)
+            .await?;
+
+        let goal = SavingsGoal::from_request(client_id, request)?;
+        self.goal_repository.insert(goal).await
+    }
+
+    async fn list_goals(&self, client_id: Uuid) -> HttpResult<Vec<GoalProgress>> {
+        let goals = self.goal_repository.list(client_id).await?;
+        let today = Utc::now().date_naive();
+
+        let mut progress = Vec::with_capacity(goals.len());
+        for goal in goals {
+            let instrument = self
+                .get_client_investment_box(client_id, *goal.financial_instrument_id())
+                .await?;
+            let ledger = self.ledger.get_ledger(&instrument).await?;
+
+            progress.push(goal.progress(&ledger, today));
+        }
+
+        Ok(progress)
+    }
+
+    async fn update_goal(
+        &self,
+        client_id: Uuid,
+        goal_id: Uuid,
+        request: UpdateGoalRequest,
+    ) -> HttpResult<SavingsGoal> {
+        let mut goal = self.get_client_goal(client_id, goal_id).await?;
+        goal.update(request)?;
+
+        self.goal_repository.update(goal).await
+    }
+
+    async fn delete_goal(&self, client_id: Uuid, user_id: Uuid, goal_id: Uuid) -> HttpResult<()> {
+        self.get_client_goal(client_id, goal_id).await?;
+
+        self.goal_repository
+            .soft_delete(&goal_id, DeletedBy::new(user_id))
+            .await
+    }
+
+    async fn list_yields(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<YieldEntry>> {
+        let instrument = self
+            .get_client_investment_box(client_id, financial_instrument_id)
+            .await?;
+
+        self.yield_repository
+            .list_by_instrument(*instrument.id())
+            .await
+    }
+}
+
+#[async_trait]
+impl DailyJob for InvestmentHandlerImpl {
+    fn name(&self) -> &'static str {
+        "investment-yield"
+    }
+
+    async fn run(&self, today: NaiveDate) -> HttpResult<()> {
+        self.accrue_yields(today).await
+    }
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateGoalRequest {
+        /// Investment box the goal is saved in.
+        pub financial_instrument_id: Uuid,
+        pub name: String,
+        pub target_amount: Decimal,
+        pub target_date: NaiveDate,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateGoalRequest {
+        pub name: Option<String>,
+        pub target_amount: Option<Decimal>,
+        pub target_date: Option<NaiveDate>,
+    }
+}
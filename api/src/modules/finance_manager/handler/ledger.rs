@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Datelike, Utc};
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::financial_instrument::{
+        adjustment::AccountAdjustment,
+        credit_limit::CreditLimitUsage,
+        ledger::{Ledger, Statement},
+        FinancialInstrument, FinancialInstrumentType,
+    },
+    handler::ledger::use_cases::{CreateAdjustmentRequest, StatementQuery},
+    repository::{
+        financial_instrument::{
+            adjustment::DynAdjustmentRepository, investment::DynYieldRepository,
+            DynFinancialInstrumentRepository, InstrumentLock,
+        },
+        income::{use_cases::IncomeListFilters, DynIncomeRepository},
+        payment::{use_cases::PaymentFilters, DynPaymentRepository},
+        transfer::{use_cases::TransferFilters, DynTransferRepository},
+    },
+};
+
+pub type DynLedgerHandler = dyn LedgerHandler + Send + Sync;
+
+#[async_trait]
+pub trait LedgerHandler {
+    async fn get_statement(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        query: StatementQuery,
+    ) -> HttpResult<Statement>;
+
+    async fn create_adjustment(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        request: CreateAdjustmentRequest,
+    ) -> HttpResult<AccountAdjustment>;
+
+    /// Ledger of the instrument with all its entries.
+    async fn get_ledger(&self, instrument: &FinancialInstrument) -> HttpResult<Ledger>;
+
+    /// Rejects debits that would overdraw a debit account, or charges over
+    /// the limit of a credit card that rejects them. The instrument stays
+    /// locked until the debit is saved with the returned lock. Comes with the
+    /// warning of a charge over the limit of a card that accepts it.
+    async fn ensure_can_debit(
+        &self,
+        instrument: &FinancialInstrument,
+        amount: Decimal,
+    ) -> HttpResult<(InstrumentLock, Option<String>)>;
+
+    /// Limit usage of a credit card, if it has a credit limit.
+    async fn credit_limit_usage(
+        &self,
+        instrument: &FinancialInstrument,
+    ) -> HttpResult<Option<CreditLimitUsage>>;
+
+    /// Checks a charge against the credit limit of a card. Returns a warning
+    /// when the limit is exceeded and the card doesn't reject it.
+    async fn check_credit_limit(
+        &self,
+        instrument: &FinancialInstrument,
+        amount: Decimal,
+    ) -> HttpResult<Option<String>>;
+}
+
+#[derive(Clone)]
+pub struct LedgerHandlerImpl {
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub income_repository: Arc<DynIncomeRepository>,
+    pub payment_repository: Arc<DynPaymentRepository>,
+    pub adjustment_repository: Arc<DynAdjustmentRepository>,
+    pub transfer_repository: Arc<DynTransferRepository>,
+    pub yield_repository: Arc<DynYieldRepository>,
+}
+
+impl LedgerHandlerImpl {
+    async fn get_client_instrument(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<FinancialInstrument> {
+        self.financial_instrument_repository
+            .get_by_id(financial_instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", financial_instrument_id.to_string())
+    }
+
+    /// Ledger of the instrument with all its entries. Refunds are dated after
+    /// their payment, so the history is never cut by date here.
+    async fn build_ledger(&self, instrument: &FinancialInstrument) -> HttpResult<Ledger> {
+        let incomes = self
+            .income_repository
+            .list(
+                &IncomeListFilters::new(*instrument.client_id())
+                    .with_financial_instrument_ids(Some(vec![*instrument.id()])),
+            )
+            .await?;
+
+        let payments = self
+            .payment_repository
+            .list(
+                &PaymentFilters::new()
+                    .with_client_id(*instrument.client_id())
+                    .with_account_ids(vec![*instrument.id()])
+                    .with_include_refunded(true),
+            )
+            .await?;
+
+        let adjustments = self
+            .adjustment_repository
+            .list_by_instrument(*instrument.id())
+            .await?;
+
+        let transfers = self
+            .transfer_repository
+            .list(
+                &TransferFilters::new(*instrument.client_id())
+                    .with_financial_instrument_ids(Some(vec![*instrument.id()])),
+            )
+            .await?;
+
+        let yields = self
+            .yield_repository
+            .list_by_instrument(*instrument.id())
+            .await?;
+
+        Ok(Ledger::new(
+            instrument,
+            &incomes,
+            &payments,
+            &adjustments,
+            &transfers,
+            &yields,
+        ))
+    }
+}
+
+#[async_trait]
+impl LedgerHandler for LedgerHandlerImpl {
+    async fn get_statement(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        query: StatementQuery,
+    ) -> HttpResult<Statement> {
+        let instrument = self
+            .get_client_instrument(client_id, financial_instrument_id)
+            .await?;
+
+        // Defaults to the current month up to today.
+        let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = query.from.unwrap_or_else(|| to.with_day(1).unwrap_or(to));
+
+        let ledger = self.build_ledger(&instrument).await?;
+        ledger.statement(from, to)
+    }
+
+    async fn create_adjustment(
+        &self,
+        client_id: Uuid,
+        financial_instrument_id: Uuid,
+        request: CreateAdjustmentRequest,
+    ) -> HttpResult<AccountAdjustment> {
+        if request.amount.is_zero() {
+            return Err(Box::new(HttpError::bad_request(
+                "Adjustment amount cannot be zero",
+            )));
+        }
+
+        let instrument = self
+            .get_client_instrument(client_id, financial_instrument_id)
+            .await?;
+
+        let adjustment = AccountAdjustment::from_request(client_id, *instrument.id(), request);
+        self.adjustment_repository.insert(adjustment).await
+    }
+
+    async fn get_ledger(&self, instrument: &FinancialInstrument) -> HttpResult<Ledger> {
+        self.build_ledger(instrument).await
+    }
+
+    async fn ensure_can_debit(
+        &self,
+        instrument: &FinancialInstrument,
+        amount: Decimal,
+    ) -> HttpResult<(InstrumentLock, Option<String>)> {
+        // Concurrent debits wait here, and see this one once it is saved. The
+        // checks run on the lock's connection, so they never wait on the pool.
+        let mut lock = self
+            .financial_instrument_repository
+            .lock(*instrument.id())
+            .await?;
+
+        if instrument.instrument_type() == &FinancialInstrumentType::CreditCard {
+            let Some(credit_limit) = instrument.credit_limit() else {
+                return Ok((lock, None));
+            };
+
+            let usage =
+                CreditLimitUsage::from_used_amount(credit_limit, lock.credit_used_amount().await?);
+            let warning =
+                usage.check_charge(amount, instrument.configuration().credit_limit_enforcement)?;
+            return Ok((lock, warning));
+        }
+
+        let balance = instrument
+            .configuration()
+            .opening_balance
+            .unwrap_or_default()
+            + lock.ledger_total().await?;
+        Ledger::ensure_balance_covers(instrument, balance, amount)?;
+
+        Ok((lock, None))
+    }
+
+    async fn credit_limit_usage(
+        &self,
+        instrument: &FinancialInstrument,
+    ) -> HttpResult<Option<CreditLimitUsage>> {
+        let Some(credit_limit) = instrument.credit_limit() else {
+            return Ok(None);
+        };
+
+        let used_amount = self
+            .financial_instrument_repository
+            .credit_used_amounts(*instrument.client_id())
+            .await?
+            .get(instrument.id())
+            .copied()
+            .unwrap_or_default();
+
+        Ok(Some(CreditLimitUsage::from_used_amount(
+            credit_limit,
+            used_amount,
+        )))
+    }
+
+    async fn check_credit_limit(
+        &self,
+        instrument: &FinancialInstrument,
+        amount: Decimal,
+    ) -> HttpResult<Option<String>> {
+        let Some(usage) = self.credit_limit_usage(instrument).await? else {
+            return Ok(None);
+        };
+
+        usage.check_charge(amount, instrument.configuration().credit_limit_enforcement)
+    }
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StatementQuery {
+        pub from: Option<NaiveDate>,
+        pub to: Option<NaiveDate>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateAdjustmentRequest {
+        /// Positive to credit the account, negative to debit it.
+        pub amount: Decimal,
+        pub description: String,
+        /// Defaults to today.
+        pub adjustment_date: Option<NaiveDate>,
+    }
+}
@@ -2,15 +2,28 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use http_error::{ext::OptionHttpExt, HttpResult};
+use util::DeletedBy;
 
 use crate::modules::finance_manager::{
-    domain::{debt::Debt, financial_instrument::FinancialInstrument, payment::Payment},
+    domain::{
+        debt::{
+            installment::Installment, invoice::filters::InvoiceFilters, late_fee::LateFeePolicy,
+            Debt,
+        },
+        financial_instrument::FinancialInstrument,
+        payment::{Payment, RegisteredPayment},
+    },
     handler::{
+        ledger::DynLedgerHandler,
         payment::use_cases::{CreatePaymentRequest, PaymentBasicData},
         pubsub::DynPubSubHandler,
     },
     repository::{
-        debt::DynDebtRepository,
+        debt::{
+            installment::{use_cases::InstallmentFilters, DynInstallmentRepository},
+            invoice::DynInvoiceRepository,
+            DynDebtRepository,
+        },
         financial_instrument::DynFinancialInstrumentRepository,
         payment::{use_cases::PaymentFilters, DynPaymentRepository},
     },
@@ -22,36 +35,76 @@ use uuid::Uuid;
 
 #[async_trait]
 pub trait PaymentHandler {
-    async fn create_payment(&self, request: CreatePaymentRequest) -> HttpResult<Payment>;
+    async fn create_payment(&self, request: CreatePaymentRequest) -> HttpResult<RegisteredPayment>;
     async fn list_payments(
         &self,
         client_id: Uuid,
         filters: PaymentFilters,
     ) -> HttpResult<Vec<Payment>>;
-    async fn refund_payment(&self, client_id: Uuid, payment_id: Uuid) -> HttpResult<()>;
+    async fn refund_payment(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        payment_id: Uuid,
+    ) -> HttpResult<()>;
 }
 
 #[derive(Clone)]
 pub struct PaymentHandlerImpl {
     pub payment_repository: Arc<DynPaymentRepository>,
     pub debt_repository: Arc<DynDebtRepository>,
+    pub installment_repository: Arc<DynInstallmentRepository>,
+    pub invoice_repository: Arc<DynInvoiceRepository>,
     pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
     pub pubsub: Arc<DynPubSubHandler>,
+    pub ledger: Arc<DynLedgerHandler>,
 }
 
 #[async_trait]
 impl PaymentHandler for PaymentHandlerImpl {
-    async fn create_payment(&self, request: CreatePaymentRequest) -> HttpResult<Payment> {
-        let (debt, instrument, payment_data, reconcile) =
+    async fn create_payment(&self, request: CreatePaymentRequest) -> HttpResult<RegisteredPayment> {
+        let (mut debt, instrument, mut payment_data, reconcile) =
             self.extract_payment_data_from_request(request).await?;
+
+        // Late fees are part of the amount due, so they must be accrued before
+        // the payment amount is defaulted and validated.
+        if !reconcile {
+            let card_policy = self.charged_card_policy(&debt).await?;
+            debt.accrue_late_fees(payment_data.payment_date, card_policy.as_ref());
+        }
+
+        // Installment payments default to what is still due on the informed
+        // installments, or on the next open one.
+        if debt.has_installments() && payment_data.amount.is_none() {
+            let installments = self
+                .installment_repository
+                .list(&InstallmentFilters::new().with_debt_ids(Some(vec![*debt.id()])))
+                .await?;
+
+            let targets = payment_data.installment_ids.as_deref();
+            let mut payable = Installment::payable(&installments, targets)?;
+            if targets.is_none() {
+                payable.truncate(1);
+            }
+
+            payment_data.amount = Some(
+                Installment::amount_due(&installments, &payable)
+                    - payment_data.early_payment_discount.unwrap_or_default(),
+            );
+        }
+
         let payment = Payment::new(&debt, instrument.id(), &payment_data);
 
-        // Validate BEFORE inserting (skip validation when reconcile is true)
+        // Validate BEFORE inserting (skip validation when reconcile is true).
+        // Reconciled payments still leave the account, so they are debited too.
         if !reconcile {
             self.pubsub.validate_payment(&debt, &payment).await?;
         }
-
-        let payment = self.payment_repository.insert(payment).await?;
+        let (lock, credit_limit_warning) = self
+            .ledger
+            .ensure_can_debit(&instrument, *payment.amount())
+            .await?;
+        let payment = self.payment_repository.insert_debit(payment, lock).await?;
 
         if reconcile {
             self.pubsub
@@ -61,7 +114,10 @@ impl PaymentHandler for PaymentHandlerImpl {
             self.pubsub.process_debt_payment(debt, &payment).await?;
         }
 
-        Ok(payment)
+        Ok(RegisteredPayment {
+            payment,
+            credit_limit_warning,
+        })
     }
 
     async fn list_payments(
@@ -73,8 +129,13 @@ impl PaymentHandler for PaymentHandlerImpl {
         self.payment_repository.list(&filters).await
     }
 
-    async fn refund_payment(&self, client_id: Uuid, payment_id: Uuid) -> HttpResult<()> {
-        let payment = self
+    async fn refund_payment(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        payment_id: Uuid,
+    ) -> HttpResult<()> {
+        let mut payment = self
             .payment_repository
             .get_by_id(&payment_id)
             .await?
@@ -94,7 +155,8 @@ impl PaymentHandler for PaymentHandlerImpl {
 
         self.pubsub.reverse_payment(debt, &payment).await?;
 
-        self.payment_repository.delete(&payment_id).await?;
+        payment.refund(DeletedBy::new(user_id));
+        self.payment_repository.refund(payment).await?;
 
         Ok(())
     }
@@ -140,6 +202,33 @@ impl PaymentHandlerImpl {
 
         Ok((debt, instrument, payment_data, reconcile))
     }
+
+    /// Late-fee policy of the card the debt was charged on. The instrument
+    /// paying the debt plays no part in it.
+    async fn charged_card_policy(&self, debt: &Debt) -> HttpResult<Option<LateFeePolicy>> {
+        let invoices = self
+            .invoice_repository
+            .list(
+                &InvoiceFilters::new(*debt.client_id())
+                    .with_related_debt_ids(Some(vec![*debt.id()])),
+            )
+            .await?;
+
+        let Some((invoice, card_id)) = invoices.into_iter().find_map(|invoice| {
+            let card_id = (*invoice.financial_instrument_id())?;
+            Some((invoice, card_id))
+        }) else {
+            return Ok(None);
+        };
+        let cards: Vec<FinancialInstrument> = self
+            .financial_instrument_repository
+            .get_by_id(card_id)
+            .await?
+            .into_iter()
+            .collect();
+
+        Ok(invoice.late_fee_policy(&cards).cloned())
+    }
 }
 
 pub mod use_cases {
@@ -184,6 +273,12 @@ pub mod use_cases {
     pub struct PaymentBasicData {
         pub payment_date: NaiveDate,
         pub amount: Option<Decimal>,
+        /// Pays these installment numbers instead of the oldest unpaid one.
+        #[serde(default)]
+        pub installment_ids: Option<Vec<i32>>,
+        /// Discount for settling future installments in advance ("antecipação").
+        #[serde(default)]
+        pub early_payment_discount: Option<Decimal>,
     }
 
     impl PaymentBasicData {
@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use http_error::{ext::OptionHttpExt, HttpResult};
+use http_error::HttpResult;
+use uuid::Uuid;
 
 use crate::modules::finance_manager::{
     domain::{
-        debt::{installment::Installment, Debt},
+        debt::{
+            installment::{Installment, InstallmentAllocation},
+            Debt,
+        },
         payment::Payment,
     },
     repository::debt::{
@@ -44,23 +48,69 @@ pub struct PubSubHandlerImpl {
 }
 
 impl PubSubHandlerImpl {
-    async fn process_latest_installment_for_debt(&self, payment: &Payment) -> HttpResult<()> {
-        let installments = self
-            .installment_repository
-            .list(&InstallmentFilters::new().with_debt_ids(Some(vec![*payment.debt_id()])))
-            .await?;
+    async fn list_debt_installments(&self, debt_id: Uuid) -> HttpResult<Vec<Installment>> {
+        self.installment_repository
+            .list(&InstallmentFilters::new().with_debt_ids(Some(vec![debt_id])))
+            .await
+    }
 
-        let mut latest_installment = Installment::get_latest_unpaid(&installments)
-            .or_not_found("latest installment for debt", payment.debt_id().to_string())?
-            .clone();
+    async fn allocate_payment_to_installments(&self, payment: &Payment) -> HttpResult<()> {
+        let mut installments = self.list_debt_installments(*payment.debt_id()).await?;
 
-        latest_installment.process_payment(payment)?;
+        let allocations = Installment::allocate_payment(&mut installments, payment)?;
+        let touched = installments
+            .into_iter()
+            .filter(|installment| {
+                allocations
+                    .iter()
+                    .any(|allocation| allocation.installment_id() == installment.installment_id())
+            })
+            .collect();
 
         self.installment_repository
-            .update(latest_installment.clone())
+            .apply_payment(touched, allocations)
+            .await
+    }
+
+    async fn reverse_installment_allocations(&self, payment: &Payment) -> HttpResult<()> {
+        // Superseded installments are listed so reversing a payment made
+        // before a renegotiation is rejected instead of not found.
+        let mut installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_debt_ids(Some(vec![*payment.debt_id()]))
+                    .with_include_superseded(true),
+            )
+            .await?;
+        let mut allocations = self
+            .installment_repository
+            .list_allocations(*payment.id())
             .await?;
 
-        Ok(())
+        // Payments made before allocations were tracked paid a single
+        // installment in full.
+        if allocations.is_empty() {
+            allocations = installments
+                .iter()
+                .filter(|installment| installment.payment_id() == &Some(*payment.id()))
+                .map(|installment| InstallmentAllocation::legacy(*payment.id(), installment))
+                .collect();
+        }
+
+        Installment::reverse_allocations(&mut installments, &allocations)?;
+        let touched = installments
+            .into_iter()
+            .filter(|installment| {
+                allocations
+                    .iter()
+                    .any(|allocation| allocation.installment_id() == installment.installment_id())
+            })
+            .collect();
+
+        self.installment_repository
+            .reverse_payment(touched, *payment.id())
+            .await
     }
 }
 
@@ -69,15 +119,8 @@ impl PubSubHandler for PubSubHandlerImpl {
     // TODO: verify the necessity of this method
     async fn validate_payment(&self, debt: &Debt, payment: &Payment) -> HttpResult<()> {
         if debt.has_installments() {
-            let installments = self
-                .installment_repository
-                .list(&InstallmentFilters::new().with_debt_ids(Some(vec![*debt.id()])))
-                .await?;
-
-            let latest_installment = Installment::get_latest_unpaid(&installments)
-                .or_not_found("unpaid installment", debt.id().to_string())?;
-
-            latest_installment.validate_payment(payment)?;
+            let installments = self.list_debt_installments(*debt.id()).await?;
+            Installment::validate_payment(&installments, payment)?;
         } else {
             debt.validate_payment_amount(payment)?;
         }
@@ -99,7 +142,7 @@ impl PubSubHandler for PubSubHandlerImpl {
 
     async fn process_debt_payment(&self, mut debt: Debt, payment: &Payment) -> HttpResult<Debt> {
         if debt.has_installments() {
-            self.process_latest_installment_for_debt(payment).await?;
+            self.allocate_payment_to_installments(payment).await?;
         }
         debt.process_payment(payment)?;
 
@@ -109,16 +152,7 @@ impl PubSubHandler for PubSubHandlerImpl {
 
     async fn reverse_payment(&self, mut debt: Debt, payment: &Payment) -> HttpResult<Debt> {
         if debt.has_installments() {
-            let installments = self
-                .installment_repository
-                .list(&InstallmentFilters::new().with_payment_id(Some(*payment.id())))
-                .await?;
-
-            if let Some(installment) = installments.first() {
-                let mut installment = installment.clone();
-                installment.reverse_payment()?;
-                self.installment_repository.update(installment).await?;
-            }
+            self.reverse_installment_allocations(payment).await?;
         }
 
         debt.reverse_payment(payment)?;
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use http_error::HttpResult;
+use telegram_api::{domain::send_message::SendMessageRequest, telegram_api::DynTelegramApiGateway};
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::{
+        domain::{
+            debt::{DebtFilters, DebtStatus},
+            reminder::{build_digest, DebtReminder, DueItem, ReminderSetting},
+        },
+        handler::reminder::use_cases::UpdateReminderSettingRequest,
+        repository::{
+            debt::{
+                installment::{use_cases::InstallmentFilters, DynInstallmentRepository},
+                DynDebtRepository,
+            },
+            reminder::DynReminderRepository,
+            telegram::DynTelegramChatRepository,
+        },
+    },
+    shared::scheduler::DailyJob,
+};
+
+pub type DynReminderHandler = dyn ReminderHandler + Send + Sync;
+
+#[async_trait]
+pub trait ReminderHandler {
+    async fn get_setting(&self, client_id: Uuid) -> HttpResult<ReminderSetting>;
+
+    async fn update_setting(
+        &self,
+        client_id: Uuid,
+        request: UpdateReminderSettingRequest,
+    ) -> HttpResult<ReminderSetting>;
+
+    /// Sends every client with a linked Telegram chat a digest of what is due
+    /// within their reminder horizon. Items already reminded are skipped.
+    async fn send_due_reminders(&self, today: NaiveDate) -> HttpResult<()>;
+}
+
+#[derive(Clone)]
+pub struct ReminderHandlerImpl {
+    pub reminder_repository: Arc<DynReminderRepository>,
+    pub telegram_chat_repository: Arc<DynTelegramChatRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub installment_repository: Arc<DynInstallmentRepository>,
+    pub telegram_gateway: Arc<DynTelegramApiGateway>,
+}
+
+#[async_trait]
+impl ReminderHandler for ReminderHandlerImpl {
+    async fn get_setting(&self, client_id: Uuid) -> HttpResult<ReminderSetting> {
+        let setting = self.reminder_repository.get_setting(client_id).await?;
+
+        Ok(setting.unwrap_or_else(|| ReminderSetting::default_for(client_id)))
+    }
+
+    async fn update_setting(
+        &self,
+        client_id: Uuid,
+        request: UpdateReminderSettingRequest,
+    ) -> HttpResult<ReminderSetting> {
+        let mut setting = self.get_setting(client_id).await?;
+        setting.update(request.horizon_days, request.active)?;
+
+        self.reminder_repository.upsert_setting(setting).await
+    }
+
+    async fn send_due_reminders(&self, today: NaiveDate) -> HttpResult<()> {
+        let mut chats_by_client: HashMap<Uuid, Vec<i64>> = HashMap::new();
+        for chat in self.telegram_chat_repository.list_all().await? {
+            chats_by_client
+                .entry(*chat.client_id())
+                .or_default()
+                .push(*chat.chat_id());
+        }
+
+        let mut settings: HashMap<Uuid, ReminderSetting> = self
+            .reminder_repository
+            .list_settings()
+            .await?
+            .into_iter()
+            .map(|setting| (*setting.client_id(), setting))
+            .collect();
+
+        for (client_id, chat_ids) in chats_by_client {
+            let setting = settings
+                .remove(&client_id)
+                .unwrap_or_else(|| ReminderSetting::default_for(client_id));
+
+            if !setting.active() {
+                continue;
+            }
+
+            // A failure for one client must not stop the others from being reminded.
+            if let Err(err) = self
+                .remind_client(client_id, &chat_ids, &setting, today)
+                .await
+            {
+                tracing::error!("failed to remind client {}: {}", client_id, err.message);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ReminderHandlerImpl {
+    async fn remind_client(
+        &self,
+        client_id: Uuid,
+        chat_ids: &[i64],
+        setting: &ReminderSetting,
+        today: NaiveDate,
+    ) -> HttpResult<()> {
+        let (start_date, end_date) = setting.window(today);
+        let sent = self
+            .reminder_repository
+            .list_sent(client_id, start_date, end_date)
+            .await?;
+
+        let items: Vec<DueItem> = self
+            .list_due_items(client_id, start_date, end_date)
+            .await?
+            .into_iter()
+            .filter(|item| !item.was_reminded(&sent))
+            .collect();
+
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        // Recorded before sending: a digest that fails to send is lost rather
+        // than sent again every day the recording fails.
+        let reminders = items
+            .iter()
+            .map(|item| DebtReminder::new(client_id, item))
+            .collect();
+        self.reminder_repository.insert_many(reminders).await?;
+
+        let digest = build_digest(&items, today);
+        for chat_id in chat_ids {
+            if let Err(err) = self
+                .telegram_gateway
+                .send_message(SendMessageRequest {
+                    chat_id: *chat_id,
+                    text: digest.clone(),
+                })
+                .await
+            {
+                tracing::error!("failed to send digest to chat {}: {}", chat_id, err.message);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Open debts and unpaid installments due within the range. Debts paid in
+    /// installments are reminded through their installments only.
+    async fn list_due_items(
+        &self,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> HttpResult<Vec<DueItem>> {
+        let open_debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(client_id)
+                    .with_statuses(vec![DebtStatus::Open])
+                    .with_start_date(start_date)
+                    .with_end_date(end_date),
+            )
+            .await?;
+
+        let mut items: Vec<DueItem> = open_debts
+            .iter()
+            .filter(|debt| !debt.has_installments())
+            .map(DueItem::from_debt)
+            .collect();
+
+        let installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_client_id(client_id)
+                    .with_is_paid(Some(false))
+                    .with_start_date(Some(start_date))
+                    .with_end_date(Some(end_date)),
+            )
+            .await?;
+
+        if installments.is_empty() {
+            return Ok(items);
+        }
+
+        let debt_ids: Vec<Uuid> = installments
+            .iter()
+            .map(|installment| *installment.debt_id())
+            .collect();
+        let debts: HashMap<Uuid, _> = self
+            .debt_repository
+            .list(&DebtFilters::new(client_id).with_ids(debt_ids))
+            .await?
+            .into_iter()
+            .map(|debt| (*debt.id(), debt))
+            .collect();
+
+        items.extend(installments.iter().filter_map(|installment| {
+            debts
+                .get(installment.debt_id())
+                .map(|debt| DueItem::from_installment(installment, debt))
+        }));
+
+        Ok(items)
+    }
+}
+
+#[async_trait]
+impl DailyJob for ReminderHandlerImpl {
+    fn name(&self) -> &'static str {
+        "due-date-reminders"
+    }
+
+    async fn run(&self, today: NaiveDate) -> HttpResult<()> {
+        self.send_due_reminders(today).await
+    }
+}
+
+pub mod use_cases {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UpdateReminderSettingRequest {
+        pub horizon_days: Option<i32>,
+        pub active: Option<bool>,
+    }
+}
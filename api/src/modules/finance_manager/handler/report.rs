@@ -0,0 +1,342 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        debt::{recurrence::RecurrenceFilters, DebtFilters, DebtStatus},
+        report::{
+            cash_flow::CashFlowReport,
+            forecast::{CommitmentForecast, MAX_FORECAST_MONTHS},
+            spending::{SpendingBreakdown, SpendingTotals},
+            ReportPeriod,
+        },
+    },
+    handler::{
+        debt::category::DynCategoryHandler,
+        report::use_cases::{CashFlowQuery, ForecastQuery, SpendingBreakdownQuery},
+    },
+    repository::{
+        debt::{
+            installment::{use_cases::InstallmentFilters, DynInstallmentRepository},
+            DynDebtRepository,
+        },
+        income::{use_cases::IncomeListFilters, DynIncomeRepository},
+        payment::{use_cases::PaymentFilters, DynPaymentRepository},
+        recurrence::{income::DynIncomeRecurrenceRepository, DynRecurrenceRepository},
+    },
+};
+
+pub type DynReportHandler = dyn ReportHandler + Send + Sync;
+
+#[async_trait]
+pub trait ReportHandler {
+    async fn get_cash_flow(
+        &self,
+        client_id: Uuid,
+        query: CashFlowQuery,
+    ) -> HttpResult<CashFlowReport>;
+
+    async fn get_spending_breakdown(
+        &self,
+        client_id: Uuid,
+        query: SpendingBreakdownQuery,
+    ) -> HttpResult<SpendingBreakdown>;
+
+    /// Unpaid installments and debts, recurrences still to be generated and
+    /// expected incomes of the coming months.
+    async fn get_commitment_forecast(
+        &self,
+        client_id: Uuid,
+        query: ForecastQuery,
+    ) -> HttpResult<CommitmentForecast>;
+
+    /// Spent and committed amounts of the client in a period. Parent
+    /// categories include the amounts of their subcategories.
+    async fn get_spending_totals(
+        &self,
+        client_id: Uuid,
+        period: &ReportPeriod,
+    ) -> HttpResult<SpendingTotals>;
+}
+
+#[derive(Clone)]
+pub struct ReportHandlerImpl {
+    pub income_repository: Arc<DynIncomeRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub installment_repository: Arc<DynInstallmentRepository>,
+    pub payment_repository: Arc<DynPaymentRepository>,
+    pub recurrence_repository: Arc<DynRecurrenceRepository>,
+    pub income_recurrence_repository: Arc<DynIncomeRecurrenceRepository>,
+    pub category: Arc<DynCategoryHandler>,
+}
+
+#[async_trait]
+impl ReportHandler for ReportHandlerImpl {
+    async fn get_cash_flow(
+        &self,
+        client_id: Uuid,
+        query: CashFlowQuery,
+    ) -> HttpResult<CashFlowReport> {
+        // Defaults to the current month.
+        let today = Utc::now().date_naive();
+        let from = query
+            .from
+            .unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+        let to = query.to.unwrap_or_else(|| end_of_month(from));
+        let granularity = query.granularity.unwrap_or_default();
+        granularity.periods(from, to)?;
+
+        let incomes = self
+            .income_repository
+            .list(
+                &IncomeListFilters::new(client_id)
+                    .with_start_date(Some(from))
+                    .with_end_date(Some(to)),
+            )
+            .await?;
+
+        let debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(client_id)
+                    .with_start_date(from)
+                    .with_end_date(to),
+            )
+            .await?;
+
+        let installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_client_id(client_id)
+                    .with_start_date(Some(from))
+                    .with_end_date(Some(to)),
+            )
+            .await?;
+
+        let payments = self
+            .payment_repository
+            .list(
+                &PaymentFilters::new()
+                    .with_client_id(client_id)
+                    .with_start_date(from)
+                    .with_end_date(to),
+            )
+            .await?;
+
+        CashFlowReport::build(
+            from,
+            to,
+            granularity,
+            today,
+            &incomes,
+            &debts,
+            &installments,
+            &payments,
+        )
+    }
+
+    async fn get_spending_breakdown(
+        &self,
+        client_id: Uuid,
+        query: SpendingBreakdownQuery,
+    ) -> HttpResult<SpendingBreakdown> {
+        // Defaults to the current month.
+        let today = Utc::now().date_naive();
+        let from = query
+            .from
+            .unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+        let to = query.to.unwrap_or_else(|| end_of_month(from));
+        if from > to {
+            return Err(Box::new(HttpError::bad_request(
+                "Report start date must not be after its end date",
+            )));
+        }
+
+        let period = ReportPeriod {
+            start: from,
+            end: to,
+        };
+        let (previous_period, last_year_period) = SpendingBreakdown::comparison_periods(&period);
+
+        let current = self.get_spending_totals(client_id, &period).await?;
+        let previous = self
+            .get_spending_totals(client_id, &previous_period)
+            .await?;
+        let last_year = self
+            .get_spending_totals(client_id, &last_year_period)
+            .await?;
+
+        Ok(SpendingBreakdown::build(
+            period, &current, &previous, &last_year,
+        ))
+    }
+
+    async fn get_commitment_forecast(
+        &self,
+        client_id: Uuid,
+        query: ForecastQuery,
+    ) -> HttpResult<CommitmentForecast> {
+        let months = query.months.unwrap_or(6);
+        if !(1..=MAX_FORECAST_MONTHS).contains(&months) {
+            return Err(Box::new(HttpError::bad_request(format!(
+                "Forecast must cover between 1 and {MAX_FORECAST_MONTHS} months"
+            ))));
+        }
+
+        // Starts at the current month.
+        let today = Utc::now().date_naive();
+        let from = today.with_day(1).unwrap_or(today);
+        let to = end_of_month(from + Months::new(months - 1));
+
+        let incomes = self
+            .income_repository
+            .list(
+                &IncomeListFilters::new(client_id)
+                    .with_start_date(Some(from))
+                    .with_end_date(Some(to)),
+            )
+            .await?;
+
+        // Every unpaid debt is a commitment, including the ones overdue from
+        // previous months
+        let debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(client_id)
+                    .with_statuses(vec![DebtStatus::Open, DebtStatus::Overdue])
+                    .with_end_date(to),
+            )
+            .await?;
+
+        let installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_client_id(client_id)
+                    .with_is_paid(Some(false))
+                    .with_end_date(Some(to)),
+            )
+            .await?;
+
+        let recurrence_filters = RecurrenceFilters::new()
+            .with_client_id(client_id)
+            .with_active(true);
+        let recurrences = self.recurrence_repository.list(&recurrence_filters).await?;
+        let income_recurrences = self
+            .income_recurrence_repository
+            .list(&recurrence_filters)
+            .await?;
+
+        Ok(CommitmentForecast::build(
+            from,
+            months,
+            &incomes,
+            &debts,
+            &installments,
+            &recurrences,
+            &income_recurrences,
+        ))
+    }
+
+    async fn get_spending_totals(
+        &self,
+        client_id: Uuid,
+        period: &ReportPeriod,
+    ) -> HttpResult<SpendingTotals> {
+        let mut debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(client_id)
+                    .with_start_date(period.start)
+                    .with_end_date(period.end),
+            )
+            .await?;
+
+        let installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_client_id(client_id)
+                    .with_start_date(Some(period.start))
+                    .with_end_date(Some(period.end)),
+            )
+            .await?;
+
+        let payments = self
+            .payment_repository
+            .list(
+                &PaymentFilters::new()
+                    .with_client_id(client_id)
+                    .with_start_date(period.start)
+                    .with_end_date(period.end),
+            )
+            .await?;
+
+        // Installments and payments in the period may belong to debts due
+        // outside of it.
+        let loaded: HashSet<Uuid> = debts.iter().map(|debt| *debt.id()).collect();
+        let missing: HashSet<Uuid> = installments
+            .iter()
+            .map(|installment| *installment.debt_id())
+            .chain(payments.iter().map(|payment| *payment.debt_id()))
+            .filter(|debt_id| !loaded.contains(debt_id))
+            .collect();
+
+        if !missing.is_empty() {
+            debts.extend(
+                self.debt_repository
+                    .list(&DebtFilters::new(client_id).with_ids(missing.into_iter().collect()))
+                    .await?,
+            );
+        }
+
+        let categories = self.category.list_categories(client_id).await?;
+
+        Ok(
+            SpendingTotals::collect(period, &debts, &installments, &payments)
+                .roll_up_subcategories(&categories),
+        )
+    }
+}
+
+fn end_of_month(date: NaiveDate) -> NaiveDate {
+    (date + Months::new(1))
+        .with_day(1)
+        .and_then(|next_month| next_month.pred_opt())
+        .unwrap_or(date)
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+
+    use crate::modules::finance_manager::domain::report::Granularity;
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CashFlowQuery {
+        pub from: Option<NaiveDate>,
+        pub to: Option<NaiveDate>,
+        /// Defaults to monthly periods.
+        pub granularity: Option<Granularity>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ForecastQuery {
+        /// How many months to project, the current one included. Defaults to 6.
+        pub months: Option<u32>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SpendingBreakdownQuery {
+        pub from: Option<NaiveDate>,
+        pub to: Option<NaiveDate>,
+    }
+}
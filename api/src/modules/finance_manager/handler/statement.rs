@@ -0,0 +1,435 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Duration;
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        debt::{DebtCategory, DebtFilters, DebtStatus},
+        financial_instrument::{FinancialInstrument, FinancialInstrumentType},
+        statement::{StatementImport, StatementTransaction, MATCH_WINDOW_DAYS},
+    },
+    handler::{
+        debt::{use_cases::CreateDebtRequest, DynDebtHandler},
+        income::{use_cases::CreateIncomeRequest, DynIncomeHandler},
+        payment::{
+            use_cases::{CreatePaymentRequest, PaymentBasicData, PaymentRequestFromUuid},
+            DynPaymentHandler,
+        },
+        statement::use_cases::{ConfirmStatementRequest, ImportStatementRequest, StatementAction},
+    },
+    repository::{
+        debt::{
+            installment::{use_cases::InstallmentFilters, DynInstallmentRepository},
+            DynDebtRepository,
+        },
+        financial_instrument::DynFinancialInstrumentRepository,
+        statement::DynStatementRepository,
+    },
+};
+
+pub type DynStatementHandler = dyn StatementHandler + Send + Sync;
+
+#[async_trait]
+pub trait StatementHandler {
+    /// Parses the statement into pending transactions, skipping lines already
+    /// imported, and suggests the open debt each outgoing line pays.
+    async fn import_statement(
+        &self,
+        client_id: Uuid,
+        request: ImportStatementRequest,
+    ) -> HttpResult<StatementImport>;
+
+    async fn get_statement_import(
+        &self,
+        client_id: Uuid,
+        import_id: Uuid,
+    ) -> HttpResult<StatementImport>;
+
+    /// Turns each informed line into a payment, a debt or an income, or
+    /// ignores it. Lines are saved one by one, so the ones processed before
+    /// a failure stay confirmed. Each line is claimed before its action runs,
+    /// so concurrent or retried confirmations never repeat it.
+    async fn confirm_statement(
+        &self,
+        client_id: Uuid,
+        import_id: Uuid,
+        request: ConfirmStatementRequest,
+    ) -> HttpResult<StatementImport>;
+}
+
+#[derive(Clone)]
+pub struct StatementHandlerImpl {
+    pub statement_repository: Arc<DynStatementRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub installment_repository: Arc<DynInstallmentRepository>,
+    pub debt_handler: Arc<DynDebtHandler>,
+    pub income_handler: Arc<DynIncomeHandler>,
+    pub payment_handler: Arc<DynPaymentHandler>,
+}
+
+impl StatementHandlerImpl {
+    async fn get_client_instrument(
+        &self,
+        client_id: Uuid,
+        instrument_id: Uuid,
+    ) -> HttpResult<FinancialInstrument> {
+        self.financial_instrument_repository
+            .get_by_id(instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", instrument_id.to_string())
+    }
+
+    async fn match_open_debts(&self, import: &mut StatementImport) -> HttpResult<()> {
+        let Some((first, last)) = import.date_range() else {
+            return Ok(());
+        };
+        let window = Duration::days(MATCH_WINDOW_DAYS);
+        let (start_date, end_date) = (first - window, last + window);
+
+        let debts = self
+            .debt_repository
+            .list(
+                &DebtFilters::new(*import.client_id())
+                    .with_statuses(vec![DebtStatus::Open, DebtStatus::Overdue])
+                    .with_start_date(start_date)
+                    .with_end_date(end_date),
+            )
+            .await?;
+        let installments = self
+            .installment_repository
+            .list(
+                &InstallmentFilters::new()
+                    .with_client_id(*import.client_id())
+                    .with_is_paid(Some(false))
+                    .with_start_date(Some(start_date))
+                    .with_end_date(Some(end_date)),
+            )
+            .await?;
+
+        import.match_open_debts(&debts, &installments);
+
+        Ok(())
+    }
+
+    /// Pays the informed debt, or the one matched on import. Amounts that
+    /// differ from a debt without installments are reconciled.
+    async fn pay(
+        &self,
+        client_id: Uuid,
+        instrument: &FinancialInstrument,
+        transaction: &mut StatementTransaction,
+        debt_id: Option<Uuid>,
+        installment_id: Option<i32>,
+    ) -> HttpResult<()> {
+        transaction.ensure_outflow(true)?;
+
+        let (debt_id, installment_id) = match debt_id {
+            Some(debt_id) => (debt_id, installment_id),
+            None => (
+                transaction.debt_id().or_bad_request(format!(
+                    "Statement line {} has no matched debt to pay",
+                    transaction.line_number()
+                ))?,
+                *transaction.installment_id(),
+            ),
+        };
+        let debt = self
+            .debt_repository
+            .get_by_id(&debt_id)
+            .await?
+            .filter(|debt| debt.client_id() == &client_id)
+            .or_not_found("debt", debt_id.to_string())?;
+
+        let amount = transaction.absolute_amount();
+        let request = CreatePaymentRequest::PaymentRequestFromUuid(PaymentRequestFromUuid {
+            debt_id,
+            financial_instrument_id: *instrument.id(),
+            reconcile: !debt.has_installments() && amount != *debt.remaining_amount(),
+            payment_basic_data: PaymentBasicData {
+                payment_date: *transaction.date(),
+                amount: Some(amount),
+                installment_ids: installment_id.map(|id| vec![id]),
+                early_payment_discount: None,
+            },
+        });
+        let registered = self.payment_handler.create_payment(request).await?;
+
+        transaction.confirm_debt(debt_id, installment_id, Some(*registered.payment.id()));
+
+        Ok(())
+    }
+
+    /// Card lines become purchases on the card's invoice. Lines of other
+    /// instruments already left them, so the new debt is paid right away.
+    async fn create_debt(
+        &self,
+        client_id: Uuid,
+        instrument: &FinancialInstrument,
+        transaction: &mut StatementTransaction,
+        category: Option<DebtCategory>,
+        tags: Option<Vec<String>>,
+    ) -> HttpResult<()> {
+        transaction.ensure_outflow(true)?;
+
+        let amount = transaction.absolute_amount();
+        let mut request = CreateDebtRequest::new(
+            category,
+            tags,
+            transaction.description().clone(),
+            amount,
+            *transaction.date(),
+            None,
+        );
+
+        let is_card = instrument.instrument_type() == &FinancialInstrumentType::CreditCard;
+        if is_card {
+            request.financial_instrument_id = Some(*instrument.id());
+            request.purchase_date = Some(*transaction.date());
+        }
+
+        let registered = self
+            .debt_handler
+            .register_new_debt(client_id, request)
+            .await?;
+        if is_card {
+            transaction.confirm_debt(*registered.debt.id(), None, None);
+            return Ok(());
+        }
+
+        let payment = self
+            .payment_handler
+            .create_payment(CreatePaymentRequest::PaymentRequestFromUuid(
+                PaymentRequestFromUuid {
+                    debt_id: *registered.debt.id(),
+                    financial_instrument_id: *instrument.id(),
+                    // The statement is the proof of payment, so the balance
+                    // checks are skipped.
+                    reconcile: true,
+                    payment_basic_data: PaymentBasicData {
+                        payment_date: *transaction.date(),
+                        amount: Some(amount),
+                        installment_ids: None,
+                        early_payment_discount: None,
+                    },
+                },
+            ))
+            .await?
+            .payment;
+
+        transaction.confirm_debt(*registered.debt.id(), None, Some(*payment.id()));
+
+        Ok(())
+    }
+
+    async fn create_income(
+        &self,
+        client_id: Uuid,
+        instrument: &FinancialInstrument,
+        transaction: &mut StatementTransaction,
+    ) -> HttpResult<()> {
+        transaction.ensure_outflow(false)?;
+
+        let income = self
+            .income_handler
+            .create_income(
+                client_id,
+                CreateIncomeRequest {
+                    financial_instrument_id: *instrument.id(),
+                    description: transaction.description().clone(),
+                    amount: transaction.absolute_amount(),
+                    date_reference: *transaction.date(),
+                },
+            )
+            .await?;
+
+        transaction.confirm_income(*income.id());
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StatementHandler for StatementHandlerImpl {
+    async fn import_statement(
+        &self,
+        client_id: Uuid,
+        request: ImportStatementRequest,
+    ) -> HttpResult<StatementImport> {
+        let instrument = self
+            .get_client_instrument(client_id, request.financial_instrument_id)
+            .await?;
+
+        let mut lines = request.format.parse(&request.content)?;
+        if request.invert_amounts {
+            for line in lines.iter_mut() {
+                line.amount = -line.amount;
+            }
+        }
+
+        let external_ids: Vec<String> = lines
+            .iter()
+            .filter_map(|line| line.external_id.clone())
+            .collect();
+        let imported = self
+            .statement_repository
+            .list_imported_external_ids(instrument.id(), &external_ids)
+            .await?;
+        lines.retain(|line| {
+            line.external_id
+                .as_ref()
+                .is_none_or(|id| !imported.contains(id))
+        });
+
+        let mut import = StatementImport::new(client_id, *instrument.id(), request.format, lines)?;
+        self.match_open_debts(&mut import).await?;
+
+        self.statement_repository.insert(import).await
+    }
+
+    async fn get_statement_import(
+        &self,
+        client_id: Uuid,
+        import_id: Uuid,
+    ) -> HttpResult<StatementImport> {
+        let import = self
+            .statement_repository
+            .get_by_id(&import_id)
+            .await?
+            .or_not_found("statement_import", import_id.to_string())?;
+
+        import.belongs_to_client(client_id)?;
+
+        Ok(import)
+    }
+
+    async fn confirm_statement(
+        &self,
+        client_id: Uuid,
+        import_id: Uuid,
+        request: ConfirmStatementRequest,
+    ) -> HttpResult<StatementImport> {
+        let mut import = self.get_statement_import(client_id, import_id).await?;
+        let instrument = self
+            .get_client_instrument(client_id, *import.financial_instrument_id())
+            .await?;
+
+        for decision in request.transactions {
+            let transaction = import.transaction_mut(decision.transaction_id)?;
+            transaction.start_processing()?;
+            if !self
+                .statement_repository
+                .claim_transaction(transaction)
+                .await?
+            {
+                return Err(Box::new(HttpError::bad_request(format!(
+                    "Statement line {} was already processed",
+                    transaction.line_number()
+                ))));
+            }
+
+            let result = match decision.action {
+                StatementAction::Pay {
+                    debt_id,
+                    installment_id,
+                } => {
+                    self.pay(client_id, &instrument, transaction, debt_id, installment_id)
+                        .await
+                }
+                StatementAction::CreateDebt { category, tags } => {
+                    self.create_debt(client_id, &instrument, transaction, category, tags)
+                        .await
+                }
+                StatementAction::CreateIncome => {
+                    self.create_income(client_id, &instrument, transaction)
+                        .await
+                }
+                StatementAction::Ignore => {
+                    transaction.ignore();
+                    Ok(())
+                }
+            };
+
+            if let Err(err) = result {
+                transaction.release();
+                if let Err(release_err) = self
+                    .statement_repository
+                    .update_transaction(transaction.clone())
+                    .await
+                {
+                    tracing::error!(
+                        "failed to release statement line {}: {}",
+                        transaction.id(),
+                        release_err.message
+                    );
+                }
+                return Err(err);
+            }
+
+            *transaction = self
+                .statement_repository
+                .update_transaction(transaction.clone())
+                .await?;
+        }
+
+        Ok(import)
+    }
+}
+
+pub mod use_cases {
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use crate::modules::finance_manager::domain::{debt::DebtCategory, statement::StatementFormat};
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ImportStatementRequest {
+        pub financial_instrument_id: Uuid,
+        pub format: StatementFormat,
+        /// Contents of the OFX or CSV file.
+        pub content: String,
+        /// For statements listing purchases as positive amounts, like most
+        /// card CSV exports.
+        #[serde(default)]
+        pub invert_amounts: bool,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConfirmStatementRequest {
+        pub transactions: Vec<StatementTransactionDecision>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StatementTransactionDecision {
+        pub transaction_id: Uuid,
+        #[serde(flatten)]
+        pub action: StatementAction,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(
+        tag = "action",
+        rename_all = "SCREAMING_SNAKE_CASE",
+        rename_all_fields = "camelCase"
+    )]
+    pub enum StatementAction {
+        /// Pays the informed debt, or the one matched on import.
+        Pay {
+            debt_id: Option<Uuid>,
+            installment_id: Option<i32>,
+        },
+        CreateDebt {
+            category: Option<DebtCategory>,
+            tags: Option<Vec<String>>,
+        },
+        CreateIncome,
+        Ignore,
+    }
+}
@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate, Utc};
+use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
+use rust_decimal::Decimal;
+use telegram_api::{
+    domain::{send_message::SendMessageRequest, telegram_update::TelegramUpdate},
+    telegram_api::DynTelegramApiGateway,
+};
+use util::date::last_day_of_month;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        debt::{DebtFilters, DebtStatus},
+        telegram::{
+            command::{TelegramCommand, HELP_MESSAGE},
+            format_currency, TelegramChat, TelegramLinkCode,
+        },
+    },
+    handler::{
+        debt::{use_cases::CreateDebtRequest, DynDebtHandler},
+        income::{use_cases::ListIncomesRequest, DynIncomeHandler},
+        payment::{
+            use_cases::{CreatePaymentRequest, PaymentBasicData, PaymentRequestFromUuid},
+            DynPaymentHandler,
+        },
+    },
+    repository::{
+        debt::DynDebtRepository, financial_instrument::DynFinancialInstrumentRepository,
+        telegram::DynTelegramChatRepository,
+    },
+};
+
+pub type DynTelegramHandler = dyn TelegramHandler + Send + Sync;
+
+#[async_trait]
+pub trait TelegramHandler {
+    /// Runs the command sent in the update and replies to the chat. Command
+    /// errors are replied as messages and reply failures are only logged, so
+    /// Telegram does not retry an update whose command already ran.
+    async fn handle_update(&self, update: TelegramUpdate) -> HttpResult<()>;
+
+    async fn create_link_code(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+    ) -> HttpResult<TelegramLinkCode>;
+
+    async fn list_chats(&self, client_id: Uuid) -> HttpResult<Vec<TelegramChat>>;
+
+    async fn unlink_chat(&self, client_id: Uuid, chat_id: i64) -> HttpResult<()>;
+
+    /// Checks the `X-Telegram-Bot-Api-Secret-Token` header against the secret
+    /// configured when the webhook was registered. Every update is rejected
+    /// while no secret is configured.
+    fn validate_webhook_secret(&self, secret: Option<&str>) -> HttpResult<()>;
+}
+
+#[derive(Clone)]
+pub struct TelegramHandlerImpl {
+    pub telegram_chat_repository: Arc<DynTelegramChatRepository>,
+    pub debt_repository: Arc<DynDebtRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub debt_handler: Arc<DynDebtHandler>,
+    pub payment_handler: Arc<DynPaymentHandler>,
+    pub income_handler: Arc<DynIncomeHandler>,
+    pub telegram_gateway: Arc<DynTelegramApiGateway>,
+    pub webhook_secret: Option<String>,
+}
+
+#[async_trait]
+impl TelegramHandler for TelegramHandlerImpl {
+    async fn handle_update(&self, update: TelegramUpdate) -> HttpResult<()> {
+        let Some(message) = update.get_message() else {
+            return Ok(());
+        };
+        let Some(text) = message.get_text() else {
+            return Ok(());
+        };
+
+        let chat_id = message.chat.id;
+        let reply = match self.execute(chat_id, text).await {
+            Ok(reply) => reply,
+            Err(err) => format!("❌ {}", err.message),
+        };
+
+        if let Err(err) = self
+            .telegram_gateway
+            .send_message(SendMessageRequest {
+                chat_id,
+                text: reply,
+            })
+            .await
+        {
+            tracing::error!("failed to reply to chat {}: {}", chat_id, err.message);
+        }
+
+        Ok(())
+    }
+
+    async fn create_link_code(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+    ) -> HttpResult<TelegramLinkCode> {
+        let link_code = TelegramLinkCode::new(client_id, user_id);
+        self.telegram_chat_repository
+            .insert_link_code(link_code)
+            .await
+    }
+
+    async fn list_chats(&self, client_id: Uuid) -> HttpResult<Vec<TelegramChat>> {
+        self.telegram_chat_repository
+            .list_by_client_id(client_id)
+            .await
+    }
+
+    async fn unlink_chat(&self, client_id: Uuid, chat_id: i64) -> HttpResult<()> {
+        self.telegram_chat_repository
+            .get_by_chat_id(chat_id)
+            .await?
+            .filter(|chat| chat.client_id() == &client_id)
+            .or_not_found("telegram_chat", chat_id.to_string())?;
+
+        self.telegram_chat_repository
+            .delete(client_id, chat_id)
+            .await
+    }
+
+    fn validate_webhook_secret(&self, secret: Option<&str>) -> HttpResult<()> {
+        let Some(expected) = &self.webhook_secret else {
+            return Err(Box::new(HttpError::service_unavailable(
+                "Telegram webhook secret is not configured",
+            )));
+        };
+
+        if secret != Some(expected.as_str()) {
+            return Err(Box::new(HttpError::unauthorized(
+                "Invalid Telegram webhook secret",
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl TelegramHandlerImpl {
+    async fn execute(&self, chat_id: i64, text: &str) -> HttpResult<String> {
+        let today = Utc::now().date_naive();
+
+        match TelegramCommand::parse(text, today)? {
+            TelegramCommand::Link { code } => self.link_chat(chat_id, &code).await,
+            TelegramCommand::Help => Ok(HELP_MESSAGE.to_string()),
+            TelegramCommand::RegisterDebt {
+                amount,
+                description,
+                due_date,
+            } => {
+                let client_id = self.linked_client_id(chat_id).await?;
+                self.register_debt(client_id, amount, description, due_date)
+                    .await
+            }
+            TelegramCommand::PayDebt {
+                debt_identification,
+                financial_instrument_identification,
+                amount,
+            } => {
+                let client_id = self.linked_client_id(chat_id).await?;
+                self.pay_debt(
+                    client_id,
+                    &debt_identification,
+                    &financial_instrument_identification,
+                    amount,
+                    today,
+                )
+                .await
+            }
+            TelegramCommand::Balance => {
+                let client_id = self.linked_client_id(chat_id).await?;
+                self.balance(client_id, today).await
+            }
+        }
+    }
+
+    async fn linked_client_id(&self, chat_id: i64) -> HttpResult<Uuid> {
+        let chat = self
+            .telegram_chat_repository
+            .get_by_chat_id(chat_id)
+            .await?
+            .ok_or_else(|| {
+                Box::new(HttpError::forbidden(
+                    "Chat não vinculado. Gere um código no app e envie /vincular <código>",
+                ))
+            })?;
+
+        Ok(*chat.client_id())
+    }
+
+    async fn link_chat(&self, chat_id: i64, code: &str) -> HttpResult<String> {
+        let link_code = self
+            .telegram_chat_repository
+            .take_link_code(code)
+            .await?
+            .or_not_found("telegram_link_code", code)?;
+        link_code.validate_not_expired(Utc::now())?;
+
+        self.telegram_chat_repository
+            .upsert(link_code.link(chat_id))
+            .await?;
+
+        Ok(format!("✅ Chat vinculado com sucesso!\n\n{HELP_MESSAGE}"))
+    }
+
+    async fn register_debt(
+        &self,
+        client_id: Uuid,
+        amount: Decimal,
+        description: String,
+        due_date: NaiveDate,
+    ) -> HttpResult<String> {
+        let request = CreateDebtRequest::new(None, None, description, amount, due_date, None);
+        let debt = self
+            .debt_handler
+            .register_new_debt(client_id, request)
+            .await?
+            .debt;
+
+        Ok(format!(
+            "✅ Dívida #{} registrada: {} — {}, vencimento {}",
+            debt.identification(),
+            debt.description(),
+            format_currency(*debt.total_amount()),
+            debt.due_date().format("%d/%m/%Y"),
+        ))
+    }
+
+    async fn pay_debt(
+        &self,
+        client_id: Uuid,
+        debt_identification: &str,
+        financial_instrument_identification: &str,
+        amount: Option<Decimal>,
+        payment_date: NaiveDate,
+    ) -> HttpResult<String> {
+        // Identifications are sequential across clients, so ownership must be
+        // checked before paying anything on behalf of this chat.
+        let debt = self
+            .debt_repository
+            .get_by_identification(debt_identification)
+            .await?
+            .filter(|debt| debt.client_id() == &client_id)
+            .or_not_found("debt", debt_identification)?;
+        let instrument = self
+            .financial_instrument_repository
+            .get_by_identification(financial_instrument_identification)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", financial_instrument_identification)?;
+
+        let request = CreatePaymentRequest::PaymentRequestFromUuid(PaymentRequestFromUuid {
+            debt_id: *debt.id(),
+            financial_instrument_id: *instrument.id(),
+            reconcile: false,
+            payment_basic_data: PaymentBasicData {
+                payment_date,
+                amount,
+                installment_ids: None,
+                early_payment_discount: None,
+            },
+        });
+        let payment = self.payment_handler.create_payment(request).await?.payment;
+
+        let debt = self
+            .debt_repository
+            .get_by_id(debt.id())
+            .await?
+            .or_not_found("debt", debt.id().to_string())?;
+
+        Ok(format!(
+            "✅ Pagamento de {} registrado na dívida #{} ({}). Restante: {}",
+            format_currency(*payment.amount()),
+            debt.identification(),
+            debt.description(),
+            format_currency(*debt.remaining_amount()),
+        ))
+    }
+
+    async fn balance(&self, client_id: Uuid, today: NaiveDate) -> HttpResult<String> {
+        let start_date = today.with_day(1).unwrap_or(today);
+        let end_date = today
+            .with_day(last_day_of_month(today.year(), today.month()))
+            .unwrap_or(today);
+
+        let incomes = self
+            .income_handler
+            .list_incomes(
+                client_id,
+                ListIncomesRequest {
+                    financial_instrument_ids: None,
+                    start_date: Some(start_date),
+                    end_date: Some(end_date),
+                },
+            )
+            .await?;
+        let debts = self
+            .debt_handler
+            .list_debts(
+                client_id,
+                &DebtFilters::new(client_id)
+                    .with_start_date(start_date)
+                    .with_end_date(end_date),
+            )
+            .await?;
+
+        let total_income: Decimal = incomes.iter().map(|income| *income.amount()).sum();
+        let total_debt: Decimal = debts.iter().map(|debt| *debt.total_amount()).sum();
+        let open_debts: Vec<_> = debts
+            .iter()
+            .filter(|debt| debt.status() != &DebtStatus::Settled)
+            .collect();
+        let total_remaining: Decimal = open_debts.iter().map(|debt| *debt.remaining_amount()).sum();
+
+        Ok(format!(
+            "📊 Resumo de {}\n\
+            Receitas: {}\n\
+            Dívidas: {}\n\
+            Saldo: {}\n\
+            Em aberto: {} ({} dívidas)",
+            today.format("%m/%Y"),
+            format_currency(total_income),
+            format_currency(total_debt),
+            format_currency(total_income - total_debt),
+            format_currency(total_remaining),
+            open_debts.len(),
+        ))
+    }
+}
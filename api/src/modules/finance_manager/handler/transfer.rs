@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use http_error::{ext::OptionHttpExt, HttpResult};
+use util::DeletedBy;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{financial_instrument::FinancialInstrument, transfer::Transfer},
+    handler::{
+        ledger::DynLedgerHandler,
+        transfer::use_cases::{CreateTransferRequest, ListTransfersRequest},
+    },
+    repository::{
+        financial_instrument::DynFinancialInstrumentRepository,
+        transfer::{use_cases::TransferFilters, DynTransferRepository},
+    },
+};
+
+pub type DynTransferHandler = dyn TransferHandler + Send + Sync;
+
+#[async_trait]
+pub trait TransferHandler {
+    async fn create_transfer(
+        &self,
+        client_id: Uuid,
+        request: CreateTransferRequest,
+    ) -> HttpResult<Transfer>;
+
+    async fn list_transfers(
+        &self,
+        client_id: Uuid,
+        request: ListTransfersRequest,
+    ) -> HttpResult<Vec<Transfer>>;
+
+    async fn delete_transfer(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        transfer_id: Uuid,
+    ) -> HttpResult<()>;
+}
+
+#[derive(Clone)]
+pub struct TransferHandlerImpl {
+    pub transfer_repository: Arc<DynTransferRepository>,
+    pub financial_instrument_repository: Arc<DynFinancialInstrumentRepository>,
+    pub ledger: Arc<DynLedgerHandler>,
+}
+
+impl TransferHandlerImpl {
+    async fn get_client_instrument(
+        &self,
+        client_id: Uuid,
+        instrument_id: Uuid,
+    ) -> HttpResult<FinancialInstrument> {
+        self.financial_instrument_repository
+            .get_by_id(instrument_id)
+            .await?
+            .filter(|instrument| instrument.client_id() == &client_id)
+            .or_not_found("financial_instrument", instrument_id.to_string())
+    }
+}
+
+#[async_trait]
+impl TransferHandler for TransferHandlerImpl {
+    async fn create_transfer(
+        &self,
+        client_id: Uuid,
+        request: CreateTransferRequest,
+    ) -> HttpResult<Transfer> {
+        let transfer = Transfer::from_request(client_id, request)?;
+
+        let source = self
+            .get_client_instrument(client_id, *transfer.source_instrument_id())
+            .await?;
+        self.get_client_instrument(client_id, *transfer.destination_instrument_id())
+            .await?;
+
+        let (lock, _) = self
+            .ledger
+            .ensure_can_debit(&source, *transfer.amount())
+            .await?;
+
+        self.transfer_repository.insert(transfer, lock).await
+    }
+
+    async fn list_transfers(
+        &self,
+        client_id: Uuid,
+        request: ListTransfersRequest,
+    ) -> HttpResult<Vec<Transfer>> {
+        let filters = TransferFilters::new(client_id)
+            .with_financial_instrument_ids(request.financial_instrument_ids)
+            .with_start_date(request.start_date)
+            .with_end_date(request.end_date);
+
+        self.transfer_repository.list(&filters).await
+    }
+
+    async fn delete_transfer(
+        &self,
+        client_id: Uuid,
+        user_id: Uuid,
+        transfer_id: Uuid,
+    ) -> HttpResult<()> {
+        let transfer = self
+            .transfer_repository
+            .get_by_id(&transfer_id)
+            .await?
+            .or_not_found("transfer", transfer_id.to_string())?;
+
+        transfer.belongs_to_client(client_id)?;
+
+        self.transfer_repository
+            .soft_delete(&transfer_id, DeletedBy::new(user_id))
+            .await
+    }
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct CreateTransferRequest {
+        pub source_instrument_id: Uuid,
+        pub destination_instrument_id: Uuid,
+        pub amount: Decimal,
+        pub description: Option<String>,
+        /// Defaults to today.
+        pub transfer_date: Option<NaiveDate>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ListTransfersRequest {
+        pub financial_instrument_ids: Option<Vec<Uuid>>,
+        pub start_date: Option<NaiveDate>,
+        pub end_date: Option<NaiveDate>,
+    }
+}
@@ -1,5 +1,11 @@
+pub mod budget;
 pub mod debt;
+pub mod export;
 pub mod financial_instrument;
 pub mod income;
 pub mod payment;
 pub mod recurrence;
+pub mod reminder;
+pub mod statement;
+pub mod telegram;
+pub mod transfer;
@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use http_error::{HttpError, HttpResult};
+use sqlx::{types::Json, Pool, Postgres};
+use util::DeletedBy;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::budget::{Budget, BudgetAlert};
+
+#[async_trait]
+pub trait BudgetRepository {
+    async fn insert(&self, budget: Budget) -> HttpResult<Budget>;
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Budget>>;
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<Budget>>;
+
+    async fn update(&self, budget: Budget) -> HttpResult<Budget>;
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()>;
+
+    /// Returns `None` when the budget already had an alert for the same
+    /// month and threshold, so each one is raised only once.
+    async fn insert_alert(&self, alert: BudgetAlert) -> HttpResult<Option<BudgetAlert>>;
+
+    /// Alerts of the client, newest first, optionally only those of a month.
+    async fn list_alerts(
+        &self,
+        client_id: Uuid,
+        month: Option<NaiveDate>,
+    ) -> HttpResult<Vec<BudgetAlert>>;
+}
+
+pub type DynBudgetRepository = dyn BudgetRepository + Send + Sync;
+
+pub struct BudgetRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl BudgetRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl BudgetRepository for BudgetRepositoryImpl {
+    async fn insert(&self, budget: Budget) -> HttpResult<Budget> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.budget (
+                id,
+                client_id,
+                category,
+                tag,
+                monthly_limit,
+                rollover,
+                notify_telegram,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(budget.id())
+        .bind(budget.client_id())
+        .bind(budget.category().clone().map(String::from))
+        .bind(budget.tag())
+        .bind(budget.monthly_limit())
+        .bind(budget.rollover())
+        .bind(budget.notify_telegram())
+        .bind(budget.created_at())
+        .bind(budget.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Budget::from(&row))
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Budget>> {
+        let row = sqlx::query(
+            r#"SELECT * FROM finance_manager.budget WHERE id = $1 AND deleted_by IS NULL"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Budget::from))
+    }
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<Budget>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.budget
+            WHERE client_id = $1 AND deleted_by IS NULL
+            ORDER BY category NULLS LAST, tag
+            "#,
+        )
+        .bind(client_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Budget::from).collect())
+    }
+
+    async fn update(&self, budget: Budget) -> HttpResult<Budget> {
+        let row = sqlx::query(
+            r#"
+            UPDATE finance_manager.budget
+            SET monthly_limit = $2, rollover = $3, notify_telegram = $4, updated_at = $5
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(budget.id())
+        .bind(budget.monthly_limit())
+        .bind(budget.rollover())
+        .bind(budget.notify_telegram())
+        .bind(budget.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Budget::from(&row))
+    }
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE finance_manager.budget
+            SET deleted_by = $2, updated_at = $3
+            WHERE id = $1 AND deleted_by IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(Json(deleted_by))
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Box::new(HttpError::not_found("budget", id)));
+        }
+
+        Ok(())
+    }
+
+    async fn insert_alert(&self, alert: BudgetAlert) -> HttpResult<Option<BudgetAlert>> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.budget_alert (
+                id,
+                client_id,
+                budget_id,
+                month,
+                threshold,
+                committed_amount,
+                available_limit,
+                usage_percentage,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (budget_id, month, threshold) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(alert.id())
+        .bind(alert.client_id())
+        .bind(alert.budget_id())
+        .bind(alert.month())
+        .bind(alert.threshold().as_str())
+        .bind(alert.committed_amount())
+        .bind(alert.available_limit())
+        .bind(alert.usage_percentage())
+        .bind(alert.created_at())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(BudgetAlert::from))
+    }
+
+    async fn list_alerts(
+        &self,
+        client_id: Uuid,
+        month: Option<NaiveDate>,
+    ) -> HttpResult<Vec<BudgetAlert>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT alert.* FROM finance_manager.budget_alert alert
+            JOIN finance_manager.budget budget ON budget.id = alert.budget_id
+            WHERE alert.client_id = $1
+                AND budget.deleted_by IS NULL
+                AND ($2::DATE IS NULL OR alert.month = $2)
+            ORDER BY alert.created_at DESC
+            "#,
+        )
+        .bind(client_id)
+        .bind(month)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(BudgetAlert::from).collect())
+    }
+}
@@ -1,16 +1,31 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use chrono::Utc;
 use http_error::{ext::OptionHttpExt, HttpError, HttpResult};
 use sqlx::types::Json;
-use sqlx::{Pool, Postgres, QueryBuilder};
+use sqlx::{Acquire, PgConnection, PgExecutor, Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use util::DeletedBy;
 
-use crate::modules::finance_manager::domain::debt::{Debt, DebtFilters};
-
+use crate::modules::finance_manager::{
+    domain::debt::{
+        batch::{DebtBatch, SavedDebtBatch},
+        installment::Installment,
+        Debt, DebtFilters, DebtStatus,
+    },
+    repository::debt::{
+        installment::insert_installment,
+        invoice::{insert_invoice, update_invoice},
+    },
+};
+
+pub mod categorization;
+pub mod category;
 pub mod installment;
 pub mod invoice;
+pub mod renegotiation;
 
 #[async_trait]
 pub trait DebtRepository {
@@ -24,6 +39,20 @@ pub trait DebtRepository {
 
     async fn update(&self, debt: Debt) -> HttpResult<Debt>;
 
+    /// Saves the created and updated debts of the batch, with their
+    /// installments and invoices, in a single transaction. Returns the saved
+    /// debts, created ones first.
+    async fn save_batch(&self, batch: DebtBatch) -> HttpResult<Vec<Debt>>;
+
+    /// Saves a best-effort batch in a single transaction, each debt with its
+    /// installments under its own savepoint. Debts failing to save are left
+    /// out of the invoices and returned with their error.
+    async fn save_batch_best_effort(&self, batch: DebtBatch) -> HttpResult<SavedDebtBatch>;
+
+    /// Saves only the category, expense type and tags of the debts, in a
+    /// single transaction, so payments registered meanwhile are kept.
+    async fn update_labels(&self, debts: Vec<Debt>) -> HttpResult<()>;
+
     async fn soft_delete_cascade(
         &self,
         client_id: Uuid,
@@ -48,45 +77,130 @@ impl DebtRepositoryImpl {
 #[async_trait]
 impl DebtRepository for DebtRepositoryImpl {
     async fn update(&self, debt: Debt) -> HttpResult<Debt> {
-        let debt_dto = entity::DebtEntity::from(debt);
+        update_debt(&self.pool, debt).await
+    }
 
-        let row = sqlx::query(
-            r#"
-            UPDATE finance_manager.debt SET 
-                category = $2,
-                expense_type = $3,
-                tags = $4,
-                description = $5, 
-                total_amount = $6, 
-                paid_amount = $7, 
-                discount_amount = $8, 
-                remaining_amount = $9, 
-                due_date = $10, 
-                status = $11, 
-                installment_count = $12,
-                updated_at = $13
-            WHERE id = $1 
-            RETURNING *
-            "#,
-        )
-        .bind(debt_dto.id)
-        .bind(&debt_dto.category)
-        .bind(&debt_dto.expense_type)
-        .bind(&debt_dto.tags)
-        .bind(&debt_dto.description)
-        .bind(debt_dto.total_amount)
-        .bind(debt_dto.paid_amount)
-        .bind(debt_dto.discount_amount)
-        .bind(debt_dto.remaining_amount)
-        .bind(debt_dto.due_date)
-        .bind(&debt_dto.status)
-        .bind(debt_dto.installment_count)
-        .bind(debt_dto.updated_at)
-        .fetch_optional(&self.pool)
-        .await?
-        .or_not_found("debt", debt_dto.id.to_string())?;
+    async fn save_batch(&self, batch: DebtBatch) -> HttpResult<Vec<Debt>> {
+        let mut tx = self.pool.begin().await?;
+        let mut debts = Vec::with_capacity(batch.new_debts.len() + batch.updated_debts.len());
+
+        for debt in batch.new_debts {
+            debts.push(insert_debt(&mut *tx, debt).await?);
+        }
+
+        for installment in batch.installments {
+            insert_installment(&mut *tx, installment).await?;
+        }
+
+        for debt in batch.updated_debts {
+            debts.push(update_debt(&mut *tx, debt).await?);
+        }
+
+        for invoice in batch.new_invoices {
+            insert_invoice(&mut *tx, invoice).await?;
+        }
+
+        for invoice in batch.updated_invoices {
+            update_invoice(&mut *tx, invoice).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(debts)
+    }
+
+    async fn save_batch_best_effort(&self, batch: DebtBatch) -> HttpResult<SavedDebtBatch> {
+        let mut tx = self.pool.begin().await?;
+        let mut saved = SavedDebtBatch::default();
+
+        let mut installments: HashMap<Uuid, Vec<Installment>> = HashMap::new();
+        for installment in batch.installments {
+            installments
+                .entry(*installment.debt_id())
+                .or_default()
+                .push(installment);
+        }
+
+        for debt in batch.new_debts {
+            let debt_id = *debt.id();
+            let debt_installments = installments.remove(&debt_id).unwrap_or_default();
+
+            let mut savepoint = (&mut *tx).begin().await?;
+            match insert_debt_with_installments(&mut savepoint, debt, debt_installments).await {
+                Ok(debt) => {
+                    savepoint.commit().await?;
+                    saved.debts.push(debt);
+                }
+                Err(err) => {
+                    savepoint.rollback().await?;
+                    saved.failures.insert(debt_id, err);
+                }
+            }
+        }
+
+        for debt in batch.updated_debts {
+            let debt_id = *debt.id();
+
+            let mut savepoint = (&mut *tx).begin().await?;
+            match update_debt(&mut *savepoint, debt).await {
+                Ok(debt) => {
+                    savepoint.commit().await?;
+                    saved.debts.push(debt);
+                }
+                Err(err) => {
+                    savepoint.rollback().await?;
+                    saved.failures.insert(debt_id, err);
+                }
+            }
+        }
+
+        for mut invoice in batch.new_invoices {
+            for debt_id in saved.failures.keys() {
+                invoice.unlink_debt(*debt_id)?;
+            }
+            // Opened only for debts that failed to save
+            if invoice.related_debt_ids().is_empty() {
+                continue;
+            }
+            insert_invoice(&mut *tx, invoice).await?;
+        }
 
-        Ok(Debt::from(entity::DebtEntity::from(&row)))
+        for mut invoice in batch.updated_invoices {
+            for debt_id in saved.failures.keys() {
+                invoice.unlink_debt(*debt_id)?;
+            }
+            update_invoice(&mut *tx, invoice).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(saved)
+    }
+
+    async fn update_labels(&self, debts: Vec<Debt>) -> HttpResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for debt in debts {
+            let debt_dto = entity::DebtEntity::from(debt);
+            sqlx::query(
+                r#"
+                UPDATE finance_manager.debt
+                SET category = $2, expense_type = $3, tags = $4, updated_at = $5
+                WHERE id = $1
+                "#,
+            )
+            .bind(debt_dto.id)
+            .bind(&debt_dto.category)
+            .bind(&debt_dto.expense_type)
+            .bind(&debt_dto.tags)
+            .bind(debt_dto.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
     }
 
     async fn soft_delete_cascade(
@@ -180,50 +294,7 @@ impl DebtRepository for DebtRepositoryImpl {
     }
 
     async fn insert(&self, debt: Debt) -> HttpResult<Debt> {
-        let debt_dto = entity::DebtEntity::from(debt);
-
-        let row = sqlx::query(
-            r#"
-            INSERT INTO finance_manager.debt (
-                id,
-                client_id,
-                category,
-                expense_type,
-                tags,
-                description, 
-                total_amount, 
-                paid_amount, 
-                discount_amount, 
-                remaining_amount, 
-                due_date,
-                status,
-                installment_count,
-                created_at,
-                updated_at
-            ) 
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            RETURNING *
-        "#,
-        )
-        .bind(debt_dto.id)
-        .bind(debt_dto.client_id)
-        .bind(&debt_dto.category)
-        .bind(&debt_dto.expense_type)
-        .bind(&debt_dto.tags)
-        .bind(&debt_dto.description)
-        .bind(debt_dto.total_amount)
-        .bind(debt_dto.paid_amount)
-        .bind(debt_dto.discount_amount)
-        .bind(debt_dto.remaining_amount)
-        .bind(debt_dto.due_date)
-        .bind(&debt_dto.status)
-        .bind(debt_dto.installment_count)
-        .bind(debt_dto.created_at)
-        .bind(debt_dto.updated_at)
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(Debt::from(entity::DebtEntity::from(&row)))
+        insert_debt(&self.pool, debt).await
     }
 
     async fn list(&self, filters: &DebtFilters) -> HttpResult<Vec<Debt>> {
@@ -258,13 +329,24 @@ impl DebtRepository for DebtRepositoryImpl {
         }
 
         if let Some(statuses) = filters.statuses() {
-            builder.push(" AND status = ANY(");
-            builder.push_bind(
-                statuses
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>(),
-            );
+            // Overdue is derived from open debts past their due date, so
+            // filtering by open still lists them.
+            let today = Utc::now().date_naive();
+
+            builder.push(" AND (FALSE");
+            for status in statuses {
+                match status {
+                    DebtStatus::Overdue => {
+                        builder.push(" OR (status = 'OPEN' AND due_date < ");
+                        builder.push_bind(today);
+                        builder.push(")");
+                    }
+                    status => {
+                        builder.push(" OR status = ");
+                        builder.push_bind(status.to_string());
+                    }
+                }
+            }
             builder.push(")");
         }
 
@@ -281,8 +363,151 @@ impl DebtRepository for DebtRepositoryImpl {
     }
 }
 
+/// Updates the debt using any executor, so it can be part of a transaction
+/// opened by another repository.
+pub(crate) async fn update_debt<'e, E>(executor: E, debt: Debt) -> HttpResult<Debt>
+where
+    E: PgExecutor<'e>,
+{
+    let debt_dto = entity::DebtEntity::from(debt);
+
+    let row = sqlx::query(
+        r#"
+        UPDATE finance_manager.debt SET 
+            category = $2,
+            expense_type = $3,
+            tags = $4,
+            description = $5, 
+            total_amount = $6, 
+            paid_amount = $7, 
+            discount_amount = $8, 
+            remaining_amount = $9, 
+            due_date = $10, 
+            status = $11, 
+            installment_count = $12,
+            installment_offset = $13,
+            late_fee_policy = $14,
+            late_fee = $15,
+            financing = $16,
+            carried_over_amount = $17,
+            carried_over_to = $18,
+            carried_over_from = $19,
+            updated_at = $20
+        WHERE id = $1 
+        RETURNING *
+        "#,
+    )
+    .bind(debt_dto.id)
+    .bind(&debt_dto.category)
+    .bind(&debt_dto.expense_type)
+    .bind(&debt_dto.tags)
+    .bind(&debt_dto.description)
+    .bind(debt_dto.total_amount)
+    .bind(debt_dto.paid_amount)
+    .bind(debt_dto.discount_amount)
+    .bind(debt_dto.remaining_amount)
+    .bind(debt_dto.due_date)
+    .bind(&debt_dto.status)
+    .bind(debt_dto.installment_count)
+    .bind(debt_dto.installment_offset)
+    .bind(debt_dto.late_fee_policy.map(Json))
+    .bind(debt_dto.late_fee.map(Json))
+    .bind(debt_dto.financing.map(Json))
+    .bind(debt_dto.carried_over_amount)
+    .bind(debt_dto.carried_over_to)
+    .bind(debt_dto.carried_over_from)
+    .bind(debt_dto.updated_at)
+    .fetch_optional(executor)
+    .await?
+    .or_not_found("debt", debt_dto.id.to_string())?;
+
+    Ok(Debt::from(entity::DebtEntity::from(&row)))
+}
+
+/// Inserts the debt using any executor, so it can be part of a transaction
+/// opened by another repository.
+async fn insert_debt_with_installments(
+    conn: &mut PgConnection,
+    debt: Debt,
+    installments: Vec<Installment>,
+) -> HttpResult<Debt> {
+    let debt = insert_debt(&mut *conn, debt).await?;
+    for installment in installments {
+        insert_installment(&mut *conn, installment).await?;
+    }
+
+    Ok(debt)
+}
+
+pub(crate) async fn insert_debt<'e, E>(executor: E, debt: Debt) -> HttpResult<Debt>
+where
+    E: PgExecutor<'e>,
+{
+    let debt_dto = entity::DebtEntity::from(debt);
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO finance_manager.debt (
+            id,
+            client_id,
+            category,
+            expense_type,
+            tags,
+            description, 
+            total_amount, 
+            paid_amount, 
+            discount_amount, 
+            remaining_amount, 
+            due_date,
+            status,
+            installment_count,
+            installment_offset,
+            late_fee_policy,
+            late_fee,
+            financing,
+            carried_over_amount,
+            carried_over_to,
+            carried_over_from,
+            created_at,
+            updated_at
+        ) 
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+            $19, $20, $21, $22
+        )
+        RETURNING *
+    "#,
+    )
+    .bind(debt_dto.id)
+    .bind(debt_dto.client_id)
+    .bind(&debt_dto.category)
+    .bind(&debt_dto.expense_type)
+    .bind(&debt_dto.tags)
+    .bind(&debt_dto.description)
+    .bind(debt_dto.total_amount)
+    .bind(debt_dto.paid_amount)
+    .bind(debt_dto.discount_amount)
+    .bind(debt_dto.remaining_amount)
+    .bind(debt_dto.due_date)
+    .bind(&debt_dto.status)
+    .bind(debt_dto.installment_count)
+    .bind(debt_dto.installment_offset)
+    .bind(debt_dto.late_fee_policy.map(Json))
+    .bind(debt_dto.late_fee.map(Json))
+    .bind(debt_dto.financing.map(Json))
+    .bind(debt_dto.carried_over_amount)
+    .bind(debt_dto.carried_over_to)
+    .bind(debt_dto.carried_over_from)
+    .bind(debt_dto.created_at)
+    .bind(debt_dto.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Debt::from(entity::DebtEntity::from(&row)))
+}
+
 pub mod entity {
-    use chrono::{NaiveDate, NaiveDateTime};
+    use chrono::{NaiveDate, NaiveDateTime, Utc};
     use rust_decimal::Decimal;
     use serde::{Deserialize, Serialize};
     use sqlx::postgres::PgRow;
@@ -293,7 +518,11 @@ pub mod entity {
 
     use util::DeletedBy;
 
-    use crate::modules::finance_manager::domain::debt::{Debt, DebtCategory, ExpenseType};
+    use crate::modules::finance_manager::domain::debt::{
+        amortization::FinancingTerms,
+        late_fee::{LateFeeBreakdown, LateFeePolicy},
+        Debt, DebtCategory, DebtStatus, ExpenseType,
+    };
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct DebtEntity {
@@ -311,6 +540,13 @@ pub mod entity {
         pub due_date: NaiveDate,
         pub status: String,
         pub installment_count: Option<i32>,
+        pub installment_offset: i32,
+        pub late_fee_policy: Option<LateFeePolicy>,
+        pub late_fee: Option<LateFeeBreakdown>,
+        pub financing: Option<FinancingTerms>,
+        pub carried_over_amount: Decimal,
+        pub carried_over_to: Option<Uuid>,
+        pub carried_over_from: Option<Uuid>,
         pub created_at: NaiveDateTime,
         pub updated_at: Option<NaiveDateTime>,
         pub deleted_by: Option<DeletedBy>,
@@ -333,6 +569,19 @@ pub mod entity {
                 due_date: row.get("due_date"),
                 status: row.get("status"),
                 installment_count: row.get("installment_count"),
+                installment_offset: row.get("installment_offset"),
+                late_fee_policy: row
+                    .get::<Option<Json<LateFeePolicy>>, _>("late_fee_policy")
+                    .map(|j| j.0),
+                late_fee: row
+                    .get::<Option<Json<LateFeeBreakdown>>, _>("late_fee")
+                    .map(|j| j.0),
+                financing: row
+                    .get::<Option<Json<FinancingTerms>>, _>("financing")
+                    .map(|j| j.0),
+                carried_over_amount: row.get("carried_over_amount"),
+                carried_over_to: row.get("carried_over_to"),
+                carried_over_from: row.get("carried_over_from"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
                 deleted_by: row
@@ -357,8 +606,19 @@ pub mod entity {
                 discount_amount: *debt.discount_amount(),
                 remaining_amount: *debt.remaining_amount(),
                 due_date: *debt.due_date(),
-                status: debt.status().clone().into(),
+                status: match debt.status() {
+                    // Derived status, stored as open
+                    DebtStatus::Overdue => DebtStatus::Open.into(),
+                    status => status.clone().into(),
+                },
                 installment_count: *debt.installment_count(),
+                installment_offset: *debt.installment_offset(),
+                late_fee_policy: debt.late_fee_policy().clone(),
+                late_fee: debt.late_fee().clone(),
+                financing: debt.financing().clone(),
+                carried_over_amount: *debt.carried_over_amount(),
+                carried_over_to: *debt.carried_over_to(),
+                carried_over_from: *debt.carried_over_from(),
                 created_at: debt.created_at().naive_utc(),
                 updated_at: debt.updated_at().map(|dt| dt.naive_utc()),
                 deleted_by: debt.deleted_by().clone(),
@@ -368,7 +628,7 @@ pub mod entity {
 
     impl From<DebtEntity> for Debt {
         fn from(dto: DebtEntity) -> Self {
-            Debt::from_row(
+            let mut debt = Debt::from_row(
                 dto.id,
                 dto.client_id,
                 DebtCategory::from(dto.category),
@@ -383,10 +643,19 @@ pub mod entity {
                 dto.due_date,
                 dto.status.into(),
                 dto.installment_count,
+                dto.installment_offset,
+                dto.late_fee_policy,
+                dto.late_fee,
+                dto.financing,
+                dto.carried_over_amount,
+                dto.carried_over_to,
+                dto.carried_over_from,
                 dto.created_at.and_utc(),
                 dto.updated_at.map(|dt| dt.and_utc()),
                 dto.deleted_by,
-            )
+            );
+            debt.refresh_overdue_status(Utc::now().date_naive());
+            debt
         }
     }
 }
@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{types::Json, Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::categorization::CategorizationRule;
+
+#[async_trait]
+pub trait CategorizationRuleRepository {
+    async fn insert(&self, rule: CategorizationRule) -> HttpResult<CategorizationRule>;
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<CategorizationRule>>;
+
+    /// Rules of the client in the order they are tried.
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>>;
+
+    async fn update(&self, rule: CategorizationRule) -> HttpResult<CategorizationRule>;
+
+    async fn delete(&self, id: &Uuid) -> HttpResult<()>;
+}
+
+pub type DynCategorizationRuleRepository = dyn CategorizationRuleRepository + Send + Sync;
+
+pub struct CategorizationRuleRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl CategorizationRuleRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl CategorizationRuleRepository for CategorizationRuleRepositoryImpl {
+    async fn insert(&self, rule: CategorizationRule) -> HttpResult<CategorizationRule> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.categorization_rule (
+                id,
+                client_id,
+                name,
+                priority,
+                active,
+                conditions,
+                actions,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(rule.id())
+        .bind(rule.client_id())
+        .bind(rule.name())
+        .bind(rule.priority())
+        .bind(rule.active())
+        .bind(Json(rule.conditions()))
+        .bind(Json(rule.actions()))
+        .bind(rule.created_at())
+        .bind(rule.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(CategorizationRule::from(&row))
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<CategorizationRule>> {
+        let row = sqlx::query("SELECT * FROM finance_manager.categorization_rule WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(CategorizationRule::from))
+    }
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<CategorizationRule>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.categorization_rule
+            WHERE client_id = $1
+            ORDER BY priority, created_at
+            "#,
+        )
+        .bind(client_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(CategorizationRule::from).collect())
+    }
+
+    async fn update(&self, rule: CategorizationRule) -> HttpResult<CategorizationRule> {
+        let row = sqlx::query(
+            r#"
+            UPDATE finance_manager.categorization_rule
+            SET name = $2, priority = $3, active = $4, conditions = $5, actions = $6,
+                updated_at = $7
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(rule.id())
+        .bind(rule.name())
+        .bind(rule.priority())
+        .bind(rule.active())
+        .bind(Json(rule.conditions()))
+        .bind(Json(rule.actions()))
+        .bind(rule.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(CategorizationRule::from(&row))
+    }
+
+    async fn delete(&self, id: &Uuid) -> HttpResult<()> {
+        sqlx::query("DELETE FROM finance_manager.categorization_rule WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::debt::{category::Category, DebtCategory};
+
+#[async_trait]
+pub trait CategoryRepository {
+    async fn insert(&self, category: Category) -> HttpResult<Category>;
+
+    /// Inserts the categories, skipping names the client already has.
+    async fn insert_many(&self, categories: Vec<Category>) -> HttpResult<()>;
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Category>>;
+
+    async fn get_by_name(
+        &self,
+        client_id: Uuid,
+        name: &DebtCategory,
+    ) -> HttpResult<Option<Category>>;
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<Category>>;
+
+    /// Updates the category. When it was renamed, the debts, recurrences,
+    /// budgets and categorization rules of `previous_name` are moved to the
+    /// new name in the same transaction.
+    async fn update(
+        &self,
+        category: Category,
+        previous_name: Option<DebtCategory>,
+    ) -> HttpResult<Category>;
+
+    /// Subcategories, debts, recurrences, budgets and categorization rules
+    /// still using the category.
+    async fn count_usages(&self, category: &Category) -> HttpResult<i64>;
+
+    async fn delete(&self, id: &Uuid) -> HttpResult<()>;
+}
+
+pub type DynCategoryRepository = dyn CategoryRepository + Send + Sync;
+
+pub struct CategoryRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl CategoryRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl CategoryRepository for CategoryRepositoryImpl {
+    async fn insert(&self, category: Category) -> HttpResult<Category> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.debt_category (
+                id,
+                client_id,
+                parent_id,
+                name,
+                color,
+                icon,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(category.id())
+        .bind(category.client_id())
+        .bind(category.parent_id())
+        .bind(category.name().as_str())
+        .bind(category.color())
+        .bind(category.icon())
+        .bind(category.created_at())
+        .bind(category.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Category::from(&row))
+    }
+
+    async fn insert_many(&self, categories: Vec<Category>) -> HttpResult<()> {
+        if categories.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO finance_manager.debt_category \
+             (id, client_id, parent_id, name, color, icon, created_at, updated_at) ",
+        );
+        builder.push_values(&categories, |mut row, category| {
+            row.push_bind(category.id())
+                .push_bind(category.client_id())
+                .push_bind(category.parent_id())
+                .push_bind(category.name().as_str())
+                .push_bind(category.color())
+                .push_bind(category.icon())
+                .push_bind(category.created_at())
+                .push_bind(category.updated_at());
+        });
+        builder.push(" ON CONFLICT (client_id, name) DO NOTHING");
+
+        builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Category>> {
+        let row = sqlx::query("SELECT * FROM finance_manager.debt_category WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(Category::from))
+    }
+
+    async fn get_by_name(
+        &self,
+        client_id: Uuid,
+        name: &DebtCategory,
+    ) -> HttpResult<Option<Category>> {
+        let row = sqlx::query(
+            "SELECT * FROM finance_manager.debt_category WHERE client_id = $1 AND name = $2",
+        )
+        .bind(client_id)
+        .bind(name.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Category::from))
+    }
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<Category>> {
+        let rows = sqlx::query(
+            "SELECT * FROM finance_manager.debt_category WHERE client_id = $1 ORDER BY name",
+        )
+        .bind(client_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Category::from).collect())
+    }
+
+    async fn update(
+        &self,
+        category: Category,
+        previous_name: Option<DebtCategory>,
+    ) -> HttpResult<Category> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE finance_manager.debt_category
+            SET parent_id = $2, name = $3, color = $4, icon = $5, updated_at = $6
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(category.id())
+        .bind(category.parent_id())
+        .bind(category.name().as_str())
+        .bind(category.color())
+        .bind(category.icon())
+        .bind(category.updated_at())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(previous_name) = previous_name {
+            for table in ["debt", "recurrence", "budget"] {
+                sqlx::query(&format!(
+                    "UPDATE finance_manager.{table} SET category = $3 \
+                     WHERE client_id = $1 AND category = $2"
+                ))
+                .bind(category.client_id())
+                .bind(previous_name.as_str())
+                .bind(category.name().as_str())
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            sqlx::query(
+                r#"
+                UPDATE finance_manager.categorization_rule
+                SET actions = jsonb_set(actions, '{category}', to_jsonb($3::TEXT))
+                WHERE client_id = $1 AND actions->>'category' = $2
+                "#,
+            )
+            .bind(category.client_id())
+            .bind(previous_name.as_str())
+            .bind(category.name().as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(Category::from(&row))
+    }
+
+    async fn count_usages(&self, category: &Category) -> HttpResult<i64> {
+        let count = sqlx::query_scalar(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM finance_manager.debt_category WHERE parent_id = $1)
+                + (SELECT COUNT(*) FROM finance_manager.debt
+                   WHERE client_id = $2 AND category = $3 AND deleted_by IS NULL)
+                + (SELECT COUNT(*) FROM finance_manager.recurrence
+                   WHERE client_id = $2 AND category = $3)
+                + (SELECT COUNT(*) FROM finance_manager.budget
+                   WHERE client_id = $2 AND category = $3 AND deleted_by IS NULL)
+                + (SELECT COUNT(*) FROM finance_manager.categorization_rule
+                   WHERE client_id = $2 AND actions->>'category' = $3)
+            "#,
+        )
+        .bind(category.id())
+        .bind(category.client_id())
+        .bind(category.name().as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn delete(&self, id: &Uuid) -> HttpResult<()> {
+        sqlx::query("DELETE FROM finance_manager.debt_category WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
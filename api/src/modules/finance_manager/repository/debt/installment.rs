@@ -1,9 +1,10 @@
 use async_trait::async_trait;
 use http_error::HttpResult;
-use sqlx::{Pool, Postgres, QueryBuilder};
+use sqlx::{PgExecutor, Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
 
 use crate::modules::finance_manager::{
-    domain::debt::installment::Installment,
+    domain::debt::installment::{Installment, InstallmentAllocation},
     repository::debt::installment::{entity::InstallmentEntity, use_cases::InstallmentFilters},
 };
 
@@ -12,6 +13,23 @@ pub trait InstallmentRepository {
     async fn insert_many(&self, installments: Vec<Installment>) -> HttpResult<Vec<Installment>>;
     async fn list(&self, filters: &InstallmentFilters) -> HttpResult<Vec<Installment>>;
     async fn update(&self, installment: Installment) -> HttpResult<Installment>;
+
+    async fn list_allocations(&self, payment_id: Uuid) -> HttpResult<Vec<InstallmentAllocation>>;
+
+    /// Saves the installments touched by a payment and how it was allocated.
+    async fn apply_payment(
+        &self,
+        installments: Vec<Installment>,
+        allocations: Vec<InstallmentAllocation>,
+    ) -> HttpResult<()>;
+
+    /// Saves the installments restored by a reversal and drops the payment's
+    /// allocations.
+    async fn reverse_payment(
+        &self,
+        installments: Vec<Installment>,
+        payment_id: Uuid,
+    ) -> HttpResult<()>;
 }
 
 pub type DynInstallmentRepository = dyn InstallmentRepository + Send + Sync;
@@ -29,31 +47,65 @@ impl InstallmentRepositoryImpl {
 #[async_trait]
 impl InstallmentRepository for InstallmentRepositoryImpl {
     async fn update(&self, installment: Installment) -> HttpResult<Installment> {
-        let installment_dto = InstallmentEntity::from(installment);
+        update_installment(&self.pool, installment).await
+    }
 
-        let row = sqlx::query(
+    async fn list_allocations(&self, payment_id: Uuid) -> HttpResult<Vec<InstallmentAllocation>> {
+        let rows = sqlx::query(
             r#"
-            UPDATE finance_manager.debt_installment SET 
-                due_date = $3,
-                amount = $4,
-                is_paid = $5,
-                payment_id = $6,
-                updated_at = $7
-            WHERE debt_id = $1 AND installment_id = $2
-            RETURNING *
+            SELECT * FROM finance_manager.installment_payment_allocation
+            WHERE payment_id = $1
+            ORDER BY installment_id
             "#,
         )
-        .bind(installment_dto.debt_id)
-        .bind(installment_dto.installment_id)
-        .bind(installment_dto.due_date)
-        .bind(installment_dto.amount)
-        .bind(installment_dto.is_paid)
-        .bind(installment_dto.payment_id)
-        .bind(installment_dto.updated_at)
-        .fetch_one(&self.pool)
+        .bind(payment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(InstallmentAllocation::from).collect())
+    }
+
+    async fn apply_payment(
+        &self,
+        installments: Vec<Installment>,
+        allocations: Vec<InstallmentAllocation>,
+    ) -> HttpResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for installment in installments {
+            update_installment(&mut *tx, installment).await?;
+        }
+
+        for allocation in allocations {
+            insert_allocation(&mut *tx, &allocation).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn reverse_payment(
+        &self,
+        installments: Vec<Installment>,
+        payment_id: Uuid,
+    ) -> HttpResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for installment in installments {
+            update_installment(&mut *tx, installment).await?;
+        }
+
+        sqlx::query(
+            r#"DELETE FROM finance_manager.installment_payment_allocation WHERE payment_id = $1"#,
+        )
+        .bind(payment_id)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(Installment::from(InstallmentEntity::from(&row)))
+        tx.commit().await?;
+
+        Ok(())
     }
 
     async fn insert_many(&self, installments: Vec<Installment>) -> HttpResult<Vec<Installment>> {
@@ -61,36 +113,7 @@ impl InstallmentRepository for InstallmentRepositoryImpl {
         let mut results: Vec<Installment> = Vec::new();
 
         for installment in installments {
-            let payload = InstallmentEntity::from(installment);
-
-            let row = sqlx::query(
-                r#"
-                    INSERT INTO finance_manager.debt_installment (
-                        debt_id,
-                        installment_id,
-                        due_date,
-                        amount,
-                        is_paid,
-                        payment_id,
-                        created_at,
-                        updated_at
-                    )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                    RETURNING *
-                "#,
-            )
-            .bind(payload.debt_id)
-            .bind(payload.installment_id)
-            .bind(payload.due_date)
-            .bind(payload.amount)
-            .bind(payload.is_paid)
-            .bind(payload.payment_id)
-            .bind(payload.created_at)
-            .bind(payload.updated_at)
-            .fetch_one(&mut *tx)
-            .await?;
-
-            results.push(Installment::from(InstallmentEntity::from(&row)));
+            results.push(insert_installment(&mut *tx, installment).await?);
         }
 
         tx.commit().await?;
@@ -116,6 +139,10 @@ impl InstallmentRepository for InstallmentRepositoryImpl {
             builder.push(")");
         }
 
+        if !filters.include_superseded() {
+            builder.push(" AND di.superseded_by IS NULL");
+        }
+
         if let Some(is_paid) = filters.is_paid() {
             builder.push(" AND di.is_paid = ");
             builder.push_bind(is_paid);
@@ -132,8 +159,15 @@ impl InstallmentRepository for InstallmentRepositoryImpl {
         }
 
         if let Some(payment_id) = filters.payment_id() {
-            builder.push(" AND di.payment_id = ");
+            builder.push(" AND (di.payment_id = ");
             builder.push_bind(payment_id);
+            builder.push(
+                " OR EXISTS (SELECT 1 FROM finance_manager.installment_payment_allocation a \
+                 WHERE a.debt_id = di.debt_id AND a.installment_id = di.installment_id \
+                 AND a.payment_id = ",
+            );
+            builder.push_bind(payment_id);
+            builder.push("))");
         }
 
         let query = builder.build();
@@ -146,6 +180,133 @@ impl InstallmentRepository for InstallmentRepositoryImpl {
     }
 }
 
+/// Inserts the installment using any executor, so it can be part of a
+/// transaction opened by another repository.
+pub(crate) async fn insert_installment<'e, E>(
+    executor: E,
+    installment: Installment,
+) -> HttpResult<Installment>
+where
+    E: PgExecutor<'e>,
+{
+    let payload = InstallmentEntity::from(installment);
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO finance_manager.debt_installment (
+            debt_id,
+            installment_id,
+            due_date,
+            amount,
+            principal_amount,
+            interest_amount,
+            paid_amount,
+            is_paid,
+            payment_id,
+            superseded_by,
+            created_at,
+            updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+    )
+    .bind(payload.debt_id)
+    .bind(payload.installment_id)
+    .bind(payload.due_date)
+    .bind(payload.amount)
+    .bind(payload.principal_amount)
+    .bind(payload.interest_amount)
+    .bind(payload.paid_amount)
+    .bind(payload.is_paid)
+    .bind(payload.payment_id)
+    .bind(payload.superseded_by)
+    .bind(payload.created_at)
+    .bind(payload.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Installment::from(InstallmentEntity::from(&row)))
+}
+
+/// Updates the installment using any executor, so it can be part of a
+/// transaction opened by another repository.
+pub(crate) async fn update_installment<'e, E>(
+    executor: E,
+    installment: Installment,
+) -> HttpResult<Installment>
+where
+    E: PgExecutor<'e>,
+{
+    let installment_dto = InstallmentEntity::from(installment);
+
+    let row = sqlx::query(
+        r#"
+        UPDATE finance_manager.debt_installment SET 
+            due_date = $3,
+            amount = $4,
+            principal_amount = $5,
+            interest_amount = $6,
+            paid_amount = $7,
+            is_paid = $8,
+            payment_id = $9,
+            superseded_by = $10,
+            updated_at = $11
+        WHERE debt_id = $1 AND installment_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(installment_dto.debt_id)
+    .bind(installment_dto.installment_id)
+    .bind(installment_dto.due_date)
+    .bind(installment_dto.amount)
+    .bind(installment_dto.principal_amount)
+    .bind(installment_dto.interest_amount)
+    .bind(installment_dto.paid_amount)
+    .bind(installment_dto.is_paid)
+    .bind(installment_dto.payment_id)
+    .bind(installment_dto.superseded_by)
+    .bind(installment_dto.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Installment::from(InstallmentEntity::from(&row)))
+}
+
+/// Records how much of a payment went to an installment, using any executor
+/// so it can be part of a transaction opened by another repository.
+pub(crate) async fn insert_allocation<'e, E>(
+    executor: E,
+    allocation: &InstallmentAllocation,
+) -> HttpResult<()>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO finance_manager.installment_payment_allocation (
+            payment_id,
+            debt_id,
+            installment_id,
+            amount,
+            discount_amount,
+            created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(allocation.payment_id())
+    .bind(allocation.debt_id())
+    .bind(allocation.installment_id())
+    .bind(allocation.amount())
+    .bind(allocation.discount_amount())
+    .bind(allocation.created_at())
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
 pub mod use_cases {
     use chrono::NaiveDate;
     use serde::{Deserialize, Serialize};
@@ -161,6 +322,8 @@ pub mod use_cases {
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
         payment_id: Option<Uuid>,
+        #[serde(default)]
+        include_superseded: bool,
     }
 
     impl InstallmentFilters {
@@ -207,6 +370,12 @@ pub mod use_cases {
             }
             self
         }
+
+        /// Also lists installments replaced by a renegotiation.
+        pub fn with_include_superseded(mut self, include_superseded: bool) -> Self {
+            self.include_superseded = include_superseded;
+            self
+        }
     }
 
     getters!(
@@ -217,6 +386,7 @@ pub mod use_cases {
             start_date: Option<NaiveDate>,
             end_date: Option<NaiveDate>,
             payment_id: Option<Uuid>,
+            include_superseded: bool,
         }
     );
 }
@@ -241,8 +411,12 @@ pub mod entity {
         pub installment_id: i32,
         pub due_date: NaiveDate,
         pub amount: Decimal,
+        pub principal_amount: Decimal,
+        pub interest_amount: Decimal,
+        pub paid_amount: Decimal,
         pub is_paid: bool,
         pub payment_id: Option<Uuid>,
+        pub superseded_by: Option<Uuid>,
         pub created_at: NaiveDateTime,
         pub updated_at: Option<NaiveDateTime>,
         pub deleted_by: Option<DeletedBy>,
@@ -255,8 +429,12 @@ pub mod entity {
                 installment_id: row.get("installment_id"),
                 due_date: row.get("due_date"),
                 amount: row.get("amount"),
+                principal_amount: row.get("principal_amount"),
+                interest_amount: row.get("interest_amount"),
+                paid_amount: row.get("paid_amount"),
                 is_paid: row.get("is_paid"),
                 payment_id: row.get("payment_id"),
+                superseded_by: row.get("superseded_by"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
                 deleted_by: row
@@ -273,8 +451,12 @@ pub mod entity {
                 installment_id: *installment.installment_id(),
                 due_date: *installment.due_date(),
                 amount: *installment.amount(),
+                principal_amount: *installment.principal_amount(),
+                interest_amount: *installment.interest_amount(),
+                paid_amount: *installment.paid_amount(),
                 is_paid: *installment.is_paid(),
                 payment_id: *installment.payment_id(),
+                superseded_by: *installment.superseded_by(),
                 created_at: installment.created_at().naive_utc(),
                 updated_at: installment.updated_at().map(|dt| dt.naive_utc()),
                 deleted_by: installment.deleted_by().clone(),
@@ -289,8 +471,12 @@ pub mod entity {
                 entity.installment_id,
                 entity.due_date,
                 entity.amount,
+                entity.principal_amount,
+                entity.interest_amount,
+                entity.paid_amount,
                 entity.is_paid,
                 entity.payment_id,
+                entity.superseded_by,
                 entity.created_at.and_utc(),
                 entity.updated_at.map(|dt| dt.and_utc()),
                 entity.deleted_by,
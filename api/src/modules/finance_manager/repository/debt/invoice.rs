@@ -1,12 +1,46 @@
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use http_error::HttpResult;
-use sqlx::{types::Json, Pool, Postgres, QueryBuilder};
+use sqlx::{types::Json, PgExecutor, Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::modules::finance_manager::domain::debt::invoice::{filters::InvoiceFilters, Invoice};
+use crate::modules::finance_manager::{
+    domain::debt::invoice::{filters::InvoiceFilters, settlement::InvoiceSettlement, Invoice},
+    repository::{
+        debt::{
+            insert_debt,
+            installment::{insert_allocation, update_installment},
+            update_debt,
+        },
+        financial_instrument::InstrumentLock,
+        payment::insert_payment,
+    },
+};
 use crate::modules::shared::repository::Repository;
 
-pub type DynInvoiceRepository = dyn Repository<Invoice, InvoiceFilters, Uuid> + Send + Sync;
+#[async_trait]
+pub trait InvoiceRepository: Repository<Invoice, InvoiceFilters, Uuid> {
+    /// Open billing cycle invoices, of every client, closing up to `today`.
+    async fn list_to_close(&self, today: NaiveDate) -> HttpResult<Vec<Invoice>>;
+
+    /// Invoice of a card for the given reference month.
+    async fn get_by_billing_cycle(
+        &self,
+        financial_instrument_id: Uuid,
+        reference_date: NaiveDate,
+    ) -> HttpResult<Option<Invoice>>;
+
+    /// Saves the payments of an invoice settlement, the debts and installments
+    /// they paid and the carry-over debt in the transaction locking the paying
+    /// instrument.
+    async fn save_settlement(
+        &self,
+        settlement: InvoiceSettlement,
+        lock: InstrumentLock,
+    ) -> HttpResult<()>;
+}
+
+pub type DynInvoiceRepository = dyn InvoiceRepository + Send + Sync;
 
 pub struct InvoiceRepositoryImpl {
     pool: Pool<Postgres>,
@@ -35,6 +69,11 @@ impl Repository<Invoice, InvoiceFilters, Uuid> for InvoiceRepositoryImpl {
             builder.push_bind(d);
         }
 
+        if let Some(financial_instrument_id) = filters.financial_instrument_id {
+            builder.push(" AND financial_instrument_id = ");
+            builder.push_bind(financial_instrument_id);
+        }
+
         let query = builder.build();
         let rows = query.fetch_all(&self.pool).await?;
 
@@ -51,36 +90,7 @@ impl Repository<Invoice, InvoiceFilters, Uuid> for InvoiceRepositoryImpl {
     }
 
     async fn insert(&self, item: Invoice) -> HttpResult<Invoice> {
-        let deleted_by = item.deleted_by().clone().map(Json);
-
-        let row = sqlx::query(
-            r#"
-            INSERT INTO finance_manager.invoice (
-                id,
-                client_id,
-                name,
-                reference_date,
-                related_debt_ids,
-                created_at,
-                updated_at,
-                deleted_by
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING *
-            "#,
-        )
-        .bind(*item.id())
-        .bind(*item.client_id())
-        .bind(item.name())
-        .bind(*item.reference_date())
-        .bind(Vec::from_iter(item.related_debt_ids().iter().copied()))
-        .bind(*item.created_at())
-        .bind(*item.updated_at())
-        .bind(deleted_by)
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(Invoice::from(&row))
+        insert_invoice(&self.pool, item).await
     }
 
     async fn insert_many(&self, items: Vec<Invoice>) -> HttpResult<Vec<Invoice>> {
@@ -88,36 +98,7 @@ impl Repository<Invoice, InvoiceFilters, Uuid> for InvoiceRepositoryImpl {
         let mut results = Vec::with_capacity(items.len());
 
         for item in items {
-            let deleted_by = item.deleted_by().clone().map(Json);
-
-            let row = sqlx::query(
-                r#"
-                INSERT INTO finance_manager.invoice (
-                    id,
-                    client_id,
-                    name,
-                    reference_date,
-                    related_debt_ids,
-                    created_at,
-                    updated_at,
-                    deleted_by
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                RETURNING *
-                "#,
-            )
-            .bind(*item.id())
-            .bind(*item.client_id())
-            .bind(item.name())
-            .bind(*item.reference_date())
-            .bind(Vec::from_iter(item.related_debt_ids().iter().copied()))
-            .bind(*item.created_at())
-            .bind(*item.updated_at())
-            .bind(deleted_by)
-            .fetch_one(&mut *tx)
-            .await?;
-
-            results.push(Invoice::from(&row));
+            results.push(insert_invoice(&mut *tx, item).await?);
         }
 
         tx.commit().await?;
@@ -125,35 +106,176 @@ impl Repository<Invoice, InvoiceFilters, Uuid> for InvoiceRepositoryImpl {
     }
 
     async fn update(&self, item: Invoice) -> HttpResult<Invoice> {
-        let deleted_by = item.deleted_by().clone().map(Json);
+        update_invoice(&self.pool, item).await
+    }
+
+    async fn delete(&self, _id: &Uuid) -> HttpResult<()> {
+        unimplemented!()
+    }
+}
 
+#[async_trait]
+impl InvoiceRepository for InvoiceRepositoryImpl {
+    async fn list_to_close(&self, today: NaiveDate) -> HttpResult<Vec<Invoice>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.invoice
+            WHERE closed_at IS NULL
+                AND closing_date <= $1
+                AND deleted_by IS NULL
+            ORDER BY closing_date
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Invoice::from).collect())
+    }
+
+    async fn get_by_billing_cycle(
+        &self,
+        financial_instrument_id: Uuid,
+        reference_date: NaiveDate,
+    ) -> HttpResult<Option<Invoice>> {
         let row = sqlx::query(
             r#"
-            UPDATE finance_manager.invoice SET
-                client_id = $2,
-                name = $3,
-                reference_date = $4,
-                related_debt_ids = $5,
-                updated_at = $6,
-                deleted_by = $7
-            WHERE id = $1
-            RETURNING *
+            SELECT * FROM finance_manager.invoice
+            WHERE financial_instrument_id = $1
+                AND reference_date = $2
+                AND deleted_by IS NULL
             "#,
         )
-        .bind(*item.id())
-        .bind(*item.client_id())
-        .bind(item.name())
-        .bind(*item.reference_date())
-        .bind(Vec::from_iter(item.related_debt_ids().iter().copied()))
-        .bind(*item.updated_at())
-        .bind(deleted_by)
-        .fetch_one(&self.pool)
+        .bind(financial_instrument_id)
+        .bind(reference_date)
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok(Invoice::from(&row))
+        Ok(row.as_ref().map(Invoice::from))
     }
 
-    async fn delete(&self, _id: &Uuid) -> HttpResult<()> {
-        unimplemented!()
+    async fn save_settlement(
+        &self,
+        settlement: InvoiceSettlement,
+        lock: InstrumentLock,
+    ) -> HttpResult<()> {
+        let mut tx = lock.tx;
+
+        // The invoice's debts reference the carry-over debt
+        if let Some(carry_over) = settlement.carry_over {
+            insert_debt(&mut *tx, carry_over.debt).await?;
+
+            if carry_over.is_new_invoice {
+                insert_invoice(&mut *tx, carry_over.invoice).await?;
+            } else {
+                update_invoice(&mut *tx, carry_over.invoice).await?;
+            }
+        }
+
+        for payment in settlement.payments {
+            insert_payment(&mut *tx, payment).await?;
+        }
+
+        for installment in settlement.installments {
+            update_installment(&mut *tx, installment).await?;
+        }
+
+        for allocation in &settlement.allocations {
+            insert_allocation(&mut *tx, allocation).await?;
+        }
+
+        for debt in settlement.debts {
+            update_debt(&mut *tx, debt).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
     }
 }
+
+/// Inserts the invoice using any executor, so it can be part of a transaction.
+pub(crate) async fn insert_invoice<'e, E>(executor: E, item: Invoice) -> HttpResult<Invoice>
+where
+    E: PgExecutor<'e>,
+{
+    let deleted_by = item.deleted_by().clone().map(Json);
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO finance_manager.invoice (
+            id,
+            client_id,
+            name,
+            reference_date,
+            related_debt_ids,
+            financial_instrument_id,
+            closing_date,
+            due_date,
+            total_amount,
+            closed_at,
+            created_at,
+            updated_at,
+            deleted_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING *
+        "#,
+    )
+    .bind(*item.id())
+    .bind(*item.client_id())
+    .bind(item.name())
+    .bind(*item.reference_date())
+    .bind(Vec::from_iter(item.related_debt_ids().iter().copied()))
+    .bind(*item.financial_instrument_id())
+    .bind(*item.closing_date())
+    .bind(*item.due_date())
+    .bind(*item.total_amount())
+    .bind(*item.closed_at())
+    .bind(*item.created_at())
+    .bind(*item.updated_at())
+    .bind(deleted_by)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Invoice::from(&row))
+}
+
+/// Updates the invoice using any executor, so it can be part of a transaction.
+pub(crate) async fn update_invoice<'e, E>(executor: E, item: Invoice) -> HttpResult<Invoice>
+where
+    E: PgExecutor<'e>,
+{
+    let deleted_by = item.deleted_by().clone().map(Json);
+
+    let row = sqlx::query(
+        r#"
+        UPDATE finance_manager.invoice SET
+            client_id = $2,
+            name = $3,
+            reference_date = $4,
+            related_debt_ids = $5,
+            due_date = $6,
+            total_amount = $7,
+            closed_at = $8,
+            updated_at = $9,
+            deleted_by = $10
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(*item.id())
+    .bind(*item.client_id())
+    .bind(item.name())
+    .bind(*item.reference_date())
+    .bind(Vec::from_iter(item.related_debt_ids().iter().copied()))
+    .bind(*item.due_date())
+    .bind(*item.total_amount())
+    .bind(*item.closed_at())
+    .bind(*item.updated_at())
+    .bind(deleted_by)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Invoice::from(&row))
+}
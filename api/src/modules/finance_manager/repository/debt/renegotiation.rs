@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::debt::{
+        batch::DebtBatch, installment::Installment, renegotiation::DebtRenegotiation, Debt,
+    },
+    repository::debt::{
+        installment::{insert_installment, update_installment},
+        invoice::{insert_invoice, update_invoice},
+        update_debt,
+    },
+};
+
+#[async_trait]
+pub trait RenegotiationRepository {
+    /// Saves the renegotiated debt, its superseded installments, the new plan,
+    /// the invoices of `invoices` it is now charged on and the renegotiation
+    /// history in a single transaction.
+    async fn save(
+        &self,
+        debt: Debt,
+        superseded: Vec<Installment>,
+        new_installments: Vec<Installment>,
+        invoices: DebtBatch,
+        renegotiation: DebtRenegotiation,
+    ) -> HttpResult<DebtRenegotiation>;
+
+    async fn list_by_debt_id(&self, debt_id: Uuid) -> HttpResult<Vec<DebtRenegotiation>>;
+}
+
+pub type DynRenegotiationRepository = dyn RenegotiationRepository + Send + Sync;
+
+pub struct RenegotiationRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl RenegotiationRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl RenegotiationRepository for RenegotiationRepositoryImpl {
+    async fn save(
+        &self,
+        debt: Debt,
+        superseded: Vec<Installment>,
+        new_installments: Vec<Installment>,
+        invoices: DebtBatch,
+        renegotiation: DebtRenegotiation,
+    ) -> HttpResult<DebtRenegotiation> {
+        let mut tx = self.pool.begin().await?;
+
+        update_debt(&mut *tx, debt).await?;
+
+        for installment in superseded {
+            update_installment(&mut *tx, installment).await?;
+        }
+
+        for installment in new_installments {
+            insert_installment(&mut *tx, installment).await?;
+        }
+
+        for invoice in invoices.new_invoices {
+            insert_invoice(&mut *tx, invoice).await?;
+        }
+
+        for invoice in invoices.updated_invoices {
+            update_invoice(&mut *tx, invoice).await?;
+        }
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.debt_renegotiation (
+                id,
+                debt_id,
+                client_id,
+                previous_remaining_amount,
+                interest_rate,
+                interest_amount,
+                renegotiated_amount,
+                installment_count,
+                due_day,
+                first_due_date,
+                first_installment_id,
+                superseded_installment_ids,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING *
+            "#,
+        )
+        .bind(renegotiation.id())
+        .bind(renegotiation.debt_id())
+        .bind(renegotiation.client_id())
+        .bind(renegotiation.previous_remaining_amount())
+        .bind(renegotiation.interest_rate())
+        .bind(renegotiation.interest_amount())
+        .bind(renegotiation.renegotiated_amount())
+        .bind(renegotiation.installment_count())
+        .bind(renegotiation.due_day())
+        .bind(renegotiation.first_due_date())
+        .bind(renegotiation.first_installment_id())
+        .bind(renegotiation.superseded_installment_ids())
+        .bind(renegotiation.created_at())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(DebtRenegotiation::from(&row))
+    }
+
+    async fn list_by_debt_id(&self, debt_id: Uuid) -> HttpResult<Vec<DebtRenegotiation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.debt_renegotiation
+            WHERE debt_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(debt_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(DebtRenegotiation::from).collect())
+    }
+}
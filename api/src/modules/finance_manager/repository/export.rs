@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use http_error::HttpResult;
+use serde_json::Value;
+use sqlx::{Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::export::ExportEntity;
+
+/// Receives the exported rows while they are read.
+#[async_trait]
+pub trait ExportRowSink {
+    async fn write_row(&mut self, row: Value) -> HttpResult<()>;
+}
+
+#[async_trait]
+pub trait ExportRepository {
+    /// Streams the rows of the entity into `sink` as JSON objects, with the
+    /// stored column names. `from` and `to` filter on the entity's main date,
+    /// when it has one.
+    async fn export_rows(
+        &self,
+        entity: ExportEntity,
+        client_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        include_deleted: bool,
+        sink: &mut (dyn ExportRowSink + Send),
+    ) -> HttpResult<()>;
+}
+
+pub type DynExportRepository = dyn ExportRepository + Send + Sync;
+
+pub struct ExportRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl ExportRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+/// Rows read per query. The connection goes back to the pool between pages,
+/// so a slow download never holds it for long.
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// Where the rows of an entity come from. The exported table is always
+/// aliased `t`.
+struct ExportSource {
+    from: &'static str,
+    client_column: &'static str,
+    date_column: Option<&'static str>,
+    /// Unique key of the rows, so pages don't overlap.
+    key_columns: &'static str,
+    deleted_columns: &'static [&'static str],
+}
+
+fn source(entity: ExportEntity) -> ExportSource {
+    match entity {
+        ExportEntity::FinancialInstruments => ExportSource {
+            from: "finance_manager.financial_instrument t",
+            client_column: "t.client_id",
+            date_column: None,
+            key_columns: "t.id",
+            deleted_columns: &[],
+        },
+        ExportEntity::Debts => ExportSource {
+            from: "finance_manager.debt t",
+            client_column: "t.client_id",
+            date_column: Some("t.due_date"),
+            key_columns: "t.id",
+            deleted_columns: &["t.deleted_by"],
+        },
+        ExportEntity::Installments => ExportSource {
+            from: "finance_manager.debt_installment t \
+                   JOIN finance_manager.debt d ON d.id = t.debt_id",
+            client_column: "d.client_id",
+            date_column: Some("t.due_date"),
+            key_columns: "t.debt_id, t.installment_id",
+            deleted_columns: &["t.deleted_by", "d.deleted_by"],
+        },
+        ExportEntity::Payments => ExportSource {
+            from: "finance_manager.payment t",
+            client_column: "t.client_id",
+            date_column: Some("t.payment_date"),
+            key_columns: "t.id",
+            deleted_columns: &["t.deleted_by"],
+        },
+        ExportEntity::Incomes => ExportSource {
+            from: "finance_manager.income t",
+            client_column: "t.client_id",
+            date_column: Some("t.reference"),
+            key_columns: "t.id",
+            deleted_columns: &[],
+        },
+        ExportEntity::Invoices => ExportSource {
+            from: "finance_manager.invoice t",
+            client_column: "t.client_id",
+            date_column: Some("t.reference_date"),
+            key_columns: "t.id",
+            deleted_columns: &["t.deleted_by"],
+        },
+        ExportEntity::Recurrences => ExportSource {
+            from: "finance_manager.recurrence t",
+            client_column: "t.client_id",
+            date_column: None,
+            key_columns: "t.id",
+            deleted_columns: &[],
+        },
+        ExportEntity::IncomeRecurrences => ExportSource {
+            from: "finance_manager.income_recurrence t",
+            client_column: "t.client_id",
+            date_column: None,
+            key_columns: "t.id",
+            deleted_columns: &[],
+        },
+    }
+}
+
+#[async_trait]
+impl ExportRepository for ExportRepositoryImpl {
+    async fn export_rows(
+        &self,
+        entity: ExportEntity,
+        client_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        include_deleted: bool,
+        sink: &mut (dyn ExportRowSink + Send),
+    ) -> HttpResult<()> {
+        let source = source(entity);
+
+        let mut offset = 0;
+
+        loop {
+            let mut builder = QueryBuilder::new("SELECT to_jsonb(t) FROM ");
+            builder.push(source.from);
+            builder.push(" WHERE ");
+            builder.push(source.client_column);
+            builder.push(" = ");
+            builder.push_bind(client_id);
+
+            if let Some(date_column) = source.date_column {
+                if let Some(from) = from {
+                    builder.push(format!(" AND {date_column} >= "));
+                    builder.push_bind(from);
+                }
+                if let Some(to) = to {
+                    builder.push(format!(" AND {date_column} <= "));
+                    builder.push_bind(to);
+                }
+            }
+
+            if !include_deleted {
+                for column in source.deleted_columns {
+                    builder.push(format!(" AND {column} IS NULL"));
+                }
+            }
+
+            builder.push(" ORDER BY ");
+            builder.push(source.date_column.unwrap_or("t.created_at"));
+            builder.push(", ");
+            builder.push(source.key_columns);
+            builder.push(" LIMIT ");
+            builder.push_bind(EXPORT_PAGE_SIZE);
+            builder.push(" OFFSET ");
+            builder.push_bind(offset);
+
+            // Read the whole page first, releasing the connection before the
+            // rows are handed to the sink
+            let rows = builder
+                .build_query_scalar::<Value>()
+                .fetch_all(&self.pool)
+                .await?;
+            let page_len = rows.len() as i64;
+
+            for row in rows {
+                sink.write_row(row).await?;
+            }
+
+            if page_len < EXPORT_PAGE_SIZE {
+                return Ok(());
+            }
+            offset += page_len;
+        }
+    }
+}
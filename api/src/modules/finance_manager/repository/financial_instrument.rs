@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use http_error::HttpResult;
-use sqlx::{Pool, Postgres, QueryBuilder, Row};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres, QueryBuilder, Row, Transaction};
 use uuid::Uuid;
 
 use crate::modules::finance_manager::{
@@ -9,6 +12,10 @@ use crate::modules::finance_manager::{
     repository::financial_instrument::entity::FinancialInstrumentEntity,
 };
 
+pub mod adjustment;
+pub mod goal;
+pub mod investment;
+
 #[async_trait]
 pub trait FinancialInstrumentRepository {
     async fn get_by_id(&self, id: Uuid) -> HttpResult<Option<FinancialInstrument>>;
@@ -26,10 +33,76 @@ pub trait FinancialInstrumentRepository {
     async fn insert(&self, instrument: FinancialInstrument) -> HttpResult<FinancialInstrument>;
 
     async fn update(&self, instrument: FinancialInstrument) -> HttpResult<()>;
+
+    /// Opens a transaction holding the instrument's row lock, so debits from
+    /// it are checked and saved one at a time.
+    async fn lock(&self, id: Uuid) -> HttpResult<InstrumentLock>;
+
+    /// Unpaid amount of the debts charged to each card of the client through
+    /// its invoices, in a single query.
+    async fn credit_used_amounts(&self, client_id: Uuid) -> HttpResult<HashMap<Uuid, Decimal>>;
 }
 
 pub type DynFinancialInstrumentRepository = dyn FinancialInstrumentRepository + Send + Sync;
 
+/// Transaction locking a financial instrument. The debit must be saved with
+/// it; dropping it rolls back and releases the lock.
+pub struct InstrumentLock {
+    pub(crate) tx: Transaction<'static, Postgres>,
+    instrument_id: Uuid,
+}
+
+impl InstrumentLock {
+    /// Sum of the ledger entries of the locked instrument, without its opening
+    /// balance. Runs on the lock's connection, in a single query.
+    pub async fn ledger_total(&mut self) -> HttpResult<Decimal> {
+        // Refunded payments are soft deleted, and their refund cancels them out
+        let total = sqlx::query_scalar(
+            r#"
+            SELECT
+                (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.income
+                 WHERE financial_instrument_id = $1)
+              - (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.payment
+                 WHERE account_id = $1 AND deleted_by IS NULL)
+              + (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.account_adjustment
+                 WHERE financial_instrument_id = $1)
+              + (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.transfer
+                 WHERE destination_instrument_id = $1 AND deleted_by IS NULL)
+              - (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.transfer
+                 WHERE source_instrument_id = $1 AND deleted_by IS NULL)
+              + (SELECT COALESCE(SUM(amount), 0) FROM finance_manager.investment_yield
+                 WHERE financial_instrument_id = $1)
+            "#,
+        )
+        .bind(self.instrument_id)
+        .fetch_one(&mut *self.tx)
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Unpaid amount of the debts charged to the locked card through its
+    /// invoices. Runs on the lock's connection.
+    pub async fn credit_used_amount(&mut self) -> HttpResult<Decimal> {
+        let used_amount = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(GREATEST(d.remaining_amount, 0)), 0)
+            FROM (
+                SELECT DISTINCT UNNEST(related_debt_ids) AS debt_id
+                FROM finance_manager.invoice
+                WHERE financial_instrument_id = $1
+            ) charged
+            JOIN finance_manager.debt d ON d.id = charged.debt_id AND d.deleted_by IS NULL
+            "#,
+        )
+        .bind(self.instrument_id)
+        .fetch_one(&mut *self.tx)
+        .await?;
+
+        Ok(used_amount)
+    }
+}
+
 pub struct FinancialInstrumentRepositoryImpl {
     pool: Pool<Postgres>,
 }
@@ -67,6 +140,45 @@ impl FinancialInstrumentRepository for FinancialInstrumentRepositoryImpl {
         Ok(())
     }
 
+    async fn lock(&self, id: Uuid) -> HttpResult<InstrumentLock> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT id FROM finance_manager.financial_instrument WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        Ok(InstrumentLock {
+            tx,
+            instrument_id: id,
+        })
+    }
+
+    async fn credit_used_amounts(&self, client_id: Uuid) -> HttpResult<HashMap<Uuid, Decimal>> {
+        // A debt paid in installments shows up on several invoices of a card
+        let rows = sqlx::query(
+            r#"
+            SELECT charged.financial_instrument_id,
+                   COALESCE(SUM(GREATEST(d.remaining_amount, 0)), 0) AS used_amount
+            FROM (
+                SELECT DISTINCT financial_instrument_id, UNNEST(related_debt_ids) AS debt_id
+                FROM finance_manager.invoice
+                WHERE client_id = $1 AND financial_instrument_id IS NOT NULL
+            ) charged
+            JOIN finance_manager.debt d ON d.id = charged.debt_id AND d.deleted_by IS NULL
+            GROUP BY charged.financial_instrument_id
+            "#,
+        )
+        .bind(client_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("financial_instrument_id"), row.get("used_amount")))
+            .collect())
+    }
+
     async fn get_by_identification(
         &self,
         identification: &str,
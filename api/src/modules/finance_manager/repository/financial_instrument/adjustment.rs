@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::financial_instrument::adjustment::AccountAdjustment;
+
+#[async_trait]
+pub trait AdjustmentRepository {
+    async fn insert(&self, adjustment: AccountAdjustment) -> HttpResult<AccountAdjustment>;
+
+    async fn list_by_instrument(
+        &self,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<AccountAdjustment>>;
+}
+
+pub type DynAdjustmentRepository = dyn AdjustmentRepository + Send + Sync;
+
+pub struct AdjustmentRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl AdjustmentRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl AdjustmentRepository for AdjustmentRepositoryImpl {
+    async fn insert(&self, adjustment: AccountAdjustment) -> HttpResult<AccountAdjustment> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.account_adjustment (
+                id,
+                client_id,
+                financial_instrument_id,
+                description,
+                amount,
+                adjustment_date,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(adjustment.id())
+        .bind(adjustment.client_id())
+        .bind(adjustment.financial_instrument_id())
+        .bind(adjustment.description())
+        .bind(adjustment.amount())
+        .bind(adjustment.adjustment_date())
+        .bind(adjustment.created_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(AccountAdjustment::from(&row))
+    }
+
+    async fn list_by_instrument(
+        &self,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<AccountAdjustment>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.account_adjustment
+            WHERE financial_instrument_id = $1
+            ORDER BY adjustment_date
+            "#,
+        )
+        .bind(financial_instrument_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(AccountAdjustment::from).collect())
+    }
+}
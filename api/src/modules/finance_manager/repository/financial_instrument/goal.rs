@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use http_error::{HttpError, HttpResult};
+use sqlx::{types::Json, Pool, Postgres};
+use util::DeletedBy;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::financial_instrument::goal::SavingsGoal;
+
+#[async_trait]
+pub trait SavingsGoalRepository {
+    async fn insert(&self, goal: SavingsGoal) -> HttpResult<SavingsGoal>;
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<SavingsGoal>>;
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<SavingsGoal>>;
+
+    async fn update(&self, goal: SavingsGoal) -> HttpResult<SavingsGoal>;
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()>;
+}
+
+pub type DynSavingsGoalRepository = dyn SavingsGoalRepository + Send + Sync;
+
+pub struct SavingsGoalRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl SavingsGoalRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl SavingsGoalRepository for SavingsGoalRepositoryImpl {
+    async fn insert(&self, goal: SavingsGoal) -> HttpResult<SavingsGoal> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.savings_goal (
+                id,
+                client_id,
+                financial_instrument_id,
+                name,
+                target_amount,
+                target_date,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(goal.id())
+        .bind(goal.client_id())
+        .bind(goal.financial_instrument_id())
+        .bind(goal.name())
+        .bind(goal.target_amount())
+        .bind(goal.target_date())
+        .bind(goal.created_at())
+        .bind(goal.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(SavingsGoal::from(&row))
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<SavingsGoal>> {
+        let row = sqlx::query(
+            r#"SELECT * FROM finance_manager.savings_goal WHERE id = $1 AND deleted_by IS NULL"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(SavingsGoal::from))
+    }
+
+    async fn list(&self, client_id: Uuid) -> HttpResult<Vec<SavingsGoal>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.savings_goal
+            WHERE client_id = $1 AND deleted_by IS NULL
+            ORDER BY target_date
+            "#,
+        )
+        .bind(client_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(SavingsGoal::from).collect())
+    }
+
+    async fn update(&self, goal: SavingsGoal) -> HttpResult<SavingsGoal> {
+        let row = sqlx::query(
+            r#"
+            UPDATE finance_manager.savings_goal
+            SET name = $2, target_amount = $3, target_date = $4, updated_at = $5
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(goal.id())
+        .bind(goal.name())
+        .bind(goal.target_amount())
+        .bind(goal.target_date())
+        .bind(goal.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(SavingsGoal::from(&row))
+    }
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE finance_manager.savings_goal
+            SET deleted_by = $2, updated_at = $3
+            WHERE id = $1 AND deleted_by IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(Json(deleted_by))
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Box::new(HttpError::not_found("savings_goal", id)));
+        }
+
+        Ok(())
+    }
+}
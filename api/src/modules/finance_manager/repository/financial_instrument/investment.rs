@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::financial_instrument::investment::YieldEntry;
+
+#[async_trait]
+pub trait YieldRepository {
+    /// Days that were already accrued are skipped, so a job re-run never
+    /// credits a yield twice.
+    async fn insert_many(&self, yields: Vec<YieldEntry>) -> HttpResult<()>;
+
+    async fn list_by_instrument(
+        &self,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<YieldEntry>>;
+}
+
+pub type DynYieldRepository = dyn YieldRepository + Send + Sync;
+
+pub struct YieldRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl YieldRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl YieldRepository for YieldRepositoryImpl {
+    async fn insert_many(&self, yields: Vec<YieldEntry>) -> HttpResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for yield_entry in yields {
+            sqlx::query(
+                r#"
+                INSERT INTO finance_manager.investment_yield (
+                    id,
+                    client_id,
+                    financial_instrument_id,
+                    accrual_date,
+                    balance,
+                    annual_rate,
+                    accrued_amount,
+                    amount,
+                    created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (financial_instrument_id, accrual_date) DO NOTHING
+                "#,
+            )
+            .bind(yield_entry.id())
+            .bind(yield_entry.client_id())
+            .bind(yield_entry.financial_instrument_id())
+            .bind(yield_entry.accrual_date())
+            .bind(yield_entry.balance())
+            .bind(yield_entry.annual_rate())
+            .bind(yield_entry.accrued_amount())
+            .bind(yield_entry.amount())
+            .bind(yield_entry.created_at())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_by_instrument(
+        &self,
+        financial_instrument_id: Uuid,
+    ) -> HttpResult<Vec<YieldEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.investment_yield
+            WHERE financial_instrument_id = $1
+            ORDER BY accrual_date
+            "#,
+        )
+        .bind(financial_instrument_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(YieldEntry::from).collect())
+    }
+}
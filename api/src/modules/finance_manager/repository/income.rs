@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use http_error::HttpResult;
-use sqlx::{Pool, Postgres, QueryBuilder, Row};
+use sqlx::{PgExecutor, Pool, Postgres, QueryBuilder, Row};
 
 use crate::modules::finance_manager::{
     domain::income::Income, repository::income::use_cases::IncomeListFilters,
@@ -70,48 +70,55 @@ impl IncomeRepository for IncomeRepositoryImpl {
     }
 
     async fn insert(&self, income: Income) -> HttpResult<Income> {
-        let income_entity = entity::IncomeEntity::from(income);
+        insert_income(&self.pool, income).await
+    }
+}
 
-        let row = sqlx::query(
-            r#"
-            INSERT INTO finance_manager.income (
-                id,
-                client_id,
-                financial_instrument_id,
-                description,
-                amount,
-                reference,
-                created_at,
-                updated_at
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING *
-            "#,
+pub(crate) async fn insert_income<'e, E>(executor: E, income: Income) -> HttpResult<Income>
+where
+    E: PgExecutor<'e>,
+{
+    let income_entity = entity::IncomeEntity::from(income);
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO finance_manager.income (
+            id,
+            client_id,
+            financial_instrument_id,
+            description,
+            amount,
+            reference,
+            created_at,
+            updated_at
         )
-        .bind(income_entity.id)
-        .bind(income_entity.client_id)
-        .bind(income_entity.financial_instrument_id)
-        .bind(income_entity.description)
-        .bind(income_entity.amount)
-        .bind(income_entity.reference)
-        .bind(income_entity.created_at)
-        .bind(income_entity.updated_at)
-        .fetch_one(&self.pool)
-        .await?;
-
-        let income_entity = entity::IncomeEntity {
-            id: row.get("id"),
-            client_id: row.get("client_id"),
-            financial_instrument_id: row.get("financial_instrument_id"),
-            description: row.get("description"),
-            amount: row.get("amount"),
-            reference: row.get("reference"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        };
-
-        Ok(Income::from(income_entity))
-    }
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(income_entity.id)
+    .bind(income_entity.client_id)
+    .bind(income_entity.financial_instrument_id)
+    .bind(income_entity.description)
+    .bind(income_entity.amount)
+    .bind(income_entity.reference)
+    .bind(income_entity.created_at)
+    .bind(income_entity.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    let income_entity = entity::IncomeEntity {
+        id: row.get("id"),
+        client_id: row.get("client_id"),
+        financial_instrument_id: row.get("financial_instrument_id"),
+        description: row.get("description"),
+        amount: row.get("amount"),
+        reference: row.get("reference"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    };
+
+    Ok(Income::from(income_entity))
 }
 
 pub mod use_cases {
@@ -1,11 +1,14 @@
 use async_trait::async_trait;
 use http_error::HttpResult;
-use sqlx::{Pool, Postgres, QueryBuilder};
+use sqlx::{types::Json, PgExecutor, Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::modules::finance_manager::{
     domain::payment::Payment,
-    repository::payment::{dto::PaymentDto, use_cases::PaymentFilters},
+    repository::{
+        financial_instrument::InstrumentLock,
+        payment::{dto::PaymentDto, use_cases::PaymentFilters},
+    },
 };
 
 pub type DynPaymentRepository = dyn PaymentRepository + Send + Sync;
@@ -13,9 +16,12 @@ pub type DynPaymentRepository = dyn PaymentRepository + Send + Sync;
 #[async_trait]
 pub trait PaymentRepository {
     async fn insert(&self, payment: Payment) -> HttpResult<Payment>;
+    /// Inserts the payment with the lock of the account it debits.
+    async fn insert_debit(&self, payment: Payment, lock: InstrumentLock) -> HttpResult<Payment>;
     async fn list(&self, filters: &PaymentFilters) -> HttpResult<Vec<Payment>>;
     async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Payment>>;
     async fn delete(&self, id: &Uuid) -> HttpResult<()>;
+    async fn refund(&self, payment: Payment) -> HttpResult<()>;
 }
 
 #[derive(Clone)]
@@ -32,45 +38,29 @@ impl PaymentRepositoryImpl {
 #[async_trait]
 impl PaymentRepository for PaymentRepositoryImpl {
     async fn insert(&self, payment: Payment) -> HttpResult<Payment> {
-        let payload = PaymentDto::from(payment);
+        insert_payment(&self.pool, payment).await
+    }
 
-        let row = sqlx::query(
-            r#"
-                INSERT INTO finance_manager.payment (
-                    id,
-                    client_id,
-                    debt_id,
-                    account_id,
-                    amount,
-                    payment_date,
-                    created_at,
-                    updated_at
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                RETURNING *
-            "#,
-        )
-        .bind(payload.id)
-        .bind(payload.client_id)
-        .bind(payload.debt_id)
-        .bind(payload.account_id)
-        .bind(payload.amount)
-        .bind(payload.payment_date)
-        .bind(payload.created_at)
-        .bind(payload.updated_at)
-        .fetch_one(&self.pool)
-        .await?;
+    async fn insert_debit(&self, payment: Payment, lock: InstrumentLock) -> HttpResult<Payment> {
+        let mut tx = lock.tx;
+        let payment = insert_payment(&mut *tx, payment).await?;
+        tx.commit().await?;
 
-        Ok(Payment::from(PaymentDto::from_row(&row)))
+        Ok(payment)
     }
 
     async fn list(&self, filters: &PaymentFilters) -> HttpResult<Vec<Payment>> {
         let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
             r#"
-                SELECT * FROM finance_manager.payment WHERE deleted_by IS NULL
+                SELECT * FROM finance_manager.payment WHERE (deleted_by IS NULL
             "#,
         );
 
+        if filters.include_refunded {
+            builder.push(" OR refunded_at IS NOT NULL");
+        }
+        builder.push(")");
+
         if let Some(client_id) = &filters.client_id {
             builder.push(" AND client_id = ");
             builder.push_bind(client_id);
@@ -130,6 +120,68 @@ impl PaymentRepository for PaymentRepositoryImpl {
 
         Ok(())
     }
+
+    async fn refund(&self, payment: Payment) -> HttpResult<()> {
+        let payload = PaymentDto::from(payment);
+
+        sqlx::query(
+            r#"
+                UPDATE finance_manager.payment
+                SET refunded_at = $2, deleted_by = $3, updated_at = $4
+                WHERE id = $1
+            "#,
+        )
+        .bind(payload.id)
+        .bind(payload.refunded_at)
+        .bind(payload.deleted_by.map(Json))
+        .bind(payload.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Inserts the payment using any executor, so it can be part of a
+/// transaction opened by another repository.
+pub(crate) async fn insert_payment<'e, E>(executor: E, payment: Payment) -> HttpResult<Payment>
+where
+    E: PgExecutor<'e>,
+{
+    let payload = PaymentDto::from(payment);
+
+    let row = sqlx::query(
+        r#"
+            INSERT INTO finance_manager.payment (
+                id,
+                client_id,
+                debt_id,
+                account_id,
+                amount,
+                discount_amount,
+                installment_ids,
+                payment_date,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+        "#,
+    )
+    .bind(payload.id)
+    .bind(payload.client_id)
+    .bind(payload.debt_id)
+    .bind(payload.account_id)
+    .bind(payload.amount)
+    .bind(payload.discount_amount)
+    .bind(&payload.installment_ids)
+    .bind(payload.payment_date)
+    .bind(payload.created_at)
+    .bind(payload.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(Payment::from(PaymentDto::from_row(&row)))
 }
 
 pub mod dto {
@@ -152,7 +204,10 @@ pub mod dto {
         pub debt_id: Uuid,
         pub account_id: Uuid,
         pub amount: Decimal,
+        pub discount_amount: Decimal,
+        pub installment_ids: Option<Vec<i32>>,
         pub payment_date: NaiveDate,
+        pub refunded_at: Option<NaiveDate>,
         pub created_at: NaiveDateTime,
         pub updated_at: Option<NaiveDateTime>,
         pub deleted_by: Option<DeletedBy>,
@@ -166,7 +221,10 @@ pub mod dto {
                 debt_id: row.get("debt_id"),
                 account_id: row.get("account_id"),
                 amount: row.get("amount"),
+                discount_amount: row.get("discount_amount"),
+                installment_ids: row.get("installment_ids"),
                 payment_date: row.get("payment_date"),
+                refunded_at: row.get("refunded_at"),
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
                 deleted_by: row
@@ -184,7 +242,10 @@ pub mod dto {
                 debt_id: *payment.debt_id(),
                 account_id: *payment.account_id(),
                 amount: *payment.amount(),
+                discount_amount: *payment.discount_amount(),
+                installment_ids: payment.installment_ids().clone(),
                 payment_date: *payment.payment_date(),
+                refunded_at: *payment.refunded_at(),
                 created_at: payment.created_at().naive_utc(),
                 updated_at: payment.updated_at().map(|dt| dt.naive_utc()),
                 deleted_by: payment.deleted_by().clone(),
@@ -200,7 +261,10 @@ pub mod dto {
                 dto.debt_id,
                 dto.account_id,
                 dto.amount,
+                dto.discount_amount,
+                dto.installment_ids,
                 dto.payment_date,
+                dto.refunded_at,
                 dto.created_at.and_utc(),
                 dto.updated_at.map(|dt| dt.and_utc()),
                 dto.deleted_by,
@@ -222,6 +286,9 @@ pub mod use_cases {
         pub account_ids: Option<Vec<Uuid>>,
         pub start_date: Option<NaiveDate>,
         pub end_date: Option<NaiveDate>,
+        /// Also lists refunded payments, as the account statement does.
+        #[serde(default)]
+        pub include_refunded: bool,
     }
 
     impl PaymentFilters {
@@ -253,5 +320,10 @@ pub mod use_cases {
             self.end_date = Some(end_date);
             self
         }
+
+        pub fn with_include_refunded(mut self, include_refunded: bool) -> Self {
+            self.include_refunded = include_refunded;
+            self
+        }
     }
 }
@@ -1,9 +1,19 @@
 use async_trait::async_trait;
-use http_error::HttpResult;
-use sqlx::{Pool, Postgres, QueryBuilder, Row};
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use sqlx::{types::Json, PgExecutor, Pool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
-use crate::modules::finance_manager::domain::debt::recurrence::{Recurrence, RecurrenceFilters};
+use crate::modules::finance_manager::{
+    domain::debt::{
+        recurrence::{Recurrence, RecurrenceExecutionLog, RecurrenceFilters},
+        Debt,
+    },
+    repository::debt::insert_debt,
+};
+
+pub mod generation_run;
+pub mod income;
 
 use entity::RecurrenceEntity;
 
@@ -18,6 +28,15 @@ pub trait RecurrenceRepository {
     async fn get_by_id(&self, id: Uuid) -> HttpResult<Option<Recurrence>>;
 
     async fn list(&self, filters: &RecurrenceFilters) -> HttpResult<Vec<Recurrence>>;
+
+    /// Inserts the debt generated for `reference_month` and saves the
+    /// recurrence with its new execution log in a single transaction.
+    async fn save_generated_debt(
+        &self,
+        recurrence: Recurrence,
+        reference_month: NaiveDate,
+        debt: Debt,
+    ) -> HttpResult<Debt>;
 }
 
 #[derive(Clone)]
@@ -142,51 +161,95 @@ impl RecurrenceRepository for RecurrenceRepositoryImpl {
     }
 
     async fn update(&self, recurrence: Recurrence) -> HttpResult<Recurrence> {
-        let payload = RecurrenceEntity::from(recurrence);
+        update_recurrence(&self.pool, recurrence).await
+    }
 
-        let row = sqlx::query(
-            r#"
-            UPDATE finance_manager.recurrence 
-            SET description = $2, amount = $3, category = $4, active = $5, 
-                start_date = $6, end_date = $7, day_of_month = $8, 
-                execution_logs = $9, updated_at = $10
-            WHERE id = $1
-            RETURNING id, client_id, description, amount, category, active, start_date, end_date, 
-                      day_of_month, execution_logs, created_at, updated_at
-            "#,
+    async fn save_generated_debt(
+        &self,
+        recurrence: Recurrence,
+        reference_month: NaiveDate,
+        debt: Debt,
+    ) -> HttpResult<Debt> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locks the recurrence so concurrent runs can't generate the same month twice.
+        let logs: Json<Vec<RecurrenceExecutionLog>> = sqlx::query_scalar(
+            "SELECT execution_logs FROM finance_manager.recurrence WHERE id = $1 FOR UPDATE",
         )
-        .bind(payload.id)
-        .bind(payload.description)
-        .bind(payload.amount)
-        .bind(String::from(payload.category.clone()))
-        .bind(payload.active)
-        .bind(payload.start_date)
-        .bind(payload.end_date)
-        .bind(payload.day_of_month)
-        .bind(payload.execution_logs)
-        .bind(payload.updated_at)
-        .fetch_one(&self.pool)
+        .bind(recurrence.id())
+        .fetch_one(&mut *tx)
         .await?;
 
-        let result = RecurrenceEntity {
-            id: row.get("id"),
-            client_id: row.get("client_id"),
-            description: row.get("description"),
-            amount: row.get("amount"),
-            category: row.get::<String, _>("category").into(),
-            active: row.get("active"),
-            start_date: row.get("start_date"),
-            end_date: row.get("end_date"),
-            day_of_month: row.get("day_of_month"),
-            execution_logs: row.get("execution_logs"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        };
+        if logs
+            .0
+            .iter()
+            .any(|log| log.reference_month() == reference_month)
+        {
+            tx.rollback().await?;
+            return Err(Box::new(HttpError::conflict(
+                "Recurrence already generated for this month",
+            )));
+        }
 
-        Ok(Recurrence::from(result))
+        let debt = insert_debt(&mut *tx, debt).await?;
+        update_recurrence(&mut *tx, recurrence).await?;
+
+        tx.commit().await?;
+        Ok(debt)
     }
 }
 
+pub(crate) async fn update_recurrence<'e, E>(
+    executor: E,
+    recurrence: Recurrence,
+) -> HttpResult<Recurrence>
+where
+    E: PgExecutor<'e>,
+{
+    let payload = RecurrenceEntity::from(recurrence);
+
+    let row = sqlx::query(
+        r#"
+        UPDATE finance_manager.recurrence 
+        SET description = $2, amount = $3, category = $4, active = $5, 
+            start_date = $6, end_date = $7, day_of_month = $8, 
+            execution_logs = $9, updated_at = $10
+        WHERE id = $1
+        RETURNING id, client_id, description, amount, category, active, start_date, end_date, 
+                  day_of_month, execution_logs, created_at, updated_at
+        "#,
+    )
+    .bind(payload.id)
+    .bind(payload.description)
+    .bind(payload.amount)
+    .bind(String::from(payload.category.clone()))
+    .bind(payload.active)
+    .bind(payload.start_date)
+    .bind(payload.end_date)
+    .bind(payload.day_of_month)
+    .bind(payload.execution_logs)
+    .bind(payload.updated_at)
+    .fetch_one(executor)
+    .await?;
+
+    let result = RecurrenceEntity {
+        id: row.get("id"),
+        client_id: row.get("client_id"),
+        description: row.get("description"),
+        amount: row.get("amount"),
+        category: row.get::<String, _>("category").into(),
+        active: row.get("active"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        day_of_month: row.get("day_of_month"),
+        execution_logs: row.get("execution_logs"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    };
+
+    Ok(Recurrence::from(result))
+}
+
 mod entity {
     use chrono::{NaiveDate, NaiveDateTime};
     use rust_decimal::Decimal;
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{types::Json, Pool, Postgres, QueryBuilder};
+
+use crate::modules::finance_manager::domain::debt::recurrence::generation::{
+    RecurrenceGenerationRun, RecurrenceGenerationRunFilters,
+};
+
+#[async_trait]
+pub trait RecurrenceGenerationRunRepository {
+    async fn insert(&self, run: RecurrenceGenerationRun) -> HttpResult<RecurrenceGenerationRun>;
+
+    async fn list(
+        &self,
+        filters: &RecurrenceGenerationRunFilters,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>>;
+}
+
+pub type DynRecurrenceGenerationRunRepository = dyn RecurrenceGenerationRunRepository + Send + Sync;
+
+pub struct RecurrenceGenerationRunRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl RecurrenceGenerationRunRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl RecurrenceGenerationRunRepository for RecurrenceGenerationRunRepositoryImpl {
+    async fn insert(&self, run: RecurrenceGenerationRun) -> HttpResult<RecurrenceGenerationRun> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.recurrence_generation_run (
+                id,
+                client_id,
+                trigger,
+                run_date,
+                created_count,
+                skipped_count,
+                failed_count,
+                items,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(run.id())
+        .bind(run.client_id())
+        .bind(run.trigger().as_str())
+        .bind(run.run_date())
+        .bind(run.created_count())
+        .bind(run.skipped_count())
+        .bind(run.failed_count())
+        .bind(Json(run.items()))
+        .bind(run.created_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(RecurrenceGenerationRun::from(&row))
+    }
+
+    async fn list(
+        &self,
+        filters: &RecurrenceGenerationRunFilters,
+    ) -> HttpResult<Vec<RecurrenceGenerationRun>> {
+        let mut builder =
+            QueryBuilder::new("SELECT * FROM finance_manager.recurrence_generation_run WHERE 1=1");
+
+        if let Some(client_id) = filters.client_id() {
+            builder.push(" AND client_id = ");
+            builder.push_bind(client_id);
+        }
+
+        if let Some(start_date) = filters.start_date() {
+            builder.push(" AND run_date >= ");
+            builder.push_bind(start_date);
+        }
+
+        if let Some(end_date) = filters.end_date() {
+            builder.push(" AND run_date <= ");
+            builder.push_bind(end_date);
+        }
+
+        builder.push(" ORDER BY created_at DESC");
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(RecurrenceGenerationRun::from).collect())
+    }
+}
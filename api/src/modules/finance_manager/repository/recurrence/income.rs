@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use http_error::{HttpError, HttpResult};
+use sqlx::{types::Json, PgExecutor, Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::{
+        debt::recurrence::RecurrenceFilters,
+        income::{
+            recurrence::{IncomeRecurrence, IncomeRecurrenceExecutionLog},
+            Income,
+        },
+    },
+    repository::income::insert_income,
+};
+
+pub type DynIncomeRecurrenceRepository = dyn IncomeRecurrenceRepository + Send + Sync;
+
+#[async_trait]
+pub trait IncomeRecurrenceRepository {
+    async fn insert(&self, recurrence: IncomeRecurrence) -> HttpResult<IncomeRecurrence>;
+
+    async fn update(&self, recurrence: IncomeRecurrence) -> HttpResult<IncomeRecurrence>;
+
+    async fn get_by_id(&self, id: Uuid) -> HttpResult<Option<IncomeRecurrence>>;
+
+    async fn list(&self, filters: &RecurrenceFilters) -> HttpResult<Vec<IncomeRecurrence>>;
+
+    /// Inserts the income generated for `reference_month` and saves the
+    /// recurrence with its new execution log in a single transaction.
+    async fn save_generated_income(
+        &self,
+        recurrence: IncomeRecurrence,
+        reference_month: NaiveDate,
+        income: Income,
+    ) -> HttpResult<Income>;
+}
+
+#[derive(Clone)]
+pub struct IncomeRecurrenceRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl IncomeRecurrenceRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl IncomeRecurrenceRepository for IncomeRecurrenceRepositoryImpl {
+    async fn insert(&self, recurrence: IncomeRecurrence) -> HttpResult<IncomeRecurrence> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.income_recurrence (
+                id,
+                client_id,
+                financial_instrument_id,
+                description,
+                amount,
+                active,
+                start_date,
+                end_date,
+                day_of_month,
+                execution_logs,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+            "#,
+        )
+        .bind(recurrence.id())
+        .bind(recurrence.client_id())
+        .bind(recurrence.financial_instrument_id())
+        .bind(recurrence.description())
+        .bind(recurrence.amount())
+        .bind(recurrence.active())
+        .bind(recurrence.start_date())
+        .bind(recurrence.end_date())
+        .bind(recurrence.day_of_month())
+        .bind(Json(recurrence.execution_logs()))
+        .bind(recurrence.created_at())
+        .bind(recurrence.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(IncomeRecurrence::from(&row))
+    }
+
+    async fn update(&self, recurrence: IncomeRecurrence) -> HttpResult<IncomeRecurrence> {
+        update_income_recurrence(&self.pool, recurrence).await
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> HttpResult<Option<IncomeRecurrence>> {
+        let row = sqlx::query("SELECT * FROM finance_manager.income_recurrence WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(IncomeRecurrence::from))
+    }
+
+    async fn list(&self, filters: &RecurrenceFilters) -> HttpResult<Vec<IncomeRecurrence>> {
+        let mut builder =
+            QueryBuilder::new("SELECT * FROM finance_manager.income_recurrence WHERE TRUE");
+
+        if let Some(client_id) = filters.client_id() {
+            builder.push(" AND client_id = ");
+            builder.push_bind(client_id);
+        }
+        if let Some(active) = filters.active() {
+            builder.push(" AND active = ");
+            builder.push_bind(active);
+        }
+        builder.push(" ORDER BY day_of_month");
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(IncomeRecurrence::from).collect())
+    }
+
+    async fn save_generated_income(
+        &self,
+        recurrence: IncomeRecurrence,
+        reference_month: NaiveDate,
+        income: Income,
+    ) -> HttpResult<Income> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locks the recurrence so concurrent runs can't generate the same month twice.
+        let logs: Json<Vec<IncomeRecurrenceExecutionLog>> = sqlx::query_scalar(
+            "SELECT execution_logs FROM finance_manager.income_recurrence WHERE id = $1 FOR UPDATE",
+        )
+        .bind(recurrence.id())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if logs
+            .0
+            .iter()
+            .any(|log| *log.reference_month() == reference_month)
+        {
+            tx.rollback().await?;
+            return Err(Box::new(HttpError::conflict(
+                "Income recurrence already generated for this month",
+            )));
+        }
+
+        let income = insert_income(&mut *tx, income).await?;
+        update_income_recurrence(&mut *tx, recurrence).await?;
+
+        tx.commit().await?;
+        Ok(income)
+    }
+}
+
+async fn update_income_recurrence<'e, E>(
+    executor: E,
+    recurrence: IncomeRecurrence,
+) -> HttpResult<IncomeRecurrence>
+where
+    E: PgExecutor<'e>,
+{
+    let row = sqlx::query(
+        r#"
+        UPDATE finance_manager.income_recurrence
+        SET financial_instrument_id = $2, description = $3, amount = $4, active = $5,
+            start_date = $6, end_date = $7, day_of_month = $8,
+            execution_logs = $9, updated_at = $10
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(recurrence.id())
+    .bind(recurrence.financial_instrument_id())
+    .bind(recurrence.description())
+    .bind(recurrence.amount())
+    .bind(recurrence.active())
+    .bind(recurrence.start_date())
+    .bind(recurrence.end_date())
+    .bind(recurrence.day_of_month())
+    .bind(Json(recurrence.execution_logs()))
+    .bind(recurrence.updated_at())
+    .fetch_one(executor)
+    .await?;
+
+    Ok(IncomeRecurrence::from(&row))
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::reminder::{DebtReminder, ReminderSetting};
+
+#[async_trait]
+pub trait ReminderRepository {
+    async fn get_setting(&self, client_id: Uuid) -> HttpResult<Option<ReminderSetting>>;
+
+    async fn list_settings(&self) -> HttpResult<Vec<ReminderSetting>>;
+
+    async fn upsert_setting(&self, setting: ReminderSetting) -> HttpResult<ReminderSetting>;
+
+    /// Reminders sent to the client for due dates within the range.
+    async fn list_sent(
+        &self,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> HttpResult<Vec<DebtReminder>>;
+
+    async fn insert_many(&self, reminders: Vec<DebtReminder>) -> HttpResult<()>;
+}
+
+pub type DynReminderRepository = dyn ReminderRepository + Send + Sync;
+
+pub struct ReminderRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl ReminderRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for ReminderRepositoryImpl {
+    async fn get_setting(&self, client_id: Uuid) -> HttpResult<Option<ReminderSetting>> {
+        let row =
+            sqlx::query(r#"SELECT * FROM finance_manager.reminder_setting WHERE client_id = $1"#)
+                .bind(client_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.as_ref().map(ReminderSetting::from))
+    }
+
+    async fn list_settings(&self) -> HttpResult<Vec<ReminderSetting>> {
+        let rows = sqlx::query(r#"SELECT * FROM finance_manager.reminder_setting"#)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(ReminderSetting::from).collect())
+    }
+
+    async fn upsert_setting(&self, setting: ReminderSetting) -> HttpResult<ReminderSetting> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.reminder_setting (
+                client_id,
+                horizon_days,
+                active,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (client_id) DO UPDATE SET
+                horizon_days = EXCLUDED.horizon_days,
+                active = EXCLUDED.active,
+                updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(*setting.client_id())
+        .bind(*setting.horizon_days())
+        .bind(*setting.active())
+        .bind(*setting.created_at())
+        .bind(*setting.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ReminderSetting::from(&row))
+    }
+
+    async fn list_sent(
+        &self,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> HttpResult<Vec<DebtReminder>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.debt_reminder
+            WHERE client_id = $1 AND due_date >= $2 AND due_date <= $3
+            "#,
+        )
+        .bind(client_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(DebtReminder::from).collect())
+    }
+
+    async fn insert_many(&self, reminders: Vec<DebtReminder>) -> HttpResult<()> {
+        if reminders.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO finance_manager.debt_reminder (debt_id, installment_id, due_date, client_id, sent_at) ",
+        );
+
+        builder.push_values(reminders.iter(), |mut b, reminder| {
+            b.push_bind(*reminder.debt_id())
+                .push_bind(*reminder.installment_id())
+                .push_bind(*reminder.due_date())
+                .push_bind(*reminder.client_id())
+                .push_bind(*reminder.sent_at());
+        });
+        builder.push(" ON CONFLICT DO NOTHING");
+
+        builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{PgExecutor, Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::statement::{StatementImport, StatementTransaction};
+
+#[async_trait]
+pub trait StatementRepository {
+    /// Inserts the import with all its transactions.
+    async fn insert(&self, import: StatementImport) -> HttpResult<StatementImport>;
+
+    /// Loads the import with its transactions, in line order.
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<StatementImport>>;
+
+    async fn update_transaction(
+        &self,
+        transaction: StatementTransaction,
+    ) -> HttpResult<StatementTransaction>;
+
+    /// Saves the status of the transaction only if the stored line is still
+    /// pending. Returns whether it was claimed.
+    async fn claim_transaction(&self, transaction: &StatementTransaction) -> HttpResult<bool>;
+
+    /// Which of `external_ids` were already imported for the instrument.
+    async fn list_imported_external_ids(
+        &self,
+        financial_instrument_id: &Uuid,
+        external_ids: &[String],
+    ) -> HttpResult<Vec<String>>;
+}
+
+pub type DynStatementRepository = dyn StatementRepository + Send + Sync;
+
+pub struct StatementRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl StatementRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl StatementRepository for StatementRepositoryImpl {
+    async fn insert(&self, import: StatementImport) -> HttpResult<StatementImport> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.statement_import (
+                id,
+                client_id,
+                financial_instrument_id,
+                format,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(import.id())
+        .bind(import.client_id())
+        .bind(import.financial_instrument_id())
+        .bind(import.format().as_str())
+        .bind(import.created_at())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut transactions = Vec::with_capacity(import.transactions().len());
+        for transaction in import.transactions() {
+            transactions.push(insert_transaction(&mut *tx, transaction).await?);
+        }
+
+        tx.commit().await?;
+
+        Ok(StatementImport::from(&row).with_transactions(transactions))
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<StatementImport>> {
+        let Some(row) = sqlx::query("SELECT * FROM finance_manager.statement_import WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let transactions = sqlx::query(
+            r#"
+            SELECT * FROM finance_manager.statement_transaction
+            WHERE import_id = $1
+            ORDER BY line_number
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(
+            StatementImport::from(&row).with_transactions(
+                transactions
+                    .iter()
+                    .map(StatementTransaction::from)
+                    .collect(),
+            ),
+        ))
+    }
+
+    async fn update_transaction(
+        &self,
+        transaction: StatementTransaction,
+    ) -> HttpResult<StatementTransaction> {
+        let row = sqlx::query(
+            r#"
+            UPDATE finance_manager.statement_transaction
+            SET status = $2, debt_id = $3, installment_id = $4, payment_id = $5,
+                income_id = $6, updated_at = $7
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(transaction.id())
+        .bind(transaction.status().as_str())
+        .bind(transaction.debt_id())
+        .bind(transaction.installment_id())
+        .bind(transaction.payment_id())
+        .bind(transaction.income_id())
+        .bind(transaction.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(StatementTransaction::from(&row))
+    }
+
+    async fn claim_transaction(&self, transaction: &StatementTransaction) -> HttpResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE finance_manager.statement_transaction
+            SET status = $2, updated_at = $3
+            WHERE id = $1 AND status = 'PENDING'
+            "#,
+        )
+        .bind(transaction.id())
+        .bind(transaction.status().as_str())
+        .bind(transaction.updated_at())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn list_imported_external_ids(
+        &self,
+        financial_instrument_id: &Uuid,
+        external_ids: &[String],
+    ) -> HttpResult<Vec<String>> {
+        if external_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT t.external_id
+            FROM finance_manager.statement_transaction t
+            JOIN finance_manager.statement_import i ON i.id = t.import_id
+            WHERE i.financial_instrument_id = $1 AND t.external_id = ANY($2)
+            "#,
+        )
+        .bind(financial_instrument_id)
+        .bind(external_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+}
+
+async fn insert_transaction<'e, E>(
+    executor: E,
+    transaction: &StatementTransaction,
+) -> HttpResult<StatementTransaction>
+where
+    E: PgExecutor<'e>,
+{
+    let row = sqlx::query(
+        r#"
+        INSERT INTO finance_manager.statement_transaction (
+            id,
+            import_id,
+            line_number,
+            transaction_date,
+            description,
+            amount,
+            external_id,
+            status,
+            debt_id,
+            installment_id,
+            payment_id,
+            income_id,
+            created_at,
+            updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING *
+        "#,
+    )
+    .bind(transaction.id())
+    .bind(transaction.import_id())
+    .bind(transaction.line_number())
+    .bind(transaction.date())
+    .bind(transaction.description())
+    .bind(transaction.amount())
+    .bind(transaction.external_id())
+    .bind(transaction.status().as_str())
+    .bind(transaction.debt_id())
+    .bind(transaction.installment_id())
+    .bind(transaction.payment_id())
+    .bind(transaction.income_id())
+    .bind(transaction.created_at())
+    .bind(transaction.updated_at())
+    .fetch_one(executor)
+    .await?;
+
+    Ok(StatementTransaction::from(&row))
+}
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use http_error::HttpResult;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::modules::finance_manager::domain::telegram::{TelegramChat, TelegramLinkCode};
+
+#[async_trait]
+pub trait TelegramChatRepository {
+    async fn get_by_chat_id(&self, chat_id: i64) -> HttpResult<Option<TelegramChat>>;
+
+    async fn list_by_client_id(&self, client_id: Uuid) -> HttpResult<Vec<TelegramChat>>;
+
+    async fn list_all(&self) -> HttpResult<Vec<TelegramChat>>;
+
+    /// Links the chat, replacing any previous link it had.
+    async fn upsert(&self, chat: TelegramChat) -> HttpResult<TelegramChat>;
+
+    async fn delete(&self, client_id: Uuid, chat_id: i64) -> HttpResult<()>;
+
+    async fn insert_link_code(&self, link_code: TelegramLinkCode) -> HttpResult<TelegramLinkCode>;
+
+    /// Removes and returns the link code, so it can only be used once.
+    async fn take_link_code(&self, code: &str) -> HttpResult<Option<TelegramLinkCode>>;
+}
+
+pub type DynTelegramChatRepository = dyn TelegramChatRepository + Send + Sync;
+
+pub struct TelegramChatRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl TelegramChatRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl TelegramChatRepository for TelegramChatRepositoryImpl {
+    async fn get_by_chat_id(&self, chat_id: i64) -> HttpResult<Option<TelegramChat>> {
+        let row = sqlx::query(r#"SELECT * FROM finance_manager.telegram_chat WHERE chat_id = $1"#)
+            .bind(chat_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(TelegramChat::from))
+    }
+
+    async fn list_by_client_id(&self, client_id: Uuid) -> HttpResult<Vec<TelegramChat>> {
+        let rows =
+            sqlx::query(r#"SELECT * FROM finance_manager.telegram_chat WHERE client_id = $1"#)
+                .bind(client_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows.iter().map(TelegramChat::from).collect())
+    }
+
+    async fn list_all(&self) -> HttpResult<Vec<TelegramChat>> {
+        let rows = sqlx::query(r#"SELECT * FROM finance_manager.telegram_chat"#)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(TelegramChat::from).collect())
+    }
+
+    async fn upsert(&self, chat: TelegramChat) -> HttpResult<TelegramChat> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.telegram_chat (
+                chat_id,
+                client_id,
+                user_id,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (chat_id) DO UPDATE SET
+                client_id = EXCLUDED.client_id,
+                user_id = EXCLUDED.user_id,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(*chat.chat_id())
+        .bind(*chat.client_id())
+        .bind(*chat.user_id())
+        .bind(*chat.created_at())
+        .bind(*chat.updated_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(TelegramChat::from(&row))
+    }
+
+    async fn delete(&self, client_id: Uuid, chat_id: i64) -> HttpResult<()> {
+        sqlx::query(
+            r#"DELETE FROM finance_manager.telegram_chat WHERE chat_id = $1 AND client_id = $2"#,
+        )
+        .bind(chat_id)
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_link_code(&self, link_code: TelegramLinkCode) -> HttpResult<TelegramLinkCode> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.telegram_link_code (
+                code,
+                client_id,
+                user_id,
+                expires_at,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(link_code.code())
+        .bind(*link_code.client_id())
+        .bind(*link_code.user_id())
+        .bind(*link_code.expires_at())
+        .bind(*link_code.created_at())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(TelegramLinkCode::from(&row))
+    }
+
+    async fn take_link_code(&self, code: &str) -> HttpResult<Option<TelegramLinkCode>> {
+        let row = sqlx::query(
+            r#"DELETE FROM finance_manager.telegram_link_code WHERE code = $1 RETURNING *"#,
+        )
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(TelegramLinkCode::from))
+    }
+}
@@ -0,0 +1,179 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use http_error::{HttpError, HttpResult};
+use sqlx::{types::Json, Pool, Postgres, QueryBuilder};
+use util::DeletedBy;
+use uuid::Uuid;
+
+use crate::modules::finance_manager::{
+    domain::transfer::Transfer,
+    repository::{financial_instrument::InstrumentLock, transfer::use_cases::TransferFilters},
+};
+
+#[async_trait]
+pub trait TransferRepository {
+    /// Inserts the transfer with the lock of its source instrument.
+    async fn insert(&self, transfer: Transfer, lock: InstrumentLock) -> HttpResult<Transfer>;
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Transfer>>;
+
+    async fn list(&self, filters: &TransferFilters) -> HttpResult<Vec<Transfer>>;
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()>;
+}
+
+pub type DynTransferRepository = dyn TransferRepository + Send + Sync;
+
+pub struct TransferRepositoryImpl {
+    pool: Pool<Postgres>,
+}
+
+impl TransferRepositoryImpl {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[async_trait]
+impl TransferRepository for TransferRepositoryImpl {
+    async fn insert(&self, transfer: Transfer, lock: InstrumentLock) -> HttpResult<Transfer> {
+        let mut tx = lock.tx;
+        let row = sqlx::query(
+            r#"
+            INSERT INTO finance_manager.transfer (
+                id,
+                client_id,
+                source_instrument_id,
+                destination_instrument_id,
+                amount,
+                description,
+                transfer_date,
+                created_at,
+                updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(transfer.id())
+        .bind(transfer.client_id())
+        .bind(transfer.source_instrument_id())
+        .bind(transfer.destination_instrument_id())
+        .bind(transfer.amount())
+        .bind(transfer.description())
+        .bind(transfer.transfer_date())
+        .bind(transfer.created_at())
+        .bind(transfer.updated_at())
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Transfer::from(&row))
+    }
+
+    async fn get_by_id(&self, id: &Uuid) -> HttpResult<Option<Transfer>> {
+        let row = sqlx::query(
+            r#"SELECT * FROM finance_manager.transfer WHERE id = $1 AND deleted_by IS NULL"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Transfer::from))
+    }
+
+    async fn list(&self, filters: &TransferFilters) -> HttpResult<Vec<Transfer>> {
+        let mut builder = QueryBuilder::new(
+            "SELECT * FROM finance_manager.transfer WHERE deleted_by IS NULL AND client_id = ",
+        );
+        builder.push_bind(filters.client_id);
+
+        if let Some(instrument_ids) = &filters.financial_instrument_ids {
+            builder.push(" AND (source_instrument_id = ANY(");
+            builder.push_bind(instrument_ids);
+            builder.push(") OR destination_instrument_id = ANY(");
+            builder.push_bind(instrument_ids);
+            builder.push("))");
+        }
+
+        if let Some(start_date) = filters.start_date {
+            builder.push(" AND transfer_date >= ");
+            builder.push_bind(start_date);
+        }
+
+        if let Some(end_date) = filters.end_date {
+            builder.push(" AND transfer_date <= ");
+            builder.push_bind(end_date);
+        }
+
+        builder.push(" ORDER BY transfer_date DESC");
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(Transfer::from).collect())
+    }
+
+    async fn soft_delete(&self, id: &Uuid, deleted_by: DeletedBy) -> HttpResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE finance_manager.transfer
+            SET deleted_by = $2, updated_at = $3
+            WHERE id = $1 AND deleted_by IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(Json(deleted_by))
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Box::new(HttpError::not_found("transfer", id)));
+        }
+
+        Ok(())
+    }
+}
+
+pub mod use_cases {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TransferFilters {
+        pub client_id: Uuid,
+        /// Transfers with any of these instruments as source or destination.
+        pub financial_instrument_ids: Option<Vec<Uuid>>,
+        pub start_date: Option<NaiveDate>,
+        pub end_date: Option<NaiveDate>,
+    }
+
+    impl TransferFilters {
+        pub fn new(client_id: Uuid) -> Self {
+            Self {
+                client_id,
+                ..Default::default()
+            }
+        }
+
+        pub fn with_financial_instrument_ids(
+            mut self,
+            financial_instrument_ids: Option<Vec<Uuid>>,
+        ) -> Self {
+            self.financial_instrument_ids = financial_instrument_ids;
+            self
+        }
+
+        pub fn with_start_date(mut self, start_date: Option<NaiveDate>) -> Self {
+            self.start_date = start_date;
+            self
+        }
+
+        pub fn with_end_date(mut self, end_date: Option<NaiveDate>) -> Self {
+            self.end_date = end_date;
+            self
+        }
+    }
+}
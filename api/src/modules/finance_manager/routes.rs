@@ -1,4 +1,12 @@
+pub mod budget;
 pub mod debt;
+pub mod export;
 pub mod financial_instrument;
+pub mod goal;
 pub mod income;
 pub mod payment;
+pub mod reminder;
+pub mod report;
+pub mod statement;
+pub mod telegram;
+pub mod transfer;
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, patch},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::budget::use_cases::{
+        BudgetQuery, CreateBudgetRequest, UpdateBudgetRequest,
+    },
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/budget",
+        Router::new()
+            .route("/", get(list_budgets).post(create_budget))
+            .route("/alert", get(list_alerts))
+            .route("/{id}", patch(update_budget).delete(delete_budget)),
+    )
+}
+
+async fn create_budget(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateBudgetRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let budget = state
+        .finance_manager_state
+        .budget_handler
+        .create_budget(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(budget))
+}
+
+async fn list_budgets(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<BudgetQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let budgets = state
+        .finance_manager_state
+        .budget_handler
+        .list_budgets(*user.client_id(), query)
+        .await?;
+
+    Ok(Json(budgets))
+}
+
+async fn update_budget(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateBudgetRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let budget = state
+        .finance_manager_state
+        .budget_handler
+        .update_budget(*user.client_id(), id, request)
+        .await?;
+
+    Ok(Json(budget))
+}
+
+async fn delete_budget(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .budget_handler
+        .delete_budget(*user.client_id(), *user.id(), id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+async fn list_alerts(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<BudgetQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let alerts = state
+        .finance_manager_state
+        .budget_handler
+        .list_alerts(*user.client_id(), query)
+        .await?;
+
+    Ok(Json(alerts))
+}
@@ -2,7 +2,7 @@ use axum::{
     extract::{Path, State},
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::{patch, post},
+    routing::{get, patch, post},
     Json, Router,
 };
 use http_error::HttpResult;
@@ -10,25 +10,34 @@ use uuid::Uuid;
 
 use crate::modules::{
     finance_manager::{
-        domain::debt::{recurrence::RecurrenceFilters, DebtFilters},
+        domain::debt::{
+            recurrence::{generation::RecurrenceGenerationRunFilters, RecurrenceFilters},
+            DebtFilters,
+        },
         handler::debt::use_cases::{
-            CreateDebtRequest, CreateRecurrenceRequest, DebtGeneratorRequest,
-            ListDebtInstallmentsRequest, UpdateDebtRequest, UpdateRecurrenceRequest,
+            CreateDebtRequest, CreateRecurrenceRequest, DebtBatchRequest, DebtGeneratorRequest,
+            ListDebtInstallmentsRequest, RenegotiateDebtRequest, SimulateInstallmentsRequest,
+            UpdateDebtRequest, UpdateRecurrenceRequest,
         },
     },
     routes::AppState,
 };
 
+pub mod categorization;
+pub mod category;
 pub mod invoice;
 
 pub fn configure_routes() -> Router<AppState> {
     let main_debt_routes = Router::new()
         .route("/list", post(list_debts))
-        .route("/", post(create_debt));
+        .route("/", post(create_debt))
+        .route("/batch", post(apply_debt_batch));
 
     let installment_routes = Router::new().nest(
         "/installment",
-        Router::new().route("/list", post(list_debt_installments)),
+        Router::new()
+            .route("/list", post(list_debt_installments))
+            .route("/simulate", post(simulate_installments)),
     );
 
     let recurrence_routes = Router::new().nest(
@@ -37,12 +46,16 @@ pub fn configure_routes() -> Router<AppState> {
             .route("/", post(create_recurrence))
             .route("/list", post(list_recurrences))
             .route("/generate", post(generate_recurrences))
+            .route("/run/list", post(list_recurrence_generation_runs))
             .route("/{recurrence_id}", patch(update_recurrence)),
     );
 
     let debt_id_routes = Router::new().nest(
         "/{debt_id}",
-        Router::new().route("/", patch(update_debt).delete(soft_delete_debt)),
+        Router::new()
+            .route("/", patch(update_debt).delete(soft_delete_debt))
+            .route("/renegotiate", post(renegotiate_debt))
+            .route("/renegotiation", get(list_debt_renegotiations)),
     );
 
     Router::new().nest(
@@ -52,6 +65,8 @@ pub fn configure_routes() -> Router<AppState> {
             .merge(installment_routes)
             .merge(recurrence_routes)
             .merge(debt_id_routes)
+            .merge(categorization::configure_routes())
+            .merge(category::configure_routes())
             .merge(invoice::configure_routes()),
     )
 }
@@ -61,13 +76,31 @@ async fn generate_recurrences(
     headers: HeaderMap,
     Json(request): Json<DebtGeneratorRequest>,
 ) -> HttpResult<impl IntoResponse> {
-    let _user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
 
-    state
+    let run = state
         .finance_manager_state
-        .debt_handler
-        .generate_current_recurrences(request)
-        .await
+        .recurrence_generation_handler
+        .generate_for_client(*user.client_id(), request.get_date())
+        .await?;
+
+    Ok(Json(run))
+}
+
+async fn list_recurrence_generation_runs(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(filters): Json<RecurrenceGenerationRunFilters>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+
+    let runs = state
+        .finance_manager_state
+        .recurrence_generation_handler
+        .list_runs(*user.client_id(), filters)
+        .await?;
+
+    Ok(Json(runs))
 }
 
 async fn list_recurrences(
@@ -149,6 +182,37 @@ async fn soft_delete_debt(
     Ok(StatusCode::OK)
 }
 
+async fn renegotiate_debt(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(debt_id): Path<Uuid>,
+    Json(request): Json<RenegotiateDebtRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let renegotiation = state
+        .finance_manager_state
+        .debt_handler
+        .renegotiate_debt(*user.client_id(), debt_id, request)
+        .await?;
+
+    Ok(Json(renegotiation))
+}
+
+async fn list_debt_renegotiations(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(debt_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let renegotiations = state
+        .finance_manager_state
+        .debt_handler
+        .list_debt_renegotiations(*user.client_id(), debt_id)
+        .await?;
+
+    Ok(Json(renegotiations))
+}
+
 async fn list_debt_installments(
     headers: HeaderMap,
     state: State<AppState>,
@@ -165,6 +229,22 @@ async fn list_debt_installments(
     Ok(Json(installments))
 }
 
+async fn simulate_installments(
+    headers: HeaderMap,
+    state: State<AppState>,
+    Json(request): Json<SimulateInstallmentsRequest>,
+) -> HttpResult<impl IntoResponse> {
+    state.auth_state.auth_handler.authenticate(&headers).await?;
+
+    let schedule = state
+        .finance_manager_state
+        .debt_handler
+        .simulate_installments(request)
+        .await?;
+
+    Ok(Json(schedule))
+}
+
 async fn create_debt(
     state: State<AppState>,
     headers: HeaderMap,
@@ -180,6 +260,21 @@ async fn create_debt(
     Ok(Json(debt))
 }
 
+async fn apply_debt_batch(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DebtBatchRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let result = state
+        .finance_manager_state
+        .debt_handler
+        .apply_debt_batch(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(result))
+}
+
 pub async fn list_debts(
     state: State<AppState>,
     headers: HeaderMap,
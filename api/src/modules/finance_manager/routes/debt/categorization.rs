@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, patch, post},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::debt::categorization::use_cases::{
+        CreateRuleRequest, UpdateRuleRequest,
+    },
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/rule",
+        Router::new()
+            .route("/", get(list_rules).post(create_rule))
+            .route("/{rule_id}", patch(update_rule).delete(delete_rule))
+            .route("/{rule_id}/preview", get(preview_rule))
+            .route("/{rule_id}/apply", post(apply_rule)),
+    )
+}
+
+async fn create_rule(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateRuleRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let rule = state
+        .finance_manager_state
+        .categorization_handler
+        .create_rule(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(rule))
+}
+
+async fn list_rules(state: State<AppState>, headers: HeaderMap) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let rules = state
+        .finance_manager_state
+        .categorization_handler
+        .list_rules(*user.client_id())
+        .await?;
+
+    Ok(Json(rules))
+}
+
+async fn update_rule(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(rule_id): Path<Uuid>,
+    Json(request): Json<UpdateRuleRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let rule = state
+        .finance_manager_state
+        .categorization_handler
+        .update_rule(*user.client_id(), rule_id, request)
+        .await?;
+
+    Ok(Json(rule))
+}
+
+async fn delete_rule(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(rule_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .categorization_handler
+        .delete_rule(*user.client_id(), rule_id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+async fn preview_rule(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(rule_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let changes = state
+        .finance_manager_state
+        .categorization_handler
+        .preview_rule(*user.client_id(), rule_id)
+        .await?;
+
+    Ok(Json(changes))
+}
+
+async fn apply_rule(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(rule_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let changes = state
+        .finance_manager_state
+        .categorization_handler
+        .apply_rule(*user.client_id(), rule_id)
+        .await?;
+
+    Ok(Json(changes))
+}
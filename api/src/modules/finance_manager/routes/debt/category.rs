@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, patch},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::debt::category::use_cases::{
+        CreateCategoryRequest, UpdateCategoryRequest,
+    },
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/category",
+        Router::new()
+            .route("/", get(list_categories).post(create_category))
+            .route(
+                "/{category_id}",
+                patch(update_category).delete(delete_category),
+            ),
+    )
+}
+
+async fn create_category(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateCategoryRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let category = state
+        .finance_manager_state
+        .category_handler
+        .create_category(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(category))
+}
+
+async fn list_categories(
+    state: State<AppState>,
+    headers: HeaderMap,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let categories = state
+        .finance_manager_state
+        .category_handler
+        .list_categories(*user.client_id())
+        .await?;
+
+    Ok(Json(categories))
+}
+
+async fn update_category(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(category_id): Path<Uuid>,
+    Json(request): Json<UpdateCategoryRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let category = state
+        .finance_manager_state
+        .category_handler
+        .update_category(*user.client_id(), category_id, request)
+        .await?;
+
+    Ok(Json(category))
+}
+
+async fn delete_category(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(category_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .category_handler
+        .delete_category(*user.client_id(), category_id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
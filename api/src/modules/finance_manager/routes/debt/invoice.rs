@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use crate::modules::{
     finance_manager::domain::debt::invoice::use_cases::{
-        CreateInvoiceRequest, ListInvoicesFilters, ManageInvoiceDebts,
+        CreateInvoiceRequest, ListInvoicesFilters, ManageInvoiceDebts, PayInvoiceRequest,
     },
     routes::AppState,
 };
@@ -21,7 +21,9 @@ pub fn configure_routes() -> Router<AppState> {
         Router::new()
             .route("/", post(create_invoice))
             .route("/list", post(list_invoices))
-            .route("/{invoice_id}", patch(manage_invoice)),
+            .route("/{invoice_id}", patch(manage_invoice))
+            .route("/{invoice_id}/close", post(close_invoice))
+            .route("/{invoice_id}/pay", post(pay_invoice)),
     )
 }
 
@@ -73,3 +75,36 @@ async fn manage_invoice(
 
     Ok(StatusCode::OK)
 }
+
+async fn close_invoice(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(invoice_id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+
+    let invoice = state
+        .finance_manager_state
+        .invoice_handler
+        .close_invoice(*user.client_id(), invoice_id)
+        .await?;
+
+    Ok(Json(invoice))
+}
+
+async fn pay_invoice(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(invoice_id): Path<Uuid>,
+    Json(request): Json<PayInvoiceRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+
+    let summary = state
+        .finance_manager_state
+        .invoice_handler
+        .pay_invoice(*user.client_id(), invoice_id, request)
+        .await?;
+
+    Ok(Json(summary))
+}
@@ -0,0 +1,42 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use futures_util::stream;
+use http_error::HttpResult;
+
+use crate::modules::{finance_manager::handler::export::use_cases::ExportQuery, routes::AppState};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().route("/export", get(export_data))
+}
+
+async fn export_data(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ExportQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let file = state
+        .finance_manager_state
+        .export_handler
+        .export_data(*user.client_id(), query)
+        .await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file.file_name),
+            ),
+        ],
+        Body::from_stream(stream::unfold(file.content, |mut content| async move {
+            content.recv().await.map(|chunk| (chunk, content))
+        })),
+    ))
+}
@@ -1,16 +1,20 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::HeaderMap,
     response::IntoResponse,
-    routing::{patch, post},
+    routing::{get, patch, post},
     Json, Router,
 };
 use http_error::HttpResult;
+use uuid::Uuid;
 
 use crate::modules::{
-    finance_manager::handler::financial_instrument::use_cases::{
-        CreateFinancialInstrumentRequest, FinancialInstrumentListFilters,
-        UpdateFinancialInstrumentRequest,
+    finance_manager::handler::{
+        financial_instrument::use_cases::{
+            CreateFinancialInstrumentRequest, FinancialInstrumentListFilters,
+            UpdateFinancialInstrumentRequest,
+        },
+        ledger::use_cases::{CreateAdjustmentRequest, StatementQuery},
     },
     routes::AppState,
 };
@@ -21,7 +25,10 @@ pub fn configure_routes() -> Router<AppState> {
         Router::new()
             .route("/", post(create_financial_instrument))
             .route("/list", post(list_financial_instruments))
-            .route("/", patch(update_financial_instrument)),
+            .route("/", patch(update_financial_instrument))
+            .route("/{id}/statement", get(get_statement))
+            .route("/{id}/adjustment", post(create_adjustment))
+            .route("/{id}/yield", get(list_yields)),
     )
 }
 
@@ -69,3 +76,50 @@ async fn list_financial_instruments(
 
     Ok(Json(instruments))
 }
+
+async fn get_statement(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Query(query): Query<StatementQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let statement = state
+        .finance_manager_state
+        .ledger_handler
+        .get_statement(*user.client_id(), id, query)
+        .await?;
+
+    Ok(Json(statement))
+}
+
+async fn create_adjustment(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateAdjustmentRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let adjustment = state
+        .finance_manager_state
+        .ledger_handler
+        .create_adjustment(*user.client_id(), id, request)
+        .await?;
+
+    Ok(Json(adjustment))
+}
+
+async fn list_yields(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let yields = state
+        .finance_manager_state
+        .investment_handler
+        .list_yields(*user.client_id(), id)
+        .await?;
+
+    Ok(Json(yields))
+}
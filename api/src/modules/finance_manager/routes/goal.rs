@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, patch},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::investment::use_cases::{CreateGoalRequest, UpdateGoalRequest},
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/goal",
+        Router::new()
+            .route("/", get(list_goals).post(create_goal))
+            .route("/{id}", patch(update_goal).delete(delete_goal)),
+    )
+}
+
+async fn create_goal(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateGoalRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let goal = state
+        .finance_manager_state
+        .investment_handler
+        .create_goal(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(goal))
+}
+
+async fn list_goals(state: State<AppState>, headers: HeaderMap) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let goals = state
+        .finance_manager_state
+        .investment_handler
+        .list_goals(*user.client_id())
+        .await?;
+
+    Ok(Json(goals))
+}
+
+async fn update_goal(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateGoalRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let goal = state
+        .finance_manager_state
+        .investment_handler
+        .update_goal(*user.client_id(), id, request)
+        .await?;
+
+    Ok(Json(goal))
+}
+
+async fn delete_goal(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .investment_handler
+        .delete_goal(*user.client_id(), *user.id(), id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
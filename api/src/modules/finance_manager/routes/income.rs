@@ -1,17 +1,39 @@
-use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::post, Json, Router};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{patch, post},
+    Json, Router,
+};
 use http_error::HttpResult;
+use uuid::Uuid;
 
 use crate::modules::{
-    finance_manager::handler::income::use_cases::{CreateIncomeRequest, ListIncomesRequest},
+    finance_manager::{
+        domain::debt::recurrence::RecurrenceFilters,
+        handler::income::use_cases::{
+            CreateIncomeRecurrenceRequest, CreateIncomeRequest, ListIncomesRequest,
+            UpdateIncomeRecurrenceRequest,
+        },
+    },
     routes::AppState,
 };
 
 pub fn configure_routes() -> Router<AppState> {
+    let recurrence_routes = Router::new().nest(
+        "/recurrence",
+        Router::new()
+            .route("/", post(create_income_recurrence))
+            .route("/list", post(list_income_recurrences))
+            .route("/{recurrence_id}", patch(update_income_recurrence)),
+    );
+
     Router::new().nest(
         "/income",
         Router::new()
             .route("/", post(create_income))
-            .route("/list", post(list_incomes)),
+            .route("/list", post(list_incomes))
+            .merge(recurrence_routes),
     )
 }
 
@@ -44,3 +66,49 @@ async fn list_incomes(
 
     Ok(Json(incomes))
 }
+
+async fn create_income_recurrence(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateIncomeRecurrenceRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let recurrence = state
+        .finance_manager_state
+        .income_handler
+        .create_income_recurrence(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(recurrence))
+}
+
+async fn list_income_recurrences(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(filters): Json<RecurrenceFilters>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let recurrences = state
+        .finance_manager_state
+        .income_handler
+        .list_income_recurrences(*user.client_id(), &filters)
+        .await?;
+
+    Ok(Json(recurrences))
+}
+
+async fn update_income_recurrence(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(recurrence_id): Path<Uuid>,
+    Json(request): Json<UpdateIncomeRecurrenceRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let recurrence = state
+        .finance_manager_state
+        .income_handler
+        .update_income_recurrence(*user.client_id(), recurrence_id, request)
+        .await?;
+
+    Ok(Json(recurrence))
+}
@@ -63,7 +63,7 @@ async fn refund_payment(
     state
         .finance_manager_state
         .payment_handler
-        .refund_payment(*user.client_id(), id)
+        .refund_payment(*user.client_id(), *user.id(), id)
         .await?;
 
     Ok(Json(
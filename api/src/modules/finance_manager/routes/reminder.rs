@@ -0,0 +1,39 @@
+use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::get, Json, Router};
+use http_error::HttpResult;
+
+use crate::modules::{
+    finance_manager::handler::reminder::use_cases::UpdateReminderSettingRequest, routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/reminder",
+        Router::new().route("/setting", get(get_setting).patch(update_setting)),
+    )
+}
+
+async fn get_setting(state: State<AppState>, headers: HeaderMap) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let setting = state
+        .finance_manager_state
+        .reminder_handler
+        .get_setting(*user.client_id())
+        .await?;
+
+    Ok(Json(setting))
+}
+
+async fn update_setting(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateReminderSettingRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let setting = state
+        .finance_manager_state
+        .reminder_handler
+        .update_setting(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(setting))
+}
@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use http_error::HttpResult;
+
+use crate::modules::{
+    finance_manager::handler::report::use_cases::{
+        CashFlowQuery, ForecastQuery, SpendingBreakdownQuery,
+    },
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/report",
+        Router::new()
+            .route("/cashFlow", get(get_cash_flow))
+            .route("/spending", get(get_spending_breakdown))
+            .route("/forecast", get(get_commitment_forecast)),
+    )
+}
+
+async fn get_cash_flow(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<CashFlowQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let report = state
+        .finance_manager_state
+        .report_handler
+        .get_cash_flow(*user.client_id(), query)
+        .await?;
+
+    Ok(Json(report))
+}
+
+async fn get_spending_breakdown(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SpendingBreakdownQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let breakdown = state
+        .finance_manager_state
+        .report_handler
+        .get_spending_breakdown(*user.client_id(), query)
+        .await?;
+
+    Ok(Json(breakdown))
+}
+
+async fn get_commitment_forecast(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ForecastQuery>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let forecast = state
+        .finance_manager_state
+        .report_handler
+        .get_commitment_forecast(*user.client_id(), query)
+        .await?;
+
+    Ok(Json(forecast))
+}
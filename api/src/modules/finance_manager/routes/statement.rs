@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::statement::use_cases::{
+        ConfirmStatementRequest, ImportStatementRequest,
+    },
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/statement",
+        Router::new()
+            .route("/import", post(import_statement))
+            .route("/{id}", get(get_statement_import))
+            .route("/{id}/confirm", post(confirm_statement)),
+    )
+}
+
+async fn import_statement(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ImportStatementRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let import = state
+        .finance_manager_state
+        .statement_handler
+        .import_statement(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(import))
+}
+
+async fn get_statement_import(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let import = state
+        .finance_manager_state
+        .statement_handler
+        .get_statement_import(*user.client_id(), id)
+        .await?;
+
+    Ok(Json(import))
+}
+
+async fn confirm_statement(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ConfirmStatementRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let import = state
+        .finance_manager_state
+        .statement_handler
+        .confirm_statement(*user.client_id(), id, request)
+        .await?;
+
+    Ok(Json(import))
+}
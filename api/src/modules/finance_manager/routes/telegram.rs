@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use http_error::HttpResult;
+use telegram_api::domain::telegram_update::TelegramUpdate;
+
+use crate::modules::routes::AppState;
+
+const WEBHOOK_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/telegram",
+        Router::new()
+            .route("/webhook", post(handle_webhook))
+            .route("/linkCode", post(create_link_code))
+            .route("/chat", get(list_chats))
+            .route("/chat/{chat_id}", delete(unlink_chat)),
+    )
+}
+
+async fn handle_webhook(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<TelegramUpdate>,
+) -> HttpResult<impl IntoResponse> {
+    let telegram_handler = &state.finance_manager_state.telegram_handler;
+    let secret = headers
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    telegram_handler.validate_webhook_secret(secret)?;
+    telegram_handler.handle_update(update).await?;
+
+    Ok(StatusCode::OK)
+}
+
+async fn create_link_code(
+    state: State<AppState>,
+    headers: HeaderMap,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let link_code = state
+        .finance_manager_state
+        .telegram_handler
+        .create_link_code(*user.client_id(), *user.id())
+        .await?;
+
+    Ok(Json(link_code))
+}
+
+async fn list_chats(state: State<AppState>, headers: HeaderMap) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let chats = state
+        .finance_manager_state
+        .telegram_handler
+        .list_chats(*user.client_id())
+        .await?;
+
+    Ok(Json(chats))
+}
+
+async fn unlink_chat(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(chat_id): Path<i64>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .telegram_handler
+        .unlink_chat(*user.client_id(), chat_id)
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Telegram chat unlinked successfully" }),
+    ))
+}
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{delete, post},
+    Json, Router,
+};
+use http_error::HttpResult;
+use uuid::Uuid;
+
+use crate::modules::{
+    finance_manager::handler::transfer::use_cases::{CreateTransferRequest, ListTransfersRequest},
+    routes::AppState,
+};
+
+pub fn configure_routes() -> Router<AppState> {
+    Router::new().nest(
+        "/transfer",
+        Router::new()
+            .route("/", post(create_transfer))
+            .route("/list", post(list_transfers))
+            .route("/{id}", delete(delete_transfer)),
+    )
+}
+
+async fn create_transfer(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateTransferRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let transfer = state
+        .finance_manager_state
+        .transfer_handler
+        .create_transfer(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(transfer))
+}
+
+async fn list_transfers(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ListTransfersRequest>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    let transfers = state
+        .finance_manager_state
+        .transfer_handler
+        .list_transfers(*user.client_id(), request)
+        .await?;
+
+    Ok(Json(transfers))
+}
+
+async fn delete_transfer(
+    state: State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> HttpResult<impl IntoResponse> {
+    let user = state.auth_state.auth_handler.authenticate(&headers).await?;
+    state
+        .finance_manager_state
+        .transfer_handler
+        .delete_transfer(*user.client_id(), *user.id(), id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,80 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use http_error::HttpResult;
+
+/// A job that runs once a day inside the api process.
+#[async_trait]
+pub trait DailyJob {
+    fn name(&self) -> &'static str;
+
+    async fn run(&self, today: NaiveDate) -> HttpResult<()>;
+}
+
+pub type DynDailyJob = dyn DailyJob + Send + Sync;
+
+/// Spawns a task that runs the job every day at `run_at` (UTC). A failed run
+/// is reported and retried on the next day, it never stops the loop.
+pub fn spawn_daily(job: Arc<DynDailyJob>, run_at: NaiveTime) {
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now().naive_utc();
+            tokio::time::sleep(duration_until_next_run(now, run_at)).await;
+
+            let today = Utc::now().date_naive();
+            if let Err(err) = job.run(today).await {
+                tracing::error!("job {} failed: {}", job.name(), err.message);
+            }
+        }
+    });
+}
+
+/// Reads a `HH:MM` time from the environment, falling back to `default`.
+pub fn run_at_from_env(key: &str, default: NaiveTime) -> NaiveTime {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| NaiveTime::parse_from_str(&value, "%H:%M").ok())
+        .unwrap_or(default)
+}
+
+fn duration_until_next_run(now: NaiveDateTime, run_at: NaiveTime) -> Duration {
+    let today_run = now.date().and_time(run_at);
+    let next_run = if today_run > now {
+        today_run
+    } else {
+        today_run + chrono::Duration::days(1)
+    };
+
+    (next_run - now).to_std().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_run_is_later_today() {
+        let run_at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+        let duration = duration_until_next_run(datetime(16, 8, 30), run_at);
+
+        assert_eq!(duration, Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn test_next_run_is_tomorrow_when_time_has_passed() {
+        let run_at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+        let duration = duration_until_next_run(datetime(16, 9, 0), run_at);
+
+        assert_eq!(duration, Duration::from_secs(24 * 60 * 60));
+    }
+}
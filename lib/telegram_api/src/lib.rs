@@ -23,3 +23,9 @@ impl TelegramApiClient {
         }
     }
 }
+
+impl Default for TelegramApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
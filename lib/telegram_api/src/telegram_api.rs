@@ -11,6 +11,8 @@ pub trait TelegramApiGateway {
     async fn send_message(&self, request: SendMessageRequest) -> HttpResult<SendMessageResponse>;
 }
 
+pub type DynTelegramApiGateway = dyn TelegramApiGateway + Send + Sync;
+
 #[async_trait]
 impl TelegramApiGateway for TelegramApiClient {
     async fn send_message(&self, request: SendMessageRequest) -> HttpResult<SendMessageResponse> {
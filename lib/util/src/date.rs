@@ -53,6 +53,26 @@ pub fn last_day_of_month(year: i32, month: u32) -> u32 {
     next_month_first.pred_opt().unwrap().day()
 }
 
+/// Returns the first day of the month of the given date.
+///
+/// # Arguments
+/// * `date` - Any date within the month
+///
+/// # Returns
+/// A NaiveDate for day 1 of the same year and month.
+///
+/// # Example
+/// ```
+/// use chrono::NaiveDate;
+/// use util::date::first_day_of_month;
+///
+/// let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+/// assert_eq!(first_day_of_month(date), NaiveDate::from_ymd_opt(2026, 10, 1).unwrap());
+/// ```
+pub fn first_day_of_month(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,6 +101,15 @@ mod tests {
         assert_eq!(date, NaiveDate::from_ymd_opt(2026, 4, 30).unwrap());
     }
 
+    #[test]
+    fn test_first_day_of_month() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+        assert_eq!(
+            first_day_of_month(date),
+            NaiveDate::from_ymd_opt(2026, 2, 1).unwrap()
+        );
+    }
+
     #[test]
     fn test_last_day_of_month() {
         assert_eq!(last_day_of_month(2026, 1), 31);
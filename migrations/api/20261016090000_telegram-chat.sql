@@ -0,0 +1,19 @@
+-- Telegram chats linked to a user (and therefore to a client)
+CREATE TABLE IF NOT EXISTS finance_manager.telegram_chat (
+    chat_id BIGINT PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    user_id UUID NOT NULL REFERENCES auth.users(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_telegram_chat_client_id ON finance_manager.telegram_chat (client_id);
+
+-- One-time codes sent through the bot (`/vincular <code>`) to link a chat
+CREATE TABLE IF NOT EXISTS finance_manager.telegram_link_code (
+    code TEXT PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    user_id UUID NOT NULL REFERENCES auth.users(id),
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,21 @@
+-- Per-client configuration of the due-date reminders
+CREATE TABLE IF NOT EXISTS finance_manager.reminder_setting (
+    client_id UUID PRIMARY KEY REFERENCES finance_manager.client_information(client_id),
+    horizon_days INT NOT NULL CHECK (horizon_days BETWEEN 0 AND 60),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL
+);
+
+-- Reminders already sent. installment_id is 0 for debts without installments,
+-- so the primary key also guarantees the same reminder is never sent twice.
+CREATE TABLE IF NOT EXISTS finance_manager.debt_reminder (
+    debt_id UUID NOT NULL REFERENCES finance_manager.debt(id),
+    installment_id INT NOT NULL DEFAULT 0,
+    due_date DATE NOT NULL,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    sent_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (debt_id, installment_id, due_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_debt_reminder_client_id ON finance_manager.debt_reminder (client_id);
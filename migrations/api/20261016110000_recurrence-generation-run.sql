@@ -0,0 +1,15 @@
+-- History of the recurrence generation runs (scheduled job or manual trigger)
+CREATE TABLE IF NOT EXISTS finance_manager.recurrence_generation_run (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    trigger TEXT NOT NULL,
+    run_date DATE NOT NULL,
+    created_count INT NOT NULL DEFAULT 0,
+    skipped_count INT NOT NULL DEFAULT 0,
+    failed_count INT NOT NULL DEFAULT 0,
+    items JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurrence_generation_run_client_id
+    ON finance_manager.recurrence_generation_run (client_id, run_date DESC);
@@ -0,0 +1,4 @@
+-- Late fee policy of the debt and the breakdown of the accrued fine and interest
+ALTER TABLE finance_manager.debt
+ADD COLUMN IF NOT EXISTS late_fee_policy JSONB,
+ADD COLUMN IF NOT EXISTS late_fee JSONB;
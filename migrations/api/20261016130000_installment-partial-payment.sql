@@ -0,0 +1,23 @@
+-- Installments can be paid in several partial payments
+ALTER TABLE finance_manager.debt_installment
+ADD COLUMN IF NOT EXISTS paid_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+UPDATE finance_manager.debt_installment
+SET paid_amount = amount
+WHERE is_paid = TRUE;
+
+-- How each payment was spread over the installments of a debt
+CREATE TABLE IF NOT EXISTS finance_manager.installment_payment_allocation (
+    payment_id UUID NOT NULL REFERENCES finance_manager.payment(id) ON DELETE CASCADE,
+    debt_id UUID NOT NULL,
+    installment_id INT NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (payment_id, debt_id, installment_id),
+    FOREIGN KEY (debt_id, installment_id)
+        REFERENCES finance_manager.debt_installment(debt_id, installment_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_installment_payment_allocation_installment
+    ON finance_manager.installment_payment_allocation (debt_id, installment_id);
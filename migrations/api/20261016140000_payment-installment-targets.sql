@@ -0,0 +1,7 @@
+-- Payments may target explicit installments and carry an early payment discount
+ALTER TABLE finance_manager.payment
+ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS installment_ids INT[] NULL;
+
+ALTER TABLE finance_manager.installment_payment_allocation
+ADD COLUMN IF NOT EXISTS discount_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
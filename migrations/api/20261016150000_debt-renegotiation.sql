@@ -0,0 +1,23 @@
+-- Installments replaced by a renegotiation are kept for history
+ALTER TABLE finance_manager.debt_installment
+ADD COLUMN IF NOT EXISTS superseded_by UUID NULL;
+
+-- History of debts re-split into a new installment plan
+CREATE TABLE IF NOT EXISTS finance_manager.debt_renegotiation (
+    id UUID PRIMARY KEY,
+    debt_id UUID NOT NULL REFERENCES finance_manager.debt(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    previous_remaining_amount DECIMAL(10, 2) NOT NULL,
+    interest_rate DECIMAL(10, 4) NOT NULL DEFAULT 0,
+    interest_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    renegotiated_amount DECIMAL(10, 2) NOT NULL,
+    installment_count INT NOT NULL,
+    due_day INT NOT NULL,
+    first_due_date DATE NOT NULL,
+    first_installment_id INT NOT NULL,
+    superseded_installment_ids INT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_debt_renegotiation_debt_id
+    ON finance_manager.debt_renegotiation (debt_id, created_at DESC);
@@ -0,0 +1,15 @@
+-- Interest and amortization system of installment plans
+ALTER TABLE finance_manager.debt
+ADD COLUMN IF NOT EXISTS financing JSONB;
+
+-- Principal and interest components of each installment
+ALTER TABLE finance_manager.debt_installment
+ADD COLUMN IF NOT EXISTS principal_amount DECIMAL(10, 2),
+ADD COLUMN IF NOT EXISTS interest_amount DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+UPDATE finance_manager.debt_installment
+SET principal_amount = amount
+WHERE principal_amount IS NULL;
+
+ALTER TABLE finance_manager.debt_installment
+ALTER COLUMN principal_amount SET NOT NULL;
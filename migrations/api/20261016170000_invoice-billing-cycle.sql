@@ -0,0 +1,16 @@
+-- Credit card invoices generated from the card's billing cycle
+ALTER TABLE finance_manager.invoice
+ADD COLUMN IF NOT EXISTS financial_instrument_id UUID NULL REFERENCES finance_manager.financial_instrument(id),
+ADD COLUMN IF NOT EXISTS closing_date DATE NULL,
+ADD COLUMN IF NOT EXISTS due_date DATE NULL,
+ADD COLUMN IF NOT EXISTS total_amount DECIMAL(10, 2) NULL,
+ADD COLUMN IF NOT EXISTS closed_at TIMESTAMPTZ NULL;
+
+-- One invoice per card and reference month
+CREATE UNIQUE INDEX IF NOT EXISTS uq_invoice_instrument_reference
+    ON finance_manager.invoice (financial_instrument_id, reference_date)
+    WHERE financial_instrument_id IS NOT NULL AND deleted_by IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_invoice_closing
+    ON finance_manager.invoice (closing_date)
+    WHERE closed_at IS NULL;
@@ -0,0 +1,17 @@
+-- Refunded payments are kept so account statements can show the refund
+ALTER TABLE finance_manager.payment
+ADD COLUMN IF NOT EXISTS refunded_at DATE NULL;
+
+-- Manual corrections of a financial instrument balance
+CREATE TABLE IF NOT EXISTS finance_manager.account_adjustment (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    financial_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    description TEXT NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    adjustment_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_adjustment_instrument
+    ON finance_manager.account_adjustment (financial_instrument_id, adjustment_date);
@@ -0,0 +1,22 @@
+-- Money moved between two financial instruments of the same client
+CREATE TABLE IF NOT EXISTS finance_manager.transfer (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    source_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    destination_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    amount DECIMAL(10, 2) NOT NULL CHECK (amount > 0),
+    description TEXT NOT NULL,
+    transfer_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL,
+    deleted_by JSONB NULL,
+    CHECK (source_instrument_id <> destination_instrument_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfer_source_active
+    ON finance_manager.transfer (source_instrument_id, transfer_date)
+    WHERE deleted_by IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transfer_destination_active
+    ON finance_manager.transfer (destination_instrument_id, transfer_date)
+    WHERE deleted_by IS NULL;
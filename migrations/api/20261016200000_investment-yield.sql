@@ -0,0 +1,29 @@
+-- Daily yields credited to investment boxes
+CREATE TABLE IF NOT EXISTS finance_manager.investment_yield (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    financial_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    accrual_date DATE NOT NULL,
+    balance DECIMAL(10, 2) NOT NULL,
+    annual_rate DECIMAL(10, 4) NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (financial_instrument_id, accrual_date)
+);
+
+-- Saving targets tied to an investment box
+CREATE TABLE IF NOT EXISTS finance_manager.savings_goal (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    financial_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    name TEXT NOT NULL,
+    target_amount DECIMAL(10, 2) NOT NULL CHECK (target_amount > 0),
+    target_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL,
+    deleted_by JSONB NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_savings_goal_client_active
+    ON finance_manager.savings_goal (client_id)
+    WHERE deleted_by IS NULL;
@@ -0,0 +1,32 @@
+-- Monthly spending limits per debt category or tag
+CREATE TABLE IF NOT EXISTS finance_manager.budget (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    category TEXT NULL,
+    tag TEXT NULL,
+    monthly_limit DECIMAL(10, 2) NOT NULL CHECK (monthly_limit > 0),
+    rollover BOOLEAN NOT NULL DEFAULT FALSE,
+    notify_telegram BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL,
+    deleted_by JSONB NULL,
+    CHECK ((category IS NULL) <> (tag IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_client_active
+    ON finance_manager.budget (client_id)
+    WHERE deleted_by IS NULL;
+
+-- Thresholds reached by a budget, raised once per month
+CREATE TABLE IF NOT EXISTS finance_manager.budget_alert (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    budget_id UUID NOT NULL REFERENCES finance_manager.budget(id),
+    month DATE NOT NULL,
+    threshold TEXT NOT NULL,
+    committed_amount DECIMAL(10, 2) NOT NULL,
+    available_limit DECIMAL(10, 2) NOT NULL,
+    usage_percentage DECIMAL(10, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (budget_id, month, threshold)
+);
@@ -0,0 +1,19 @@
+-- Incomes received every month, generated with the debt recurrences
+CREATE TABLE IF NOT EXISTS finance_manager.income_recurrence (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    financial_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    description TEXT NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL CHECK (amount > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    start_date DATE NOT NULL,
+    end_date DATE NULL,
+    day_of_month INT NOT NULL CHECK (day_of_month BETWEEN 1 AND 31),
+    execution_logs JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_income_recurrence_client_active
+    ON finance_manager.income_recurrence (client_id)
+    WHERE active;
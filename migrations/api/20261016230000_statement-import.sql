@@ -0,0 +1,31 @@
+-- Bank and card statements imported for a financial instrument
+CREATE TABLE IF NOT EXISTS finance_manager.statement_import (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    financial_instrument_id UUID NOT NULL REFERENCES finance_manager.financial_instrument(id),
+    format TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+-- Statement lines, staged until confirmed as payments, debts or incomes
+CREATE TABLE IF NOT EXISTS finance_manager.statement_transaction (
+    id UUID PRIMARY KEY,
+    import_id UUID NOT NULL REFERENCES finance_manager.statement_import(id),
+    line_number INTEGER NOT NULL,
+    transaction_date DATE NOT NULL,
+    description TEXT NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    external_id TEXT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    debt_id UUID NULL REFERENCES finance_manager.debt(id),
+    installment_id INTEGER NULL,
+    payment_id UUID NULL REFERENCES finance_manager.payment(id),
+    income_id UUID NULL REFERENCES finance_manager.income(id),
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL,
+    UNIQUE (import_id, line_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_statement_transaction_external_id
+    ON finance_manager.statement_transaction (external_id)
+    WHERE external_id IS NOT NULL;
@@ -0,0 +1,47 @@
+-- Per-client debt categories, replacing the fixed category names.
+-- Debts, recurrences and budgets keep referencing categories by name.
+CREATE TABLE IF NOT EXISTS finance_manager.debt_category (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    parent_id UUID NULL REFERENCES finance_manager.debt_category(id),
+    name TEXT NOT NULL,
+    color TEXT NULL,
+    icon TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL,
+    UNIQUE (client_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_debt_category_parent_id
+    ON finance_manager.debt_category (parent_id);
+
+-- Names outside the former fixed categories were already read as UNKNOWN
+UPDATE finance_manager.debt SET category = 'UNKNOWN'
+WHERE category NOT IN ('UNKNOWN', 'HOME', 'TRANSPORT', 'HEALTH', 'FOOD',
+                       'LIFESTYLE', 'EDUCATION', 'GOALS', 'PERSONAL');
+
+UPDATE finance_manager.recurrence SET category = 'UNKNOWN'
+WHERE category NOT IN ('UNKNOWN', 'HOME', 'TRANSPORT', 'HEALTH', 'FOOD',
+                       'LIFESTYLE', 'EDUCATION', 'GOALS', 'PERSONAL');
+
+UPDATE finance_manager.budget SET category = 'UNKNOWN'
+WHERE category NOT IN ('UNKNOWN', 'HOME', 'TRANSPORT', 'HEALTH', 'FOOD',
+                       'LIFESTYLE', 'EDUCATION', 'GOALS', 'PERSONAL');
+
+-- Seeds the former fixed categories for every client
+INSERT INTO finance_manager.debt_category (id, client_id, name, color, icon, created_at)
+SELECT gen_random_uuid(), client.client_id, seed.name, seed.color, seed.icon, NOW()
+FROM finance_manager.client_information client
+CROSS JOIN (
+    VALUES
+        ('UNKNOWN', '#9E9E9E', 'help-circle'),
+        ('HOME', '#8D6E63', 'home'),
+        ('TRANSPORT', '#42A5F5', 'car'),
+        ('HEALTH', '#EF5350', 'heart-pulse'),
+        ('FOOD', '#FFA726', 'utensils'),
+        ('LIFESTYLE', '#AB47BC', 'sparkles'),
+        ('EDUCATION', '#5C6BC0', 'graduation-cap'),
+        ('GOALS', '#66BB6A', 'target'),
+        ('PERSONAL', '#26A69A', 'user')
+) AS seed (name, color, icon)
+ON CONFLICT (client_id, name) DO NOTHING;
@@ -0,0 +1,15 @@
+-- Client rules filling the category, expense type and tags of new debts
+CREATE TABLE IF NOT EXISTS finance_manager.categorization_rule (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES finance_manager.client_information(client_id),
+    name TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    conditions JSONB NOT NULL,
+    actions JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_categorization_rule_client_id
+    ON finance_manager.categorization_rule (client_id, priority);
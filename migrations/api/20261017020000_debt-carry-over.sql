@@ -0,0 +1,5 @@
+-- Balance of an invoice moved to the next one is not a payment: the debts
+-- keep how much was carried over and the debt now billing it
+ALTER TABLE finance_manager.debt
+ADD COLUMN IF NOT EXISTS carried_over_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS carried_over_to UUID NULL REFERENCES finance_manager.debt(id);
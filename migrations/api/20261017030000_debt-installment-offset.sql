@@ -0,0 +1,4 @@
+-- A renegotiated plan keeps counting its own installments: ids taken by the
+-- replaced plans are skipped when numbering them
+ALTER TABLE finance_manager.debt
+ADD COLUMN IF NOT EXISTS installment_offset INT NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Debts billing a carried over balance are flagged with the invoice they
+-- come from, so reports don't count the same money twice
+ALTER TABLE finance_manager.debt
+ADD COLUMN IF NOT EXISTS carried_over_from UUID NULL REFERENCES finance_manager.invoice(id);
@@ -0,0 +1,9 @@
+-- Daily yields are accrued at full precision and only whole cents are
+-- credited, so small balances still yield once their accruals add up
+ALTER TABLE finance_manager.investment_yield
+ADD COLUMN IF NOT EXISTS accrued_amount DECIMAL(20, 10) NULL;
+
+UPDATE finance_manager.investment_yield SET accrued_amount = amount WHERE accrued_amount IS NULL;
+
+ALTER TABLE finance_manager.investment_yield
+ALTER COLUMN accrued_amount SET NOT NULL;